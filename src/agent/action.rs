@@ -0,0 +1,35 @@
+//! # Contains the [Action] enum
+//!
+//! The [Action] enum represents what an [Agent](crate::agent::Agent) asks the [Game](crate::game::Game) to do in its turn.
+//! Besides moves, agents of human players can ask to take back moves and to save or load the game.
+use std::fmt::{Display, Formatter};
+
+/// # Enum representing the actions an agent can request in its turn, see [Agent::choose_action](crate::agent::Agent::choose_action)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Play on the field with the given human index
+    Move(u8),
+    /// Take back the last move of the agent and the reply of the opponent, see [Game::play](crate::game::Game::play)
+    Undo,
+    /// Save the game to the given file before the agent moves, see [Game::play](crate::game::Game::play)
+    Save(String),
+    /// Continue the game saved in the given file, see [Game::play](crate::game::Game::play)
+    Load(String),
+}
+
+impl From<u8> for Action {
+    fn from(index: u8) -> Self {
+        Action::Move(index)
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Move(index) => write!(f, "move {}", index),
+            Action::Undo => write!(f, "undo"),
+            Action::Save(path) => write!(f, "save {}", path),
+            Action::Load(path) => write!(f, "load {}", path),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! # Contains the [AgentError] enum
+//!
+//! The [AgentError] enum represents the reasons an [Agent](crate::agent::Agent) can fail to make a move.
+//! The [Game](crate::game::Game) does not panic on these errors, the failing agent forfeits the game instead.
+use std::fmt::{Display, Formatter};
+
+/// # Enum representing the reasons an agent cannot make a move
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AgentError {
+    /// The agent found no move, e.g. because the game is over
+    NoMove,
+    /// The agent chose the given illegal move
+    IllegalMove(u8),
+    /// The agent could not communicate, e.g. with a remote engine or a user
+    Io(String),
+    /// Any other failure, described by the message
+    Other(String),
+    /// The time of the agent ran out, see [Game::with_time_control](crate::game::Game::with_time_control)
+    Timeout,
+}
+
+impl Display for AgentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentError::NoMove => write!(f, "no move found"),
+            AgentError::IllegalMove(index) => write!(f, "illegal move {}", index),
+            AgentError::Io(message) => write!(f, "I/O error: {}", message),
+            AgentError::Other(message) => write!(f, "{}", message),
+            AgentError::Timeout => write!(f, "the time ran out"),
+        }
+    }
+}
+
+impl std::error::Error for AgentError {}
+
+impl From<std::io::Error> for AgentError {
+    fn from(error: std::io::Error) -> Self {
+        AgentError::Io(error.to_string())
+    }
+}
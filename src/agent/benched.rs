@@ -1,7 +1,12 @@
 //! # Contains the [BenchedAgent] struct
 //!
 //! The [BenchedAgent] struct is used to benchmark agents.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
 use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
 use csv::Writer;
@@ -16,9 +21,13 @@ use std::sync::{Arc, Mutex};
 /// The agent contains a writer to write the benchmark results to a CSV file.
 ///
 /// The agent writes a row to the CSV file for each act call.
+/// If the agent is used in a [Game](crate::game::Game), the rows contain the id of the game.
+/// Clones of the agent write to the same writer.
+#[derive(Clone)]
 pub struct BenchedAgent<A> {
     agent: A,
     writer: Arc<Mutex<Writer<File>>>,
+    game_id: Option<u64>,
 }
 
 impl<A: Agent> BenchedAgent<A> {
@@ -28,14 +37,25 @@ impl<A: Agent> BenchedAgent<A> {
     /// * `writer` - The writer to write the benchmark results to
     /// * `agent` - The agent to benchmark
     pub fn new(writer: Arc<Mutex<Writer<File>>>, agent: A) -> BenchedAgent<A> {
-        BenchedAgent { agent, writer }
+        BenchedAgent {
+            agent,
+            writer,
+            game_id: None,
+        }
     }
-}
 
-impl<A: Agent> Agent for BenchedAgent<A> {
-    fn act(&mut self, board: UltimateBoard, player: Player, turn: u32) -> Option<u8> {
+    /// Measures the time the wrapped agent needs to act and writes it with the information of the agent
+    ///
+    /// # Arguments
+    /// * `act` - Asks the wrapped agent for its move or action
+    /// # Returns
+    /// The result of the wrapped agent
+    fn bench<T>(
+        &mut self,
+        act: impl FnOnce(&mut A) -> Result<T, AgentError>,
+    ) -> Result<T, AgentError> {
         let start = std::time::Instant::now();
-        let result = self.agent.act(board, player, turn);
+        let result = act(&mut self.agent);
         let duration = start.elapsed();
         println!("Duration: {:?}", duration);
 
@@ -43,32 +63,87 @@ impl<A: Agent> Agent for BenchedAgent<A> {
 
         match acquired_lock {
             Ok(mut w) => w
-                .serialize(Row::from_info(self.agent.get_info(), duration.as_micros()))
+                .serialize(Row::from_info(
+                    self.agent.get_info(),
+                    duration.as_micros(),
+                    self.game_id,
+                ))
                 .expect("Could not write row"),
             Err(e) => e
                 .into_inner()
-                .serialize(Row::from_info(self.agent.get_info(), duration.as_micros()))
+                .serialize(Row::from_info(
+                    self.agent.get_info(),
+                    duration.as_micros(),
+                    self.game_id,
+                ))
                 .expect("Could not write row"),
         }
 
         result
     }
+}
+
+impl<A: Agent> Agent for BenchedAgent<A> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.bench(|agent| agent.act(board, player, turn))
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        self.bench(|agent| agent.choose_action(board, player, turn))
+    }
 
     fn get_info(&self) -> AgentInfo {
         let sub_info = self.agent.get_info();
 
-        AgentInfo::new(
-            format!("Benched({})", sub_info.name),
-            sub_info.player,
-            sub_info.turn_num,
-            sub_info.config.clone(),
-        )
+        AgentInfo {
+            name: format!("Benched({})", sub_info.name),
+            ..sub_info
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.game_id = Some(metadata.game_id);
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
     }
 }
 
 /// # Struct representing a row in the CSV file
 ///
-/// The row contains the name of the agent, the player, the turn number, the configuration, and the duration of the act call.
+/// The row contains the name of the agent, the player, the turn number, the configuration, the duration of the act call
+/// and the id of the game, if known.
+///
+/// If the agent reports [statistics](crate::agent::minimax_agent::search_statistics::SearchStatistics) of its search,
+/// they are recorded as well, so the duration can be correlated with the work done by the search.
+/// The same holds for the number of simulations and the principal variation of a [MctsReport](crate::agent::monte_carlo_tree_agent::mcts_report::MctsReport).
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Row {
     pub name: String,
@@ -76,6 +151,26 @@ pub struct Row {
     pub turn_num: u32,
     pub config: String,
     pub duration: u128,
+    /// Missing in CSV files written before game ids were recorded
+    #[serde(default)]
+    pub game_id: Option<u64>,
+    /// Missing in CSV files written before search statistics were recorded
+    #[serde(default)]
+    pub nodes: Option<u64>,
+    #[serde(default)]
+    pub quiescence_nodes: Option<u64>,
+    #[serde(default)]
+    pub beta_cutoffs: Option<u64>,
+    #[serde(default)]
+    pub tt_hits: Option<u64>,
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+    /// Missing in CSV files written before MCTS reports were recorded
+    #[serde(default)]
+    pub simulations: Option<f64>,
+    /// The moves of the principal variation, separated by spaces
+    #[serde(default)]
+    pub principal_variation: Option<String>,
 }
 
 impl Row {
@@ -84,13 +179,105 @@ impl Row {
     /// # Arguments
     /// * `info` - The information of the agent
     /// * `duration` - The duration of the act call
-    fn from_info(info: AgentInfo, duration: u128) -> Row {
+    /// * `game_id` - The id of the game the act call was made in
+    fn from_info(info: AgentInfo, duration: u128, game_id: Option<u64>) -> Row {
+        let statistics = info.statistics;
+        let report = info.mcts_report;
+
         Row {
             name: info.name,
             player: info.player,
             turn_num: info.turn_num,
             config: info.config,
             duration,
+            game_id,
+            nodes: statistics.map(|s| s.nodes),
+            quiescence_nodes: statistics.map(|s| s.quiescence_nodes),
+            beta_cutoffs: statistics.map(|s| s.beta_cutoffs),
+            tt_hits: statistics.map(|s| s.tt_hits),
+            max_depth: statistics.map(|s| s.max_depth),
+            simulations: report.as_ref().map(|r| r.simulations),
+            principal_variation: report.map(|r| {
+                r.principal_variation
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::minimax_agent::MiniMaxAgent;
+    use crate::agent::random_agent::RandomAgent;
+    use crate::game::Game;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_game_id() {
+        let path = std::env::temp_dir().join("hausarbeit_test_game_id.csv");
+        let writer = Arc::new(Mutex::new(Writer::from_path(&path).unwrap()));
+
+        let mut game = Game::with_metadata(
+            Box::new(BenchedAgent::new(writer.clone(), RandomAgent::new())),
+            Box::new(RandomAgent::new()),
+            GameMetadata::with_id(7),
+        );
+        game.play();
+        writer.lock().unwrap().flush().unwrap();
+
+        let rows: Vec<Row> = csv::Reader::from_path(&path)
+            .unwrap()
+            .deserialize()
+            .map(Result::unwrap)
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+
+        let moves = game.get_record().get_moves().len();
+        assert_eq!(rows.len(), moves.div_ceil(2));
+        assert!(rows.iter().all(|row| row.game_id == Some(7)));
+
+        // Rows written before game ids were recorded can still be read
+        let old = "name,player,turn_num,config,duration\nRAND,One,0,,5\n";
+        let row: Row = csv::Reader::from_reader(old.as_bytes())
+            .deserialize()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.game_id, None);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let path = std::env::temp_dir().join("hausarbeit_test_benched_statistics.csv");
+        let writer = Arc::new(Mutex::new(Writer::from_path(&path).unwrap()));
+
+        let mut game = Game::new(
+            Box::new(BenchedAgent::new(
+                writer.clone(),
+                MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One)),
+            )),
+            Box::new(BenchedAgent::new(writer.clone(), RandomAgent::new())),
+        );
+        game.play();
+        writer.lock().unwrap().flush().unwrap();
+
+        let rows: Vec<Row> = csv::Reader::from_path(&path)
+            .unwrap()
+            .deserialize()
+            .map(Result::unwrap)
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+
+        // Only the searching agent reports statistics
+        for row in rows {
+            match row.player {
+                Player::One => assert!(row.nodes.is_some_and(|nodes| nodes > 0)),
+                Player::Two => assert_eq!(row.nodes, None),
+            }
         }
     }
 }
@@ -0,0 +1,435 @@
+//! # Contains the [CoachAgent] struct
+//!
+//! The CoachAgent struct represents an [Agent] that wraps another agent, usually a [HumanAgent](crate::agent::human_agent::HumanAgent),
+//! and reviews every move the wrapped agent makes.
+//!
+//! For more information see the [CoachAgent] struct.
+use std::fmt::Display;
+
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::parameterized_heuristic::{extract_features, FEATURE_NAMES};
+use crate::heuristic::Heuristic;
+
+/// The maximum number of features mentioned in the explanation of a blunder
+const MAX_EXPLAINED_FEATURES: usize = 3;
+
+/// An agent that reviews the moves of the wrapped agent using the provided [Heuristic].
+///
+/// After the wrapped agent has chosen a move, every possible move is evaluated with the heuristic.
+/// If the evaluation of the chosen move is worse than the evaluation of the best move by more than the threshold,
+/// the move is reported as a blunder and the better move is explained.
+/// Every report is passed to the wrapped agent with [Agent::move_reviewed], so a [HumanAgent](crate::agent::human_agent::HumanAgent) shows it to the player,
+/// the report of the last move is also returned by [CoachAgent::get_last_report].
+///
+/// The moves are evaluated [for](Heuristic::evaluate_for) the player to move, so one heuristic can coach both sides.
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::coach_agent::CoachAgent;
+/// use hausarbeit::agent::human_agent::HumanAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let agent = CoachAgent::new(HumanAgent::default(), CustomHeuristic::new(Player::One), 0.2);
+/// ```
+pub struct CoachAgent<A, H> {
+    agent: A,
+    heuristic: H,
+    threshold: f64,
+    last_report: Option<CoachReport>,
+}
+
+impl<A: Agent, H: Heuristic> CoachAgent<A, H> {
+    /// Creates a new [CoachAgent]
+    ///
+    /// # Arguments
+    /// * `agent` - The agent whose moves are reviewed
+    /// * `heuristic` - The heuristic used to evaluate the moves
+    /// * `threshold` - The evaluation drop above which a move is considered a blunder
+    pub fn new(agent: A, heuristic: H, threshold: f64) -> CoachAgent<A, H> {
+        CoachAgent {
+            agent,
+            heuristic,
+            threshold,
+            last_report: None,
+        }
+    }
+
+    /// Returns the report of the last reviewed move
+    ///
+    /// # Returns
+    /// The report of the last reviewed move, None if no move was reviewed yet
+    pub fn get_last_report(&self) -> Option<&CoachReport> {
+        self.last_report.as_ref()
+    }
+
+    /// Reviews the given move and passes the report to the wrapped agent
+    ///
+    /// # Arguments
+    /// * `board` - The board before the move was made
+    /// * `chosen_move` - The move chosen by the wrapped agent
+    fn report(&mut self, board: UltimateBoard, chosen_move: u8) {
+        let report = self.review(board, chosen_move);
+        self.agent.move_reviewed(report.to_string().trim_end());
+        self.last_report = Some(report);
+    }
+
+    /// Reviews the given move
+    ///
+    /// # Arguments
+    /// * `board` - The board before the move was made
+    /// * `chosen_move` - The move chosen by the wrapped agent
+    /// # Returns
+    /// The report of the review
+    pub fn review(&self, board: UltimateBoard, chosen_move: u8) -> CoachReport {
+        let player = board.get_current_player();
+        let evaluate = |index: u8| {
+            let mut new_board = board;
+            new_board.make_move(index);
+            self.heuristic.evaluate_for(new_board, player)
+        };
+
+        let chosen_evaluation = evaluate(chosen_move);
+
+        let (best_move, best_evaluation) = board
+            .get_possible_moves()
+            .map(|index| (index, evaluate(index)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or((chosen_move, chosen_evaluation));
+
+        let is_blunder = best_evaluation - chosen_evaluation > self.threshold;
+
+        CoachReport {
+            chosen_move,
+            chosen_evaluation,
+            best_move,
+            best_evaluation,
+            is_blunder,
+            explanation: if is_blunder {
+                CoachAgent::<A, H>::explain(board, chosen_move, best_move)
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    /// Explains why the best move is better than the chosen move
+    ///
+    /// The explanation compares the [features](extract_features) of the board states after both moves
+    /// and names the features that differ the most.
+    fn explain(board: UltimateBoard, chosen_move: u8, best_move: u8) -> Vec<String> {
+        let player = board.get_current_player();
+
+        let mut chosen_board = board;
+        chosen_board.make_move(chosen_move);
+
+        let mut best_board = board;
+        best_board.make_move(best_move);
+
+        if best_board.get_game_status() == GameResult::Win(player) {
+            return vec![format!("{} wins the game.", format_move(best_move))];
+        }
+
+        let chosen_features = extract_features(chosen_board, player);
+        let best_features = extract_features(best_board, player);
+
+        let mut deltas: Vec<(usize, f64)> = best_features
+            .iter()
+            .zip(chosen_features)
+            .map(|(best, chosen)| best - chosen)
+            .enumerate()
+            .filter(|(_, delta)| delta.abs() > 1e-9)
+            .collect();
+        deltas.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+
+        let mut explanation: Vec<String> = deltas
+            .into_iter()
+            .take(MAX_EXPLAINED_FEATURES)
+            .map(|(index, delta)| {
+                format!(
+                    "{} changes {} by {:+.2} compared to {}.",
+                    format_move(best_move),
+                    FEATURE_NAMES[index],
+                    delta,
+                    format_move(chosen_move)
+                )
+            })
+            .collect();
+
+        if explanation.is_empty() {
+            explanation.push(format!(
+                "{} leads to a better position.",
+                format_move(best_move)
+            ));
+        }
+
+        explanation
+    }
+}
+
+impl<A: Agent, H: Heuristic> Agent for CoachAgent<A, H> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        let chosen_move = self.agent.act(board, player, turn)?;
+
+        self.report(*board, chosen_move);
+
+        Ok(chosen_move)
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        let action = self.agent.choose_action(board, player, turn)?;
+
+        if let Action::Move(chosen_move) = action {
+            self.report(*board, chosen_move);
+        }
+
+        Ok(action)
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let sub_info = self.agent.get_info();
+
+        AgentInfo {
+            name: format!("Coached({})", sub_info.name),
+            ..sub_info
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+}
+
+/// # Struct representing the review of a single move
+///
+/// The report contains the chosen and the best move as well as their evaluations.
+/// If the chosen move is a blunder, the report contains an explanation of the better move.
+#[derive(Clone, Debug)]
+pub struct CoachReport {
+    pub chosen_move: u8,
+    pub chosen_evaluation: f64,
+    pub best_move: u8,
+    pub best_evaluation: f64,
+    pub is_blunder: bool,
+    pub explanation: Vec<String>,
+}
+
+impl Display for CoachReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.is_blunder {
+            return writeln!(
+                f,
+                "{} was a good move ({:.2}).",
+                format_move(self.chosen_move),
+                self.chosen_evaluation
+            );
+        }
+
+        writeln!(
+            f,
+            "{} was a blunder ({:.2}), {} was better ({:.2}).",
+            format_move(self.chosen_move),
+            self.chosen_evaluation,
+            format_move(self.best_move),
+            self.best_evaluation
+        )?;
+
+        for line in self.explanation.iter() {
+            writeln!(f, "  * {}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a move as the board and field number the human player would input
+fn format_move(index: u8) -> String {
+    format!("Board {} field {}", index / 9 + 1, index % 9 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+    use crate::game::Game;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use std::sync::{Arc, Mutex};
+
+    /// Player One has won the boards 0 and 1 and wins the game with the move 20 on board 2
+    fn winning_board() -> UltimateBoard {
+        let squares = format!(
+            "XXX......XXX......XX.......OO.......OO.......O........{}",
+            ".".repeat(27)
+        );
+        UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap()
+    }
+
+    #[test]
+    fn test_blunder() {
+        let board = winning_board();
+        let mut agent = CoachAgent::new(
+            ScriptedAgent::new(vec![21]),
+            CustomHeuristic::new(Player::Two),
+            0.2,
+        );
+
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(21));
+
+        let report = agent.get_last_report().unwrap();
+        assert!(report.is_blunder);
+        assert_eq!(report.chosen_move, 21);
+        assert_eq!(report.best_move, 20);
+        assert_eq!(
+            report.explanation,
+            vec!["Board 3 field 3 wins the game.".to_string()]
+        );
+        assert!(report
+            .to_string()
+            .starts_with("Board 3 field 4 was a blunder"));
+    }
+
+    /// Agent recording the reviews of its moves
+    struct ReviewedAgent {
+        agent: ScriptedAgent,
+        reviews: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Agent for ReviewedAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            self.agent.act(board, player, turn)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+
+        fn move_reviewed(&mut self, review: &str) {
+            self.reviews.lock().unwrap().push(review.to_string());
+        }
+    }
+
+    #[test]
+    fn test_review_in_game() {
+        let coach = CoachAgent::new(
+            ScriptedAgent::new(vec![]),
+            CustomHeuristic::new(Player::One),
+            0.01,
+        );
+
+        // Both players play the first possible move until player One can blunder
+        let mut board = UltimateBoard::new();
+        let mut script = vec![];
+        let blunder = loop {
+            if script.len() % 2 == 0 {
+                if let Some(index) = board
+                    .get_possible_moves()
+                    .find(|&index| coach.review(board, index).is_blunder)
+                {
+                    break index;
+                }
+            }
+            let index = board.get_possible_moves().next().unwrap();
+            board.make_move(index);
+            script.push(index);
+        };
+        let blunder_board = board;
+        script.push(blunder);
+
+        let reviews = Arc::new(Mutex::new(vec![]));
+        let mut game = Game::new(
+            Box::new(CoachAgent::new(
+                ReviewedAgent {
+                    agent: ScriptedAgent::new(script.clone()),
+                    reviews: reviews.clone(),
+                },
+                CustomHeuristic::new(Player::One),
+                0.01,
+            )),
+            Box::new(ScriptedAgent::new(script.clone())),
+        );
+        game.play();
+
+        // Every move of player One is reviewed, the review of the blunder reaches the wrapped agent
+        let reviews = reviews.lock().unwrap();
+        assert_eq!(reviews.len(), script.len().div_ceil(2));
+        assert_eq!(
+            reviews.last().unwrap(),
+            coach.review(blunder_board, blunder).to_string().trim_end()
+        );
+        assert!(reviews.last().unwrap().contains("was a blunder"));
+    }
+
+    #[test]
+    fn test_good_move() {
+        let board = winning_board();
+        let coach = CoachAgent::new(
+            ScriptedAgent::new(vec![]),
+            CustomHeuristic::new(Player::One),
+            0.2,
+        );
+
+        let report = coach.review(board, 20);
+        assert!(!report.is_blunder);
+        assert!(report.explanation.is_empty());
+        assert_eq!(
+            report.to_string(),
+            "Board 3 field 3 was a good move (1.00).\n"
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        // The move 2 wins the small board 0, the move 3 does not
+        let squares = format!("XX.......{}", ".".repeat(72));
+        let board = UltimateBoard::from_position(&format!("{} 0 X", squares)).unwrap();
+
+        let explanation = CoachAgent::<ScriptedAgent, CustomHeuristic>::explain(board, 3, 2);
+
+        assert!(!explanation.is_empty() && explanation.len() <= MAX_EXPLAINED_FEATURES);
+        assert!(explanation.contains(
+            &"Board 1 field 3 changes the difference in small boards won by +1.00 compared to Board 1 field 4."
+                .to_string()
+        ));
+    }
+}
@@ -0,0 +1,201 @@
+//! # Contains the [EnsembleAgent] struct
+//!
+//! The EnsembleAgent struct represents an [Agent] that lets several agents vote on the move.
+//!
+//! For more information see the [EnsembleAgent] struct.
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// An agent that plays the move with the most votes of its members
+///
+/// All members are asked for their move in parallel, every member votes for its move with its weight.
+/// The move with the highest total weight is played, with equal weights this is a majority vote.
+/// Ties are broken in favor of the move of the member added first.
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::ensemble_agent::EnsembleAgent;
+/// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let agent = EnsembleAgent::new()
+///     .with_agent(MiniMaxAgent::new(2, 1, CustomHeuristic::new(Player::One)), 1.)
+///     .with_agent(MiniMaxAgent::new(6, 1, CustomHeuristic::new(Player::One)), 2.)
+///     .with_agent(MonteCarloTreeAgent::new(1000), 1.);
+/// ```
+pub struct EnsembleAgent {
+    members: Vec<(Box<dyn Agent>, f64)>,
+    player: Player,
+    turn: u32,
+}
+
+impl Default for EnsembleAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnsembleAgent {
+    /// Creates a new [EnsembleAgent] without members
+    pub fn new() -> EnsembleAgent {
+        EnsembleAgent {
+            members: vec![],
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Adds a member to the ensemble
+    ///
+    /// # Arguments
+    /// * `agent` - The member
+    /// * `weight` - The weight of the votes of the member, must be positive
+    /// # Returns
+    /// The ensemble with the member added
+    pub fn with_agent(mut self, agent: impl Agent + 'static, weight: f64) -> EnsembleAgent {
+        assert!(weight > 0., "Weight must be positive");
+        self.members.push((Box::new(agent), weight));
+        self
+    }
+
+    /// Counts the votes of the members
+    ///
+    /// # Arguments
+    /// * `votes` - The move of every member, None if the member has no move, and its weight
+    /// # Returns
+    /// The move with the highest total weight, None if no member has a move
+    fn count_votes(votes: &[(Option<u8>, f64)]) -> Option<u8> {
+        let mut totals: Vec<(u8, f64)> = vec![];
+
+        for &(vote, weight) in votes {
+            let Some(index) = vote else {
+                continue;
+            };
+
+            match totals.iter_mut().find(|(candidate, _)| *candidate == index) {
+                Some((_, total)) => *total += weight,
+                None => totals.push((index, weight)),
+            }
+        }
+
+        // The first of the moves with the highest total is kept
+        totals
+            .into_iter()
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+impl Agent for EnsembleAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let votes: Vec<(Option<u8>, f64)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .members
+                .iter_mut()
+                .map(|(agent, weight)| {
+                    let weight = *weight;
+                    scope.spawn(move || (agent.act(board, player, turn).ok(), weight))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        EnsembleAgent::count_votes(&votes).ok_or(AgentError::NoMove)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let members: Vec<String> = self
+            .members
+            .iter()
+            .map(|(agent, weight)| format!("{}*{}", weight, agent.get_info().get_name()))
+            .collect();
+
+        AgentInfo::new(
+            "ENSEMBLE".to_string(),
+            self.player,
+            self.turn,
+            members.join(" + "),
+        )
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        for (agent, _) in self.members.iter_mut() {
+            agent.set_game_metadata(metadata);
+        }
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        for (agent, _) in self.members.iter_mut() {
+            agent.opponent_moved(board, index);
+        }
+    }
+
+    fn new_game(&mut self, player: Player) {
+        for (agent, _) in self.members.iter_mut() {
+            agent.new_game(player);
+        }
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        for (agent, _) in self.members.iter_mut() {
+            agent.game_over(board, result);
+        }
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        for (agent, _) in self.members.iter_mut() {
+            agent.clock_updated(clock);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+
+    #[test]
+    fn test_voting() {
+        assert_eq!(
+            EnsembleAgent::count_votes(&[(Some(1), 1.), (Some(2), 1.), (Some(2), 1.)]),
+            Some(2)
+        );
+        assert_eq!(
+            EnsembleAgent::count_votes(&[(Some(1), 3.), (Some(2), 1.), (Some(2), 1.)]),
+            Some(1)
+        );
+        assert_eq!(
+            EnsembleAgent::count_votes(&[(None, 1.), (Some(2), 1.), (Some(3), 1.)]),
+            Some(2)
+        );
+        assert_eq!(EnsembleAgent::count_votes(&[(None, 1.)]), None);
+
+        let mut agent = EnsembleAgent::new()
+            .with_agent(ScriptedAgent::new(vec![0]), 1.)
+            .with_agent(ScriptedAgent::new(vec![40]), 1.)
+            .with_agent(ScriptedAgent::new(vec![40]), 1.);
+
+        assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(40));
+        assert_eq!(agent.get_info().config, "1*SCRIPT + 1*SCRIPT + 1*SCRIPT");
+    }
+}
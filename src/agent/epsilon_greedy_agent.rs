@@ -0,0 +1,171 @@
+//! # Contains the [EpsilonGreedyAgent] struct
+//!
+//! The EpsilonGreedyAgent struct represents an [Agent] that sometimes plays a random move instead of the move of another agent.
+//! It is used to measure how well agents handle suboptimal play and to diversify the games between deterministic agents.
+//!
+//! For more information see the [EpsilonGreedyAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use rand::seq::IteratorRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// An agent that plays a random move with the probability epsilon, and the move of another agent otherwise
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::epsilon_greedy_agent::EpsilonGreedyAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+///
+/// let agent = EpsilonGreedyAgent::new(0.1, MonteCarloTreeAgent::new(1000)).with_seed(42);
+/// ```
+/// The agent plays a random move in about every tenth turn.
+#[derive(Clone)]
+pub struct EpsilonGreedyAgent<A: Agent> {
+    epsilon: f64,
+    agent: A,
+    rng: ChaCha20Rng,
+}
+
+impl<A: Agent> EpsilonGreedyAgent<A> {
+    /// Creates a new [EpsilonGreedyAgent] with a random number generator seeded from the operating system
+    ///
+    /// # Arguments
+    /// * `epsilon` - The probability of playing a random move, must be between 0 and 1
+    /// * `agent` - The agent playing the other moves
+    pub fn new(epsilon: f64, agent: A) -> EpsilonGreedyAgent<A> {
+        assert!(
+            (0. ..=1.).contains(&epsilon),
+            "Epsilon must be between 0 and 1"
+        );
+        EpsilonGreedyAgent {
+            epsilon,
+            agent,
+            rng: ChaCha20Rng::from_entropy(),
+        }
+    }
+
+    /// Seeds the random number generator
+    ///
+    /// Agents with the same seed choose the same turns for random moves and the same random moves.
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The agent with the seeded random number generator
+    pub fn with_seed(mut self, seed: u64) -> EpsilonGreedyAgent<A> {
+        self.rng = ChaCha20Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Plays a random move with the probability epsilon
+    ///
+    /// # Arguments
+    /// * `board` - The current board
+    /// # Returns
+    /// The random move, None if the wrapped agent has to play
+    fn explore(&mut self, board: &UltimateBoard) -> Option<Result<u8, AgentError>> {
+        if self.rng.gen::<f64>() < self.epsilon {
+            return Some(
+                board
+                    .get_possible_moves()
+                    .choose(&mut self.rng)
+                    .ok_or(AgentError::NoMove),
+            );
+        }
+
+        None
+    }
+}
+
+impl<A: Agent> Agent for EpsilonGreedyAgent<A> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        match self.explore(board) {
+            Some(result) => result,
+            None => self.agent.act(board, player, turn),
+        }
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        match self.explore(board) {
+            Some(result) => result.map(Action::Move),
+            None => self.agent.choose_action(board, player, turn),
+        }
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let info = self.agent.get_info();
+
+        AgentInfo {
+            name: format!("EpsilonGreedy({})", info.name),
+            config: format!("{} (epsilon {})", info.config, self.epsilon),
+            ..info
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+
+    #[test]
+    fn test_epsilon() {
+        let board = UltimateBoard::new();
+
+        let mut greedy = EpsilonGreedyAgent::new(0., ScriptedAgent::new(vec![40])).with_seed(0);
+        assert_eq!(greedy.act(&board, Player::One, 0), Ok(40));
+
+        let mut random = EpsilonGreedyAgent::new(1., ScriptedAgent::new(vec![40])).with_seed(0);
+        let moves: Vec<_> = (0..20)
+            .map(|_| random.act(&board, Player::One, 0).unwrap())
+            .collect();
+        assert!(moves.iter().any(|&index| index != 40));
+
+        // The same seed chooses the same random moves
+        let mut same = EpsilonGreedyAgent::new(1., ScriptedAgent::new(vec![40])).with_seed(0);
+        assert!(moves
+            .iter()
+            .all(|&index| same.act(&board, Player::One, 0) == Ok(index)));
+
+        assert_eq!(greedy.get_info().get_name(), "EpsilonGreedy(SCRIPT)");
+    }
+}
@@ -0,0 +1,142 @@
+//! # Contains the [GreedyHeuristicAgent] struct
+//!
+//! The GreedyHeuristicAgent struct represents an [Agent] that plays the move with the best one-ply heuristic evaluation.
+//! It is a baseline with search depth 1, a sanity check for new heuristics and a fast fallback agent.
+//!
+//! For more information see the [GreedyHeuristicAgent] struct.
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::Heuristic;
+
+/// An agent that plays the move with the best heuristic evaluation
+///
+/// Every legal move is made and the resulting board is evaluated with the heuristic.
+/// Of the moves with the best value, the lowest index is played.
+///
+/// The boards are evaluated [for](Heuristic::evaluate_for) the player of the agent, so the agent can play both sides with one heuristic.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use hausarbeit::agent::greedy_agent::GreedyHeuristicAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::time_limited_agent::TimeLimitedAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let agent = TimeLimitedAgent::new(Duration::from_millis(100), MonteCarloTreeAgent::new(1000))
+///     .with_fallback(GreedyHeuristicAgent::new(CustomHeuristic::new(Player::One)));
+/// ```
+#[derive(Clone)]
+pub struct GreedyHeuristicAgent<H> {
+    heuristic: H,
+    last_value: Option<f64>,
+    player: Player,
+    turn: u32,
+}
+
+impl<H: Heuristic> GreedyHeuristicAgent<H> {
+    /// Creates a new [GreedyHeuristicAgent]
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic used to evaluate the moves
+    pub fn new(heuristic: H) -> GreedyHeuristicAgent<H> {
+        GreedyHeuristicAgent {
+            heuristic,
+            last_value: None,
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Finds the move with the best heuristic evaluation
+    ///
+    /// # Arguments
+    /// * `board` - The board to choose a move on
+    /// * `player` - The player to choose the move for
+    /// # Returns
+    /// The best move and its value, None if the game is over
+    pub fn get_best_move(&self, board: UltimateBoard, player: Player) -> Option<(u8, f64)> {
+        board
+            .get_possible_moves()
+            .map(|index| {
+                let mut new_board = board;
+                new_board.make_move(index);
+                (index, self.heuristic.evaluate_for(new_board, player))
+            })
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+impl<H: Heuristic> Agent for GreedyHeuristicAgent<H> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let (index, value) = self
+            .get_best_move(*board, player)
+            .ok_or(AgentError::NoMove)?;
+        self.last_value = Some(value);
+
+        Ok(index)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let info = AgentInfo::new(
+            "GREEDY".to_string(),
+            self.player,
+            self.turn,
+            self.heuristic.get_name(),
+        );
+
+        match self.last_value {
+            Some(value) => info.with_evaluation(value),
+            None => info,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use crate::heuristic::MAX_VALUE;
+
+    #[test]
+    fn test_winning_move() {
+        // Player One has won the boards 0 and 1 and wins the game with the move 20 on board 2
+        let squares = format!(
+            "XXX......XXX......XX.......OO.......OO.......O........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap();
+        let mut agent = GreedyHeuristicAgent::new(CustomHeuristic::new(Player::One));
+
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(20));
+        assert_eq!(agent.get_info().get_evaluation(), Some(MAX_VALUE));
+    }
+
+    #[test]
+    fn test_other_player() {
+        // Player Two has won the boards 0 and 1 and wins the game with the move 20 on board 2
+        let squares = format!(
+            "OOO......OOO......OO.......XX.......XX.......X........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 O", squares)).unwrap();
+
+        // The heuristic was created for Player One, but the agent plays for Player Two
+        let mut agent = GreedyHeuristicAgent::new(CustomHeuristic::new(Player::One));
+
+        assert_eq!(agent.act(&board, Player::Two, 0), Ok(20));
+        assert_eq!(agent.get_info().get_evaluation(), Some(MAX_VALUE));
+    }
+}
@@ -0,0 +1,105 @@
+//! # Contains the [MoveAnalysis] struct
+//!
+//! The MoveAnalysis struct compares a move of the human player with the best move of an engine,
+//! see [HumanAgent::with_analysis_engine](super::HumanAgent::with_analysis_engine).
+//!
+//! The analysis is computed from a [multi-PV](crate::agent::minimax_agent::minimax_config::MiniMaxConfig::with_multi_pv) search
+//! of the board before the move, so the values of the best and the chosen move are exact and comparable.
+use std::fmt::{Display, Formatter};
+
+use super::messages::{Language, Message};
+use crate::agent::minimax_agent::search_result::SearchResult;
+use crate::heuristic::MIN_VALUE;
+
+/// # Struct representing the analysis of a single move
+///
+/// # Fields
+/// * `chosen_move` - The move the player made
+/// * `value_before` - The evaluation of the board before the move, i.e. the value of the best move
+/// * `value_after` - The evaluation of the board after the chosen move
+/// * `best_move` - The best move according to the engine
+/// * `is_blunder` - Whether the evaluation dropped by more than the threshold
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoveAnalysis {
+    pub chosen_move: u8,
+    pub value_before: f64,
+    pub value_after: f64,
+    pub best_move: u8,
+    pub is_blunder: bool,
+}
+
+impl MoveAnalysis {
+    /// Analyses a move with the result of a multi-PV search of the board before the move
+    ///
+    /// # Arguments
+    ///
+    /// A move missing from the lines is assumed to lose, as the search only leaves out moves
+    /// that are not better than a forced loss if it reports a line for every possible move.
+    /// # Arguments
+    /// * `result` - The result of the search with a line for every possible move
+    /// * `chosen_move` - The move the player made
+    /// * `threshold` - The evaluation drop above which a move is considered a blunder
+    /// # Returns
+    /// The analysis
+    pub fn from_search(result: &SearchResult, chosen_move: u8, threshold: f64) -> Self {
+        let value_after = result
+            .lines
+            .iter()
+            .find(|line| line.root_move == chosen_move)
+            .map_or(MIN_VALUE, |line| line.value);
+
+        MoveAnalysis {
+            chosen_move,
+            value_before: result.value,
+            value_after,
+            best_move: result.best_move,
+            is_blunder: result.value - value_after > threshold,
+        }
+    }
+}
+
+/// Describes the analysis in English, see [Message::Analysis] for other languages
+impl Display for MoveAnalysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            Language::English.translate(Message::Analysis(self))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::minimax_agent::MiniMaxAgent;
+    use crate::game::player::Player;
+    use crate::game::ultimate_board::UltimateBoard;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_analysis() {
+        // X wins the game with 20, any other move is a blunder
+        let squares = format!(
+            "XXX......XXX......XX.......OO.......OO.......O........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap();
+        let mut engine =
+            MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One)).with_multi_pv(81);
+        let result = engine.search(board).unwrap();
+
+        assert_eq!(result.best_move, 20);
+        let best = MoveAnalysis::from_search(&result, 20, 0.25);
+        assert!(!best.is_blunder);
+        assert!(best.to_string().contains("was the best move"));
+
+        let worst = result.lines.last().unwrap().root_move;
+        let analysis = MoveAnalysis::from_search(&result, worst, 0.25);
+        assert!(analysis.is_blunder);
+        assert!(analysis.to_string().contains("blunder"));
+
+        // Every possible move has a line
+        assert_eq!(result.lines.len(), board.get_possible_moves().count());
+    }
+}
@@ -0,0 +1,173 @@
+//! # Contains the input of the [HumanAgent](super::HumanAgent)
+//!
+//! Reading from the console blocks until the player has typed something,
+//! so the agent could not update the [clocks](crate::game::clock::Clock) or notice that the time has run out while waiting.
+//! The [PollInput] trait allows waiting for input with a timeout instead.
+//!
+//! The [ThreadedInput] struct reads the standard input, or any other reader, on a background thread
+//! and passes the read bytes over a channel, so it can be polled.
+
+use std::io::{BufRead, Cursor, ErrorKind, Read};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+/// The receiver of the thread reading the standard input, shared by all agents reading from it
+static STDIN: Lazy<Arc<Mutex<Receiver<Vec<u8>>>>> = Lazy::new(|| spawn_reader(std::io::stdin()));
+
+/// Trait representing an input that can be waited on with a timeout
+pub trait PollInput: BufRead {
+    /// Waits until the input can be read without blocking
+    ///
+    /// # Arguments
+    /// * `timeout` - The maximum time to wait
+    /// # Returns
+    /// True if the input can be read or has ended, false if the timeout has passed
+    fn poll_input(&mut self, timeout: Duration) -> std::io::Result<bool>;
+}
+
+/// In-memory inputs can always be read without blocking
+impl<T: AsRef<[u8]>> PollInput for Cursor<T> {
+    fn poll_input(&mut self, _timeout: Duration) -> std::io::Result<bool> {
+        Ok(true)
+    }
+}
+
+impl PollInput for &[u8] {
+    fn poll_input(&mut self, _timeout: Duration) -> std::io::Result<bool> {
+        Ok(true)
+    }
+}
+
+/// # Struct representing an input read on a background thread
+///
+/// The thread reads the underlying reader until it ends, the bytes are buffered until they are consumed.
+pub struct ThreadedInput {
+    receiver: Arc<Mutex<Receiver<Vec<u8>>>>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl ThreadedInput {
+    /// Creates a new input reading the given reader on a new thread
+    ///
+    /// # Arguments
+    /// * `reader` - The reader to read from
+    pub fn new(reader: impl Read + Send + 'static) -> ThreadedInput {
+        ThreadedInput::from_receiver(spawn_reader(reader))
+    }
+
+    /// Creates a new input reading the standard input
+    ///
+    /// The standard input is read by a single thread started on the first call,
+    /// so several agents can read from it one after the other.
+    /// Once the thread is started, the standard input should not be read in another way.
+    pub fn stdin() -> ThreadedInput {
+        ThreadedInput::from_receiver(STDIN.clone())
+    }
+
+    fn from_receiver(receiver: Arc<Mutex<Receiver<Vec<u8>>>>) -> ThreadedInput {
+        ThreadedInput {
+            receiver,
+            buffer: vec![],
+            position: 0,
+        }
+    }
+}
+
+impl PollInput for ThreadedInput {
+    fn poll_input(&mut self, timeout: Duration) -> std::io::Result<bool> {
+        if self.position < self.buffer.len() {
+            return Ok(true);
+        }
+
+        match self.receiver.lock().unwrap().recv_timeout(timeout) {
+            Ok(bytes) => {
+                self.buffer = bytes;
+                self.position = 0;
+                Ok(true)
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(false),
+            Err(RecvTimeoutError::Disconnected) => Ok(true),
+        }
+    }
+}
+
+impl BufRead for ThreadedInput {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.position == self.buffer.len() {
+            // The input has ended if the reading thread has stopped
+            let Ok(bytes) = self.receiver.lock().unwrap().recv() else {
+                return Ok(&[]);
+            };
+            self.buffer = bytes;
+            self.position = 0;
+        }
+
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.buffer.len());
+    }
+}
+
+impl Read for ThreadedInput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+/// Starts a thread reading the reader until it ends and sending the read bytes over a channel
+fn spawn_reader(mut reader: impl Read + Send + 'static) -> Arc<Mutex<Receiver<Vec<u8>>>> {
+    let (sender, receiver) = channel();
+
+    std::thread::spawn(move || {
+        let mut buffer = [0; 1024];
+
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(length) => {
+                    if sender.send(buffer[..length].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    Arc::new(Mutex::new(receiver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threaded_input() {
+        let mut input = ThreadedInput::new(Cursor::new("5\n1\n"));
+        let mut line = String::new();
+
+        assert!(input.poll_input(Duration::from_secs(1)).unwrap());
+        input.read_line(&mut line).unwrap();
+        assert_eq!(line, "5\n");
+
+        line.clear();
+        input.read_line(&mut line).unwrap();
+        assert_eq!(line, "1\n");
+
+        // The ended input can be polled and reads nothing
+        assert!(input.poll_input(Duration::from_secs(1)).unwrap());
+        assert_eq!(input.read_line(&mut line).unwrap(), 0);
+    }
+}
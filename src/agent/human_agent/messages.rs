@@ -0,0 +1,326 @@
+//! # Contains the message catalog of the [HumanAgent](super::HumanAgent)
+//!
+//! Every text the agent prints to the player is a [Message], which is translated into the [Language]
+//! chosen with [HumanAgent::with_language](super::HumanAgent::with_language).
+//!
+//! The commands the player types, e.g. `hint` or `undo`, are the same in every language.
+use crate::game::notation;
+use crate::game::player::Player;
+use crate::heuristic::is_proven;
+
+use super::analysis::MoveAnalysis;
+
+/// # Enum representing the languages of the message catalog
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+/// # Enum representing what a number entered by the player selects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Board,
+    Field,
+}
+
+/// # Enum representing the texts printed to the player
+#[derive(Clone, Copy, Debug)]
+pub enum Message<'a> {
+    /// The analysis of the last move, shown above the next prompt
+    LastAnalysis(&'a MoveAnalysis),
+    /// The analysis of the move just made
+    Analysis(&'a MoveAnalysis),
+    Analysing,
+    /// The last move of the opponent as human index
+    OpponentPlayed(u8),
+    /// The player of the agent
+    PlayingAs(Player),
+    Waiting,
+    GameWon,
+    GameLost,
+    GameDrawn,
+    /// The formatted remaining time of both players
+    Clock(&'a str, &'a str),
+    TimeUp,
+    TuiHelp,
+    TuiHintHelp,
+    CanNotPlaySquare,
+    NoUndo,
+    /// Why a requested action could not be performed
+    ActionFailed(&'a str),
+    /// The review of the move just made by a [CoachAgent](crate::agent::coach_agent::CoachAgent)
+    MoveReview(&'a str),
+    MustPlayHighlighted,
+    PlayAnyBoard,
+    NoHintEngine,
+    /// The suggested move, its evaluation and the search depth
+    Hint(u8, f64, u32),
+    NoHint,
+    InputNumber(Target),
+    /// The valid numbers, separated by commas
+    ValidNumbers(Target, &'a str),
+    HintHelp,
+    UndoHelp,
+    SaveLoadHelp,
+    Saving(&'a str),
+    Loading(&'a str),
+    InvalidMove(u8),
+    InvalidNumber(Target),
+    InvalidInput,
+}
+
+impl Language {
+    /// Returns the language with the given name
+    ///
+    /// # Arguments
+    /// * `name` - The English name or the ISO 639-1 code of the language, e.g. `german` or `de`
+    /// # Returns
+    /// The language, None if the language is not in the catalog
+    pub fn from_name(name: &str) -> Option<Language> {
+        match name.trim().to_lowercase().as_str() {
+            "english" | "en" => Some(Language::English),
+            "german" | "deutsch" | "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// Translates a message into the language
+    ///
+    /// # Arguments
+    /// * `message` - The message
+    /// # Returns
+    /// The text of the message
+    pub fn translate(&self, message: Message) -> String {
+        match self {
+            Language::English => english(message),
+            Language::German => german(message),
+        }
+    }
+}
+
+fn english(message: Message) -> String {
+    match message {
+        Message::LastAnalysis(analysis) => format!(
+            "Analysis of your last move: {}",
+            english(Message::Analysis(analysis))
+        ),
+        Message::Analysis(analysis) => {
+            let chosen_move = notation::to_algebraic(analysis.chosen_move);
+            if analysis.chosen_move == analysis.best_move
+                || analysis.value_after >= analysis.value_before
+            {
+                return format!(
+                    "{} was the best move (evaluation {}).",
+                    chosen_move,
+                    format_value(Language::English, analysis.value_after)
+                );
+            }
+
+            let text = format!(
+                "{} changed the evaluation from {} to {}, the best move was {}.",
+                chosen_move,
+                format_value(Language::English, analysis.value_before),
+                format_value(Language::English, analysis.value_after),
+                notation::to_algebraic(analysis.best_move)
+            );
+            if analysis.is_blunder {
+                text + " This is a blunder!"
+            } else {
+                text
+            }
+        }
+        Message::Analysing => "Analysing your move...".to_string(),
+        Message::OpponentPlayed(index) => {
+            format!("Your opponent played {}.", notation::to_algebraic(index))
+        }
+        Message::PlayingAs(player) => format!("You play {}.", symbol(player)),
+        Message::Waiting => "Waiting for your opponent...".to_string(),
+        Message::GameWon => "You won the game!".to_string(),
+        Message::GameLost => "You lost the game.".to_string(),
+        Message::GameDrawn => "The game is a draw.".to_string(),
+        Message::Clock(x, o) => format!("Clock: X {} | O {}", x, o),
+        Message::TimeUp => "Your time has run out.".to_string(),
+        Message::TuiHelp => "Select a square with the arrow keys or the mouse, play it with Enter or a click, take back your last move with u and quit with q.".to_string(),
+        Message::TuiHintHelp => "Press ? for a suggested move.".to_string(),
+        Message::CanNotPlaySquare => "You can not play on this square.".to_string(),
+        Message::NoUndo => "There is no move to take back.".to_string(),
+        Message::ActionFailed(reason) => format!("Your request failed: {}.", reason),
+        Message::MoveReview(review) => format!("Coach: {}", review),
+        Message::MustPlayHighlighted => "You have to play on the highlighted board.".to_string(),
+        Message::PlayAnyBoard => "You can play on any board.".to_string(),
+        Message::NoHintEngine => "No hint engine is available.".to_string(),
+        Message::Hint(index, value, depth) => format!(
+            "Hint: play {} (evaluation {:.2} at depth {}).",
+            notation::to_algebraic(index),
+            value,
+            depth
+        ),
+        Message::NoHint => "No hint is available.".to_string(),
+        Message::InputNumber(target) => format!(
+            "Input a number between 1 and 9 to play on the corresponding {}, or a move like b2/c3.",
+            english_target(target)
+        ),
+        Message::ValidNumbers(target, valid) => {
+            format!("Only these {}s are valid: {}", english_target(target), valid)
+        }
+        Message::HintHelp => "Type hint for a suggested move.".to_string(),
+        Message::UndoHelp => "Type undo to take back your last move.".to_string(),
+        Message::SaveLoadHelp => {
+            "Type save <file> to save the game or load <file> to continue a saved game.".to_string()
+        }
+        Message::Saving(path) => format!("Saving the game to {}.", path),
+        Message::Loading(path) => format!("Loading the game from {}.", path),
+        Message::InvalidMove(index) => format!(
+            "Invalid move {}. Please try again.",
+            notation::to_algebraic(index)
+        ),
+        Message::InvalidNumber(target) => {
+            format!("Invalid {}. Please try again.", english_target(target))
+        }
+        Message::InvalidInput => "Invalid input. Please try again.".to_string(),
+    }
+}
+
+fn german(message: Message) -> String {
+    match message {
+        Message::LastAnalysis(analysis) => format!(
+            "Analyse deines letzten Zuges: {}",
+            german(Message::Analysis(analysis))
+        ),
+        Message::Analysis(analysis) => {
+            let chosen_move = notation::to_algebraic(analysis.chosen_move);
+            if analysis.chosen_move == analysis.best_move
+                || analysis.value_after >= analysis.value_before
+            {
+                return format!(
+                    "{} war der beste Zug (Bewertung: {}).",
+                    chosen_move,
+                    format_value(Language::German, analysis.value_after)
+                );
+            }
+
+            let text = format!(
+                "{} hat die Bewertung von {} auf {} verändert, der beste Zug war {}.",
+                chosen_move,
+                format_value(Language::German, analysis.value_before),
+                format_value(Language::German, analysis.value_after),
+                notation::to_algebraic(analysis.best_move)
+            );
+            if analysis.is_blunder {
+                text + " Das ist ein grober Fehler!"
+            } else {
+                text
+            }
+        }
+        Message::Analysing => "Dein Zug wird analysiert...".to_string(),
+        Message::OpponentPlayed(index) => {
+            format!("Dein Gegner hat {} gespielt.", notation::to_algebraic(index))
+        }
+        Message::PlayingAs(player) => format!("Du spielst {}.", symbol(player)),
+        Message::Waiting => "Warte auf deinen Gegner...".to_string(),
+        Message::GameWon => "Du hast das Spiel gewonnen!".to_string(),
+        Message::GameLost => "Du hast das Spiel verloren.".to_string(),
+        Message::GameDrawn => "Das Spiel endet unentschieden.".to_string(),
+        Message::Clock(x, o) => format!("Uhr: X {} | O {}", x, o),
+        Message::TimeUp => "Deine Zeit ist abgelaufen.".to_string(),
+        Message::TuiHelp => "Wähle ein Feld mit den Pfeiltasten oder der Maus, spiele es mit Enter oder einem Klick, nimm deinen letzten Zug mit u zurück und beende das Spiel mit q.".to_string(),
+        Message::TuiHintHelp => "Drücke ? für einen Zugvorschlag.".to_string(),
+        Message::CanNotPlaySquare => "Auf dieses Feld kannst du nicht spielen.".to_string(),
+        Message::NoUndo => "Es gibt keinen Zug, den du zurücknehmen kannst.".to_string(),
+        Message::ActionFailed(reason) => format!("Deine Anfrage ist fehlgeschlagen: {}.", reason),
+        Message::MoveReview(review) => format!("Trainer: {}", review),
+        Message::MustPlayHighlighted => "Du musst auf dem hervorgehobenen Brett spielen.".to_string(),
+        Message::PlayAnyBoard => "Du kannst auf einem beliebigen Brett spielen.".to_string(),
+        Message::NoHintEngine => "Es ist keine Engine für Tipps verfügbar.".to_string(),
+        Message::Hint(index, value, depth) => format!(
+            "Tipp: spiele {} (Bewertung {:.2} bei Tiefe {}).",
+            notation::to_algebraic(index),
+            value,
+            depth
+        ),
+        Message::NoHint => "Es ist kein Tipp verfügbar.".to_string(),
+        Message::InputNumber(target) => format!(
+            "Gib eine Zahl zwischen 1 und 9 ein, um auf dem entsprechenden {} zu spielen, oder einen Zug wie b2/c3.",
+            german_target(target, false)
+        ),
+        Message::ValidNumbers(target, valid) => format!(
+            "Nur diese {} sind gültig: {}",
+            german_target(target, true),
+            valid
+        ),
+        Message::HintHelp => "Gib hint ein, um einen Zugvorschlag zu erhalten.".to_string(),
+        Message::UndoHelp => "Gib undo ein, um deinen letzten Zug zurückzunehmen.".to_string(),
+        Message::SaveLoadHelp => "Gib save <Datei> ein, um das Spiel zu speichern, oder load <Datei>, um ein gespeichertes Spiel fortzusetzen.".to_string(),
+        Message::Saving(path) => format!("Das Spiel wird in {} gespeichert.", path),
+        Message::Loading(path) => format!("Das Spiel wird aus {} geladen.", path),
+        Message::InvalidMove(index) => format!(
+            "Ungültiger Zug {}. Bitte versuche es erneut.",
+            notation::to_algebraic(index)
+        ),
+        Message::InvalidNumber(target) => format!(
+            "Ungültiges {}. Bitte versuche es erneut.",
+            german_target(target, false)
+        ),
+        Message::InvalidInput => "Ungültige Eingabe. Bitte versuche es erneut.".to_string(),
+    }
+}
+
+fn symbol(player: Player) -> &'static str {
+    match player {
+        Player::One => "X",
+        Player::Two => "O",
+    }
+}
+
+fn english_target(target: Target) -> &'static str {
+    match target {
+        Target::Board => "board",
+        Target::Field => "field",
+    }
+}
+
+fn german_target(target: Target, plural: bool) -> &'static str {
+    match (target, plural) {
+        (Target::Board, false) => "Brett",
+        (Target::Board, true) => "Bretter",
+        (Target::Field, false) => "Feld",
+        (Target::Field, true) => "Felder",
+    }
+}
+
+/// Formats an evaluation, won and lost positions are named instead of printing the extreme values
+fn format_value(language: Language, value: f64) -> String {
+    match language {
+        Language::English if is_proven(value) && value > 0. => "a win".to_string(),
+        Language::English if is_proven(value) => "a loss".to_string(),
+        Language::German if is_proven(value) && value > 0. => "gewonnen".to_string(),
+        Language::German if is_proven(value) => "verloren".to_string(),
+        _ => format!("{:.2}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        assert_eq!(Language::from_name("DE"), Some(Language::German));
+        assert_eq!(Language::from_name("french"), None);
+
+        assert_eq!(
+            Language::English.translate(Message::ValidNumbers(Target::Board, "1, 2")),
+            "Only these boards are valid: 1, 2"
+        );
+        assert_eq!(
+            Language::German.translate(Message::ValidNumbers(Target::Board, "1, 2")),
+            "Nur diese Bretter sind gültig: 1, 2"
+        );
+        assert_eq!(
+            Language::German.translate(Message::OpponentPlayed(36)),
+            "Dein Gegner hat b2/a1 gespielt."
+        );
+    }
+}
@@ -0,0 +1,1251 @@
+//! # Contains the [HumanAgent] struct
+//! The HumanAgent struct represents an [Agent] that allows a human player to play the game.
+//! The human player can input the moves via the console, or any other input and output, see [HumanAgent::from_io].
+//! The board is printed to the console before each move, with the last move of the opponent marked
+//! and the small boards won by a player shaded in the color of the player.
+//!
+//! If:
+//! * a move is invalid, the player is prompted to input a new move.
+//! * the player has to play on a specific board, the board is highlighted by a colorful border.
+//!
+//! The colors depend on the [theme](theme::Theme), which also offers a plain ASCII mode, see [HumanAgent::with_theme].
+//! The prompts are taken from a [message catalog](messages) in English or German, see [HumanAgent::with_language].
+//!
+//! The human player can also select the squares with the arrow keys or the mouse in a [terminal user interface](tui),
+//! see [HumanAgent::with_tui].
+//!
+//! In the analysis mode every move of the human player is compared with the best move of an engine, see [HumanAgent::with_analysis_engine].
+//!
+//! If the game is played with a [time control](crate::game::clock::TimeControl), both clocks are displayed above the board
+//! and refreshed while the agent waits for input. The player forfeits if their time runs out.
+//!
+//! Recorded games can be reviewed with the same board in the [ReplayViewer](replay::ReplayViewer).
+//!
+//! You can start a game with a human player by calling the [start_game_with_human] function.
+//! Two human players can play over the network by joining a relay started with [host_network_games],
+//! see [join_network_game_with_human].
+
+pub mod analysis;
+pub mod input;
+pub mod messages;
+pub mod replay;
+pub mod theme;
+pub mod tui;
+
+use std::io::{Stdout, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::minimax_agent::search_result::SearchResult;
+use crate::agent::minimax_agent::MiniMaxAgent;
+use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+use crate::agent::registry::AgentRegistry;
+use crate::agent::remote_agent::{AgentServer, Relay};
+use crate::agent::{Agent, AgentInfo};
+use crate::game::board::BoardSymbol;
+use crate::game::clock::{format_time, Clock, TimeControl};
+use crate::game::game_record::GameRecord;
+use crate::game::game_result::GameResult;
+use crate::game::notation;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::game::Game;
+use crate::heuristic::parameterized_heuristic::ParameterizedHeuristic;
+use crate::heuristic::Heuristic;
+use analysis::MoveAnalysis;
+use input::{PollInput, ThreadedInput};
+use messages::{Language, Message, Target};
+use theme::Theme;
+
+/// The input of the player, see [HumanAgent::read_number]
+enum Selection {
+    /// A number between 1 and 9
+    Number(u8),
+    /// A whole legal move in algebraic notation or another action like `undo`
+    Action(Action),
+}
+
+/// A function searching the given board for a hint, see [HumanAgent::with_hint_engine]
+pub type HintEngine = dyn FnMut(UltimateBoard) -> Option<SearchResult> + Send + Sync;
+
+/// An [Agent] that allows a human player to play the game.
+///
+/// The human player can input the moves via the console.
+/// The board is printed to the console before each move.
+///
+/// If:
+/// * a move is invalid, the player is prompted to input a new move.
+/// * the player has to play on a specific board, the board is highlighted by a colorful border.
+///
+/// The agent can read the moves from any [PollInput] and print to any [Write] instead of the console,
+/// e.g. to test the interaction or to use another frontend, see [HumanAgent::from_io].
+///
+/// Instead of entering numbers, the player can select the squares with the arrow keys, see [HumanAgent::with_tui].
+///
+/// If a helper engine is set with [HumanAgent::with_hint_engine], the player can type `hint` (`?` in the terminal user interface)
+/// to get the move suggested by the engine and its evaluation.
+///
+/// The player can type `undo` (`u` in the terminal user interface) to take back the last own move and the reply of the opponent,
+/// see [Action::Undo]. Actions other than moves are only offered if the agent is asked with [choose_action](Agent::choose_action),
+/// e.g. by a [Game].
+///
+/// If an analysis engine is set with [HumanAgent::with_analysis_engine], every move of the player is compared with the best move of the engine.
+///
+/// The player can type `save <file>` to save the game and `load <file>` to continue a saved game,
+/// see [Action::Save] and [Action::Load]. Games started with [start_game_with_human] can be resumed with [resume_game_with_human].
+///
+/// If the game is played with a [time control](crate::game::clock::TimeControl), both clocks are displayed and refreshed every second
+/// while the agent waits for input. The agent returns [AgentError::Timeout] when the time of the player runs out.
+///
+/// You can start a game with a human player by calling the [start_game_with_human] function.
+pub struct HumanAgent<R = ThreadedInput, W = Stdout> {
+    input: R,
+    output: W,
+    /// Whether the squares are selected in the [terminal user interface](tui)
+    tui: bool,
+    /// The human index of the square under the cursor of the terminal user interface
+    cursor: u8,
+    /// The theme the board is drawn with
+    theme: Theme,
+    /// The language of the prompts
+    language: Language,
+    /// The moves of the opponent, the last one is marked in the terminal user interface
+    opponent_moves: Vec<u8>,
+    /// The engine suggesting moves on request
+    hint_engine: Option<Box<HintEngine>>,
+    /// The engine analysing every move of the player and the evaluation drop above which a move is a blunder
+    analysis_engine: Option<Box<HintEngine>>,
+    analysis_threshold: f64,
+    /// The analysis of the last move of the player, shown above the next prompt
+    last_analysis: Option<MoveAnalysis>,
+    /// The clocks at the start of the current move and the time the move started
+    clock: Option<(Clock, Instant)>,
+    /// Whether the player can request actions other than moves in the current turn
+    actions: bool,
+    player: Player,
+    turn: u32,
+}
+
+impl Default for HumanAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HumanAgent {
+    /// Creates a new [HumanAgent] reading from the standard input and printing to the standard output
+    pub fn new() -> HumanAgent {
+        HumanAgent::from_io(ThreadedInput::stdin(), std::io::stdout())
+    }
+}
+
+impl<R: PollInput, W: Write> HumanAgent<R, W> {
+    /// Creates a new [HumanAgent] reading the moves from the given input and printing to the given output
+    ///
+    /// # Arguments
+    /// * `input` - The input the moves are read from, one number per line
+    /// * `output` - The output the board and the prompts are printed to
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use hausarbeit::agent::Agent;
+    /// use hausarbeit::agent::human_agent::HumanAgent;
+    /// use hausarbeit::game::player::Player;
+    /// use hausarbeit::game::ultimate_board::UltimateBoard;
+    ///
+    /// // Play on the center board, then on the top left field
+    /// let mut agent = HumanAgent::from_io(Cursor::new("5\n1\n"), Vec::new());
+    ///
+    /// assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(36));
+    /// ```
+    pub fn from_io(input: R, output: W) -> HumanAgent<R, W> {
+        HumanAgent {
+            input,
+            output,
+            tui: false,
+            cursor: 40,
+            theme: Theme::from_env(),
+            language: Language::default(),
+            opponent_moves: vec![],
+            hint_engine: None,
+            analysis_engine: None,
+            analysis_threshold: 0.,
+            last_analysis: None,
+            clock: None,
+            actions: false,
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Lets the player select the squares with the arrow keys in the [terminal user interface](tui)
+    ///
+    /// The keys are read from the input without waiting for a line break,
+    /// so the terminal of the standard input should be switched to [raw mode](tui::RawMode).
+    /// # Returns
+    /// The agent using the terminal user interface
+    pub fn with_tui(mut self) -> HumanAgent<R, W> {
+        self.tui = true;
+        self
+    }
+
+    /// Sets the theme the board is drawn with
+    ///
+    /// By default the theme is chosen by the environment, see [Theme::from_env].
+    /// # Arguments
+    /// * `theme` - The theme, e.g. [Theme::Ascii] for terminals without colors
+    /// # Returns
+    /// The agent using the theme
+    pub fn with_theme(mut self, theme: Theme) -> HumanAgent<R, W> {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets the language of the prompts, see [messages]
+    ///
+    /// # Arguments
+    /// * `language` - The language, English by default
+    /// # Returns
+    /// The agent printing the prompts in the language
+    pub fn with_language(mut self, language: Language) -> HumanAgent<R, W> {
+        self.language = language;
+        self
+    }
+
+    /// Sets the engine suggesting moves when the player types `hint`
+    ///
+    /// The engine searches the board with [MiniMaxAgent::search], its heuristic should evaluate the board for the player of the agent.
+    /// # Arguments
+    /// * `engine` - The helper engine
+    /// # Returns
+    /// The agent giving hints
+    pub fn with_hint_engine<H: Heuristic + 'static>(
+        mut self,
+        mut engine: MiniMaxAgent<H>,
+    ) -> HumanAgent<R, W> {
+        self.hint_engine = Some(Box::new(move |board| engine.search(board)));
+        self
+    }
+
+    /// Analyses every move of the player with the given engine
+    ///
+    /// After each move the evaluation before and after the move and the best alternative are printed,
+    /// moves losing more than the threshold are marked as blunders, see [MoveAnalysis].
+    /// The engine searches all moves in [multi-PV](MiniMaxAgent::with_multi_pv) mode,
+    /// its heuristic should evaluate the board for the player of the agent.
+    /// # Arguments
+    /// * `engine` - The analysis engine
+    /// * `threshold` - The evaluation drop above which a move is considered a blunder
+    /// # Returns
+    /// The agent analysing the moves of the player
+    pub fn with_analysis_engine<H: Heuristic + 'static>(
+        mut self,
+        engine: MiniMaxAgent<H>,
+        threshold: f64,
+    ) -> HumanAgent<R, W> {
+        let mut engine = engine.with_multi_pv(81);
+        self.analysis_engine = Some(Box::new(move |board| engine.search(board)));
+        self.analysis_threshold = threshold;
+        self
+    }
+
+    /// Returns the output the agent prints to
+    pub fn get_output(&self) -> &W {
+        &self.output
+    }
+
+    /// Prints a message in the language of the agent
+    fn say(&mut self, message: Message) -> Result<(), AgentError> {
+        writeln!(self.output, "{}", self.language.translate(message))?;
+        Ok(())
+    }
+
+    /// Prints the analysis of the last move of the player and the last move of the opponent in [algebraic notation](notation)
+    fn show_last_move(&mut self) -> Result<(), AgentError> {
+        self.say(Message::PlayingAs(self.player))?;
+        if let Some(analysis) = self.last_analysis.clone() {
+            self.say(Message::LastAnalysis(&analysis))?;
+        }
+        if let Some(&index) = self.opponent_moves.last() {
+            self.say(Message::OpponentPlayed(index))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the remaining time of both players, counting down the time of the current move
+    fn get_clock_line(&self) -> Option<String> {
+        let (clock, start) = self.clock?;
+        let remaining = |player: Player| {
+            if player == self.player {
+                clock.get_remaining(player).saturating_sub(start.elapsed())
+            } else {
+                clock.get_remaining(player)
+            }
+        };
+
+        Some(self.language.translate(Message::Clock(
+            &format_time(remaining(Player::One)),
+            &format_time(remaining(Player::Two)),
+        )))
+    }
+
+    /// Clears the output and prints the clocks, if any, in the first line
+    fn clear_screen(&mut self) -> Result<(), AgentError> {
+        write!(self.output, "{esc}[2J{esc}[1;1H", esc = 27 as char)?;
+        if let Some(line) = self.get_clock_line() {
+            writeln!(self.output, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Overwrites the clocks in the first line without moving the cursor
+    fn refresh_clock(&mut self) -> Result<(), AgentError> {
+        if let Some(line) = self.get_clock_line() {
+            write!(
+                self.output,
+                "{esc}7{esc}[1;1H{esc}[2K{}{esc}8",
+                line,
+                esc = 27 as char
+            )?;
+            self.output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Waits until the player has entered something, refreshing the clocks every second
+    ///
+    /// # Returns
+    /// [AgentError::Timeout] if the time of the player runs out before
+    fn wait_for_input(&mut self) -> Result<(), AgentError> {
+        while let Some((clock, start)) = self.clock {
+            let remaining = clock
+                .get_remaining(self.player)
+                .saturating_sub(start.elapsed());
+
+            if remaining.is_zero() {
+                self.say(Message::TimeUp)?;
+                return Err(AgentError::Timeout);
+            }
+
+            if self
+                .input
+                .poll_input(remaining.min(Duration::from_secs(1)))?
+            {
+                break;
+            }
+            self.refresh_clock()?;
+        }
+
+        Ok(())
+    }
+
+    /// Clears the output and prints the board
+    fn show_board(
+        &mut self,
+        board: UltimateBoard,
+        highlighted_board: Option<u8>,
+    ) -> Result<(), AgentError> {
+        self.clear_screen()?;
+        print_board(
+            &mut self.output,
+            board,
+            highlighted_board,
+            self.opponent_moves.last().copied(),
+            &self.theme,
+        )?;
+        self.show_last_move()
+    }
+
+    /// Lets the player select a legal square in the terminal user interface with the keys or the mouse
+    ///
+    /// The mouse is only reported while the player selects a square.
+    /// # Arguments
+    /// * `board` - The board to play on
+    /// # Returns
+    /// The selected move, or an error if the input has ended or the player quit
+    fn select_square(&mut self, board: UltimateBoard) -> Result<Action, AgentError> {
+        write!(self.output, "{}", tui::ENABLE_MOUSE)?;
+        let result = self.read_square(board);
+        write!(self.output, "{}", tui::DISABLE_MOUSE)?;
+        self.output.flush()?;
+
+        result
+    }
+
+    /// Returns the square under the mouse pointer
+    ///
+    /// # Arguments
+    /// * `column` - The column of the terminal cell under the pointer
+    /// * `row` - The row of the terminal cell under the pointer
+    fn square_under_pointer(&self, column: u16, row: u16) -> Option<u8> {
+        // The board is rendered below the clocks, if any
+        let top = self.clock.is_some() as u16;
+
+        row.checked_sub(top)
+            .and_then(|row| tui::square_at(column, row))
+    }
+
+    /// Reads keys and mouse events until the player plays a legal square, see [HumanAgent::select_square]
+    fn read_square(&mut self, board: UltimateBoard) -> Result<Action, AgentError> {
+        if !board.is_legal_move(self.cursor) {
+            self.cursor = board
+                .get_possible_moves()
+                .next()
+                .ok_or(AgentError::NoMove)?;
+        }
+
+        let mut message = String::new();
+        let mut redraw = true;
+
+        loop {
+            if redraw {
+                self.clear_screen()?;
+                tui::render(
+                    &mut self.output,
+                    board,
+                    self.cursor,
+                    self.opponent_moves.last().copied(),
+                    &self.theme,
+                )?;
+                self.show_last_move()?;
+                self.say(Message::TuiHelp)?;
+                if self.hint_engine.is_some() {
+                    self.say(Message::TuiHintHelp)?;
+                }
+                write!(self.output, "{}", message)?;
+                self.output.flush()?;
+            }
+            redraw = true;
+
+            self.wait_for_input()?;
+            let key = match tui::read_key(&mut self.input)? {
+                // A click selects and plays the square under the pointer
+                Some(tui::Key::Click { column, row }) => {
+                    match self.square_under_pointer(column, row) {
+                        Some(index) => {
+                            self.cursor = index;
+                            Some(tui::Key::Select)
+                        }
+                        None => Some(tui::Key::Other),
+                    }
+                }
+                // The cursor follows the pointer, the board is only redrawn if the square changes
+                Some(tui::Key::Hover { column, row }) => {
+                    match self.square_under_pointer(column, row) {
+                        Some(index) if index != self.cursor => {
+                            self.cursor = index;
+                            message.clear();
+                        }
+                        _ => redraw = false,
+                    }
+                    continue;
+                }
+                key => key,
+            };
+
+            match key {
+                None => return Err(AgentError::Io("The input has ended".to_string())),
+                Some(tui::Key::Quit) => {
+                    return Err(AgentError::Other("The player quit the game".to_string()))
+                }
+                Some(tui::Key::Select) if board.is_legal_move(self.cursor) => {
+                    return Ok(Action::Move(self.cursor))
+                }
+                Some(tui::Key::Select) => {
+                    message = self.language.translate(Message::CanNotPlaySquare) + "\n"
+                }
+                Some(tui::Key::Hint) => message = self.get_hint(board) + "\n",
+                Some(tui::Key::Undo) => match self.undo() {
+                    Some(action) => return Ok(action),
+                    None => message = self.language.translate(Message::NoUndo) + "\n",
+                },
+                Some(key) => {
+                    self.cursor = tui::move_cursor(self.cursor, key);
+                    message.clear();
+                }
+            }
+        }
+    }
+
+    /// Lets the player select the board and the field with the console prompts
+    ///
+    /// # Arguments
+    /// * `board` - The board to play on
+    /// # Returns
+    /// The selected move or another action like `undo`, an error if the input has ended
+    fn read_move(&mut self, board: UltimateBoard) -> Result<Action, AgentError> {
+        self.show_board(board, board.get_next_board_index())?;
+
+        let possible_moves = board.get_possible_moves().collect::<Vec<u8>>();
+
+        let selected_board = match board.get_next_board_index() {
+            Some(next_board_index) => {
+                self.say(Message::MustPlayHighlighted)?;
+                next_board_index
+            }
+            None => {
+                self.say(Message::PlayAnyBoard)?;
+
+                let valid_boards: Vec<u8> = board
+                    .get_board_status()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &status)| status == GameResult::Continue)
+                    .map(|(index, _)| index as u8 + 1)
+                    .collect();
+
+                let selected_board = match self.read_number(board, Target::Board, &valid_boards)? {
+                    Selection::Number(number) => number - 1,
+                    Selection::Action(action) => return Ok(action),
+                };
+                self.show_board(board, Some(selected_board))?;
+                selected_board
+            }
+        };
+
+        let valid_fields: Vec<u8> = possible_moves
+            .iter()
+            .filter(|&&index| index / 9 == selected_board)
+            .map(|&index| index % 9 + 1)
+            .collect();
+
+        match self.read_number(board, Target::Field, &valid_fields)? {
+            Selection::Number(number) => Ok(Action::Move(selected_board * 9 + number - 1)),
+            Selection::Action(action) => Ok(action),
+        }
+    }
+
+    /// Analyses the move with the analysis engine and prints the analysis
+    ///
+    /// The analysis is shown again above the next prompt.
+    /// # Arguments
+    /// * `board` - The board before the move
+    /// * `index` - The move of the player
+    fn analyze(&mut self, board: UltimateBoard, index: u8) -> Result<(), AgentError> {
+        let Some(engine) = self.analysis_engine.as_mut() else {
+            return Ok(());
+        };
+
+        // The engine borrows the agent, so the message is printed directly
+        writeln!(
+            self.output,
+            "{}",
+            self.language.translate(Message::Analysing)
+        )?;
+        self.output.flush()?;
+        self.last_analysis = engine(board)
+            .map(|result| MoveAnalysis::from_search(&result, index, self.analysis_threshold));
+
+        if let Some(analysis) = self.last_analysis.clone() {
+            self.say(Message::Analysis(&analysis))?;
+        }
+        Ok(())
+    }
+
+    /// Lets the player choose the action of the turn and analyses the chosen move
+    ///
+    /// # Arguments
+    /// * `board` - The board to play on
+    /// * `player` - The player of the agent
+    /// * `turn` - The number of moves made so far by both players
+    /// * `actions` - Whether the player can request actions other than moves
+    /// # Returns
+    /// The chosen action, an error if the input has ended
+    fn play_turn(
+        &mut self,
+        board: UltimateBoard,
+        player: Player,
+        turn: u32,
+        actions: bool,
+    ) -> Result<Action, AgentError> {
+        self.player = player;
+        self.turn = turn;
+        self.actions = actions;
+
+        let action = if self.tui {
+            self.select_square(board)?
+        } else {
+            self.read_move(board)?
+        };
+
+        if let Action::Move(index) = action {
+            self.analyze(board, index)?;
+            self.say(Message::Waiting)?;
+            self.output.flush()?;
+        }
+        Ok(action)
+    }
+
+    /// Asks to take back the last own move and the reply of the opponent
+    ///
+    /// # Returns
+    /// [Action::Undo] to return from [choose_action](Agent::choose_action),
+    /// None if the player has not moved yet or can not request actions in this turn
+    fn undo(&mut self) -> Option<Action> {
+        if !self.actions || self.turn < 2 {
+            return None;
+        }
+
+        self.opponent_moves.pop();
+        self.last_analysis = None;
+        Some(Action::Undo)
+    }
+
+    /// Searches the board with the hint engine
+    ///
+    /// # Arguments
+    /// * `board` - The board to search
+    /// # Returns
+    /// The description of the suggested move
+    fn get_hint(&mut self, board: UltimateBoard) -> String {
+        let Some(engine) = self.hint_engine.as_mut() else {
+            return self.language.translate(Message::NoHintEngine);
+        };
+
+        let message = match engine(board) {
+            Some(result) => Message::Hint(result.best_move, result.value, result.depth),
+            None => Message::NoHint,
+        };
+        self.language.translate(message)
+    }
+
+    /// Reads a number between 1 and 9 until a valid one is entered
+    ///
+    /// The player can also enter a whole move in [algebraic notation](notation),
+    /// ask for a [hint](HumanAgent::with_hint_engine), take back the last move or save and load the game.
+    /// # Arguments
+    /// * `board` - The board to play on
+    /// * `target` - What the number selects
+    /// * `valid` - The valid numbers
+    /// # Returns
+    /// The entered number or move, or an error if the input has ended
+    fn read_number(
+        &mut self,
+        board: UltimateBoard,
+        target: Target,
+        valid: &[u8],
+    ) -> Result<Selection, AgentError> {
+        loop {
+            self.say(Message::InputNumber(target))?;
+            self.say(Message::ValidNumbers(
+                target,
+                &valid.iter().sorted().join(", "),
+            ))?;
+            if self.hint_engine.is_some() {
+                self.say(Message::HintHelp)?;
+            }
+            if self.actions && self.turn >= 2 {
+                self.say(Message::UndoHelp)?;
+            }
+            if self.actions {
+                self.say(Message::SaveLoadHelp)?;
+            }
+            self.output.flush()?;
+
+            self.wait_for_input()?;
+            let mut input = String::new();
+
+            if self.input.read_line(&mut input)? == 0 {
+                return Err(AgentError::Io("The input has ended".to_string()));
+            }
+
+            if input.trim() == "undo" {
+                match self.undo() {
+                    Some(action) => return Ok(Selection::Action(action)),
+                    None => self.say(Message::NoUndo)?,
+                }
+                continue;
+            }
+
+            if let Some(path) = input.trim().strip_prefix("save ").filter(|_| self.actions) {
+                self.say(Message::Saving(path.trim()))?;
+                return Ok(Selection::Action(Action::Save(path.trim().to_string())));
+            }
+
+            if let Some(path) = input.trim().strip_prefix("load ").filter(|_| self.actions) {
+                self.say(Message::Loading(path.trim()))?;
+                return Ok(Selection::Action(Action::Load(path.trim().to_string())));
+            }
+
+            if input.trim() == "hint" {
+                let hint = self.get_hint(board);
+                writeln!(self.output, "{}", hint)?;
+                continue;
+            }
+
+            if let Some(index) = notation::parse_algebraic(&input) {
+                if board.is_legal_move(index) {
+                    return Ok(Selection::Action(Action::Move(index)));
+                }
+                self.say(Message::InvalidMove(index))?;
+                continue;
+            }
+
+            match input.trim().parse::<u8>() {
+                Ok(value) if valid.contains(&value) => return Ok(Selection::Number(value)),
+                Ok(value) if (1..10).contains(&value) => {
+                    self.say(Message::InvalidNumber(target))?
+                }
+                _ => self.say(Message::InvalidInput)?,
+            }
+        }
+    }
+}
+
+/// Prints the board with the given small board highlighted
+///
+/// The last move is marked and the small boards won by a player are shaded in the color of the player.
+///
+/// # Arguments
+/// * `output` - The output the board is printed to
+/// * `board` - The board
+/// * `highlighted_board` - The index of the small board to highlight, None to number the small boards instead
+/// * `last_move` - The human index of the move to mark, e.g. the last move of the opponent
+/// * `theme` - The theme the board is drawn with
+fn print_board(
+    output: &mut impl Write,
+    board: UltimateBoard,
+    highlighted_board: Option<u8>,
+    last_move: Option<u8>,
+    theme: &Theme,
+) -> std::io::Result<()> {
+    for row in 0..17 {
+        let big_row = if row < 6 {
+            0
+        } else if row < 12 {
+            1
+        } else {
+            2
+        };
+
+        if row == 0 || row == 4 || row == 6 || row == 10 || row == 12 || row == 16 {
+            let highlighted = highlighted_columns(highlighted_board, big_row);
+
+            // Print small board border
+            if (row == 0 || row == 6 || row == 12) && highlighted_board.is_none() {
+                write!(
+                    output,
+                    "{}{}",
+                    3 * big_row + 1,
+                    theme.border("              ", highlighted[0])
+                )?;
+                write!(output, "|")?;
+                write!(
+                    output,
+                    "{}{}",
+                    3 * big_row + 2,
+                    theme.border("              ", highlighted[1])
+                )?;
+                write!(output, "|")?;
+                write!(
+                    output,
+                    "{}{}",
+                    3 * big_row + 3,
+                    theme.border("              ", highlighted[2])
+                )?;
+            } else {
+                write!(
+                    output,
+                    "{}",
+                    theme.border("               ", highlighted[0])
+                )?;
+                write!(output, "|")?;
+                write!(
+                    output,
+                    "{}",
+                    theme.border("               ", highlighted[1])
+                )?;
+                write!(output, "|")?;
+                write!(
+                    output,
+                    "{}",
+                    theme.border("               ", highlighted[2])
+                )?;
+            }
+            writeln!(output)?
+        } else if row == 5 || row == 11 {
+            // Print board divider
+            writeln!(
+                output,
+                "{}",
+                theme.divider(" - - - - - - - + - - - - - - - + - - - - - - - ")
+            )?;
+        } else {
+            let sub_row = match row {
+                1 | 7 | 13 => 0,
+                2 | 8 | 14 => 1,
+                3 | 9 | 15 => 2,
+                _ => panic!("Invalid row"),
+            };
+            let highlighted = highlighted_columns(highlighted_board, big_row);
+
+            // Print board row
+            for i in (big_row * 3)..(big_row * 3 + 3) {
+                // Print Small board border
+                write!(
+                    output,
+                    "{}",
+                    theme.border("  ", highlighted[(i % 3) as usize])
+                )?;
+
+                let row = board.get_boards()[i as usize].extract_row(sub_row);
+                let winner = match board.get_board_status()[i as usize] {
+                    GameResult::Win(player) => Some(player),
+                    _ => None,
+                };
+
+                write!(
+                    output,
+                    "{}",
+                    row.iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            let square = 3 * sub_row + index as u8;
+                            let is_last_move = last_move == Some(i * 9 + square);
+
+                            match item {
+                                BoardSymbol::X => theme.square(Player::One, is_last_move),
+                                BoardSymbol::O => theme.square(Player::Two, is_last_move),
+                                BoardSymbol::Empty if highlighted_board == Some(i) => {
+                                    theme.background(&format!(" {} ", square + 1), false)
+                                }
+                                BoardSymbol::Empty => theme.board_background("   ", winner),
+                            }
+                        })
+                        .join(" ")
+                )?;
+
+                // Print Small board border
+                write!(
+                    output,
+                    "{}",
+                    theme.border("  ", highlighted[(i % 3) as usize])
+                )?;
+
+                if i % 3 != 2 {
+                    write!(output, "|")?;
+                }
+            }
+
+            writeln!(output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns which of the small boards in the given row is highlighted
+fn highlighted_columns(highlighted_board: Option<u8>, big_row: u8) -> [bool; 3] {
+    match highlighted_board {
+        Some(index) if big_row == index / 3 => [0, 1, 2].map(|column| index % 3 == column),
+        _ => [false; 3],
+    }
+}
+
+impl<R: PollInput + Send + Sync, W: Write + Send + Sync> Agent for HumanAgent<R, W> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        match self.play_turn(*board, player, turn, false)? {
+            Action::Move(index) => Ok(index),
+            action => unreachable!("{} can only be requested with choose_action", action),
+        }
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        self.play_turn(*board, player, turn, true)
+    }
+
+    fn action_failed(&mut self, _action: &Action, reason: &str) {
+        if let Err(error) = self.say(Message::ActionFailed(reason)) {
+            eprintln!("Could not show the failed request: {}", error);
+        }
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        if let Err(error) = self.say(Message::MoveReview(review)) {
+            eprintln!("Could not show the review: {}", error);
+        }
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new("Human".to_string(), self.player, self.turn, "".to_string())
+    }
+
+    fn opponent_moved(&mut self, _board: UltimateBoard, index: u8) {
+        self.opponent_moves.push(index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.player = player;
+        self.opponent_moves.clear();
+        self.last_analysis = None;
+        self.clock = None;
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        let message = match result {
+            GameResult::Win(player) if player == self.player => Message::GameWon,
+            GameResult::Win(_) => Message::GameLost,
+            GameResult::Draw => Message::GameDrawn,
+            GameResult::Continue => return,
+        };
+
+        self.clock = None;
+        if let Err(error) = self.show_board(board, None).and_then(|_| self.say(message)) {
+            eprintln!("Could not show the result: {}", error);
+        }
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.clock = Some((*clock, Instant::now()));
+    }
+}
+
+/// # Starts a game with a human player.
+///
+/// The human player can input the moves via the console.
+/// The agents are created with the [AgentRegistry], so a saved game can be resumed with [resume_game_with_human].
+pub fn start_game_with_human() {
+    let mut game = AgentRegistry::new()
+        //.create_game("human", "minimax:d=8,q=1,h=custom")
+        .create_game("human", "mcts:i=10000")
+        .unwrap();
+    print_board(
+        &mut std::io::stdout(),
+        *game.get_board(),
+        None,
+        None,
+        &Theme::from_env(),
+    )
+    .unwrap();
+    println!("Result: {:?}", game.play());
+}
+
+/// # Resumes a game with a human player saved with `save <file>`.
+///
+/// The agents are created from the specifications stored in the saved game, see [AgentRegistry::resume_game].
+/// # Arguments
+/// * `path` - The path of the saved game
+pub fn resume_game_with_human(path: &str) {
+    let record = GameRecord::load(path).unwrap();
+    let mut game = AgentRegistry::new().resume_game(record).unwrap();
+    println!("Result: {:?}", game.play());
+}
+
+/// # Starts a game with a human player in the terminal user interface.
+///
+/// The human player selects the squares with the arrow keys, see [HumanAgent::with_tui].
+/// If the terminal can not be switched to [raw mode](tui::RawMode), the moves are entered with the prompts instead.
+pub fn start_tui_game_with_human() {
+    let raw_mode = tui::RawMode::enable();
+
+    let agent = match &raw_mode {
+        Ok(_) => HumanAgent::new().with_tui(),
+        Err(error) => {
+            eprintln!(
+                "The terminal user interface is not available ({}), enter the moves with the prompts instead.",
+                error
+            );
+            HumanAgent::new()
+        }
+    };
+
+    let mut game = Game::new(Box::new(agent), Box::new(MonteCarloTreeAgent::new(10000)));
+    let result = game.play();
+
+    println!("{}", game.get_board());
+    println!("Result: {:?}", result);
+}
+
+/// # Starts a game with a human player and a time control.
+///
+/// Both clocks are displayed above the board, the player whose time runs out forfeits.
+/// # Arguments
+/// * `time_control` - The time control of the game
+pub fn start_timed_game_with_human(time_control: TimeControl) {
+    let mut game = Game::new(
+        Box::new(HumanAgent::new()),
+        Box::new(MonteCarloTreeAgent::new(10000)),
+    )
+    .with_time_control(time_control);
+    let result = game.play();
+
+    println!("{}", game.get_board());
+    println!("Clock: {}", game.get_clock().unwrap());
+    println!("Result: {:?}", result);
+}
+
+/// # Relays games between two human players over the network.
+///
+/// The players join with [join_network_game_with_human], the first player to join plays X.
+/// # Arguments
+/// * `address` - The address to listen on, e.g. `0.0.0.0:7878`
+pub fn host_network_games(address: &str) {
+    let relay = Relay::bind(address).unwrap();
+    println!("Relay listening on {}", relay.local_addr().unwrap());
+
+    loop {
+        match relay.play_game() {
+            Ok(result) => println!("Result: {:?}", result),
+            Err(error) => eprintln!("Game failed: {}", error),
+        }
+    }
+}
+
+/// # Joins a game against another human player hosted with [host_network_games].
+///
+/// The board is shown from the perspective of the player, who is told when it is their turn.
+/// # Arguments
+/// * `address` - The address of the relay
+pub fn join_network_game_with_human(address: &str) {
+    let stream = TcpStream::connect(address).unwrap();
+    println!("Waiting for your opponent to join...");
+
+    AgentServer::new(HumanAgent::new()).serve(stream).unwrap();
+}
+
+pub fn human_against_human() {
+    let mut game = Game::new(
+        Box::new(MiniMaxAgent::new(
+            10,
+            2,
+            ParameterizedHeuristic::new(
+                Player::One,
+                vec![
+                    -0.9011298820760223,
+                    -0.9047473011303433,
+                    -1.9878186210206341,
+                    -0.940735228598089,
+                    1.3140632491937836,
+                    0.5190040302978252,
+                    0.7128491119909083,
+                    1.2756963483965846,
+                    2.264309782234436,
+                    0.14115748887705593,
+                    1.2441779567914344,
+                    2.0944754371556287,
+                ],
+            ),
+        )),
+        Box::new(HumanAgent::new()),
+    );
+    print_board(
+        &mut std::io::stdout(),
+        *game.get_board(),
+        None,
+        None,
+        &Theme::from_env(),
+    )
+    .unwrap();
+    println!("Result: {:?}", game.play());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_input() {
+        let board = UltimateBoard::new();
+
+        // Invalid inputs are rejected until a valid field is entered
+        let mut agent = HumanAgent::from_io(Cursor::new("0\nx\n5\n1\n"), Vec::new());
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(36));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert_eq!(output.matches("Invalid input").count(), 2);
+
+        // The player is told when to wait and how the game ended
+        agent.game_over(board, GameResult::Win(Player::One));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("You play X."));
+        assert!(output.contains("Waiting for your opponent..."));
+        assert!(output.ends_with("You won the game!\n"));
+
+        // The prompts are printed in the language of the agent
+        let mut agent = HumanAgent::from_io(Cursor::new("x\n5\n1\n"), Vec::new())
+            .with_language(Language::German);
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(36));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Ungültige Eingabe."));
+        assert!(output.contains("Nur diese Felder sind gültig"));
+
+        let mut board = board;
+        board.make_move(36);
+        let mut agent = HumanAgent::from_io(Cursor::new("x\n2\n"), Vec::new());
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(1));
+
+        // A hint is printed before the move is entered
+        let mut agent = HumanAgent::from_io(Cursor::new("hint\n5\n1\n"), Vec::new())
+            .with_hint_engine(MiniMaxAgent::new(1, 0, CustomHeuristic::new(Player::One)));
+        assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(36));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Hint: play "));
+
+        // Moves can be entered in algebraic notation
+        let mut agent = HumanAgent::from_io(Cursor::new("b2/a1\na1/b1\n"), Vec::new());
+        agent.opponent_moved(board, 36);
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(1));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Your opponent played b2/a1."));
+        assert!(output.contains("Invalid move b2/a1."));
+
+        // Saving the game is requested from the game
+        let mut agent = HumanAgent::from_io(Cursor::new("save game.json\n"), Vec::new());
+        assert_eq!(
+            agent.choose_action(&board, Player::Two, 1),
+            Ok(Action::Save("game.json".to_string()))
+        );
+
+        // Moves can only be taken back after the first own move
+        let mut agent = HumanAgent::from_io(Cursor::new("undo\n"), Vec::new());
+        assert!(agent.choose_action(&board, Player::Two, 1).is_err());
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("There is no move to take back."));
+        let mut agent = HumanAgent::from_io(Cursor::new("undo\n"), Vec::new());
+        assert_eq!(
+            agent.choose_action(&board, Player::Two, 3),
+            Ok(Action::Undo)
+        );
+
+        // Moves are only taken back if the agent is asked for an action
+        let mut agent = HumanAgent::from_io(Cursor::new("undo\n2\n"), Vec::new());
+        assert_eq!(agent.act(&board, Player::Two, 3), Ok(1));
+
+        // The player is told why a request failed
+        agent.action_failed(&Action::Undo, "there is no move to take back");
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Your request failed: there is no move to take back."));
+
+        // The player is shown the review of a coach
+        agent.move_reviewed("Board 1 field 1 was a good move (0.00).");
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Coach: Board 1 field 1 was a good move (0.00)."));
+
+        let mut agent = HumanAgent::from_io(Cursor::new(""), Vec::new());
+        assert!(matches!(
+            agent.act(&board, Player::Two, 1),
+            Err(AgentError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_analysis() {
+        // X wins the game with 20 (c1/c1), 21 (c1/a2) is a blunder
+        let squares = format!(
+            "XXX......XXX......XX.......OO.......OO.......O........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap();
+
+        let mut agent = HumanAgent::from_io(Cursor::new("c1/a2\n"), Vec::new())
+            .with_analysis_engine(
+                MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One)),
+                0.25,
+            );
+        assert_eq!(agent.act(&board, Player::One, 10), Ok(21));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("from a win to 0.52, the best move was c1/c1. This is a blunder!"));
+    }
+
+    #[test]
+    fn test_hint() {
+        // X wins the game with 20 (c1/c1)
+        let squares = format!(
+            "XXX......XXX......XX.......OO.......OO.......O........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap();
+        let engine = || MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One));
+
+        // The hint does not replace the move of the player
+        let mut agent =
+            HumanAgent::from_io(Cursor::new("hint\n3\n"), Vec::new()).with_hint_engine(engine());
+        assert_eq!(agent.act(&board, Player::One, 10), Ok(20));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Type hint for a suggested move."));
+        assert!(output.contains("Hint: play c1/c1 (evaluation 1.00 at depth 2)."));
+
+        // Without a helper engine, the player is told that no hints are available
+        let mut agent = HumanAgent::from_io(Cursor::new("hint\n3\n"), Vec::new());
+        assert_eq!(agent.act(&board, Player::One, 10), Ok(20));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(!output.contains("Type hint for a suggested move."));
+        assert!(output.contains("No hint engine is available."));
+
+        // The terminal user interface shows the hint when ? is pressed
+        let mut agent = HumanAgent::from_io(Cursor::new("?\n"), Vec::new())
+            .with_tui()
+            .with_hint_engine(engine());
+        assert_eq!(agent.act(&board, Player::One, 10), Ok(20));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Press ? for a suggested move."));
+        assert!(output.contains("Hint: play c1/c1"));
+    }
+
+    #[test]
+    fn test_clock() {
+        let board = UltimateBoard::new();
+        let time_control = TimeControl::new(Duration::from_secs(300), Duration::ZERO);
+
+        // Both clocks are displayed above the board
+        let mut agent = HumanAgent::from_io(Cursor::new("5\n1\n"), Vec::new());
+        agent.clock_updated(&Clock::from_time_control(time_control));
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(36));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("Clock: X 5:00 | O 5:00"));
+
+        // The flag falls before the input is read
+        let mut agent = HumanAgent::from_io(Cursor::new("5\n1\n"), Vec::new());
+        agent.clock_updated(&Clock::from_time_control(TimeControl::new(
+            Duration::ZERO,
+            Duration::ZERO,
+        )));
+        assert_eq!(agent.act(&board, Player::One, 0), Err(AgentError::Timeout));
+    }
+
+    #[test]
+    fn test_tui() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        // The cursor starts on the first legal square 36, the square above it is on another board
+        let mut agent =
+            HumanAgent::from_io(Cursor::new("\x1b[A \x1b[B\x1b[C\n"), Vec::new()).with_tui();
+        agent.opponent_moved(board, 40);
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(37));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("You can not play on this square."));
+
+        let mut agent = HumanAgent::from_io(Cursor::new("uq"), Vec::new()).with_tui();
+        assert!(agent.act(&board, Player::Two, 1).is_err());
+
+        // The pointer moves the cursor, a click on an illegal square is rejected,
+        // a click on the bottom right square of the center board plays it
+        let input = Cursor::new("\x1b[<35;15;6M\x1b[<0;2;1M\x1b[<0;18;7M");
+        let mut agent = HumanAgent::from_io(input, Vec::new()).with_tui();
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(44));
+        let output = String::from_utf8(agent.get_output().clone()).unwrap();
+        assert!(output.contains("You can not play on this square."));
+        assert!(output.contains(tui::DISABLE_MOUSE));
+
+        // The clocks are displayed above the board, so the board starts one row lower
+        let input = Cursor::new("\x1b[<0;18;8M");
+        let mut agent = HumanAgent::from_io(input, Vec::new()).with_tui();
+        agent.clock_updated(&Clock::from_time_control(TimeControl::new(
+            Duration::from_secs(300),
+            Duration::ZERO,
+        )));
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(44));
+    }
+}
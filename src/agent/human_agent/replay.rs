@@ -0,0 +1,298 @@
+//! # Contains the [ReplayViewer] struct
+//!
+//! The ReplayViewer steps through the positions of a recorded [GameRecord] with the same board and [theme](super::theme) as the [HumanAgent](super::HumanAgent),
+//! e.g. to review interesting games of a tournament.
+//!
+//! The viewer is controlled with the keys of the [terminal user interface](super::tui):
+//! * Right or Enter steps one move forward, Left one move back
+//! * Up jumps to the start of the game, Down to the end
+//! * `q` quits the viewer
+//!
+//! If the moves were annotated by a [LoggingAgent](crate::agent::logging_agent::LoggingAgent),
+//! the stored evaluations are shown below the board, see [ReplayViewer::with_annotations].
+use std::io::{BufRead, Stdout, Write};
+
+use super::input::ThreadedInput;
+use super::print_board;
+use super::theme::Theme;
+use super::tui::{read_key, Key, RawMode};
+use crate::agent::agent_error::AgentError;
+use crate::agent::logging_agent::{read_annotations, Annotation};
+use crate::game::game_record::GameRecord;
+use crate::game::game_result::GameResult;
+use crate::game::notation;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// # Struct representing a viewer stepping through a recorded game
+///
+/// The viewer reads the keys from the input and prints the positions to the output, see [ReplayViewer::from_io].
+pub struct ReplayViewer<R = ThreadedInput, W = Stdout> {
+    input: R,
+    output: W,
+    record: GameRecord,
+    /// The board before the first move and after each move
+    boards: Vec<UltimateBoard>,
+    /// The name of the agent and its evaluation of each move
+    evaluations: Vec<Option<(String, f64)>>,
+    position: usize,
+    theme: Theme,
+}
+
+impl ReplayViewer {
+    /// Creates a new [ReplayViewer] reading the keys from the console
+    ///
+    /// # Arguments
+    /// * `record` - The recorded game
+    /// # Returns
+    /// The viewer, or [AgentError::IllegalMove] if the record contains an illegal move
+    pub fn new(record: GameRecord) -> Result<ReplayViewer, AgentError> {
+        ReplayViewer::from_io(record, ThreadedInput::stdin(), std::io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> ReplayViewer<R, W> {
+    /// Creates a new [ReplayViewer] reading the keys from the given input and printing to the given output
+    ///
+    /// The viewer starts at the position before the first move.
+    /// # Arguments
+    /// * `record` - The recorded game
+    /// * `input` - The input the keys are read from
+    /// * `output` - The output the positions are printed to
+    /// # Returns
+    /// The viewer, or [AgentError::IllegalMove] if the record contains an illegal move
+    pub fn from_io(record: GameRecord, input: R, output: W) -> Result<Self, AgentError> {
+        let boards = record.replay()?;
+
+        Ok(ReplayViewer {
+            input,
+            output,
+            evaluations: vec![None; record.get_moves().len()],
+            record,
+            boards,
+            position: 0,
+            theme: Theme::from_env(),
+        })
+    }
+
+    /// Sets the theme the board is drawn with
+    ///
+    /// By default the theme is chosen by the environment, see [Theme::from_env].
+    /// # Arguments
+    /// * `theme` - The theme
+    /// # Returns
+    /// The viewer using the theme
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Shows the evaluations of the annotated moves
+    ///
+    /// An annotation belongs to a move if it was made in the same position with the same move.
+    /// Annotations of other games are ignored if the game id was logged.
+    /// # Arguments
+    /// * `annotations` - The annotations, e.g. read with [read_annotations]
+    /// # Returns
+    /// The viewer with the evaluations
+    pub fn with_annotations(mut self, annotations: &[Annotation]) -> Self {
+        let game_id = self.record.get_metadata().game_id;
+
+        for (turn, &index) in self.record.get_moves().iter().enumerate() {
+            let position = self.boards[turn].get_position();
+
+            self.evaluations[turn] = annotations
+                .iter()
+                .filter(|annotation| annotation.game_id.is_none_or(|id| id == game_id))
+                .find(|annotation| {
+                    annotation.chosen_move == Some(index) && annotation.position == position
+                })
+                .and_then(|annotation| {
+                    annotation
+                        .evaluation
+                        .map(|evaluation| (annotation.name.clone(), evaluation))
+                });
+        }
+
+        self
+    }
+
+    /// Get the number of moves played to reach the current position
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the board of the current position
+    pub fn get_board(&self) -> UltimateBoard {
+        self.boards[self.position]
+    }
+
+    /// Shows the positions until the viewer is quit or the input ends
+    pub fn run(&mut self) -> Result<(), AgentError> {
+        loop {
+            self.print_position()?;
+
+            let last = self.boards.len() - 1;
+            self.position = match read_key(&mut self.input)? {
+                None | Some(Key::Quit) => return Ok(()),
+                Some(Key::Right | Key::Select) => (self.position + 1).min(last),
+                Some(Key::Left) => self.position.saturating_sub(1),
+                Some(Key::Up) => 0,
+                Some(Key::Down) => last,
+                Some(_) => continue,
+            };
+        }
+    }
+
+    /// Prints the current position with the last move and its evaluation
+    fn print_position(&mut self) -> Result<(), AgentError> {
+        let metadata = self.record.get_metadata();
+        let board = self.boards[self.position];
+
+        write!(self.output, "{esc}[2J{esc}[1;1H", esc = 27 as char)?;
+        writeln!(
+            self.output,
+            "Game {}: {} (X) vs {} (O)",
+            metadata.game_id, metadata.agent_names[0], metadata.agent_names[1]
+        )?;
+        writeln!(
+            self.output,
+            "Move {} of {}",
+            self.position,
+            self.record.get_moves().len()
+        )?;
+
+        if self.position > 0 {
+            let player = match self.boards[self.position - 1].get_current_player() {
+                Player::One => "X",
+                Player::Two => "O",
+            };
+            let index = self.record.get_moves()[self.position - 1];
+            write!(
+                self.output,
+                "{} played {}",
+                player,
+                notation::to_algebraic(index)
+            )?;
+            match &self.evaluations[self.position - 1] {
+                Some((name, evaluation)) => {
+                    writeln!(self.output, " (evaluation of {}: {:.2})", name, evaluation)?
+                }
+                None => writeln!(self.output)?,
+            }
+        }
+
+        print_board(
+            &mut self.output,
+            board,
+            board.get_next_board_index(),
+            self.position
+                .checked_sub(1)
+                .map(|turn| self.record.get_moves()[turn]),
+            &self.theme,
+        )?;
+
+        if self.position == self.boards.len() - 1 {
+            self.print_result()?;
+        }
+        writeln!(
+            self.output,
+            "Right/Enter: next move, Left: previous move, Up: start, Down: end, q: quit"
+        )?;
+
+        Ok(())
+    }
+
+    /// Prints the result of the game
+    fn print_result(&mut self) -> Result<(), AgentError> {
+        match self.record.get_result() {
+            GameResult::Win(Player::One) => write!(self.output, "X won")?,
+            GameResult::Win(Player::Two) => write!(self.output, "O won")?,
+            GameResult::Draw => write!(self.output, "The game is a draw")?,
+            GameResult::Continue => write!(self.output, "The game was not finished")?,
+        }
+        match self.record.get_forfeit() {
+            Some(forfeit) => writeln!(self.output, " by forfeit: {}.", forfeit.reason)?,
+            None => writeln!(self.output, ".")?,
+        }
+
+        Ok(())
+    }
+}
+
+/// # Replays a recorded game in the console
+///
+/// # Arguments
+/// * `path` - The path of the game record, see [GameRecord::save]
+/// * `annotations` - The path of the annotations of the moves, see [read_annotations]
+pub fn replay_game(path: &str, annotations: Option<&str>) {
+    let record = GameRecord::load(path).unwrap();
+    let mut viewer = ReplayViewer::new(record).unwrap();
+    if let Some(annotations) = annotations {
+        viewer = viewer.with_annotations(&read_annotations(annotations).unwrap());
+    }
+
+    // Without raw mode the keys are only read after a line break
+    let _raw_mode = RawMode::enable().inspect_err(|error| {
+        eprintln!(
+            "The keys can not be read directly ({}), press Enter to show the next move and q and Enter to quit.",
+            error
+        )
+    });
+    viewer.run().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_metadata::GameMetadata;
+    use std::io::Cursor;
+
+    fn board_after(moves: &[u8]) -> UltimateBoard {
+        let mut board = UltimateBoard::new();
+        for &index in moves {
+            board.make_move(index);
+        }
+        board
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut record = GameRecord::new(GameMetadata::with_id(7));
+        for index in [40, 41, 49] {
+            record.push_move(index);
+        }
+        let annotation = Annotation {
+            game_id: Some(7),
+            name: "MiniMax".to_string(),
+            player: Player::Two,
+            turn: 1,
+            position: board_after(&[40]).get_position(),
+            legal_moves: 8,
+            chosen_move: Some(41),
+            evaluation: Some(1.5),
+            nodes: None,
+            max_depth: None,
+            visits: None,
+            win_rate: None,
+            principal_variation: None,
+        };
+
+        // Forward twice, back once, forward, then to the end and back to the start
+        let input = Cursor::new(b"dd\x1b[Dl\x1b[Bw".to_vec());
+        let mut viewer = ReplayViewer::from_io(record.clone(), input, Vec::new())
+            .unwrap()
+            .with_theme(Theme::Ascii)
+            .with_annotations(&[annotation]);
+        viewer.run().unwrap();
+        assert_eq!(viewer.get_position(), 0);
+
+        let output = String::from_utf8(viewer.output).unwrap();
+        assert!(output.contains("Move 2 of 3"));
+        assert!(output.contains("O played b2/c2 (evaluation of MiniMax: 1.50)"));
+        assert!(output.contains("The game was not finished."));
+
+        record.push_move(0);
+        assert!(ReplayViewer::from_io(record, Cursor::new(vec![]), Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! # Contains the [Theme] enum
+//! The Theme enum defines how the [HumanAgent](super::HumanAgent) and the [ReplayViewer](super::replay::ReplayViewer) draw the board.
+//!
+//! The dark theme is the default, the light theme is readable on terminals with a light background
+//! and the ASCII theme prints no colors at all, e.g. for terminals without truecolor support.
+//! In the ASCII theme the highlighted board is framed with `#` instead of a colored border,
+//! the empty squares of won boards are marked with `~` instead of being shaded in the color of the winner
+//! and the last move is enclosed in brackets, or printed in lower case in the [terminal user interface](super::tui).
+//! The small board a move would send the opponent to is filled with `:` in the terminal user interface.
+//!
+//! The theme can be chosen with the `UTTT_THEME` environment variable, see [Theme::from_env],
+//! or passed to [HumanAgent::with_theme](super::HumanAgent::with_theme).
+use colored::{ColoredString, Colorize, CustomColor};
+
+use crate::game::player::Player;
+
+/// # Struct representing the colors of a colored [Theme]
+///
+/// # Fields
+/// * `highlight` - The border of the highlighted board and the background of the legal squares
+/// * `background` - The background of the board
+/// * `x` - The color of the squares of [Player::One]
+/// * `o` - The color of the squares of [Player::Two]
+/// * `x_board` - The shaded background of the small boards won by [Player::One]
+/// * `o_board` - The shaded background of the small boards won by [Player::Two]
+/// * `target` - The background of the small board the selected move sends the opponent to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub highlight: CustomColor,
+    pub background: CustomColor,
+    pub x: CustomColor,
+    pub o: CustomColor,
+    pub x_board: CustomColor,
+    pub o_board: CustomColor,
+    pub target: CustomColor,
+}
+
+/// # Enum representing the appearance of the board in the console
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    /// Draws the board with the true colors of the palette
+    Colored(Palette),
+    /// Draws the board with plain characters only
+    Ascii,
+}
+
+impl Theme {
+    /// The default theme for terminals with a dark background
+    pub fn dark() -> Theme {
+        Theme::Colored(Palette {
+            highlight: CustomColor::new(87, 46, 105),
+            background: CustomColor::new(30, 31, 34),
+            x: CustomColor::new(154, 46, 34),
+            o: CustomColor::new(18, 128, 106),
+            x_board: CustomColor::new(64, 34, 33),
+            o_board: CustomColor::new(22, 56, 50),
+            target: CustomColor::new(42, 60, 96),
+        })
+    }
+
+    /// A theme for terminals with a light background
+    pub fn light() -> Theme {
+        Theme::Colored(Palette {
+            highlight: CustomColor::new(196, 160, 224),
+            background: CustomColor::new(236, 236, 236),
+            x: CustomColor::new(238, 140, 128),
+            o: CustomColor::new(128, 208, 190),
+            x_board: CustomColor::new(247, 214, 209),
+            o_board: CustomColor::new(206, 238, 230),
+            target: CustomColor::new(196, 216, 244),
+        })
+    }
+
+    /// Returns the theme with the given name
+    ///
+    /// # Arguments
+    /// * `name` - `dark`, `light` or `ascii`, the case is ignored
+    /// # Returns
+    /// The theme, None if the name is unknown
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name.trim().to_lowercase().as_str() {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "ascii" => Some(Theme::Ascii),
+            _ => None,
+        }
+    }
+
+    /// Returns the theme chosen by the environment
+    ///
+    /// The theme is read from the `UTTT_THEME` variable, see [Theme::from_name].
+    /// If it is not set, the ASCII theme is used if `NO_COLOR` is set and the dark theme otherwise.
+    pub fn from_env() -> Theme {
+        if let Some(theme) = std::env::var("UTTT_THEME")
+            .ok()
+            .and_then(|name| Theme::from_name(&name))
+        {
+            return theme;
+        }
+
+        match std::env::var_os("NO_COLOR") {
+            Some(value) if !value.is_empty() => Theme::Ascii,
+            _ => Theme::dark(),
+        }
+    }
+
+    /// Draws a part of the border of a small board
+    ///
+    /// # Arguments
+    /// * `text` - The text of the border, usually spaces
+    /// * `highlighted` - Whether the small board is highlighted
+    pub fn border(&self, text: &str, highlighted: bool) -> ColoredString {
+        match self {
+            Theme::Colored(_) => self.background(text, highlighted),
+            Theme::Ascii if highlighted => text.replace(' ', "#").normal(),
+            Theme::Ascii => text.normal(),
+        }
+    }
+
+    /// Draws text on the background of the board
+    ///
+    /// # Arguments
+    /// * `text` - The text
+    /// * `highlighted` - Whether the text is drawn on the highlight color, e.g. a legal square
+    pub fn background(&self, text: &str, highlighted: bool) -> ColoredString {
+        match self {
+            Theme::Colored(palette) if highlighted => text.on_custom_color(palette.highlight),
+            Theme::Colored(palette) => text.on_custom_color(palette.background),
+            Theme::Ascii => text.normal(),
+        }
+    }
+
+    /// Draws text on the background of a small board, shaded in the color of the winner if the board is won
+    ///
+    /// # Arguments
+    /// * `text` - The text
+    /// * `winner` - The player who won the small board, if any
+    pub fn board_background(&self, text: &str, winner: Option<Player>) -> ColoredString {
+        match (self, winner) {
+            (Theme::Colored(palette), Some(Player::One)) => text.on_custom_color(palette.x_board),
+            (Theme::Colored(palette), Some(Player::Two)) => text.on_custom_color(palette.o_board),
+            (Theme::Ascii, Some(_)) if text == "   " => " ~ ".normal(),
+            _ => self.background(text, false),
+        }
+    }
+
+    /// Draws a square of the small board the selected move sends the opponent to
+    ///
+    /// # Arguments
+    /// * `text` - The text of the square
+    pub fn target(&self, text: &str) -> ColoredString {
+        match self {
+            Theme::Colored(palette) => text.on_custom_color(palette.target),
+            Theme::Ascii => text.replace(' ', ":").normal(),
+        }
+    }
+
+    /// Draws a square of a player in the board printed by the [HumanAgent](super::HumanAgent)
+    ///
+    /// # Arguments
+    /// * `player` - The player owning the square
+    /// * `last_move` - Whether the square is the last move, which is underlined or enclosed in brackets
+    pub fn square(&self, player: Player, last_move: bool) -> ColoredString {
+        let text = match (player, last_move, self) {
+            (Player::One, true, Theme::Ascii) => "[X]",
+            (Player::Two, true, Theme::Ascii) => "[O]",
+            (Player::One, _, _) => " X ",
+            (Player::Two, _, _) => " O ",
+        };
+
+        match self {
+            Theme::Colored(palette) => {
+                let square = text.on_custom_color(self.player_color(palette, player));
+                if last_move {
+                    square.bold().underline()
+                } else {
+                    square
+                }
+            }
+            Theme::Ascii => text.normal(),
+        }
+    }
+
+    /// Draws the symbol of a player in the [terminal user interface](super::tui)
+    ///
+    /// # Arguments
+    /// * `player` - The player owning the square
+    /// * `last_move` - Whether the square is the last move, which is underlined or printed in lower case
+    pub fn symbol(&self, player: Player, last_move: bool) -> ColoredString {
+        let text = match player {
+            Player::One => "X",
+            Player::Two => "O",
+        };
+
+        match self {
+            Theme::Colored(palette) => {
+                let symbol = text.custom_color(self.player_color(palette, player)).bold();
+                if last_move {
+                    symbol.underline()
+                } else {
+                    symbol
+                }
+            }
+            Theme::Ascii if last_move => text.to_lowercase().normal(),
+            Theme::Ascii => text.normal(),
+        }
+    }
+
+    /// Returns the marker of an empty square the player can play on in the [terminal user interface](super::tui)
+    pub fn legal_square(&self) -> &'static str {
+        match self {
+            Theme::Colored(_) => "·",
+            Theme::Ascii => ".",
+        }
+    }
+
+    /// Draws a divider between the small boards
+    ///
+    /// # Arguments
+    /// * `text` - The divider
+    pub fn divider(&self, text: &str) -> ColoredString {
+        match self {
+            Theme::Colored(_) => text.bold(),
+            Theme::Ascii => text.normal(),
+        }
+    }
+
+    fn player_color(&self, palette: &Palette, player: Player) -> CustomColor {
+        match player {
+            Player::One => palette.x,
+            Player::Two => palette.o,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::from_env()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::human_agent::print_board;
+    use crate::game::ultimate_board::UltimateBoard;
+
+    #[test]
+    fn test_ascii_theme() {
+        assert_eq!(Theme::from_name("Light"), Some(Theme::light()));
+        assert_eq!(Theme::from_name("ascii"), Some(Theme::Ascii));
+        assert_eq!(Theme::from_name("solarized"), None);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let mut output = Vec::new();
+        print_board(
+            &mut output,
+            board,
+            board.get_next_board_index(),
+            Some(40),
+            &Theme::Ascii,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        // The highlighted center board is framed with '#', nothing is colored
+        assert!(!output.contains('\x1b'));
+        assert!(output.contains("##|"));
+        assert!(output.contains("[X]"));
+        assert_eq!(output.matches("###############").count(), 2);
+        assert!(!output.contains(" ~ "));
+
+        // The empty squares of the won top left board are marked
+        let squares = format!("XXX......{}", ".".repeat(72));
+        let board = UltimateBoard::from_position(&format!("{} 4 O", squares)).unwrap();
+        let mut output = Vec::new();
+        print_board(&mut output, board, None, None, &Theme::Ascii).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches(" ~ ").count(), 6);
+    }
+
+    #[test]
+    fn test_colored_theme() {
+        let theme = Theme::dark();
+        let Theme::Colored(palette) = theme else {
+            panic!();
+        };
+
+        // The last move is underlined
+        assert_eq!(
+            theme.square(Player::One, false),
+            " X ".on_custom_color(palette.x)
+        );
+        assert_eq!(
+            theme.square(Player::Two, true),
+            " O ".on_custom_color(palette.o).bold().underline()
+        );
+        assert_eq!(
+            theme.symbol(Player::One, true),
+            "X".custom_color(palette.x).bold().underline()
+        );
+
+        // Won boards are shaded in the color of the winner
+        assert_eq!(
+            theme.board_background("   ", Some(Player::One)),
+            "   ".on_custom_color(palette.x_board)
+        );
+        assert_eq!(
+            theme.board_background("   ", Some(Player::Two)),
+            "   ".on_custom_color(palette.o_board)
+        );
+        assert_eq!(
+            theme.board_background("   ", None),
+            theme.background("   ", false)
+        );
+    }
+}
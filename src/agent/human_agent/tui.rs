@@ -0,0 +1,399 @@
+//! # Contains the terminal user interface of the [HumanAgent](super::HumanAgent)
+//!
+//! In the terminal user interface the human player moves a cursor over the squares of the ultimate board with the arrow keys
+//! and plays the selected square with Enter or Space.
+//! The squares the player can play on are highlighted and the last move of the opponent is marked.
+//!
+//! The squares can also be played by clicking them. While the pointer or the cursor is on a legal square,
+//! the small board the move would send the opponent to is highlighted.
+//! The mouse is reported by the terminal after [ENABLE_MOUSE] is printed, see [Key::Click] and [Key::Hover].
+//!
+//! The keys are read from the input of the agent, so the terminal has to pass every key press without waiting for a line break,
+//! see [RawMode].
+//!
+//! The interface deliberately does not use ratatui: the board is rendered with ANSI escape sequences to the output of the agent
+//! and the raw mode is switched with `stty`. This keeps the crate free of ratatui and its terminal backend crossterm,
+//! and the interface can be driven and tested with any input and output like the prompts, see [HumanAgent::from_io](super::HumanAgent::from_io).
+//! The price is that the raw mode only works on Unix terminals, elsewhere the agent falls back to the prompts.
+use std::io::{BufRead, Write};
+use std::process::{Command, Stdio};
+
+use super::theme::Theme;
+use crate::game::game_result::GameResult;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// # Enum representing the keys of the terminal user interface
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Enter or Space, plays the selected square
+    Select,
+    /// `q`, quits the game
+    Quit,
+    /// `?`, asks for a hint
+    Hint,
+    /// `u`, takes back the last move
+    Undo,
+    /// A click with the left mouse button on the terminal cell in the given column and row, counted from 0
+    Click {
+        column: u16,
+        row: u16,
+    },
+    /// The mouse pointer moved to the terminal cell in the given column and row, counted from 0
+    Hover {
+        column: u16,
+        row: u16,
+    },
+    /// Any other key
+    Other,
+}
+
+/// Makes the terminal report clicks and movements of the mouse in the SGR format
+pub const ENABLE_MOUSE: &str = "\x1b[?1003h\x1b[?1006h";
+
+/// Stops the reports of the mouse enabled with [ENABLE_MOUSE]
+pub const DISABLE_MOUSE: &str = "\x1b[?1003l\x1b[?1006l";
+
+/// Reads the next key from the input
+///
+/// The arrow keys are read from their ANSI escape sequences, the letters `w`, `a`, `s`, `d` and `h`, `j`, `k`, `l` are also accepted.
+/// # Arguments
+/// * `input` - The input to read from
+/// # Returns
+/// The key, None if the input has ended
+pub fn read_key(input: &mut impl BufRead) -> std::io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(match byte {
+        // Escape sequences of the arrow keys: ESC [ A-D
+        0x1b => match (read_byte(input)?, read_byte(input)?) {
+            (Some(b'['), Some(b'<')) => read_mouse(input)?,
+            (Some(b'['), Some(b'A')) => Key::Up,
+            (Some(b'['), Some(b'B')) => Key::Down,
+            (Some(b'['), Some(b'C')) => Key::Right,
+            (Some(b'['), Some(b'D')) => Key::Left,
+            _ => Key::Other,
+        },
+        b'w' | b'k' => Key::Up,
+        b's' | b'j' => Key::Down,
+        b'a' | b'h' => Key::Left,
+        b'd' | b'l' => Key::Right,
+        b'\n' | b'\r' | b' ' => Key::Select,
+        b'q' => Key::Quit,
+        b'?' => Key::Hint,
+        b'u' => Key::Undo,
+        _ => Key::Other,
+    }))
+}
+
+/// Reads the rest of an SGR mouse report `ESC [ < button ; column ; row M`, `m` instead of `M` for a release
+fn read_mouse(input: &mut impl BufRead) -> std::io::Result<Key> {
+    let mut report = String::new();
+    let end = loop {
+        match read_byte(input)? {
+            Some(byte @ (b'M' | b'm')) => break byte,
+            Some(byte) if report.len() < 16 => report.push(byte as char),
+            _ => return Ok(Key::Other),
+        }
+    };
+
+    let numbers: Vec<u16> = report.split(';').filter_map(|n| n.parse().ok()).collect();
+    let [button, column, row] = numbers[..] else {
+        return Ok(Key::Other);
+    };
+    let (column, row) = (column.saturating_sub(1), row.saturating_sub(1));
+
+    // Bit 5 marks a movement, the lower bits the button, 3 is no button
+    Ok(match (button & 32 != 0, button & 3, end) {
+        (true, _, _) => Key::Hover { column, row },
+        (false, 0, b'M') => Key::Click { column, row },
+        _ => Key::Other,
+    })
+}
+
+fn read_byte(input: &mut impl BufRead) -> std::io::Result<Option<u8>> {
+    let mut byte = [0];
+
+    match input.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+/// Converts a human index into the row and column of the 9x9 grid of squares
+pub fn index_to_grid(index: u8) -> (u8, u8) {
+    let (board, square) = (index / 9, index % 9);
+    ((board / 3) * 3 + square / 3, (board % 3) * 3 + square % 3)
+}
+
+/// Converts the row and column of the 9x9 grid of squares into a human index
+pub fn grid_to_index(row: u8, column: u8) -> u8 {
+    ((row / 3) * 3 + column / 3) * 9 + (row % 3) * 3 + column % 3
+}
+
+/// Returns the square drawn at the given position by [render]
+///
+/// # Arguments
+/// * `column` - The column relative to the first column of the board
+/// * `row` - The row relative to the first row of the board
+/// # Returns
+/// The human index of the square, None if the position is on a divider or outside of the board
+pub fn square_at(column: u16, row: u16) -> Option<u8> {
+    // Every small board is 3 squares of 3 characters wide followed by a divider,
+    // and 3 rows high followed by a divider
+    let (big_column, small_column) = (column / 10, column % 10);
+    let (big_row, small_row) = (row / 4, row % 4);
+
+    if big_column > 2 || big_row > 2 || small_column == 9 || small_row == 3 {
+        return None;
+    }
+
+    Some(grid_to_index(
+        (big_row * 3 + small_row) as u8,
+        (big_column * 3 + small_column / 3) as u8,
+    ))
+}
+
+/// Moves the cursor one square in the direction of the key
+///
+/// The cursor stays at the edge of the board.
+/// # Arguments
+/// * `cursor` - The human index of the square under the cursor
+/// * `key` - The pressed key
+/// # Returns
+/// The human index of the square under the moved cursor
+pub fn move_cursor(cursor: u8, key: Key) -> u8 {
+    let (row, column) = index_to_grid(cursor);
+
+    match key {
+        Key::Up => grid_to_index(row.saturating_sub(1), column),
+        Key::Down => grid_to_index((row + 1).min(8), column),
+        Key::Left => grid_to_index(row, column.saturating_sub(1)),
+        Key::Right => grid_to_index(row, (column + 1).min(8)),
+        _ => cursor,
+    }
+}
+
+/// Renders the board with the cursor, the legal squares and the last move
+///
+/// The square under the cursor is enclosed in brackets, the legal squares are highlighted
+/// and the symbol of the last move is underlined. The small boards won by a player are shaded.
+/// If the cursor is on a legal square, the small board the move sends the opponent to is highlighted as well,
+/// unless the opponent could play on any board after the move.
+/// # Arguments
+/// * `output` - The output to render to
+/// * `board` - The board
+/// * `cursor` - The human index of the square under the cursor
+/// * `last_move` - The human index of the last move, if any
+/// * `theme` - The theme the board is drawn with
+pub fn render(
+    output: &mut impl Write,
+    board: UltimateBoard,
+    cursor: u8,
+    last_move: Option<u8>,
+    theme: &Theme,
+) -> std::io::Result<()> {
+    let target_board = if board.is_legal_move(cursor) {
+        let mut next = board;
+        next.make_move(cursor);
+        next.get_next_board_index()
+    } else {
+        None
+    };
+
+    for row in 0..9 {
+        if row == 3 || row == 6 {
+            writeln!(
+                output,
+                "{}",
+                theme.divider("-----------+-----------+-----------")
+            )?;
+        }
+
+        for column in 0..9 {
+            if column == 3 || column == 6 {
+                write!(output, "|")?;
+            }
+
+            let index = grid_to_index(row, column);
+            let symbol = match board.get_boards()[(index / 9) as usize].get_square(index % 9) {
+                Some(player) => theme.symbol(player, last_move == Some(index)).to_string(),
+                None if board.is_legal_move(index) => theme.legal_square().to_string(),
+                None => " ".to_string(),
+            };
+
+            let (open, close) = if index == cursor {
+                ("[", "]")
+            } else {
+                (" ", " ")
+            };
+            let cell = format!("{}{}{}", open, symbol, close);
+
+            if board.is_legal_move(index) {
+                write!(output, "{}", theme.background(&cell, true))?;
+            } else if target_board == Some(index / 9) {
+                write!(output, "{}", theme.target(&cell))?;
+            } else {
+                let winner = match board.get_board_status()[(index / 9) as usize] {
+                    GameResult::Win(player) => Some(player),
+                    _ => None,
+                };
+                write!(output, "{}", theme.board_background(&cell, winner))?;
+            }
+        }
+
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
+
+/// # Guard switching the terminal into raw mode
+///
+/// In raw mode every key press is passed to the program immediately and not echoed.
+/// The previous mode is restored when the guard is dropped.
+///
+/// The mode is changed with `stty` instead of a terminal library like crossterm, which is not a dependency of the crate,
+/// so it only works on Unix terminals. If `stty` is missing or the standard input is no terminal,
+/// [RawMode::enable] fails and the callers fall back to reading whole lines.
+pub struct RawMode;
+
+impl RawMode {
+    /// Switches the terminal of the standard input into raw mode
+    ///
+    /// # Returns
+    /// The guard restoring the previous mode, an error if `stty` could not switch the mode
+    pub fn enable() -> std::io::Result<RawMode> {
+        RawMode::stty(&["-icanon", "-echo"])?;
+        Ok(RawMode)
+    }
+
+    fn stty(arguments: &[&str]) -> std::io::Result<()> {
+        let status = Command::new("stty")
+            .args(arguments)
+            .stdin(Stdio::inherit())
+            .status()?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(format!(
+                "stty {} failed with {}",
+                arguments.join(" "),
+                status
+            )))
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Err(error) = RawMode::stty(&["icanon", "echo"]) {
+            eprintln!("Could not restore the terminal mode: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_keys() {
+        let mut input = Cursor::new("\x1b[A\x1b[Bd q\x1b[Z");
+        let keys: Vec<Option<Key>> = (0..7).map(|_| read_key(&mut input).unwrap()).collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                Some(Key::Up),
+                Some(Key::Down),
+                Some(Key::Right),
+                Some(Key::Select),
+                Some(Key::Quit),
+                Some(Key::Other),
+                None
+            ]
+        );
+
+        for index in 0..81 {
+            let (row, column) = index_to_grid(index);
+            assert_eq!(grid_to_index(row, column), index);
+        }
+
+        assert_eq!(move_cursor(0, Key::Right), 1);
+        assert_eq!(move_cursor(2, Key::Right), 9);
+        assert_eq!(move_cursor(0, Key::Up), 0);
+        assert_eq!(move_cursor(8, Key::Down), 29);
+
+        // SGR mouse reports: a left click, a movement without a button and a release
+        let mut input = Cursor::new("\x1b[<0;5;2M\x1b[<35;12;9M\x1b[<0;5;2m");
+        assert_eq!(
+            read_key(&mut input).unwrap(),
+            Some(Key::Click { column: 4, row: 1 })
+        );
+        assert_eq!(
+            read_key(&mut input).unwrap(),
+            Some(Key::Hover { column: 11, row: 8 })
+        );
+        assert_eq!(read_key(&mut input).unwrap(), Some(Key::Other));
+
+        assert_eq!(square_at(4, 1), Some(4));
+        assert_eq!(square_at(11, 8), Some(63));
+        assert_eq!(square_at(9, 0), None);
+        assert_eq!(square_at(0, 3), None);
+        assert_eq!(square_at(30, 0), None);
+    }
+
+    #[test]
+    fn test_render() {
+        // X won the top left board and played the last move on the center of the center board
+        let squares = format!("XXX......{}O...X....{}", ".".repeat(27), ".".repeat(36));
+        let board = UltimateBoard::from_position(&format!("{} 4 O", squares)).unwrap();
+
+        let mut output = Vec::new();
+        render(&mut output, board, 36, Some(40), &Theme::Ascii).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let rows: Vec<&str> = output.lines().collect();
+
+        // The last move is printed in lower case, the other squares in upper case, the dividers are rows as well
+        assert!(rows[5].contains(" x "));
+        assert!(rows[0].contains(" X "));
+        assert!(rows[4].contains("[O]"));
+
+        // The empty squares of the won board are marked, the squares of the other boards are not
+        assert_eq!(rows[1].matches(" ~ ").count(), 3);
+        assert!(!rows[6].contains('~'));
+    }
+
+    #[test]
+    fn test_target_board() {
+        let squares = format!("XXX......{}O...X....{}", ".".repeat(27), ".".repeat(36));
+        let board = UltimateBoard::from_position(&format!("{} 4 O", squares)).unwrap();
+
+        let render_rows = |cursor: u8| {
+            let mut output = Vec::new();
+            render(&mut output, board, cursor, None, &Theme::Ascii).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        // The move on the top center square of the center board sends the opponent to the top center board
+        let output = render_rows(37);
+        let rows: Vec<&str> = output.lines().collect();
+        assert_eq!(rows[0].matches(":::").count(), 3);
+        assert!(rows[0].starts_with(" X "));
+        assert!(!rows[4].contains(':'));
+
+        // The opponent may play on any board after a move to the won top left board
+        assert!(!render_rows(36).contains(':'));
+
+        // An illegal square sends the opponent nowhere
+        assert!(!render_rows(0).contains(':'));
+    }
+}
@@ -0,0 +1,197 @@
+//! # Contains the [HybridAgent] struct
+//!
+//! The HybridAgent struct represents an [Agent] that delegates to different agents depending on the phase of the game,
+//! e.g. a fast agent in the opening, a Monte Carlo Tree Search in the middlegame and an exact solver in the endgame.
+//!
+//! For more information see the [HybridAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// # Enum representing the start of a phase of the game
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhaseStart {
+    /// The phase starts at the given turn, turns are counted for both players starting at 0
+    Turn(u32),
+    /// The phase starts once at most the given number of squares can be played, see [UltimateBoard::count_empty_squares]
+    EmptySquares(u32),
+}
+
+impl PhaseStart {
+    /// Checks whether the phase has started
+    ///
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `turn` - The current turn
+    /// # Returns
+    /// True if the phase has started
+    fn has_started(&self, board: UltimateBoard, turn: u32) -> bool {
+        match *self {
+            PhaseStart::Turn(start) => turn >= start,
+            PhaseStart::EmptySquares(empty_squares) => board.count_empty_squares() <= empty_squares,
+        }
+    }
+}
+
+/// An agent that delegates to different agents depending on the phase of the game
+///
+/// The first agent plays until another phase starts.
+/// Every move is made by the agent of the last added phase that has started,
+/// so the phases should be added in the order they occur in the game.
+/// All agents are notified about the moves of the opponent, so they can keep their state up to date.
+///
+/// The [RandomStartAgent](crate::agent::random_start::RandomStartAgent) is a hybrid agent with random moves or book moves in the opening.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use hausarbeit::agent::hybrid_agent::{HybridAgent, PhaseStart};
+/// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::random_agent::RandomAgent;
+/// use hausarbeit::agent::tablebase_agent::TablebaseAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::game::tablebase::Tablebase;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let tablebase = Arc::new(Tablebase::new(12));
+///
+/// let agent = HybridAgent::new(RandomAgent::new())
+///     .with_phase(PhaseStart::Turn(2), MonteCarloTreeAgent::new(1000))
+///     .with_phase(
+///         PhaseStart::EmptySquares(12),
+///         TablebaseAgent::new(tablebase, MiniMaxAgent::new(4, 1, CustomHeuristic::new(Player::One))),
+///     );
+/// ```
+pub struct HybridAgent {
+    agents: Vec<Box<dyn Agent>>,
+    phases: Vec<PhaseStart>,
+    active: usize,
+}
+
+impl HybridAgent {
+    /// Creates a new [HybridAgent]
+    ///
+    /// # Arguments
+    /// * `agent` - The agent playing until another phase starts
+    pub fn new(agent: impl Agent + 'static) -> HybridAgent {
+        HybridAgent {
+            agents: vec![Box::new(agent)],
+            phases: vec![],
+            active: 0,
+        }
+    }
+
+    /// Adds a phase of the game
+    ///
+    /// # Arguments
+    /// * `start` - The start of the phase
+    /// * `agent` - The agent playing in the phase
+    /// # Returns
+    /// The agent with the phase added
+    pub fn with_phase(mut self, start: PhaseStart, agent: impl Agent + 'static) -> HybridAgent {
+        self.phases.push(start);
+        self.agents.push(Box::new(agent));
+        self
+    }
+
+    /// Activates the agent of the latest phase that has started
+    ///
+    /// # Arguments
+    /// * `board` - The current board
+    /// * `turn` - The current turn
+    fn activate(&mut self, board: &UltimateBoard, turn: u32) {
+        self.active = self
+            .phases
+            .iter()
+            .rposition(|phase| phase.has_started(*board, turn))
+            .map_or(0, |phase| phase + 1);
+    }
+}
+
+impl Agent for HybridAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.activate(board, turn);
+        self.agents[self.active].act(board, player, turn)
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        self.activate(board, turn);
+        self.agents[self.active].choose_action(board, player, turn)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        self.agents[self.active].get_info()
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        for agent in self.agents.iter_mut() {
+            agent.set_game_metadata(metadata);
+        }
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        for agent in self.agents.iter_mut() {
+            agent.opponent_moved(board, index);
+        }
+    }
+
+    fn new_game(&mut self, player: Player) {
+        for agent in self.agents.iter_mut() {
+            agent.new_game(player);
+        }
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        for agent in self.agents.iter_mut() {
+            agent.game_over(board, result);
+        }
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        for agent in self.agents.iter_mut() {
+            agent.clock_updated(clock);
+        }
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agents[self.active].action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agents[self.active].move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+
+    #[test]
+    fn test_phases() {
+        let mut agent = HybridAgent::new(ScriptedAgent::new(vec![40]))
+            .with_phase(PhaseStart::Turn(1), ScriptedAgent::new(vec![0, 36]))
+            .with_phase(PhaseStart::EmptySquares(0), ScriptedAgent::new(vec![]));
+
+        let mut board = UltimateBoard::new();
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(40));
+
+        board.make_move(40);
+        assert_eq!(agent.act(&board, Player::Two, 1), Ok(36));
+        assert_eq!(agent.active, 1);
+
+        assert!(PhaseStart::EmptySquares(81).has_started(UltimateBoard::new(), 0));
+        assert!(!PhaseStart::EmptySquares(80).has_started(UltimateBoard::new(), 0));
+    }
+}
@@ -0,0 +1,347 @@
+//! # Contains the [LoggingAgent] struct
+//!
+//! The [LoggingAgent] struct records every move of another agent together with the position and the evaluation of the agent.
+//! Unlike the [BenchedAgent](crate::agent::benched::BenchedAgent), which records the duration of the moves,
+//! the records can be used to find blunders and to build training data sets.
+//!
+//! The records are written to an [AnnotationLog] as CSV or as JSON lines and can be read again with [read_annotations].
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, Write};
+use std::sync::{Arc, Mutex};
+
+/// # Struct representing a single move of an agent
+///
+/// The statistics of the search are None if the agent does not report them,
+/// the evaluation is reported by the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent),
+/// the visits and the win rate by the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Annotation {
+    /// The id of the game, if the agent is used in a [Game](crate::game::Game)
+    pub game_id: Option<u64>,
+    pub name: String,
+    pub player: Player,
+    pub turn: u32,
+    /// The position before the move, see [UltimateBoard::get_position]
+    pub position: String,
+    /// The number of legal moves in the position
+    pub legal_moves: u32,
+    /// The index of the chosen move, None if the agent did not find a move
+    pub chosen_move: Option<u8>,
+    /// The value of the chosen move from the perspective of the player of the agent
+    pub evaluation: Option<f64>,
+    pub nodes: Option<u64>,
+    pub max_depth: Option<u32>,
+    /// The number of simulations through the chosen move
+    pub visits: Option<f64>,
+    /// The mean reward of the simulations through the chosen move
+    pub win_rate: Option<f64>,
+    /// The moves of the principal variation, separated by spaces
+    pub principal_variation: Option<String>,
+}
+
+impl Annotation {
+    /// Creates a new [Annotation] from the given [AgentInfo]
+    ///
+    /// # Arguments
+    /// * `board` - The board before the move
+    /// * `chosen_move` - The move of the agent
+    /// * `info` - The information of the agent after the move
+    /// * `game_id` - The id of the game the move was made in
+    fn from_info(
+        board: UltimateBoard,
+        chosen_move: Option<u8>,
+        info: AgentInfo,
+        game_id: Option<u64>,
+    ) -> Annotation {
+        let statistics = info.statistics;
+        let child = info.mcts_report.as_ref().and_then(|report| {
+            report
+                .children
+                .iter()
+                .find(|child| Some(child.move_index) == chosen_move)
+        });
+
+        Annotation {
+            game_id,
+            player: info.player,
+            turn: info.turn_num,
+            position: board.get_position(),
+            legal_moves: board.get_possible_moves().count() as u32,
+            chosen_move,
+            evaluation: info.evaluation,
+            nodes: statistics.map(|s| s.nodes),
+            max_depth: statistics.map(|s| s.max_depth),
+            visits: child.map(|c| c.visits),
+            win_rate: child.map(|c| c.win_rate),
+            principal_variation: info.mcts_report.as_ref().map(|r| {
+                r.principal_variation
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+            name: info.name,
+        }
+    }
+}
+
+/// # Enum representing the destination of the [Annotation]s
+///
+/// The log can be shared by multiple [LoggingAgent]s, e.g. both players of a game.
+pub enum AnnotationLog<W: Write> {
+    /// Writes the annotations as CSV with a header row
+    Csv(Box<csv::Writer<W>>),
+    /// Writes every annotation as a JSON object on its own line
+    JsonLines(W),
+}
+
+impl<W: Write> AnnotationLog<W> {
+    /// Creates a new [AnnotationLog] writing CSV
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the CSV
+    pub fn csv(writer: W) -> AnnotationLog<W> {
+        AnnotationLog::Csv(Box::new(csv::Writer::from_writer(writer)))
+    }
+
+    /// Creates a new [AnnotationLog] writing JSON lines
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the JSON lines
+    pub fn json_lines(writer: W) -> AnnotationLog<W> {
+        AnnotationLog::JsonLines(writer)
+    }
+
+    /// Writes an annotation
+    ///
+    /// # Arguments
+    /// * `annotation` - The annotation to write
+    /// # Returns
+    /// An error if the annotation could not be written
+    pub fn write(&mut self, annotation: &Annotation) -> Result<(), Error> {
+        match self {
+            AnnotationLog::Csv(writer) => writer.serialize(annotation).map_err(Error::other),
+            AnnotationLog::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, annotation)?;
+                writer.write_all(b"\n")
+            }
+        }
+    }
+
+    /// Flushes the buffered annotations to the destination
+    pub fn flush(&mut self) -> Result<(), Error> {
+        match self {
+            AnnotationLog::Csv(writer) => writer.flush(),
+            AnnotationLog::JsonLines(writer) => writer.flush(),
+        }
+    }
+
+    /// Returns the destination of the annotations
+    ///
+    /// Buffered annotations are only included after [flushing](AnnotationLog::flush).
+    pub fn get_ref(&self) -> &W {
+        match self {
+            AnnotationLog::Csv(writer) => writer.get_ref(),
+            AnnotationLog::JsonLines(writer) => writer,
+        }
+    }
+}
+
+/// Reads the annotations written by an [AnnotationLog]
+///
+/// Files ending with `.csv` are read as CSV, all other files as JSON lines.
+/// # Arguments
+/// * `path` - The path of the file to read
+/// # Returns
+/// The annotations in the order they were written, or an error if the file can not be read
+pub fn read_annotations(path: &str) -> Result<Vec<Annotation>, Error> {
+    let reader = BufReader::new(File::open(path)?);
+
+    if path.ends_with(".csv") {
+        return csv::Reader::from_reader(reader)
+            .deserialize()
+            .map(|annotation| annotation.map_err(Error::other))
+            .collect();
+    }
+
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// # Struct representing an agent that annotates the moves of another agent
+///
+/// The agent wraps another agent and calls the act method of the wrapped agent.
+/// For each act call an [Annotation] is written to the log.
+///
+/// # Example
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use hausarbeit::agent::logging_agent::{AnnotationLog, LoggingAgent};
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+///
+/// let log = Arc::new(Mutex::new(AnnotationLog::json_lines(Vec::new())));
+///
+/// let agent = LoggingAgent::new(log, MonteCarloTreeAgent::new(1000));
+/// ```
+pub struct LoggingAgent<A, W: Write> {
+    agent: A,
+    log: Arc<Mutex<AnnotationLog<W>>>,
+    game_id: Option<u64>,
+}
+
+impl<A: Agent, W: Write + Send> LoggingAgent<A, W> {
+    /// Creates a new [LoggingAgent] wrapping the given agent and writing to the given log
+    ///
+    /// # Arguments
+    /// * `log` - The log to write the annotations to
+    /// * `agent` - The agent to annotate
+    pub fn new(log: Arc<Mutex<AnnotationLog<W>>>, agent: A) -> LoggingAgent<A, W> {
+        LoggingAgent {
+            agent,
+            log,
+            game_id: None,
+        }
+    }
+
+    /// Writes the annotation of a move of the wrapped agent to the log
+    ///
+    /// # Arguments
+    /// * `board` - The board the agent acted on
+    /// * `index` - The move of the agent, None if it could not play
+    fn log(&mut self, board: UltimateBoard, index: Option<u8>) {
+        let annotation = Annotation::from_info(board, index, self.agent.get_info(), self.game_id);
+
+        let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = log.write(&annotation) {
+            eprintln!("Could not write annotation: {}", e);
+        }
+    }
+}
+
+/// Clones of the agent write to the same log
+impl<A: Clone, W: Write> Clone for LoggingAgent<A, W> {
+    fn clone(&self) -> Self {
+        LoggingAgent {
+            agent: self.agent.clone(),
+            log: self.log.clone(),
+            game_id: self.game_id,
+        }
+    }
+}
+
+impl<A: Agent, W: Write + Send> Agent for LoggingAgent<A, W> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        let result = self.agent.act(board, player, turn);
+        self.log(*board, result.as_ref().ok().copied());
+
+        result
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        let result = self.agent.choose_action(board, player, turn);
+
+        // Only moves and failures are annotated, other actions do not change the board
+        match result {
+            Ok(Action::Move(index)) => self.log(*board, Some(index)),
+            Ok(_) => {}
+            Err(_) => self.log(*board, None),
+        }
+
+        result
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let sub_info = self.agent.get_info();
+
+        AgentInfo {
+            name: format!("Logging({})", sub_info.name),
+            ..sub_info
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.game_id = Some(metadata.game_id);
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::minimax_agent::MiniMaxAgent;
+    use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_annotations() {
+        let board = UltimateBoard::new();
+
+        let log = Arc::new(Mutex::new(AnnotationLog::json_lines(Vec::new())));
+        let mut agent = LoggingAgent::new(
+            log.clone(),
+            MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One)),
+        );
+        let index = agent.act(&board, Player::One, 0);
+
+        let log = log.lock().unwrap();
+        let line = String::from_utf8(log.get_ref().clone()).unwrap();
+        let annotation: Annotation = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(annotation.position, board.get_position());
+        assert_eq!(annotation.legal_moves, 81);
+        assert_eq!(annotation.chosen_move, index.ok());
+        assert!(annotation.evaluation.is_some());
+
+        let log = Arc::new(Mutex::new(AnnotationLog::csv(Vec::new())));
+        let mut agent = LoggingAgent::new(log.clone(), MonteCarloTreeAgent::new(200));
+        agent.act(&board, Player::One, 0).unwrap();
+        agent.act(&board, Player::One, 0).unwrap();
+
+        let mut log = log.lock().unwrap();
+        log.flush().unwrap();
+        let mut reader = csv::Reader::from_reader(log.get_ref().as_slice());
+        let annotations: Vec<Annotation> = reader.deserialize().map(|a| a.unwrap()).collect();
+        assert_eq!(annotations.len(), 2);
+        assert!(annotations[0].visits.is_some());
+    }
+}
@@ -0,0 +1,173 @@
+//! # Contains the [IncrementalEvaluation] struct
+//!
+//! The [IncrementalEvaluation] keeps the [features](Heuristic::initialize_features) of the positions on the current path of a search
+//! of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//!
+//! A move only changes the small board it is made on and rarely the status of a small board,
+//! so only the features depending on them are updated when a move is made.
+//! Leaf positions are evaluated with [Heuristic::evaluate_features] instead of computing the features from scratch.
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::Heuristic;
+
+/// # Struct containing the features of the positions along the current path of a search
+///
+/// Every [made move](IncrementalEvaluation::make_move) pushes the board and the features of the new position,
+/// which are removed again when the move is [unmade](IncrementalEvaluation::unmake_move).
+/// The features of all positions are stored in a single vector, so making a move does not allocate once the path was reached before.
+/// The features are computed for a single player, usually the player to move at the root of the search.
+pub struct IncrementalEvaluation {
+    player: Player,
+    num_features: usize,
+    features: Vec<f64>,
+    boards: Vec<UltimateBoard>,
+}
+
+impl IncrementalEvaluation {
+    /// Creates a new [IncrementalEvaluation] starting at the given board
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic whose features are tracked
+    /// * `board` - The board the search starts on
+    /// * `player` - The player the boards are evaluated for
+    /// # Returns
+    /// The incremental evaluation, None if the heuristic does not support [incremental evaluation](Heuristic::initialize_features)
+    pub fn new(heuristic: &impl Heuristic, board: UltimateBoard, player: Player) -> Option<Self> {
+        let features = heuristic.initialize_features(board, player)?;
+
+        Some(IncrementalEvaluation {
+            player,
+            num_features: features.len(),
+            features,
+            boards: vec![board],
+        })
+    }
+
+    /// Updates the features after a move
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic whose features are tracked
+    /// * `board` - The board after the move
+    /// * `index` - The index of the move
+    pub fn make_move(&mut self, heuristic: &impl Heuristic, board: UltimateBoard, index: u8) {
+        let previous = *self.boards.last().unwrap();
+
+        let start = self.features.len();
+        self.features
+            .extend_from_within(start - self.num_features..start);
+
+        heuristic.update_features(
+            &mut self.features[start..],
+            previous,
+            board,
+            index,
+            self.player,
+        );
+
+        self.boards.push(board);
+    }
+
+    /// Restores the features before the last move
+    ///
+    /// The starting position is never removed.
+    pub fn unmake_move(&mut self) {
+        if self.boards.len() > 1 {
+            self.boards.pop();
+            self.features
+                .truncate(self.features.len() - self.num_features);
+        }
+    }
+
+    /// Evaluates the board of the current position for the player of the evaluation
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic whose features are tracked
+    /// * `board` - The board of the current position, may differ from the board of the last move by a [null move](UltimateBoard::make_null_move)
+    /// # Returns
+    /// The value of the board
+    pub fn evaluate(&self, heuristic: &impl Heuristic, board: UltimateBoard) -> f64 {
+        let features = &self.features[self.features.len() - self.num_features..];
+
+        heuristic.evaluate_features(board, self.player, features)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_result::GameResult::Continue;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use crate::heuristic::parameterized_heuristic::{
+        ParameterizedHeuristic, NUM_FEATURES, NUM_PHASES,
+    };
+    use rand::prelude::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn assert_incremental(heuristic: impl Heuristic) {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        for player in Player::iter() {
+            let mut board = UltimateBoard::new();
+            let mut evaluation = IncrementalEvaluation::new(&heuristic, board, player).unwrap();
+
+            while board.get_game_status() == Continue {
+                let index = board.get_possible_moves().choose(&mut rng).unwrap();
+
+                // Unmaking a move restores the features of the position before
+                let mut new_board = board;
+                new_board.make_move(index);
+                evaluation.make_move(&heuristic, new_board, index);
+                evaluation.unmake_move();
+                assert!(
+                    (evaluation.evaluate(&heuristic, board)
+                        - heuristic.evaluate_for(board, player))
+                    .abs()
+                        < 1e-9
+                );
+
+                board = new_board;
+                evaluation.make_move(&heuristic, board, index);
+                assert!(
+                    (evaluation.evaluate(&heuristic, board)
+                        - heuristic.evaluate_for(board, player))
+                    .abs()
+                        < 1e-9
+                );
+
+                if board.get_game_status() != Continue {
+                    continue;
+                }
+
+                // The features do not change with a null move
+                let mut passed_board = board;
+                passed_board.make_null_move();
+                assert!(
+                    (evaluation.evaluate(&heuristic, passed_board)
+                        - heuristic.evaluate_for(passed_board, player))
+                    .abs()
+                        < 1e-9
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_evaluation() {
+        let values: Vec<f64> = (0..NUM_FEATURES).map(|i| i as f64 / 3. - 2.).collect();
+
+        assert_incremental(CustomHeuristic::new(Player::Two));
+        assert_incremental(ParameterizedHeuristic::new(
+            Player::One,
+            values[..12].to_vec(),
+        ));
+        assert_incremental(ParameterizedHeuristic::with_look_up_table(
+            Player::One,
+            values.clone(),
+        ));
+        assert_incremental(ParameterizedHeuristic::new(
+            Player::Two,
+            values.repeat(NUM_PHASES),
+        ));
+    }
+}
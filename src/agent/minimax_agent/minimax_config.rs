@@ -0,0 +1,464 @@
+//! # Contains the [MiniMaxConfig] struct
+//!
+//! The [MiniMaxConfig] struct contains all options of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//! A configuration is created with [MiniMaxConfig::new] and customized with the `with_*` methods,
+//! before it is passed to [MiniMaxAgent::from_config](crate::agent::minimax_agent::MiniMaxAgent::from_config).
+use crate::agent::minimax_agent::quiescence_policy::QuiescencePolicy;
+use crate::agent::minimax_agent::transposition_table::DEFAULT_CAPACITY;
+use crate::game::tablebase::Tablebase;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// # Struct containing the configuration of a [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent)
+///
+/// All optional features are disabled by default, except for move ordering.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use hausarbeit::agent::minimax_agent::minimax_config::MiniMaxConfig;
+/// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let config = MiniMaxConfig::new(10, 1)
+///     .with_time_limit(Duration::from_millis(500))
+///     .with_threads(4)
+///     .with_transposition_table_capacity(1 << 16);
+///
+/// let agent = MiniMaxAgent::from_config(config, CustomHeuristic::new(Player::One));
+/// ```
+#[derive(Clone)]
+pub struct MiniMaxConfig {
+    /// The depth minimax should search to
+    ///
+    /// If a time limit is set, this is the maximum depth of the iterative deepening.
+    pub depth: u32,
+    /// The depth the quiescence search should search to, 0 to disable the quiescence search
+    pub quiescence_search_depth: u32,
+    /// The definition of noisy positions for the quiescence search, None for the [FreeChoicePolicy](crate::agent::minimax_agent::quiescence_policy::FreeChoicePolicy)
+    pub quiescence_policy: Option<Arc<dyn QuiescencePolicy>>,
+    /// The time budget per move, None if the search always completes [depth](MiniMaxConfig::depth)
+    pub time_limit: Option<Duration>,
+    /// The maximum number of entries of the transposition table
+    pub transposition_table_capacity: usize,
+    /// Whether the moves are ordered before they are searched
+    pub move_ordering: bool,
+    /// Whether symmetric positions share their transposition table entries
+    pub symmetric_transpositions: bool,
+    /// Whether the transposition table entries are verified with a fingerprint of the position
+    pub hash_verification: bool,
+    /// The initial half width of the aspiration window, None if aspiration windows are disabled
+    pub aspiration_window: Option<f64>,
+    /// The number of threads searching in parallel
+    pub threads: u32,
+    /// Whether the root moves are searched in parallel, each with its own transposition table
+    pub root_parallel: bool,
+    /// Whether null move pruning is used
+    pub null_move_pruning: bool,
+    /// Whether the depth is extended for forced moves
+    pub forced_move_extensions: bool,
+    /// The approximate number of nodes searched per move, None if the depth is fixed
+    pub node_budget: Option<u64>,
+    /// The maximum distance of a root move to the best value to be chosen, None if the best move is always chosen
+    pub tie_breaking: Option<f64>,
+    /// The temperature of the choice among the candidates, None if the candidates are chosen uniformly
+    pub tie_breaking_temperature: Option<f64>,
+    /// The seed of the random number generator used to break ties, None for a random seed
+    pub seed: Option<u64>,
+    /// Whether the agent searches the predicted reply of the opponent while the opponent is thinking
+    pub pondering: bool,
+    /// The number of best root moves whose exact values and principal variations are reported, 1 to only report the best move
+    pub multi_pv: usize,
+    /// The tablebase probed for the exact values of positions close to the end of the game, None if no tablebase is used
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+impl MiniMaxConfig {
+    /// Creates a new [MiniMaxConfig] with all optional features disabled, except for move ordering
+    ///
+    /// # Arguments
+    /// * `depth` - The depth minimax should search to
+    /// * `quiescence_search_depth` - The depth the quiescence search should search to
+    pub fn new(depth: u32, quiescence_search_depth: u32) -> MiniMaxConfig {
+        MiniMaxConfig {
+            depth,
+            quiescence_search_depth,
+            quiescence_policy: None,
+            time_limit: None,
+            transposition_table_capacity: DEFAULT_CAPACITY,
+            move_ordering: true,
+            symmetric_transpositions: false,
+            hash_verification: false,
+            aspiration_window: None,
+            threads: 1,
+            root_parallel: false,
+            null_move_pruning: false,
+            forced_move_extensions: false,
+            node_budget: None,
+            tie_breaking: None,
+            tie_breaking_temperature: None,
+            seed: None,
+            pondering: false,
+            multi_pv: 1,
+            tablebase: None,
+        }
+    }
+
+    /// Sets a time budget per move
+    ///
+    /// The agent uses iterative deepening: the depth is increased by one until the time budget is exceeded
+    /// or the maximum [depth](MiniMaxConfig::depth) is reached.
+    /// The best move of the last completed depth is played.
+    /// The first iteration always completes, so the time budget may be exceeded for very small budgets.
+    /// # Arguments
+    /// * `time_limit` - The time budget per move
+    /// # Returns
+    /// The configuration with the time budget set
+    pub fn with_time_limit(mut self, time_limit: Duration) -> MiniMaxConfig {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Sets the policy defining the noisy positions searched by the quiescence search
+    ///
+    /// By default the [FreeChoicePolicy](crate::agent::minimax_agent::quiescence_policy::FreeChoicePolicy) is used.
+    /// # Arguments
+    /// * `policy` - The quiescence policy
+    /// # Returns
+    /// The configuration using the given quiescence policy
+    pub fn with_quiescence_policy(
+        mut self,
+        policy: impl QuiescencePolicy + 'static,
+    ) -> MiniMaxConfig {
+        self.quiescence_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Sets the capacity of the transposition table
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of entries of the transposition table
+    /// # Returns
+    /// The configuration with the capacity set
+    pub fn with_transposition_table_capacity(mut self, capacity: usize) -> MiniMaxConfig {
+        self.transposition_table_capacity = capacity;
+        self
+    }
+
+    /// Enables or disables move ordering
+    ///
+    /// Move ordering is enabled by default.
+    /// See [MoveOrdering](crate::agent::minimax_agent::move_ordering::MoveOrdering) for the order the moves are searched in.
+    /// # Arguments
+    /// * `move_ordering` - Whether the moves should be ordered
+    /// # Returns
+    /// The configuration with move ordering enabled or disabled
+    pub fn with_move_ordering(mut self, move_ordering: bool) -> MiniMaxConfig {
+        self.move_ordering = move_ordering;
+        self
+    }
+
+    /// Enables or disables symmetry-aware transposition table lookups
+    ///
+    /// Positions that are rotations or reflections of each other are stored under their
+    /// [canonical hash](crate::game::ultimate_board::UltimateBoard::get_canonical_hash), so they share their entries.
+    /// This reduces the number of searched nodes, especially in the opening,
+    /// but computing the canonical hash is more expensive than using the incremental hash.
+    /// Disabled by default.
+    /// # Arguments
+    /// * `symmetric_transpositions` - Whether symmetric positions should share their entries
+    /// # Returns
+    /// The configuration with symmetry-aware lookups enabled or disabled
+    pub fn with_symmetric_transpositions(
+        mut self,
+        symmetric_transpositions: bool,
+    ) -> MiniMaxConfig {
+        self.symmetric_transpositions = symmetric_transpositions;
+        self
+    }
+
+    /// Enables or disables the verification of transposition table entries
+    ///
+    /// Every entry is stored with a [fingerprint](crate::game::ultimate_board::UltimateBoard::get_fingerprint) of its position,
+    /// which is compared on every lookup. Entries of other positions with the same hash are not used,
+    /// the [collisions](crate::agent::minimax_agent::MiniMaxAgent::get_hash_collisions) are counted and logged.
+    /// This is a debug mode, computing the fingerprints slows down the search.
+    /// Disabled by default.
+    /// # Arguments
+    /// * `hash_verification` - Whether the entries should be verified
+    /// # Returns
+    /// The configuration with the verification enabled or disabled
+    pub fn with_hash_verification(mut self, hash_verification: bool) -> MiniMaxConfig {
+        self.hash_verification = hash_verification;
+        self
+    }
+
+    /// Enables aspiration windows for iterative deepening
+    ///
+    /// Each iteration after the first is started with the window `[value - window, value + window]`
+    /// around the value of the previous iteration, which is widened if the search fails.
+    /// Only used if a [time limit](MiniMaxConfig::with_time_limit) is set.
+    /// # Arguments
+    /// * `window` - The initial distance of alpha and beta to the previous value, must be positive
+    /// # Returns
+    /// The configuration with aspiration windows enabled
+    pub fn with_aspiration_window(mut self, window: f64) -> MiniMaxConfig {
+        assert!(window > 0., "Aspiration window must be positive");
+        self.aspiration_window = Some(window);
+        self
+    }
+
+    /// Sets the number of threads used for the search
+    ///
+    /// If more than one thread is used, the search is parallelized using [Lazy SMP](https://www.chessprogramming.org/Lazy_SMP).
+    /// The threads are spawned for every move in addition to the threads of the calling code.
+    /// # Arguments
+    /// * `threads` - The number of threads, including the calling thread
+    /// # Returns
+    /// The configuration using the given number of threads
+    pub fn with_threads(mut self, threads: u32) -> MiniMaxConfig {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Enables or disables root-level parallel search
+    ///
+    /// Every root move is searched in its own [rayon] task with its own transposition table and a full window,
+    /// so the tasks do not have to share any state.
+    /// The tasks run on the global rayon thread pool, the [threads](MiniMaxConfig::threads) are ignored.
+    /// The transposition table of the agent is not used, so nothing is kept across moves.
+    /// # Arguments
+    /// * `root_parallel` - Whether the root moves should be searched in parallel
+    /// # Returns
+    /// The configuration with root-level parallel search enabled or disabled
+    pub fn with_root_parallel(mut self, root_parallel: bool) -> MiniMaxConfig {
+        self.root_parallel = root_parallel;
+        self
+    }
+
+    /// Enables or disables null move pruning
+    ///
+    /// Before the moves of a node are searched, the player to move passes and the position is searched with reduced depth.
+    /// If the position is still good enough to cause a cutoff, the node is pruned.
+    /// Null move pruning is disabled by default and never used if only few small boards are left open.
+    /// # Arguments
+    /// * `null_move_pruning` - Whether null move pruning should be used
+    /// # Returns
+    /// The configuration with null move pruning enabled or disabled
+    pub fn with_null_move_pruning(mut self, null_move_pruning: bool) -> MiniMaxConfig {
+        self.null_move_pruning = null_move_pruning;
+        self
+    }
+
+    /// Enables or disables forced move extensions
+    ///
+    /// The depth of a node is not reduced if the player to move has only one possible move
+    /// or has to respond to an immediate threat of the opponent to win the game.
+    /// Forced move extensions are disabled by default.
+    /// # Arguments
+    /// * `forced_move_extensions` - Whether forced moves should be extended
+    /// # Returns
+    /// The configuration with forced move extensions enabled or disabled
+    pub fn with_forced_move_extensions(mut self, forced_move_extensions: bool) -> MiniMaxConfig {
+        self.forced_move_extensions = forced_move_extensions;
+        self
+    }
+
+    /// Sets a node budget per move, so the depth is chosen based on the branching factor
+    ///
+    /// The depth is chosen such that the number of possible moves to the power of the depth does not exceed the budget.
+    /// Positions in which the next player may play on any open board are searched shallower,
+    /// positions constrained to a single board deeper.
+    /// This keeps the time per move roughly constant.
+    /// The [depth](MiniMaxConfig::depth) is the maximum depth.
+    /// # Arguments
+    /// * `node_budget` - The approximate number of nodes searched per move
+    /// # Returns
+    /// The configuration with adaptive depth enabled
+    pub fn with_node_budget(mut self, node_budget: u64) -> MiniMaxConfig {
+        self.node_budget = Some(node_budget);
+        self
+    }
+
+    /// Enables randomized tie-breaking among the best moves
+    ///
+    /// All root moves whose values are at most `epsilon` below the best value are candidates.
+    /// The played move is chosen uniformly among the candidates, or by [temperature](MiniMaxConfig::with_tie_breaking_temperature) if set.
+    /// The root moves are searched with a lowered window, so tie-breaking makes the search slightly slower.
+    /// # Arguments
+    /// * `epsilon` - The maximum distance of a candidate to the best value, 0 to only break exact ties
+    /// # Returns
+    /// The configuration with tie-breaking enabled
+    pub fn with_tie_breaking(mut self, epsilon: f64) -> MiniMaxConfig {
+        assert!(epsilon >= 0., "Tie-breaking epsilon must not be negative");
+        self.tie_breaking = Some(epsilon);
+        self
+    }
+
+    /// Chooses the candidates of the [tie-breaking](MiniMaxConfig::with_tie_breaking) by temperature
+    ///
+    /// A candidate is chosen with a probability proportional to `exp((value - best value) / temperature)`.
+    /// # Arguments
+    /// * `temperature` - The temperature, must be positive
+    /// # Returns
+    /// The configuration choosing the candidates by temperature
+    pub fn with_tie_breaking_temperature(mut self, temperature: f64) -> MiniMaxConfig {
+        assert!(temperature > 0., "Temperature must be positive");
+        self.tie_breaking_temperature = Some(temperature);
+        self
+    }
+
+    /// Seeds the random number generator used for tie-breaking
+    ///
+    /// Agents with the same seed and configuration play the same moves.
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The configuration with the seed set
+    pub fn with_seed(mut self, seed: u64) -> MiniMaxConfig {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Enables the multi-PV analysis mode
+    ///
+    /// The search reports the best `lines` root moves, each with its exact value and
+    /// [principal variation](https://www.chessprogramming.org/Principal_Variation), in the
+    /// [lines](crate::agent::minimax_agent::search_result::SearchResult::lines) of the result.
+    /// Root moves are only cut off if they are worse than the worst of the best lines, so the search gets slower the more lines are requested.
+    /// Aspiration windows are not used in this mode.
+    /// # Arguments
+    /// * `lines` - The number of reported root moves, must be positive
+    /// # Returns
+    /// The configuration with the multi-PV mode enabled
+    pub fn with_multi_pv(mut self, lines: usize) -> MiniMaxConfig {
+        assert!(lines > 0, "Number of lines must be positive");
+        self.multi_pv = lines;
+        self
+    }
+
+    /// Enables or disables pondering
+    ///
+    /// After a move has been made, the reply of the opponent predicted by the principal variation
+    /// is searched in a background thread until the opponent has moved.
+    /// The results are stored in the transposition table and reused for the next move.
+    ///
+    /// Pondering is stopped when the agent is [notified](crate::agent::Agent::opponent_moved) about the move of the opponent
+    /// or at the latest when the next move is requested.
+    /// Pondering is disabled by default.
+    /// # Arguments
+    /// * `pondering` - Whether the agent should ponder
+    /// # Returns
+    /// The configuration with pondering enabled or disabled
+    pub fn with_pondering(mut self, pondering: bool) -> MiniMaxConfig {
+        self.pondering = pondering;
+        self
+    }
+
+    /// Probes a tablebase during the search
+    ///
+    /// Positions stored in the [Tablebase] are not searched further, their exact result is used instead:
+    /// [MAX_VALUE](crate::heuristic::MAX_VALUE) for a win, [MIN_VALUE](crate::heuristic::MIN_VALUE) for a loss and 0 for a draw.
+    /// How often a position was found is reported in the [statistics](crate::agent::minimax_agent::search_statistics::SearchStatistics::tablebase_hits).
+    /// # Arguments
+    /// * `tablebase` - The tablebase
+    /// # Returns
+    /// The configuration probing the tablebase
+    pub fn with_tablebase(mut self, tablebase: Arc<Tablebase>) -> MiniMaxConfig {
+        self.tablebase = Some(tablebase);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::minimax_agent::MiniMaxAgent;
+    use crate::agent::Agent;
+    use crate::game::player::Player;
+    use crate::game::ultimate_board::UltimateBoard;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_defaults() {
+        let config = MiniMaxConfig::new(4, 1);
+
+        assert_eq!((config.depth, config.quiescence_search_depth), (4, 1));
+        assert!(config.move_ordering);
+        assert_eq!(config.transposition_table_capacity, DEFAULT_CAPACITY);
+        assert_eq!((config.threads, config.multi_pv), (1, 1));
+        assert!(config.quiescence_policy.is_none());
+        assert!(config.time_limit.is_none() && config.node_budget.is_none());
+        assert!(config.aspiration_window.is_none() && config.tie_breaking.is_none());
+        assert!(config.seed.is_none() && config.tablebase.is_none());
+        assert!(
+            !(config.symmetric_transpositions
+                || config.hash_verification
+                || config.root_parallel
+                || config.null_move_pruning
+                || config.forced_move_extensions
+                || config.pondering)
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let config = MiniMaxConfig::new(6, 2)
+            .with_time_limit(Duration::from_millis(200))
+            .with_transposition_table_capacity(1024)
+            .with_move_ordering(false)
+            .with_symmetric_transpositions(true)
+            .with_hash_verification(true)
+            .with_aspiration_window(0.1)
+            .with_threads(0)
+            .with_null_move_pruning(true)
+            .with_forced_move_extensions(true)
+            .with_node_budget(1000)
+            .with_tie_breaking(0.01)
+            .with_seed(3)
+            .with_multi_pv(2);
+
+        assert_eq!(config.time_limit, Some(Duration::from_millis(200)));
+        assert_eq!(config.transposition_table_capacity, 1024);
+        assert!(!config.move_ordering);
+        assert!(config.symmetric_transpositions && config.hash_verification);
+        assert_eq!(config.aspiration_window, Some(0.1));
+        // At least the calling thread searches
+        assert_eq!(config.threads, 1);
+        assert!(config.null_move_pruning && config.forced_move_extensions);
+        assert_eq!(config.node_budget, Some(1000));
+        assert_eq!(config.tie_breaking, Some(0.01));
+        assert_eq!(config.seed, Some(3));
+        assert_eq!(config.multi_pv, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_multi_pv() {
+        MiniMaxConfig::new(4, 1).with_multi_pv(0);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = MiniMaxConfig::new(3, 1)
+            .with_tie_breaking(0.05)
+            .with_seed(7);
+        let mut from_config = MiniMaxAgent::from_config(config, CustomHeuristic::new(Player::One));
+
+        // The builder methods of the agent configure the same search
+        let mut built = MiniMaxAgent::new(3, 1, CustomHeuristic::new(Player::One))
+            .with_tie_breaking(0.05)
+            .with_seed(7);
+        assert_eq!(built.get_config().tie_breaking, Some(0.05));
+        assert_eq!(built.get_config().seed, Some(7));
+
+        let mut board = UltimateBoard::new();
+        for turn in 0..6 {
+            let player = board.get_current_player();
+            let index = from_config.act(&board, player, turn).unwrap();
+            assert_eq!(built.act(&board, player, turn), Ok(index));
+            board.make_move(index);
+        }
+    }
+}
@@ -0,0 +1,2336 @@
+//! # Contains the [MiniMaxAgent] struct
+//! The MiniMaxAgent struct represents an [Agent] that uses the minimax algorithm to determine the best move.
+//! The agent uses the provided [Heuristic] to evaluate the board state.
+//!
+//! For more information see the [MiniMaxAgent] struct.
+
+use crate::agent::agent_error::AgentError;
+use crate::agent::minimax_agent::incremental_evaluation::IncrementalEvaluation;
+use crate::agent::minimax_agent::minimax_config::MiniMaxConfig;
+use crate::agent::minimax_agent::move_ordering::MoveOrdering;
+use crate::agent::minimax_agent::quiescence_policy::{FreeChoicePolicy, QuiescencePolicy};
+use crate::agent::minimax_agent::search_result::{SearchLine, SearchResult};
+use crate::agent::minimax_agent::search_statistics::SearchStatistics;
+use crate::agent::minimax_agent::transposition_table::{
+    EntryFlag, TranspositionEntry, TranspositionTable,
+};
+use crate::agent::{Agent, AgentInfo, AnytimeAgent};
+use crate::game::game_result::GameResult;
+use crate::game::game_result::GameResult::Continue;
+use crate::game::player::Player;
+use crate::game::tablebase::Tablebase;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{is_proven, Heuristic, MAX_VALUE, MIN_VALUE};
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub mod incremental_evaluation;
+pub mod minimax_config;
+pub mod move_ordering;
+pub mod quiescence_policy;
+pub mod search_result;
+pub mod search_statistics;
+pub mod transposition_table;
+
+/// The depth reduction of the search after a [null move](https://www.chessprogramming.org/Null_Move_Pruning)
+const NULL_MOVE_REDUCTION: u32 = 2;
+
+/// The minimum number of small boards that are not yet decided for null move pruning to be used
+///
+/// With few open boards the player to move is often forced into bad moves ([zugzwang](https://www.chessprogramming.org/Zugzwang)),
+/// so passing would overestimate the position.
+const NULL_MOVE_MIN_OPEN_BOARDS: usize = 4;
+
+/// The amount the root window is lowered in addition to the tie-breaking margin
+///
+/// Ensures that moves with exactly the best value are searched with an exact value as well.
+const TIE_BREAKING_TOLERANCE: f64 = 1e-9;
+
+/// The maximum ply up to which [forced move extensions](MiniMaxAgent::with_forced_move_extensions) are applied, as a multiple of the depth
+///
+/// Limits the growth of the search tree in long forced sequences.
+const MAX_EXTENSION_PLY_FACTOR: usize = 2;
+
+/// The key combined with the hash of a board if [Player::Two] is to move at the root of the search
+///
+/// The values in the transposition table are given from the perspective of the root player,
+/// so the entries of searches for different players must not be mixed up.
+const ROOT_PLAYER_TWO_KEY: u64 = 0x9E37_79B9_7F4A_7C15;
+
+#[allow(rustdoc::private_intra_doc_links)]
+/// An Ultimate Tic Tac Toe agent that uses the minimax algorithm to determine the best move.
+/// The agent uses the provided heuristic to evaluate the board state.
+///
+/// <b>Optimizations</b>:
+/// * [Alpha-beta pruning](https://www.chessprogramming.org/Alpha-Beta)
+/// * [Transposition table](https://www.chessprogramming.org/Transposition_Table) using [Zobrist Hashing](https://www.chessprogramming.org/Zobrist_Hashing), kept across moves
+///   and optionally shared by symmetric positions with [MiniMaxAgent::with_symmetric_transpositions]
+/// * [Quiescence search](https://www.chessprogramming.org/Quiescence_Search) to combat the [Horizon effect](https://www.chessprogramming.org/Horizon_Effect)
+/// * [Move ordering](https://www.chessprogramming.org/Move_Ordering) using the transposition table, killer moves and the history heuristic
+/// * [Incremental evaluation](IncrementalEvaluation) of the features if the heuristic supports it
+/// * [Iterative deepening](https://www.chessprogramming.org/Iterative_Deepening) if a [time limit](MiniMaxAgent::with_time_limit) is set
+/// * Parallel search using [Lazy SMP](https://www.chessprogramming.org/Lazy_SMP) if enabled with [MiniMaxAgent::with_threads]
+///   or by searching the root moves in parallel if enabled with [MiniMaxAgent::with_root_parallel]
+/// * [Aspiration windows](https://www.chessprogramming.org/Aspiration_Windows) if enabled with [MiniMaxAgent::with_aspiration_window]
+/// * [Null move pruning](https://www.chessprogramming.org/Null_Move_Pruning) if enabled with [MiniMaxAgent::with_null_move_pruning]
+/// * [Pondering](https://www.chessprogramming.org/Pondering) on the time of the opponent if enabled with [MiniMaxAgent::with_pondering]
+/// * [Search extensions](https://www.chessprogramming.org/Extensions) for forced moves if enabled with [MiniMaxAgent::with_forced_move_extensions]
+/// * Adaptive depth based on the branching factor if a [node budget](MiniMaxAgent::with_node_budget) is set
+///
+/// A running search can be stopped from another thread with the [stop token](MiniMaxAgent::get_stop_token).
+/// The agent can also search in the background as an [AnytimeAgent], deepening the search until it is stopped.
+///
+/// By default the agent is deterministic. Randomized choices among equally good moves can be enabled with [MiniMaxAgent::with_tie_breaking].
+///
+/// All options are collected in a [MiniMaxConfig], which can be passed to [MiniMaxAgent::from_config].
+///
+/// Note: Quiescence search depth has a large impact on the performance of the agent. The effect of Quiescence search may be small.
+/// Quiescence search can be disabled by setting the [quiescence_search_depth](MiniMaxConfig::quiescence_search_depth) to 0.
+/// Which positions are searched by the quiescence search can be changed with [MiniMaxAgent::with_quiescence_policy].
+pub struct MiniMaxAgent<H> {
+    /// The configuration of the search
+    config: MiniMaxConfig,
+    /// The heuristic used to evaluate the board state
+    heuristic: H,
+    /// The random number generator used to break ties
+    rng: ChaCha20Rng,
+    /// The transposition table, kept across moves and shared with the pondering thread
+    transposition_table: Arc<TranspositionTable>,
+    /// The running pondering thread, if any
+    ponder: Option<Ponder>,
+    /// The running [anytime](AnytimeAgent) search, if any
+    thinking: Option<Thinking>,
+    /// The statistics of the last search, reported by [get_info](Agent::get_info)
+    last_statistics: Option<SearchStatistics>,
+    /// The value of the best move of the last search, reported by [get_info](Agent::get_info)
+    last_value: Option<f64>,
+    /// Flag set by another thread to stop the running search
+    stop_token: Arc<AtomicBool>,
+    player: Player,
+    turn: u32,
+}
+
+impl<H: Heuristic> MiniMaxAgent<H> {
+    /// Creates a new [MiniMaxAgent] with the default configuration
+    ///
+    /// See [MiniMaxConfig::new] for the defaults.
+    /// # Arguments
+    /// * `depth` - The depth minimax should search to
+    /// * `quiescence_search_depth` - The depth the quiescence search should search to
+    /// * `heuristic` - The heuristic used to evaluate the board state
+    pub fn new(depth: u32, quiescence_search_depth: u32, heuristic: H) -> MiniMaxAgent<H> {
+        MiniMaxAgent::from_config(
+            MiniMaxConfig::new(depth, quiescence_search_depth),
+            heuristic,
+        )
+    }
+
+    /// Creates a new [MiniMaxAgent] from the given configuration
+    ///
+    /// # Arguments
+    /// * `config` - The configuration of the search
+    /// * `heuristic` - The heuristic used to evaluate the board state
+    pub fn from_config(config: MiniMaxConfig, heuristic: H) -> MiniMaxAgent<H> {
+        let rng = match config.seed {
+            Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+            None => ChaCha20Rng::from_entropy(),
+        };
+
+        MiniMaxAgent {
+            transposition_table: Arc::new(TranspositionTable::new(
+                config.transposition_table_capacity,
+            )),
+            config,
+            heuristic,
+            rng,
+            ponder: None,
+            thinking: None,
+            last_statistics: None,
+            last_value: None,
+            stop_token: Arc::new(AtomicBool::new(false)),
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Returns the configuration of the agent
+    pub fn get_config(&self) -> &MiniMaxConfig {
+        &self.config
+    }
+
+    /// Returns the number of key collisions detected in the transposition table of the agent
+    ///
+    /// Collisions are only detected if the [hash verification](MiniMaxConfig::with_hash_verification) is enabled.
+    /// The collisions in the temporary tables of the [root parallel search](MiniMaxConfig::with_root_parallel) are not included.
+    pub fn get_hash_collisions(&self) -> u64 {
+        self.transposition_table.get_collisions()
+    }
+
+    /// Returns the stop token of the agent
+    ///
+    /// Setting the token to true aborts the running search, which then returns the best move found so far.
+    /// This is the case for the last completed iteration of the [iterative deepening](MiniMaxAgent::iterative_deepening)
+    /// or the best completely searched root move. If no root move was searched completely, the result of a search with depth 1 is returned.
+    ///
+    /// The token is reset at the start of every search.
+    /// # Example
+    /// ```
+    /// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+    /// use hausarbeit::game::player::Player;
+    /// use hausarbeit::game::ultimate_board::UltimateBoard;
+    /// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+    /// use std::sync::atomic::Ordering;
+    /// use std::time::Duration;
+    ///
+    /// let mut agent = MiniMaxAgent::new(20, 1, CustomHeuristic::new(Player::One));
+    /// let stop_token = agent.get_stop_token();
+    ///
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(Duration::from_millis(100));
+    ///     stop_token.store(true, Ordering::Relaxed);
+    /// });
+    ///
+    /// assert!(agent.search(UltimateBoard::new()).is_some());
+    /// ```
+    pub fn get_stop_token(&self) -> Arc<AtomicBool> {
+        self.stop_token.clone()
+    }
+
+    /// Uses the given stop token instead of the own one
+    ///
+    /// Allows stopping multiple agents with a single token, see [MiniMaxAgent::get_stop_token].
+    /// # Arguments
+    /// * `stop_token` - The flag that aborts the running search when set to true
+    pub fn with_stop_token(mut self, stop_token: Arc<AtomicBool>) -> MiniMaxAgent<H> {
+        self.stop_token = stop_token;
+        self
+    }
+
+    /// Sets a time budget per move, see [MiniMaxConfig::with_time_limit]
+    pub fn with_time_limit(mut self, time_limit: Duration) -> MiniMaxAgent<H> {
+        self.config = self.config.with_time_limit(time_limit);
+        self
+    }
+
+    /// Sets the quiescence policy, see [MiniMaxConfig::with_quiescence_policy]
+    pub fn with_quiescence_policy(
+        mut self,
+        policy: impl QuiescencePolicy + 'static,
+    ) -> MiniMaxAgent<H> {
+        self.config = self.config.with_quiescence_policy(policy);
+        self
+    }
+
+    /// Enables or disables move ordering, see [MiniMaxConfig::with_move_ordering]
+    pub fn with_move_ordering(mut self, move_ordering: bool) -> MiniMaxAgent<H> {
+        self.config = self.config.with_move_ordering(move_ordering);
+        self
+    }
+
+    /// Enables or disables symmetry-aware transposition table lookups, see [MiniMaxConfig::with_symmetric_transpositions]
+    pub fn with_symmetric_transpositions(
+        mut self,
+        symmetric_transpositions: bool,
+    ) -> MiniMaxAgent<H> {
+        self.config = self
+            .config
+            .with_symmetric_transpositions(symmetric_transpositions);
+        self
+    }
+
+    /// Enables or disables the verification of transposition table entries, see [MiniMaxConfig::with_hash_verification]
+    pub fn with_hash_verification(mut self, hash_verification: bool) -> MiniMaxAgent<H> {
+        self.config = self.config.with_hash_verification(hash_verification);
+        self
+    }
+
+    /// Enables aspiration windows, see [MiniMaxConfig::with_aspiration_window]
+    pub fn with_aspiration_window(mut self, window: f64) -> MiniMaxAgent<H> {
+        self.config = self.config.with_aspiration_window(window);
+        self
+    }
+
+    /// Sets the number of threads, see [MiniMaxConfig::with_threads]
+    pub fn with_threads(mut self, threads: u32) -> MiniMaxAgent<H> {
+        self.config = self.config.with_threads(threads);
+        self
+    }
+
+    /// Enables or disables root-level parallel search, see [MiniMaxConfig::with_root_parallel]
+    pub fn with_root_parallel(mut self, root_parallel: bool) -> MiniMaxAgent<H> {
+        self.config = self.config.with_root_parallel(root_parallel);
+        self
+    }
+
+    /// Enables or disables null move pruning, see [MiniMaxConfig::with_null_move_pruning]
+    pub fn with_null_move_pruning(mut self, null_move_pruning: bool) -> MiniMaxAgent<H> {
+        self.config = self.config.with_null_move_pruning(null_move_pruning);
+        self
+    }
+
+    /// Enables or disables forced move extensions, see [MiniMaxConfig::with_forced_move_extensions]
+    pub fn with_forced_move_extensions(mut self, forced_move_extensions: bool) -> MiniMaxAgent<H> {
+        self.config = self
+            .config
+            .with_forced_move_extensions(forced_move_extensions);
+        self
+    }
+
+    /// Sets a node budget per move, see [MiniMaxConfig::with_node_budget]
+    pub fn with_node_budget(mut self, node_budget: u64) -> MiniMaxAgent<H> {
+        self.config = self.config.with_node_budget(node_budget);
+        self
+    }
+
+    /// Enables randomized tie-breaking, see [MiniMaxConfig::with_tie_breaking]
+    pub fn with_tie_breaking(mut self, epsilon: f64) -> MiniMaxAgent<H> {
+        self.config = self.config.with_tie_breaking(epsilon);
+        self
+    }
+
+    /// Chooses the tie-breaking candidates by temperature, see [MiniMaxConfig::with_tie_breaking_temperature]
+    pub fn with_tie_breaking_temperature(mut self, temperature: f64) -> MiniMaxAgent<H> {
+        self.config = self.config.with_tie_breaking_temperature(temperature);
+        self
+    }
+
+    /// Seeds the random number generator used for tie-breaking, see [MiniMaxConfig::with_seed]
+    pub fn with_seed(mut self, seed: u64) -> MiniMaxAgent<H> {
+        self.config = self.config.with_seed(seed);
+        self.rng = ChaCha20Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Enables or disables pondering, see [MiniMaxConfig::with_pondering]
+    pub fn with_pondering(mut self, pondering: bool) -> MiniMaxAgent<H> {
+        self.config = self.config.with_pondering(pondering);
+        self
+    }
+
+    /// Enables the multi-PV analysis mode, see [MiniMaxConfig::with_multi_pv]
+    pub fn with_multi_pv(mut self, lines: usize) -> MiniMaxAgent<H> {
+        self.config = self.config.with_multi_pv(lines);
+        self
+    }
+
+    /// Probes a tablebase during the search, see [MiniMaxConfig::with_tablebase]
+    pub fn with_tablebase(mut self, tablebase: Arc<Tablebase>) -> MiniMaxAgent<H> {
+        self.config = self.config.with_tablebase(tablebase);
+        self
+    }
+
+    /// Returns the depth the given board should be searched with
+    ///
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// # Returns
+    /// The depth based on the [node budget](MiniMaxAgent::with_node_budget) if set, the [depth](MiniMaxConfig::depth) of the agent otherwise
+    fn get_depth(&self, board: UltimateBoard) -> u32 {
+        let Some(node_budget) = self.config.node_budget else {
+            return self.config.depth;
+        };
+
+        let branching_factor = board.get_possible_moves().count().max(2) as f64;
+
+        let depth = (node_budget as f64).ln() / branching_factor.ln();
+
+        (depth.floor() as u32).clamp(1, self.config.depth)
+    }
+
+    /// Stops the pondering thread, if any
+    ///
+    /// Blocks until the thread has finished.
+    fn stop_pondering(&mut self) {
+        self.ponder = None;
+    }
+
+    /// Searches the given board for the best move of the current player
+    ///
+    /// In contrast to [act](Agent::act), the whole result of the search is returned,
+    /// so the thinking of the engine can be displayed by analysis tools.
+    /// The evaluation is given from the perspective of the player to move.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// # Returns
+    /// The result of the search, None if there is no possible move
+    pub fn search(&mut self, board: UltimateBoard) -> Option<SearchResult> {
+        if board.get_game_status() != Continue {
+            return None;
+        }
+
+        self.stop_pondering();
+        self.thinking = None;
+
+        // A full table does not accept new positions, so it is reset between moves
+        // The table is not shared anymore, as the pondering thread has been stopped
+        if self.transposition_table.is_full() {
+            if let Some(table) = Arc::get_mut(&mut self.transposition_table) {
+                table.clear();
+            }
+        }
+
+        self.stop_token.store(false, Ordering::Relaxed);
+
+        let depth = self.get_depth(board);
+
+        let result = if self.config.root_parallel {
+            self.root_parallel(board, depth)
+        } else if self.config.threads > 1 {
+            self.lazy_smp(board, depth)
+        } else {
+            let mut context = SearchContext::new(&self.transposition_table);
+            context.stop_token = Some(&self.stop_token);
+
+            self.search_with_context(board, depth, &mut context)
+        };
+
+        // Only a search stopped by the stop token before any root move was searched completely has no result
+        let mut result = match result {
+            Some(result) => result,
+            None => {
+                let mut context = SearchContext::new(&self.transposition_table);
+
+                let (best_move, value) =
+                    self.get_best_move(board, 1, MIN_VALUE, MAX_VALUE, &mut context)?;
+
+                self.create_result(board, best_move, value, 1, &context)
+            }
+        };
+
+        if self.config.tie_breaking.is_some() {
+            self.break_tie(board, &mut result);
+        }
+
+        Some(result)
+    }
+
+    /// Chooses the played move among the candidates of the result
+    ///
+    /// The best move, value and principal variation of the result are replaced by the chosen candidate.
+    /// # Arguments
+    /// * `board` - The board the search was started on
+    /// * `result` - The result of the search
+    fn break_tie(&mut self, board: UltimateBoard, result: &mut SearchResult) {
+        if result.candidates.len() < 2 {
+            return;
+        }
+
+        let best_value = result.value;
+
+        let weights = result.candidates.iter().map(|&(_, value)| {
+            match self.config.tie_breaking_temperature {
+                Some(temperature) => ((value - best_value) / temperature).exp(),
+                None => 1.,
+            }
+        });
+
+        let Ok(distribution) = WeightedIndex::new(weights) else {
+            return;
+        };
+
+        let (chosen_move, value) = result.candidates[distribution.sample(&mut self.rng)];
+
+        if chosen_move != result.best_move {
+            result.best_move = chosen_move;
+            result.value = value;
+            result.principal_variation = self.get_principal_variation(
+                &self.transposition_table,
+                board,
+                chosen_move,
+                result.depth,
+            );
+        }
+    }
+
+    /// Searches the given board using the given context
+    ///
+    /// Uses [iterative deepening](MiniMaxAgent::iterative_deepening) if a time limit is set,
+    /// otherwise a single search with the given depth is done.
+    /// If the single search is stopped, the best completely searched root move is returned.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the search
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The result of the search
+    fn search_with_context(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        context: &mut SearchContext,
+    ) -> Option<SearchResult> {
+        match self.config.time_limit {
+            Some(time_limit) => self.iterative_deepening(board, depth, Some(time_limit), context),
+            None => {
+                let (best_move, value) = self
+                    .get_best_move(board, depth, MIN_VALUE, MAX_VALUE, context)
+                    .or(context.root_best)?;
+
+                Some(self.create_result(board, best_move, value, depth, context))
+            }
+        }
+    }
+
+    /// Creates the [SearchResult] of a completed search
+    ///
+    /// The [principal variation](https://www.chessprogramming.org/Principal_Variation) is collected
+    /// by following the best moves stored in the transposition table, starting with the best move.
+    /// # Arguments
+    /// * `board` - The board the search was started on
+    /// * `best_move` - The best move found by the search
+    /// * `value` - The value of the best move
+    /// * `depth` - The depth of the completed search
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The result of the search
+    fn create_result(
+        &self,
+        board: UltimateBoard,
+        best_move: u8,
+        value: f64,
+        depth: u32,
+        context: &SearchContext,
+    ) -> SearchResult {
+        SearchResult {
+            best_move,
+            value,
+            depth,
+            principal_variation: self.get_principal_variation(
+                context.transposition_table,
+                board,
+                best_move,
+                depth,
+            ),
+            statistics: context.statistics,
+            candidates: context.candidates.clone(),
+            lines: context
+                .lines
+                .iter()
+                .map(|&(root_move, value)| SearchLine {
+                    root_move,
+                    value,
+                    principal_variation: self.get_principal_variation(
+                        context.transposition_table,
+                        board,
+                        root_move,
+                        depth,
+                    ),
+                })
+                .collect(),
+        }
+    }
+
+    /// Collects the [principal variation](https://www.chessprogramming.org/Principal_Variation) starting with the given move
+    ///
+    /// The best moves stored in the transposition table are followed until the depth is reached.
+    /// # Arguments
+    /// * `transposition_table` - The transposition table filled by the search
+    /// * `board` - The board the search was started on
+    /// * `first_move` - The move made on the board
+    /// * `depth` - The maximum length of the principal variation
+    /// # Returns
+    /// The principal variation
+    fn get_principal_variation(
+        &self,
+        transposition_table: &TranspositionTable,
+        board: UltimateBoard,
+        first_move: u8,
+        depth: u32,
+    ) -> Vec<u8> {
+        let mut principal_variation = vec![first_move];
+        let root_player = board.get_current_player();
+
+        let mut board = board;
+        board.make_move(first_move);
+
+        while (principal_variation.len() as u32) < depth && board.get_game_status() == Continue {
+            match self
+                .probe(transposition_table, board, root_player)
+                .and_then(|entry| entry.best_move)
+            {
+                // The stored move may belong to another position in case of a hash collision
+                Some(index) if board.is_legal_move(index) => {
+                    board.make_move(index);
+                    principal_variation.push(index);
+                }
+                _ => break,
+            }
+        }
+
+        principal_variation
+    }
+
+    /// Returns the best move for the current player using multiple threads
+    ///
+    /// Implements [Lazy SMP](https://www.chessprogramming.org/Lazy_SMP):
+    /// helper threads search the same position and share the [transposition table](TranspositionTable) with the main thread.
+    /// Every second helper searches one ply deeper to diversify the stored entries.
+    /// The helpers are stopped as soon as the main thread has finished its search.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the search of the main thread
+    /// # Returns
+    /// The result of the search of the main thread, with the statistics of all threads
+    fn lazy_smp(&self, board: UltimateBoard, depth: u32) -> Option<SearchResult> {
+        let stop = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            let helpers: Vec<_> = (1..self.config.threads)
+                .map(|helper| {
+                    let stop = &stop;
+
+                    scope.spawn(move || {
+                        let mut context = SearchContext::new(&self.transposition_table);
+                        context.stop = Some(stop);
+
+                        self.iterative_deepening(
+                            board,
+                            depth + helper % 2,
+                            self.config.time_limit,
+                            &mut context,
+                        );
+
+                        context.statistics
+                    })
+                })
+                .collect();
+
+            let mut context = SearchContext::new(&self.transposition_table);
+            context.stop_token = Some(&self.stop_token);
+
+            let result =
+                self.iterative_deepening(board, depth, self.config.time_limit, &mut context);
+
+            stop.store(true, Ordering::Relaxed);
+
+            let mut result = result?;
+
+            for helper in helpers {
+                result.statistics += helper.join().unwrap_or_default();
+            }
+
+            Some(result)
+        })
+    }
+
+    /// Returns the best move for the current player by searching the root moves in parallel
+    ///
+    /// Uses iterative deepening if a time limit is set, see [MiniMaxAgent::search_root_moves] for a single iteration.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The maximum depth of the search
+    /// # Returns
+    /// The result of the last completed depth, with the statistics of all iterations
+    fn root_parallel(&self, board: UltimateBoard, depth: u32) -> Option<SearchResult> {
+        let deadline = self
+            .config
+            .time_limit
+            .map(|time_limit| Instant::now() + time_limit);
+
+        let first_depth = if deadline.is_some() { 1 } else { depth };
+
+        let mut result: Option<SearchResult> = None;
+
+        for current_depth in first_depth..=depth {
+            // The first iteration is not aborted by the deadline or the stop token to always have a move to play
+            let iteration_deadline = result.as_ref().and(deadline);
+            let stop_token = (result.is_some() || deadline.is_none()).then_some(&*self.stop_token);
+
+            match self.search_root_moves(board, current_depth, iteration_deadline, stop_token) {
+                Some(mut new_result) => {
+                    if let Some(previous_result) = result {
+                        new_result.statistics += previous_result.statistics;
+                    }
+                    result = Some(new_result);
+                }
+                None => break,
+            }
+        }
+
+        result
+    }
+
+    /// Searches every root move in its own [rayon] task
+    ///
+    /// Each task uses its own transposition table and searches its move with a full window.
+    /// Without a shared alpha no cutoffs happen at the root, but the tasks are completely independent.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the search
+    /// * `deadline` - The point in time the search is aborted at, None if the search is not time limited
+    /// * `stop_token` - The flag that aborts the search, None if the search cannot be stopped
+    /// # Returns
+    /// The result of the search, None if the search was aborted
+    fn search_root_moves(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        deadline: Option<Instant>,
+        stop_token: Option<&AtomicBool>,
+    ) -> Option<SearchResult> {
+        let possible_moves: Vec<u8> = board.get_possible_moves().collect();
+
+        let capacity = self.config.transposition_table_capacity / possible_moves.len();
+
+        let values: Vec<(u8, f64, Vec<u8>, SearchStatistics)> = possible_moves
+            .into_par_iter()
+            .map(|current_move| {
+                let table = TranspositionTable::new(capacity);
+                let mut context = SearchContext::new(&table);
+                context.deadline = deadline;
+                context.stop_token = stop_token;
+
+                let mut new_board = board;
+                new_board.make_move(current_move);
+
+                context.player = board.get_current_player();
+                context.evaluation =
+                    IncrementalEvaluation::new(&self.heuristic, new_board, context.player);
+
+                let value = self.minimax(
+                    new_board,
+                    depth - 1,
+                    1,
+                    false,
+                    true,
+                    MIN_VALUE,
+                    MAX_VALUE,
+                    &mut context,
+                )?;
+
+                let principal_variation =
+                    self.get_principal_variation(&table, board, current_move, depth);
+
+                Some((current_move, value, principal_variation, context.statistics))
+            })
+            .collect::<Option<_>>()?;
+
+        let mut statistics = SearchStatistics::default();
+        let mut best = 0;
+
+        for (i, (_, value, _, move_statistics)) in values.iter().enumerate() {
+            statistics += *move_statistics;
+
+            if *value > values[best].1 {
+                best = i;
+            }
+        }
+
+        let (best_move, value, principal_variation, _) = values[best].clone();
+
+        let candidates = match self.config.tie_breaking {
+            Some(epsilon) => values
+                .iter()
+                .filter(|(_, candidate_value, _, _)| *candidate_value >= value - epsilon)
+                .map(|(index, candidate_value, _, _)| (*index, *candidate_value))
+                .collect(),
+            None => vec![],
+        };
+
+        let lines = if self.config.multi_pv > 1 {
+            let mut lines: Vec<SearchLine> = values
+                .into_iter()
+                .map(|(root_move, value, principal_variation, _)| SearchLine {
+                    root_move,
+                    value,
+                    principal_variation,
+                })
+                .collect();
+
+            lines.sort_by(|a, b| b.value.total_cmp(&a.value));
+            lines.truncate(self.config.multi_pv);
+            lines
+        } else {
+            vec![]
+        };
+
+        Some(SearchResult {
+            best_move,
+            value,
+            depth,
+            principal_variation,
+            statistics,
+            candidates,
+            lines,
+        })
+    }
+
+    /// Returns the best move for the current player using iterative deepening
+    ///
+    /// Searches with increasing depth until the time limit is exceeded or the maximum depth is completed.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `max_depth` - The maximum depth of the search
+    /// * `time_limit` - The time budget for the move, None if all depths should be completed
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The result of the last completed depth
+    fn iterative_deepening(
+        &self,
+        board: UltimateBoard,
+        max_depth: u32,
+        time_limit: Option<Duration>,
+        context: &mut SearchContext,
+    ) -> Option<SearchResult> {
+        let deadline = time_limit.map(|time_limit| Instant::now() + time_limit);
+
+        // The first iteration is not aborted by the deadline or the stop token to always have a move to play
+        let stop_token = context.stop_token.take();
+
+        let (mut best_move, mut value) =
+            self.get_best_move(board, 1, MIN_VALUE, MAX_VALUE, context)?;
+        let mut completed_depth = 1;
+
+        context.deadline = deadline;
+        context.stop_token = stop_token;
+
+        for depth in 2..=max_depth {
+            let result = match self.config.aspiration_window {
+                Some(window) => self.aspiration_search(board, depth, value, window, context),
+                None => self.get_best_move(board, depth, MIN_VALUE, MAX_VALUE, context),
+            };
+
+            match result {
+                Some((index, new_value)) => {
+                    best_move = index;
+                    value = new_value;
+                    completed_depth = depth;
+                }
+                None => break,
+            }
+        }
+
+        Some(self.create_result(board, best_move, value, completed_depth, context))
+    }
+
+    /// Searches the root with an [aspiration window](https://www.chessprogramming.org/Aspiration_Windows)
+    ///
+    /// The search starts with a narrow window around the value of the previous iteration.
+    /// If the value falls outside the window, the window is doubled and the search is repeated.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the minimax algorithm
+    /// * `previous_value` - The value of the previous iteration
+    /// * `window` - The initial distance of alpha and beta to the previous value
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The index of the field to play on and its value, None if the search was aborted
+    fn aspiration_search(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        previous_value: f64,
+        mut window: f64,
+        context: &mut SearchContext,
+    ) -> Option<(u8, f64)> {
+        // Won or lost positions are too close to the limits for a window
+        // The values of multi-PV lines outside of the window would not be exact
+        if is_proven(previous_value) || self.config.multi_pv > 1 {
+            return self.get_best_move(board, depth, MIN_VALUE, MAX_VALUE, context);
+        }
+
+        loop {
+            let alpha = f64::max(previous_value - window, MIN_VALUE);
+            let beta = f64::min(previous_value + window, MAX_VALUE);
+
+            let (best_move, value) = self.get_best_move(board, depth, alpha, beta, context)?;
+
+            let failed_low = value <= alpha && alpha > MIN_VALUE;
+            let failed_high = value >= beta && beta < MAX_VALUE;
+
+            if !failed_low && !failed_high {
+                return Some((best_move, value));
+            }
+
+            window *= 2.;
+        }
+    }
+
+    /// Returns the best move for the current player
+    ///
+    /// The minimax algorithm is used to determine the best move.
+    ///
+    /// This is the root call for the minimax algorithm.
+    ///
+    /// For more info see [`MiniMaxAgent::minimax`]
+    ///
+    /// If [tie-breaking](MiniMaxAgent::with_tie_breaking) is enabled, the moves are searched with alpha lowered by the margin,
+    /// so all candidates get exact values. The candidates are stored in the context.
+    ///
+    /// The best move among the completely searched moves is stored in the context as well, so a stopped search still has a move to play.
+    ///
+    /// In [multi-PV](MiniMaxConfig::with_multi_pv) mode, the moves are searched with alpha lowered to the value of the worst of the best lines,
+    /// so all lines get exact values. The lines are stored in the context.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the minimax algorithm
+    /// * `alpha` - The alpha value for alpha-beta pruning
+    /// * `beta` - The beta value for alpha-beta pruning
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The index of the field to play on and its value, None if the search was aborted
+    fn get_best_move(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        mut alpha: f64,
+        beta: f64,
+        context: &mut SearchContext,
+    ) -> Option<(u8, f64)> {
+        let possible_moves = self.order_moves(board, 0, context);
+
+        let mut best_move = possible_moves[0];
+
+        let original_alpha = alpha;
+
+        let margin = self
+            .config
+            .tie_breaking
+            .map(|epsilon| epsilon + TIE_BREAKING_TOLERANCE);
+
+        let mut values = vec![];
+
+        context.root_best = None;
+        context.player = board.get_current_player();
+        context.evaluation = IncrementalEvaluation::new(&self.heuristic, board, context.player);
+
+        // Iterate over all possible moves
+        // Maximizing
+        for current_move in possible_moves {
+            let mut new_board = board;
+
+            new_board.make_move(current_move);
+            self.make_move(new_board, current_move, context);
+
+            let mut child_alpha = alpha - margin.unwrap_or(0.);
+
+            if self.config.multi_pv > 1 {
+                child_alpha = child_alpha.min(self.get_multi_pv_alpha(&values, original_alpha));
+            }
+
+            let value = self.minimax(
+                new_board,
+                depth - 1,
+                1,
+                false,
+                true,
+                child_alpha,
+                beta,
+                context,
+            );
+
+            self.unmake_move(context);
+
+            let value = value?;
+
+            // Values at the lower bound of the window are only upper bounds of the real value
+            if value > child_alpha {
+                values.push((current_move, value));
+            }
+
+            if value > alpha {
+                alpha = value;
+                best_move = current_move;
+            }
+
+            context.root_best = Some((best_move, alpha));
+
+            // In multi-PV mode the remaining root moves are searched for their lines, even after a winning move
+            if alpha >= beta && self.config.multi_pv == 1 {
+                break;
+            }
+        }
+
+        if let Some(epsilon) = self.config.tie_breaking {
+            context.candidates = values
+                .iter()
+                .copied()
+                .filter(|&(_, value)| value >= alpha - epsilon)
+                .collect();
+        }
+
+        if self.config.multi_pv > 1 {
+            values.sort_by(|a, b| b.1.total_cmp(&a.1));
+            values.truncate(self.config.multi_pv);
+            context.lines = values;
+        }
+
+        self.store(
+            context.transposition_table,
+            board,
+            context.player,
+            TranspositionEntry {
+                depth,
+                value: alpha,
+                flag: EntryFlag::from_bounds(alpha, original_alpha, beta),
+                best_move: Some(best_move),
+            },
+        );
+
+        Some((best_move, alpha))
+    }
+
+    /// Returns the alpha value root moves are searched with in [multi-PV](MiniMaxConfig::with_multi_pv) mode
+    ///
+    /// # Arguments
+    /// * `values` - The exact values of the already searched root moves
+    /// * `alpha` - The alpha value the root search was started with
+    /// # Returns
+    /// The value of the worst of the best lines, the given alpha if there are not enough lines yet
+    fn get_multi_pv_alpha(&self, values: &[(u8, f64)], alpha: f64) -> f64 {
+        if values.len() < self.config.multi_pv {
+            return alpha;
+        }
+
+        let mut sorted_values: Vec<f64> = values.iter().map(|&(_, value)| value).collect();
+        sorted_values.sort_by(|a, b| b.total_cmp(a));
+
+        sorted_values[self.config.multi_pv - 1]
+    }
+
+    /// The minimax algorithm
+    ///
+    /// Alpha-beta pruning is used to reduce the number of nodes that need to be evaluated.
+    ///
+    /// A [transposition table](https://www.chessprogramming.org/Transposition_Table) is used to store the values of already evaluated nodes.
+    /// Stored bounds are used to narrow the alpha-beta window, only exact values are returned directly.
+    ///
+    /// Calls [MiniMaxAgent::quiescence_search] if the depth is 0.
+    ///
+    /// If enabled, [null move pruning](MiniMaxAgent::null_move_cutoff) is tried before the moves are searched.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the minimax algorithm
+    /// * `ply` - The distance to the root of the search
+    /// * `maximizing` - Whether the current player is maximizing
+    /// * `null_move_allowed` - Whether a null move may be made, false directly after a null move
+    /// * `alpha` - The alpha value for alpha-beta pruning
+    /// * `beta` - The beta value for alpha-beta pruning
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The value of the current state, None if the search was aborted
+    #[allow(clippy::too_many_arguments)]
+    fn minimax(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        ply: usize,
+        maximizing: bool,
+        null_move_allowed: bool,
+        mut alpha: f64,
+        mut beta: f64,
+        context: &mut SearchContext,
+    ) -> Option<f64> {
+        if context.is_aborted() {
+            return None;
+        }
+
+        // Positions close to the end of the game are not searched if their result is known
+        let tablebase_result = self
+            .config
+            .tablebase
+            .as_ref()
+            .filter(|_| board.get_game_status() == Continue)
+            .and_then(|tablebase| tablebase.probe(board));
+
+        if let Some(result) = tablebase_result {
+            context.statistics.tablebase_hits += 1;
+
+            return Some(match result {
+                GameResult::Win(player) if (player == board.get_current_player()) == maximizing => {
+                    MAX_VALUE
+                }
+                GameResult::Win(_) => MIN_VALUE,
+                _ => 0.,
+            });
+        }
+
+        if depth == 0 {
+            return Some(self.quiescence_search(
+                board,
+                self.config.quiescence_search_depth,
+                ply,
+                maximizing,
+                alpha,
+                beta,
+                context,
+            ));
+        }
+
+        context.statistics.visit(ply);
+
+        if board.get_game_status() != Continue {
+            return Some(self.evaluate(board, context));
+        }
+
+        // Check if the board is in the transposition table
+        if let Some(entry) = self.probe(context.transposition_table, board, context.player) {
+            if entry.depth >= depth {
+                context.statistics.tt_hits += 1;
+
+                match entry.flag {
+                    EntryFlag::Exact => return Some(entry.value),
+                    EntryFlag::LowerBound => alpha = f64::max(alpha, entry.value),
+                    EntryFlag::UpperBound => beta = f64::min(beta, entry.value),
+                }
+
+                if alpha >= beta {
+                    return Some(entry.value);
+                }
+            }
+        }
+
+        if null_move_allowed {
+            if let Some(value) =
+                self.null_move_cutoff(board, depth, ply, maximizing, alpha, beta, context)?
+            {
+                return Some(value);
+            }
+        }
+
+        let original_alpha = alpha;
+        let original_beta = beta;
+
+        let mut best_move = None;
+        let mut best_value = if maximizing { MIN_VALUE } else { MAX_VALUE };
+
+        let possible_moves = self.order_moves(board, ply, context);
+
+        let extension = u32::from(
+            self.config.forced_move_extensions
+                && ply < self.config.depth as usize * MAX_EXTENSION_PLY_FACTOR
+                && (possible_moves.len() == 1 || MiniMaxAgent::<H>::is_threatened(board)),
+        );
+
+        for current_move in possible_moves {
+            let mut new_board = board;
+            new_board.make_move(current_move);
+            self.make_move(new_board, current_move, context);
+
+            let value = self.minimax(
+                new_board,
+                depth - 1 + extension,
+                ply + 1,
+                !maximizing,
+                true,
+                alpha,
+                beta,
+                context,
+            );
+
+            self.unmake_move(context);
+
+            let value = value?;
+
+            if maximizing {
+                if best_move.is_none() || value > best_value {
+                    best_value = value;
+                    best_move = Some(current_move);
+                }
+                alpha = f64::max(alpha, value);
+            } else {
+                if best_move.is_none() || value < best_value {
+                    best_value = value;
+                    best_move = Some(current_move);
+                }
+                beta = f64::min(beta, value);
+            }
+
+            if alpha >= beta {
+                context.statistics.beta_cutoffs += 1;
+
+                if self.config.move_ordering {
+                    context.move_ordering.record_cutoff(
+                        current_move,
+                        ply,
+                        depth,
+                        board.get_current_player(),
+                    );
+                }
+                break;
+            }
+        }
+
+        let value = if maximizing { alpha } else { beta };
+
+        self.store(
+            context.transposition_table,
+            board,
+            context.player,
+            TranspositionEntry {
+                depth,
+                value,
+                flag: EntryFlag::from_bounds(value, original_alpha, original_beta),
+                best_move,
+            },
+        );
+
+        Some(value)
+    }
+
+    /// Tries to prune the node using [null move pruning](https://www.chessprogramming.org/Null_Move_Pruning)
+    ///
+    /// The current player passes and the position is searched with the depth reduced by [NULL_MOVE_REDUCTION].
+    /// If the value still causes a cutoff, the node is pruned without searching its moves.
+    ///
+    /// Null moves are not made if null move pruning is disabled, the remaining depth is too small
+    /// or less than [NULL_MOVE_MIN_OPEN_BOARDS] small boards are open.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the minimax algorithm
+    /// * `ply` - The distance to the root of the search
+    /// * `maximizing` - Whether the current player is maximizing
+    /// * `alpha` - The alpha value for alpha-beta pruning
+    /// * `beta` - The beta value for alpha-beta pruning
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The value of the node if it can be pruned, None inside the option otherwise, None if the search was aborted
+    #[allow(clippy::too_many_arguments)]
+    fn null_move_cutoff(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        ply: usize,
+        maximizing: bool,
+        alpha: f64,
+        beta: f64,
+        context: &mut SearchContext,
+    ) -> Option<Option<f64>> {
+        if !self.config.null_move_pruning || depth <= NULL_MOVE_REDUCTION {
+            return Some(None);
+        }
+
+        let decided_boards = board.won_boards_mask(Player::One)
+            | board.won_boards_mask(Player::Two)
+            | board.drawn_boards_mask();
+
+        if 9 - decided_boards.into_iter().count() < NULL_MOVE_MIN_OPEN_BOARDS {
+            return Some(None);
+        }
+
+        let mut new_board = board;
+        new_board.make_null_move();
+
+        let value = self.minimax(
+            new_board,
+            depth - 1 - NULL_MOVE_REDUCTION,
+            ply + 1,
+            !maximizing,
+            false,
+            alpha,
+            beta,
+            context,
+        )?;
+
+        Some(if maximizing && value >= beta {
+            Some(beta)
+        } else if !maximizing && value <= alpha {
+            Some(alpha)
+        } else {
+            None
+        })
+    }
+
+    /// Checks if the opponent threatens to win the game with the next move
+    ///
+    /// The threat is detected by letting the current player pass with a [null move](UltimateBoard::make_null_move)
+    /// and checking if any move of the opponent wins the game.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// # Returns
+    /// Whether the current player has to respond to a threat
+    fn is_threatened(board: UltimateBoard) -> bool {
+        let opponent = board.get_current_player().get_opponent();
+
+        let mut passed_board = board;
+        passed_board.make_null_move();
+
+        passed_board.get_possible_moves().any(|index| {
+            let mut new_board = passed_board;
+            new_board.make_move(index);
+            new_board.get_game_status() == GameResult::Win(opponent)
+        })
+    }
+
+    /// Evaluates the board with the heuristic for the player to move at the root of the search
+    ///
+    /// Uses the [incremental evaluation](IncrementalEvaluation) of the search if the heuristic supports it.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The value of the board
+    fn evaluate(&self, board: UltimateBoard, context: &SearchContext) -> f64 {
+        match &context.evaluation {
+            Some(evaluation) => evaluation.evaluate(&self.heuristic, board),
+            None => self.heuristic.evaluate_for(board, context.player),
+        }
+    }
+
+    /// Updates the incremental evaluation of the search after a move, if any
+    ///
+    /// Every call must be followed by a call of [MiniMaxAgent::unmake_move] after the position has been searched.
+    /// # Arguments
+    /// * `board` - The board after the move
+    /// * `index` - The index of the move
+    /// * `context` - The state of the search
+    fn make_move(&self, board: UltimateBoard, index: u8, context: &mut SearchContext) {
+        if let Some(evaluation) = &mut context.evaluation {
+            evaluation.make_move(&self.heuristic, board, index);
+        }
+    }
+
+    /// Restores the incremental evaluation of the search before the last move, if any
+    fn unmake_move(&self, context: &mut SearchContext) {
+        if let Some(evaluation) = &mut context.evaluation {
+            evaluation.unmake_move();
+        }
+    }
+
+    /// Looks up the board in the transposition table
+    ///
+    /// If [symmetric transpositions](MiniMaxConfig::with_symmetric_transpositions) are enabled,
+    /// the canonical hash is used and the stored best move is transformed back to the orientation of the board.
+    /// If the [hash verification](MiniMaxConfig::with_hash_verification) is enabled, entries of other positions are not returned.
+    /// # Arguments
+    /// * `transposition_table` - The transposition table to look up the board in
+    /// * `board` - The current state of the board
+    /// * `root_player` - The player to move at the root of the search
+    /// # Returns
+    /// The entry of the board, if any
+    fn probe(
+        &self,
+        transposition_table: &TranspositionTable,
+        board: UltimateBoard,
+        root_player: Player,
+    ) -> Option<TranspositionEntry> {
+        let (hash, symmetry) = self.get_table_key(board, root_player);
+
+        let entry = if self.config.hash_verification {
+            transposition_table.probe_verified(hash, board.get_fingerprint(symmetry))
+        } else {
+            transposition_table.probe(hash)
+        }?;
+
+        if !self.config.symmetric_transpositions {
+            return Some(entry);
+        }
+
+        let inverse = UltimateBoard::inverse_symmetry(symmetry);
+
+        Some(TranspositionEntry {
+            best_move: entry
+                .best_move
+                .map(|index| UltimateBoard::transform_move(index, inverse)),
+            ..entry
+        })
+    }
+
+    /// Stores the entry of the board in the transposition table
+    ///
+    /// If [symmetric transpositions](MiniMaxConfig::with_symmetric_transpositions) are enabled,
+    /// the entry is stored under the canonical hash with the best move in the orientation of the canonical board.
+    /// If the [hash verification](MiniMaxConfig::with_hash_verification) is enabled, the entry is stored with the fingerprint of the board.
+    /// # Arguments
+    /// * `transposition_table` - The transposition table to store the entry in
+    /// * `board` - The current state of the board
+    /// * `root_player` - The player to move at the root of the search
+    /// * `entry` - The entry of the board
+    fn store(
+        &self,
+        transposition_table: &TranspositionTable,
+        board: UltimateBoard,
+        root_player: Player,
+        entry: TranspositionEntry,
+    ) {
+        let (hash, symmetry) = self.get_table_key(board, root_player);
+
+        let entry = if self.config.symmetric_transpositions {
+            TranspositionEntry {
+                best_move: entry
+                    .best_move
+                    .map(|index| UltimateBoard::transform_move(index, symmetry)),
+                ..entry
+            }
+        } else {
+            entry
+        };
+
+        if self.config.hash_verification {
+            transposition_table.store_verified(hash, board.get_fingerprint(symmetry), entry);
+        } else {
+            transposition_table.store(hash, entry);
+        }
+    }
+
+    /// Returns the key of the board in the transposition table
+    ///
+    /// The hash is combined with [ROOT_PLAYER_TWO_KEY] if [Player::Two] is the root player.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `root_player` - The player to move at the root of the search
+    /// # Returns
+    /// The canonical hash and symmetry of the board if [symmetric transpositions](MiniMaxConfig::with_symmetric_transpositions) are enabled,
+    /// otherwise the hash of the board and the identity
+    fn get_table_key(&self, board: UltimateBoard, root_player: Player) -> (u64, usize) {
+        let (hash, symmetry) = if self.config.symmetric_transpositions {
+            board.get_canonical_hash()
+        } else {
+            (board.get_hash(), 0)
+        };
+
+        match root_player {
+            Player::One => (hash, symmetry),
+            Player::Two => (hash ^ ROOT_PLAYER_TWO_KEY, symmetry),
+        }
+    }
+
+    /// Returns the possible moves of the board in the order they should be searched
+    ///
+    /// If [move ordering](MiniMaxAgent::with_move_ordering) is disabled, the moves are returned in the order of [UltimateBoard::get_possible_moves].
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `ply` - The distance to the root of the search
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The possible moves of the board
+    fn order_moves(&self, board: UltimateBoard, ply: usize, context: &SearchContext) -> Vec<u8> {
+        if !self.config.move_ordering {
+            return board.get_possible_moves().collect();
+        }
+
+        let table_move = self
+            .probe(context.transposition_table, board, context.player)
+            .and_then(|entry| entry.best_move);
+
+        context.move_ordering.order(
+            board.get_possible_moves(),
+            table_move,
+            ply,
+            board.get_current_player(),
+        )
+    }
+
+    /// The [quiescence search](https://www.chessprogramming.org/Quiescence_Search) algorithm
+    ///
+    /// This algorithm is used to avoid the [horizon effect](https://www.chessprogramming.org/Horizon_Effect).
+    ///
+    /// Only continues searching with the noisy moves defined by the [quiescence policy](MiniMaxAgent::with_quiescence_policy).
+    /// If only some of the possible moves are noisy, the player may also make a quiet move,
+    /// so the heuristic value of the position is used as a bound ("stand pat").
+    ///
+    /// If the depth is 0, the [heuristic](MiniMaxAgent::heuristic) is used to evaluate the board.
+    /// the quiescence search can be disabled by setting [quiescence_search_depth](MiniMaxConfig::quiescence_search_depth) to 0.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `depth` - The depth of the quiescence search algorithm
+    /// * `ply` - The distance to the root of the search
+    /// * `maximizing` - Whether the current player is maximizing
+    /// * `alpha` - The alpha value for alpha-beta pruning
+    /// * `beta` - The beta value for alpha-beta pruning
+    /// * `context` - The state of the search
+    /// # Returns
+    /// The value of the current state
+    #[allow(clippy::too_many_arguments)]
+    fn quiescence_search(
+        &self,
+        board: UltimateBoard,
+        depth: u32,
+        ply: usize,
+        maximizing: bool,
+        mut alpha: f64,
+        mut beta: f64,
+        context: &mut SearchContext,
+    ) -> f64 {
+        context.statistics.visit(ply);
+        context.statistics.quiescence_nodes += 1;
+
+        if depth == 0 {
+            return self.evaluate(board, context);
+        }
+
+        if board.get_game_status() != Continue {
+            return self.evaluate(board, context);
+        }
+
+        let possible_moves = match &self.config.quiescence_policy {
+            Some(policy) => policy.get_noisy_moves(board),
+            None => FreeChoicePolicy.get_noisy_moves(board),
+        };
+
+        if possible_moves.is_empty() {
+            return self.evaluate(board, context);
+        }
+
+        if possible_moves.len() < board.get_possible_moves().count() {
+            let stand_pat = self.evaluate(board, context);
+
+            if maximizing {
+                alpha = f64::max(alpha, stand_pat);
+            } else {
+                beta = f64::min(beta, stand_pat);
+            }
+
+            if alpha >= beta {
+                return if maximizing { alpha } else { beta };
+            }
+        }
+
+        if maximizing {
+            for current_move in possible_moves {
+                let mut new_board = board;
+                new_board.make_move(current_move);
+                self.make_move(new_board, current_move, context);
+
+                let value = self.quiescence_search(
+                    new_board,
+                    depth - 1,
+                    ply + 1,
+                    false,
+                    alpha,
+                    beta,
+                    context,
+                );
+
+                self.unmake_move(context);
+
+                alpha = f64::max(alpha, value);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+            alpha
+        } else {
+            for current_move in possible_moves {
+                let mut new_board = board;
+                new_board.make_move(current_move);
+                self.make_move(new_board, current_move, context);
+
+                let value = self.quiescence_search(
+                    new_board,
+                    depth - 1,
+                    ply + 1,
+                    true,
+                    alpha,
+                    beta,
+                    context,
+                );
+
+                self.unmake_move(context);
+
+                beta = f64::min(beta, value);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+            beta
+        }
+    }
+}
+
+impl<H: Heuristic + 'static> MiniMaxAgent<H> {
+    /// Starts pondering on the predicted reply of the opponent
+    ///
+    /// The board after the predicted reply is searched in a background thread up to the [depth](MiniMaxConfig::depth)
+    /// of the agent, sharing the transposition table.
+    /// # Arguments
+    /// * `board` - The board the search was started on
+    /// * `result` - The result of the search, containing the predicted reply
+    fn start_pondering(&mut self, board: UltimateBoard, result: &SearchResult) {
+        let [own_move, predicted_reply, ..] = result.principal_variation[..] else {
+            return;
+        };
+
+        let mut board = board;
+        board.make_move(own_move);
+        board.make_move(predicted_reply);
+
+        if board.get_game_status() != Continue {
+            return;
+        }
+
+        let searcher = self.background_searcher();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+            let mut context = SearchContext::new(&searcher.transposition_table);
+            context.stop = Some(&thread_stop);
+
+            searcher.iterative_deepening(board, searcher.config.depth, None, &mut context);
+        });
+
+        self.ponder = Some(Ponder {
+            stop,
+            handle: Some(handle),
+        });
+    }
+
+    /// Creates a single threaded copy of the agent for a search in a background thread
+    ///
+    /// The copy shares the transposition table with the agent.
+    fn background_searcher(&self) -> MiniMaxAgent<H> {
+        MiniMaxAgent {
+            config: MiniMaxConfig {
+                time_limit: None,
+                threads: 1,
+                node_budget: None,
+                tie_breaking: None,
+                pondering: false,
+                ..self.config.clone()
+            },
+            heuristic: self.heuristic.clone(),
+            rng: ChaCha20Rng::from_entropy(),
+            transposition_table: Arc::clone(&self.transposition_table),
+            ponder: None,
+            thinking: None,
+            last_statistics: None,
+            last_value: None,
+            stop_token: Arc::new(AtomicBool::new(false)),
+            player: self.player,
+            turn: self.turn,
+        }
+    }
+}
+
+/// Creates a fresh agent with the same configuration and heuristic
+///
+/// The transposition table, the running searches and the stop token are not shared with the clone.
+impl<H: Heuristic> Clone for MiniMaxAgent<H> {
+    fn clone(&self) -> Self {
+        MiniMaxAgent::from_config(self.config.clone(), self.heuristic.clone())
+    }
+}
+
+impl<H: Heuristic + 'static> Agent for MiniMaxAgent<H> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let result = self.search(*board).ok_or(AgentError::NoMove)?;
+
+        self.last_statistics = Some(result.statistics);
+        self.last_value = Some(result.value);
+
+        if self.config.pondering {
+            self.start_pondering(*board, &result);
+        }
+
+        Ok(result.best_move)
+    }
+
+    fn opponent_moved(&mut self, _board: UltimateBoard, _index: u8) {
+        self.stop_pondering();
+    }
+
+    /// Clears the transposition table of the previous game
+    fn new_game(&mut self, _player: Player) {
+        self.stop_pondering();
+        self.thinking = None;
+
+        if let Some(table) = Arc::get_mut(&mut self.transposition_table) {
+            table.clear();
+        }
+    }
+
+    fn game_over(&mut self, _board: UltimateBoard, _result: GameResult) {
+        self.stop_pondering();
+        self.thinking = None;
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let mut config = format!(
+            "{}+{}",
+            self.config.depth, self.config.quiescence_search_depth
+        );
+
+        if let Some(time_limit) = self.config.time_limit {
+            config += &format!(" ({}ms)", time_limit.as_millis());
+        }
+
+        if self.config.threads > 1 {
+            config += &format!(" ({} threads)", self.config.threads);
+        }
+
+        if self.config.null_move_pruning {
+            config += " (NMP)";
+        }
+
+        if self.config.pondering {
+            config += " (ponder)";
+        }
+
+        if self.config.forced_move_extensions {
+            config += " (ext)";
+        }
+
+        if let Some(epsilon) = self.config.tie_breaking {
+            config += &format!(" (tie {})", epsilon);
+        }
+
+        if self.config.multi_pv > 1 {
+            config += &format!(" ({} PV)", self.config.multi_pv);
+        }
+
+        if self.config.symmetric_transpositions {
+            config += " (sym)";
+        }
+
+        if self.config.hash_verification {
+            config += " (verify)";
+        }
+
+        if self.config.root_parallel {
+            config += " (root parallel)";
+        }
+
+        if let Some(node_budget) = self.config.node_budget {
+            config += &format!(" ({} nodes)", node_budget);
+        }
+
+        if let Some(policy) = &self.config.quiescence_policy {
+            config += &format!(" (qs: {})", policy.get_name());
+        }
+
+        if let Some(tablebase) = &self.config.tablebase {
+            config += &format!(" (tb: {})", tablebase.get_max_empty_squares());
+        }
+
+        let mut info = AgentInfo::new(self.heuristic.get_name(), self.player, self.turn, config);
+
+        if let Some(statistics) = self.last_statistics {
+            info = info.with_statistics(statistics);
+        }
+
+        if let Some(value) = self.last_value {
+            info = info.with_evaluation(value);
+        }
+
+        info
+    }
+}
+
+impl<H: Heuristic + 'static> AnytimeAgent for MiniMaxAgent<H> {
+    /// Starts an iterative deepening search up to the [depth](MiniMaxConfig::depth) of the agent
+    fn start_thinking(&mut self, board: UltimateBoard) {
+        self.stop_pondering();
+        self.thinking = None;
+
+        if board.get_game_status() != Continue {
+            return;
+        }
+
+        self.player = board.get_current_player();
+
+        let searcher = self.background_searcher();
+
+        let best_move = Arc::new(Mutex::new(None));
+        let thread_best_move = Arc::clone(&best_move);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+            let mut context = SearchContext::new(&searcher.transposition_table);
+            context.stop = Some(&thread_stop);
+
+            for depth in 1..=searcher.config.depth {
+                let Some(result) =
+                    searcher.get_best_move(board, depth, MIN_VALUE, MAX_VALUE, &mut context)
+                else {
+                    break;
+                };
+
+                *thread_best_move.lock().unwrap() = Some(result);
+            }
+        });
+
+        self.thinking = Some(Thinking {
+            board,
+            best_move,
+            ponder: Ponder {
+                stop,
+                handle: Some(handle),
+            },
+        });
+    }
+
+    /// Returns the best move of the last completed depth
+    fn best_move_so_far(&self) -> Option<u8> {
+        let thinking = self.thinking.as_ref()?;
+        let best_move = *thinking.best_move.lock().unwrap();
+
+        best_move.map(|(index, _)| index)
+    }
+
+    /// Returns the best move of the last completed depth, or of a search with depth 1 if no depth was completed
+    fn stop(&mut self) -> Option<u8> {
+        let Thinking {
+            board,
+            best_move,
+            ponder,
+        } = self.thinking.take()?;
+
+        drop(ponder);
+
+        let best_move = *best_move.lock().unwrap();
+
+        let (best_move, value) = match best_move {
+            Some(result) => result,
+            None => {
+                let mut context = SearchContext::new(&self.transposition_table);
+                self.get_best_move(board, 1, MIN_VALUE, MAX_VALUE, &mut context)?
+            }
+        };
+
+        self.last_value = Some(value);
+
+        Some(best_move)
+    }
+}
+
+/// # Struct representing a running [anytime](AnytimeAgent) search
+struct Thinking {
+    /// The board the search was started on
+    board: UltimateBoard,
+    /// The best move of the last completed depth and its value
+    best_move: Arc<Mutex<Option<(u8, f64)>>>,
+    /// The thread of the search, stopped when dropped
+    ponder: Ponder,
+}
+
+/// # Struct representing a running pondering thread
+///
+/// The thread is stopped when the struct is dropped.
+struct Ponder {
+    /// Flag to abort the search of the thread
+    stop: Arc<AtomicBool>,
+    /// The handle of the thread, None once the thread has been joined
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for Ponder {
+    /// Stops the thread and waits until it has finished
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// # Struct containing the state of a single search
+///
+/// The context is created for every call of [act](Agent::act) and passed to all nodes of the search.
+struct SearchContext<'a> {
+    /// The transposition table of the agent, possibly shared with other threads
+    transposition_table: &'a TranspositionTable,
+    /// The killer moves and history scores of the search
+    move_ordering: MoveOrdering,
+    /// The point in time the search is aborted at, None if the search is not time limited
+    deadline: Option<Instant>,
+    /// Flag set by another thread to abort the search
+    stop: Option<&'a AtomicBool>,
+    /// The [stop token](MiniMaxAgent::get_stop_token) of the agent
+    stop_token: Option<&'a AtomicBool>,
+    /// The counters of the search
+    statistics: SearchStatistics,
+    /// The candidates of the last completed root search, only collected if tie-breaking is enabled
+    candidates: Vec<(u8, f64)>,
+    /// The best moves of the last completed root search and their values, only collected in multi-PV mode
+    lines: Vec<(u8, f64)>,
+    /// The best completely searched root move of the current root search and its value
+    root_best: Option<(u8, f64)>,
+    /// The player the positions are evaluated for, the player to move at the root of the search
+    player: Player,
+    /// The evaluations of the small boards along the current path, None if the heuristic does not support incremental evaluation
+    evaluation: Option<IncrementalEvaluation>,
+}
+
+impl<'a> SearchContext<'a> {
+    /// Creates a new [SearchContext] without a deadline or stop flag
+    ///
+    /// # Arguments
+    /// * `transposition_table` - The transposition table used by the search
+    fn new(transposition_table: &'a TranspositionTable) -> Self {
+        SearchContext {
+            transposition_table,
+            move_ordering: MoveOrdering::new(),
+            deadline: None,
+            stop: None,
+            stop_token: None,
+            statistics: SearchStatistics::default(),
+            candidates: vec![],
+            lines: vec![],
+            root_best: None,
+            player: Player::default(),
+            evaluation: None,
+        }
+    }
+
+    /// Checks if the search should be aborted
+    fn is_aborted(&self) -> bool {
+        self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+            || self
+                .stop_token
+                .is_some_and(|stop_token| stop_token.load(Ordering::Relaxed))
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use rand::seq::IteratorRandom;
+
+    #[test]
+    fn test_time_limit() {
+        let mut agent = MiniMaxAgent::new(20, 1, CustomHeuristic::new(Player::One))
+            .with_time_limit(Duration::from_millis(100));
+
+        let board = UltimateBoard::new();
+
+        let start = Instant::now();
+        let best_move = agent.act(&board, Player::One, 0);
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(best_move.is_ok_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_aspiration_window() {
+        let agent = MiniMaxAgent::new(3, 0, CustomHeuristic::new(Player::One));
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        board.make_move(36);
+
+        let table = TranspositionTable::default();
+        let mut context = SearchContext::new(&table);
+        let (_, previous_value) = agent
+            .get_best_move(board, 2, MIN_VALUE, MAX_VALUE, &mut context)
+            .unwrap();
+
+        let table = TranspositionTable::default();
+        let mut context = SearchContext::new(&table);
+        let (_, full_value) = agent
+            .get_best_move(board, 3, MIN_VALUE, MAX_VALUE, &mut context)
+            .unwrap();
+
+        let table = TranspositionTable::default();
+        let mut context = SearchContext::new(&table);
+        let (_, aspiration_value) = agent
+            .aspiration_search(board, 3, previous_value, 0.5, &mut context)
+            .unwrap();
+
+        assert_eq!(full_value, aspiration_value);
+    }
+
+    #[test]
+    fn test_search_result() {
+        let mut agent = MiniMaxAgent::new(4, 0, CustomHeuristic::new(Player::One));
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        board.make_move(36);
+
+        let result = agent.search(board).unwrap();
+
+        assert_eq!(result.depth, 4);
+        assert_eq!(result.principal_variation[0], result.best_move);
+        assert!(result.principal_variation.len() <= 4);
+        assert!(result.statistics.nodes > result.statistics.quiescence_nodes);
+        assert!(result.statistics.beta_cutoffs > 0);
+        assert!(result.statistics.max_depth >= 4);
+
+        // The principal variation must be a legal sequence of moves
+        for index in result.principal_variation {
+            assert!(board.is_legal_move(index));
+            board.make_move(index);
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut agent = MiniMaxAgent::new(3, 2, CustomHeuristic::new(Player::One));
+        assert!(agent.get_info().get_statistics().is_none());
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        // The statistics of the last search are reported in the info
+        agent.act(&board, Player::Two, 1).unwrap();
+        let statistics = *agent.get_info().get_statistics().unwrap();
+        assert!(statistics.nodes > statistics.quiescence_nodes);
+        assert!(statistics.quiescence_nodes > 0);
+        assert!(statistics.max_depth >= 3);
+
+        // The second search of the position uses the entries of the first search
+        agent.act(&board, Player::Two, 1).unwrap();
+        let repeated = *agent.get_info().get_statistics().unwrap();
+        assert!(repeated.tt_hits > 0);
+        assert!(repeated.nodes < statistics.nodes);
+    }
+
+    #[test]
+    fn test_move_ordering() {
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4] {
+            board.make_move(index);
+        }
+
+        let mut ordered = MiniMaxAgent::new(5, 0, CustomHeuristic::new(Player::One));
+        let mut unordered =
+            MiniMaxAgent::new(5, 0, CustomHeuristic::new(Player::One)).with_move_ordering(false);
+
+        let ordered = ordered.search(board).unwrap();
+        let unordered = unordered.search(board).unwrap();
+
+        // The order of the moves does not change the value, but cuts off more branches
+        assert_eq!(ordered.value, unordered.value);
+        assert!(ordered.statistics.nodes < unordered.statistics.nodes);
+    }
+
+    #[test]
+    fn test_transpositions_of_both_players() {
+        // The depth depends on the number of moves, so the table contains entries
+        // of deeper searches for the other player than the current search requires
+        let new_agent =
+            || MiniMaxAgent::new(6, 0, CustomHeuristic::new(Player::One)).with_node_budget(6561);
+        let mut agent = new_agent();
+
+        let mut rng = ChaCha20Rng::seed_from_u64(4);
+        let mut board = UltimateBoard::new();
+
+        // The agent searches the positions of both players with the same table
+        for _ in 0..20 {
+            let result = agent.search(board).unwrap();
+            let fresh = new_agent().search(board).unwrap();
+            assert_eq!(result.value, fresh.value);
+
+            board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_quiescence_policy() {
+        let mut agent = MiniMaxAgent::new(3, 2, CustomHeuristic::new(Player::One))
+            .with_quiescence_policy(quiescence_policy::SmallBoardWinPolicy);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let best_move = agent.act(&board, Player::Two, 1);
+
+        assert!(best_move.is_ok_and(|index| board.is_legal_move(index)));
+        assert!(agent.get_info().config.contains("(qs: win)"));
+    }
+
+    #[test]
+    fn test_node_budget() {
+        let agent =
+            MiniMaxAgent::new(10, 1, CustomHeuristic::new(Player::One)).with_node_budget(1_000_000);
+
+        let mut board = UltimateBoard::new();
+        assert_eq!(agent.get_depth(board), 3);
+
+        board.make_move(40);
+        assert_eq!(agent.get_depth(board), 6);
+
+        let agent = MiniMaxAgent::new(4, 1, CustomHeuristic::new(Player::One));
+        assert_eq!(agent.get_depth(board), 4);
+    }
+
+    #[test]
+    fn test_tie_breaking() {
+        let board = UltimateBoard::new();
+
+        let play = |seed: u64| {
+            let mut agent = MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One))
+                .with_tie_breaking(0.)
+                .with_seed(seed);
+
+            agent.search(board).unwrap()
+        };
+
+        let result = play(0);
+
+        // The empty board is symmetric, so multiple moves have the best value
+        assert!(result.candidates.len() > 1);
+        assert!(result
+            .candidates
+            .iter()
+            .all(|&(_, value)| value == result.value));
+        assert!(result
+            .candidates
+            .iter()
+            .any(|&(index, _)| index == result.best_move));
+
+        assert_eq!(play(0).best_move, result.best_move);
+    }
+
+    #[test]
+    fn test_symmetric_transpositions() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let search = |symmetric_transpositions: bool| {
+            MiniMaxAgent::new(4, 0, CustomHeuristic::new(Player::One))
+                .with_symmetric_transpositions(symmetric_transpositions)
+                .search(board)
+                .unwrap()
+        };
+
+        let result = search(false);
+        let symmetric_result = search(true);
+
+        assert_eq!(symmetric_result.value, result.value);
+        assert!(symmetric_result.statistics.nodes < result.statistics.nodes);
+
+        for index in symmetric_result.principal_variation {
+            assert!(board.is_legal_move(index));
+            board.make_move(index);
+        }
+    }
+
+    #[test]
+    fn test_hash_verification() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        for symmetric_transpositions in [false, true] {
+            let search = |hash_verification: bool| {
+                let mut agent = MiniMaxAgent::new(5, 1, CustomHeuristic::new(Player::One))
+                    .with_symmetric_transpositions(symmetric_transpositions)
+                    .with_hash_verification(hash_verification);
+
+                (agent.search(board).unwrap(), agent.get_hash_collisions())
+            };
+
+            let (result, collisions) = search(true);
+
+            assert_eq!(result, search(false).0);
+            assert_eq!(collisions, 0);
+        }
+    }
+
+    #[test]
+    fn test_null_move_pruning() {
+        let mut agent =
+            MiniMaxAgent::new(5, 1, CustomHeuristic::new(Player::One)).with_null_move_pruning(true);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let best_move = agent.act(&board, Player::Two, 1);
+
+        assert!(best_move.is_ok_and(|index| board.is_legal_move(index)));
+        assert!(agent.get_info().config.contains("NMP"));
+    }
+
+    #[test]
+    fn test_heuristic_of_other_player() {
+        // O has won the top left and top center board and wins the game in square 20
+        let squares = format!("OOO......OOO......OO.......{}", ".".repeat(54));
+        let board = UltimateBoard::from_position(&format!("{} 2 O", squares)).unwrap();
+
+        // The positions are evaluated for the player of the agent, not the player of the heuristic
+        for threads in [1, 2] {
+            let mut agent =
+                MiniMaxAgent::new(3, 1, CustomHeuristic::new(Player::One)).with_threads(threads);
+
+            assert_eq!(agent.act(&board, Player::Two, 1), Ok(20));
+        }
+    }
+
+    #[test]
+    fn test_is_threatened() {
+        let mut board = UltimateBoard::new();
+
+        assert!(!MiniMaxAgent::<CustomHeuristic>::is_threatened(board));
+
+        // Player Two could win the game on one of the boards player One may play on
+        for index in [
+            3, 28, 13, 39, 31, 43, 71, 72, 0, 8, 74, 18, 7, 67, 36, 2, 22, 37, 15, 58, 44, 75, 27,
+            5, 49, 40,
+        ] {
+            board.make_move(index);
+        }
+
+        assert_eq!(board.get_game_status(), Continue);
+        assert!(MiniMaxAgent::<CustomHeuristic>::is_threatened(board));
+    }
+
+    #[test]
+    fn test_pondering() {
+        let mut agent =
+            MiniMaxAgent::new(4, 1, CustomHeuristic::new(Player::One)).with_pondering(true);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let best_move = agent.act(&board, Player::One, 1).unwrap();
+        board.make_move(best_move);
+
+        // The pondering thread fills the shared transposition table while the opponent thinks
+        std::thread::sleep(Duration::from_millis(50));
+
+        let reply = board.get_possible_moves().next().unwrap();
+        board.make_move(reply);
+        agent.opponent_moved(board, reply);
+
+        assert!(agent.ponder.is_none());
+        assert!(agent
+            .act(&board, Player::One, 3)
+            .is_ok_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_anytime() {
+        let mut agent = MiniMaxAgent::new(20, 1, CustomHeuristic::new(Player::One));
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        agent.start_thinking(board);
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(agent
+            .best_move_so_far()
+            .is_some_and(|index| board.is_legal_move(index)));
+        assert!(agent.stop().is_some_and(|index| board.is_legal_move(index)));
+        assert_eq!(agent.stop(), None);
+    }
+
+    #[test]
+    fn test_stop_token() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        for time_limit in [None, Some(Duration::from_secs(60))] {
+            let mut agent = MiniMaxAgent::new(20, 1, CustomHeuristic::new(Player::One));
+
+            if let Some(time_limit) = time_limit {
+                agent = agent.with_time_limit(time_limit);
+            }
+
+            let stop_token = agent.get_stop_token();
+
+            let stopper = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(100));
+                stop_token.store(true, Ordering::Relaxed);
+            });
+
+            let start = Instant::now();
+            let result = agent.search(board).unwrap();
+            stopper.join().unwrap();
+
+            assert!(start.elapsed() < Duration::from_secs(10));
+            assert!(board.is_legal_move(result.best_move));
+        }
+    }
+
+    #[test]
+    fn test_multi_pv() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let search = |root_parallel: bool| {
+            MiniMaxAgent::new(3, 1, CustomHeuristic::new(Player::One))
+                .with_multi_pv(3)
+                .with_root_parallel(root_parallel)
+                .search(board)
+                .unwrap()
+        };
+
+        let result = search(false);
+
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.lines[0].value, result.value);
+
+        for line in result.lines.iter() {
+            assert_eq!(line.principal_variation[0], line.root_move);
+        }
+
+        // The root parallel search gets exact values for all root moves
+        let values = |result: &SearchResult| -> Vec<f64> {
+            result.lines.iter().map(|line| line.value).collect()
+        };
+
+        assert_eq!(values(&result), values(&search(true)));
+    }
+
+    #[test]
+    fn test_root_parallel() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let search = |root_parallel: bool| {
+            MiniMaxAgent::new(3, 1, CustomHeuristic::new(Player::One))
+                .with_root_parallel(root_parallel)
+                .search(board)
+                .unwrap()
+        };
+
+        let result = search(true);
+
+        assert_eq!(result.value, search(false).value);
+        assert_eq!(result.principal_variation[0], result.best_move);
+        assert!(board.is_legal_move(result.best_move));
+    }
+
+    #[test]
+    fn test_lazy_smp() {
+        let mut agent = MiniMaxAgent::new(3, 1, CustomHeuristic::new(Player::One)).with_threads(3);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let best_move = agent.act(&board, Player::Two, 1);
+
+        assert!(best_move.is_ok_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_tablebase() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut tablebase = Tablebase::new(10);
+
+        // A position with few empty squares won by the player to move
+        let board = loop {
+            let mut board = UltimateBoard::new();
+            while board.get_game_status() == GameResult::Continue
+                && board.count_empty_squares() > 10
+            {
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            if tablebase.solve(board) == Some(GameResult::Win(board.get_current_player())) {
+                break board;
+            }
+        };
+        let player = board.get_current_player();
+
+        let result = MiniMaxAgent::new(1, 0, CustomHeuristic::new(player))
+            .with_tablebase(Arc::new(tablebase))
+            .search(board)
+            .unwrap();
+
+        assert_eq!(result.value, MAX_VALUE);
+        assert!(result.statistics.tablebase_hits > 0);
+    }
+}
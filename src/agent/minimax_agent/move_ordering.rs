@@ -0,0 +1,154 @@
+//! # Contains the [MoveOrdering] struct
+//!
+//! The [MoveOrdering] struct sorts the possible moves of a position before they are searched by the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//!
+//! Alpha-beta pruning cuts off more branches the earlier good moves are searched.
+//! The moves are ordered as follows:
+//! 1. The best move stored in the [transposition table](crate::agent::minimax_agent::transposition_table::TranspositionTable)
+//! 2. The [killer moves](https://www.chessprogramming.org/Killer_Heuristic) of the current ply
+//! 3. All other moves, sorted by the [history heuristic](https://www.chessprogramming.org/History_Heuristic)
+use crate::game::player::Player;
+use std::cmp::Reverse;
+
+/// The number of killer moves stored per ply
+const NUM_KILLER_MOVES: usize = 2;
+
+/// The number of squares of the ultimate board
+const NUM_SQUARES: usize = 81;
+
+/// # Struct containing the state used to order moves during a search
+///
+/// # Fields
+/// * `killer_moves` - The moves that caused a beta cutoff, per ply
+/// * `history` - The history scores of the moves, per player
+pub struct MoveOrdering {
+    killer_moves: Vec<[Option<u8>; NUM_KILLER_MOVES]>,
+    history: [[u32; NUM_SQUARES]; 2],
+}
+
+impl MoveOrdering {
+    /// Creates a new [MoveOrdering] without any killer moves or history
+    pub fn new() -> Self {
+        MoveOrdering {
+            killer_moves: vec![],
+            history: [[0; NUM_SQUARES]; 2],
+        }
+    }
+
+    /// Orders the given moves
+    ///
+    /// # Arguments
+    /// * `moves` - The moves to order
+    /// * `table_move` - The best move stored in the transposition table, if any
+    /// * `ply` - The distance to the root of the search
+    /// * `player` - The player to move
+    /// # Returns
+    /// The moves, the most promising move first
+    pub fn order(
+        &self,
+        moves: impl Iterator<Item = u8>,
+        table_move: Option<u8>,
+        ply: usize,
+        player: Player,
+    ) -> Vec<u8> {
+        let killer_moves = self
+            .killer_moves
+            .get(ply)
+            .copied()
+            .unwrap_or([None; NUM_KILLER_MOVES]);
+
+        let mut moves: Vec<u8> = moves.collect();
+
+        moves.sort_by_cached_key(|&index| {
+            Reverse(if table_move == Some(index) {
+                u32::MAX
+            } else if let Some(position) = killer_moves.iter().position(|&k| k == Some(index)) {
+                u32::MAX - 1 - position as u32
+            } else {
+                self.history[player as usize][index as usize]
+            })
+        });
+
+        moves
+    }
+
+    /// Records a move that caused a beta cutoff
+    ///
+    /// The move is stored as a killer move of the ply and its history score is increased.
+    /// # Arguments
+    /// * `index` - The move that caused the cutoff
+    /// * `ply` - The distance to the root of the search
+    /// * `depth` - The remaining depth of the search, deeper cutoffs are weighted higher
+    /// * `player` - The player that made the move
+    pub fn record_cutoff(&mut self, index: u8, ply: usize, depth: u32, player: Player) {
+        if self.killer_moves.len() <= ply {
+            self.killer_moves.resize(ply + 1, [None; NUM_KILLER_MOVES]);
+        }
+
+        let killers = &mut self.killer_moves[ply];
+
+        if killers[0] != Some(index) {
+            killers[1] = killers[0];
+            killers[0] = Some(index);
+        }
+
+        let score = &mut self.history[player as usize][index as usize];
+        *score = score.saturating_add(depth * depth);
+    }
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order() {
+        let mut ordering = MoveOrdering::new();
+        let moves = || [0, 1, 2, 3, 4, 5].into_iter();
+
+        // Without any state, the order of the moves is kept
+        assert_eq!(
+            ordering.order(moves(), None, 0, Player::One),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+
+        ordering.record_cutoff(3, 1, 1, Player::One);
+        ordering.record_cutoff(4, 1, 2, Player::One);
+        ordering.record_cutoff(5, 2, 4, Player::One);
+
+        // The table move comes first, then the killer moves of the ply, the most recent first,
+        // then the other moves by their history score
+        assert_eq!(
+            ordering.order(moves(), Some(1), 1, Player::One),
+            vec![1, 4, 3, 5, 0, 2]
+        );
+
+        // The history is kept per player
+        assert_eq!(
+            ordering.order(moves(), None, 0, Player::Two),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_killer_moves() {
+        let mut ordering = MoveOrdering::new();
+        let moves = || [0, 1, 2, 3].into_iter();
+
+        // Only the two most recent killer moves are kept, a repeated cutoff does not evict the other killer move
+        ordering.record_cutoff(1, 0, 0, Player::One);
+        ordering.record_cutoff(2, 0, 0, Player::One);
+        ordering.record_cutoff(2, 0, 0, Player::One);
+        ordering.record_cutoff(3, 0, 0, Player::One);
+        assert_eq!(
+            ordering.order(moves(), None, 0, Player::One),
+            vec![3, 2, 0, 1]
+        );
+    }
+}
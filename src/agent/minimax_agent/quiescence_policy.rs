@@ -0,0 +1,187 @@
+//! # Contains the [QuiescencePolicy] trait and implementations
+//!
+//! A [QuiescencePolicy] defines which positions are noisy and therefore searched further by the
+//! [quiescence search](https://www.chessprogramming.org/Quiescence_Search) of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//!
+//! The following policies are provided:
+//! * [FreeChoicePolicy] - Continues if the next player may play on any open board (default)
+//! * [SmallBoardWinPolicy] - Continues with moves that win a small board
+//! * [MetaThreatPolicy] - Continues with moves that create a new threat on the meta-board
+//! * [CombinedPolicy] - Continues with the moves of any of multiple policies
+use crate::game::board::Board;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// # Trait representing the definition of noisy positions for the quiescence search
+///
+/// If the returned moves are only a part of the possible moves, the player to move may also
+/// make a quiet move instead, so the static evaluation of the position is used as a lower bound ("stand pat").
+pub trait QuiescencePolicy: Send + Sync {
+    /// Returns the moves the quiescence search continues with
+    ///
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// # Returns
+    /// The noisy moves, empty if the position is quiet
+    fn get_noisy_moves(&self, board: UltimateBoard) -> Vec<u8>;
+
+    /// Returns the name of the policy, used in the configuration of the agent
+    fn get_name(&self) -> String;
+}
+
+/// Policy treating positions in which the next player may play on any open board as noisy
+///
+/// All possible moves of such a position are searched.
+/// This is the default policy of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FreeChoicePolicy;
+
+impl QuiescencePolicy for FreeChoicePolicy {
+    fn get_noisy_moves(&self, board: UltimateBoard) -> Vec<u8> {
+        if board.get_next_board_index().is_some() {
+            return vec![];
+        }
+
+        board.get_possible_moves().collect()
+    }
+
+    fn get_name(&self) -> String {
+        "free".to_string()
+    }
+}
+
+/// Policy treating moves that win a small board as noisy
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmallBoardWinPolicy;
+
+impl QuiescencePolicy for SmallBoardWinPolicy {
+    fn get_noisy_moves(&self, board: UltimateBoard) -> Vec<u8> {
+        let player = board.get_current_player();
+        let won_boards = board.won_boards_mask(player).into_iter().count();
+
+        board
+            .get_possible_moves()
+            .filter(|&index| {
+                let mut new_board = board;
+                new_board.make_move(index);
+                new_board.won_boards_mask(player).into_iter().count() > won_boards
+            })
+            .collect()
+    }
+
+    fn get_name(&self) -> String {
+        "win".to_string()
+    }
+}
+
+/// Policy treating moves that create a new threat on the meta-board as noisy
+///
+/// A threat is a line of the meta-board with two boards won by the player and an undecided third board.
+/// Moves that win the game are noisy as well.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetaThreatPolicy;
+
+impl MetaThreatPolicy {
+    /// Counts the threats of the player on the meta-board
+    fn count_meta_threats(board: &UltimateBoard, player: Player) -> u8 {
+        let opponent = player.get_opponent();
+
+        // Drawn boards block lines like boards won by the opponent
+        let mut bitboards = [board.drawn_boards_mask(); 2];
+        bitboards[player as usize] = board.won_boards_mask(player);
+        bitboards[opponent as usize] |= board.won_boards_mask(opponent);
+
+        Board::from_bitboards(bitboards, 0).count_threats(player)
+    }
+}
+
+impl QuiescencePolicy for MetaThreatPolicy {
+    fn get_noisy_moves(&self, board: UltimateBoard) -> Vec<u8> {
+        let player = board.get_current_player();
+        let threats = MetaThreatPolicy::count_meta_threats(&board, player);
+
+        board
+            .get_possible_moves()
+            .filter(|&index| {
+                let mut new_board = board;
+                new_board.make_move(index);
+                new_board.get_game_status() == GameResult::Win(player)
+                    || MetaThreatPolicy::count_meta_threats(&new_board, player) > threats
+            })
+            .collect()
+    }
+
+    fn get_name(&self) -> String {
+        "threat".to_string()
+    }
+}
+
+/// Policy combining multiple policies
+///
+/// A move is noisy if it is noisy for any of the policies.
+pub struct CombinedPolicy {
+    policies: Vec<Box<dyn QuiescencePolicy>>,
+}
+
+impl CombinedPolicy {
+    /// Creates a new [CombinedPolicy]
+    ///
+    /// # Arguments
+    /// * `policies` - The policies to combine
+    pub fn new(policies: Vec<Box<dyn QuiescencePolicy>>) -> CombinedPolicy {
+        CombinedPolicy { policies }
+    }
+}
+
+impl QuiescencePolicy for CombinedPolicy {
+    fn get_noisy_moves(&self, board: UltimateBoard) -> Vec<u8> {
+        let mut moves: Vec<u8> = self
+            .policies
+            .iter()
+            .flat_map(|policy| policy.get_noisy_moves(board))
+            .collect();
+
+        moves.sort_unstable();
+        moves.dedup();
+
+        moves
+    }
+
+    fn get_name(&self) -> String {
+        self.policies
+            .iter()
+            .map(|policy| policy.get_name())
+            .collect::<Vec<String>>()
+            .join("+")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies() {
+        let mut board = UltimateBoard::new();
+
+        assert_eq!(FreeChoicePolicy.get_noisy_moves(board).len(), 81);
+
+        // Player Two can win board 0 with field 6 (index 6)
+        for index in [0, 8, 72, 7, 63] {
+            board.make_move(index);
+        }
+
+        assert!(FreeChoicePolicy.get_noisy_moves(board).is_empty());
+        assert_eq!(SmallBoardWinPolicy.get_noisy_moves(board), vec![6]);
+        assert!(MetaThreatPolicy.get_noisy_moves(board).is_empty());
+
+        let combined = CombinedPolicy::new(vec![
+            Box::new(FreeChoicePolicy),
+            Box::new(SmallBoardWinPolicy),
+        ]);
+
+        assert_eq!(combined.get_noisy_moves(board), vec![6]);
+        assert_eq!(combined.get_name(), "free+win");
+    }
+}
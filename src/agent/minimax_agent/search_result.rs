@@ -0,0 +1,44 @@
+//! # Contains the [SearchResult] struct
+//!
+//! The [SearchResult] struct contains the outcome of a search of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//! It is returned by [MiniMaxAgent::search](crate::agent::minimax_agent::MiniMaxAgent::search) and can be used to display the thinking of the engine.
+//!
+//! In [multi-PV](crate::agent::minimax_agent::minimax_config::MiniMaxConfig::with_multi_pv) mode the result also contains
+//! the best root moves as [SearchLine]s, ranked by their value.
+use crate::agent::minimax_agent::search_statistics::SearchStatistics;
+
+/// # Struct containing the result of a search
+///
+/// # Fields
+/// * `best_move` - The index of the field to play on
+/// * `value` - The evaluation of the best move from the perspective of the player to move
+/// * `depth` - The depth of the last completed iteration
+/// * `principal_variation` - The expected sequence of moves, starting with the best move
+/// * `statistics` - The counters of the search, including the work of helper threads
+/// * `candidates` - The root moves whose values are within the tie-breaking margin of the best value and their values,
+///   empty if [tie-breaking](crate::agent::minimax_agent::MiniMaxAgent::with_tie_breaking) is disabled
+/// * `lines` - The best root moves with their values and principal variations, the best line first,
+///   empty if [multi-PV](crate::agent::minimax_agent::minimax_config::MiniMaxConfig::with_multi_pv) is disabled
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub best_move: u8,
+    pub value: f64,
+    pub depth: u32,
+    pub principal_variation: Vec<u8>,
+    pub statistics: SearchStatistics,
+    pub candidates: Vec<(u8, f64)>,
+    pub lines: Vec<SearchLine>,
+}
+
+/// # Struct containing a single root move of a multi-PV search
+///
+/// # Fields
+/// * `root_move` - The index of the root move
+/// * `value` - The exact value of the root move from the perspective of the player to move
+/// * `principal_variation` - The expected sequence of moves, starting with the root move
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchLine {
+    pub root_move: u8,
+    pub value: f64,
+    pub principal_variation: Vec<u8>,
+}
@@ -0,0 +1,89 @@
+//! # Contains the [SearchStatistics] struct
+//!
+//! The [SearchStatistics] struct counts the work done by a search of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//! The statistics are part of the [SearchResult](crate::agent::minimax_agent::search_result::SearchResult)
+//! and the [AgentInfo](crate::agent::AgentInfo) of the agent, so they can be recorded by the [BenchedAgent](crate::agent::benched::BenchedAgent).
+use std::ops::AddAssign;
+
+/// # Struct containing the counters of a search
+///
+/// # Fields
+/// * `nodes` - The number of nodes visited, including quiescence search nodes
+/// * `quiescence_nodes` - The number of nodes visited by the quiescence search
+/// * `beta_cutoffs` - The number of nodes whose remaining moves were pruned
+/// * `tt_hits` - The number of transposition table entries that were deep enough to be used
+/// * `max_depth` - The maximum distance to the root reached, including extensions and the quiescence search
+/// * `node_limit_hits` - The number of times a node was not stored because the tree of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent) was full
+/// * `depth_limit_hits` - The number of times a node of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent) was not expanded because it reached the maximum depth
+/// * `tablebase_hits` - The number of positions whose result was found in the [Tablebase](crate::game::tablebase::Tablebase)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStatistics {
+    pub nodes: u64,
+    pub quiescence_nodes: u64,
+    pub beta_cutoffs: u64,
+    pub tt_hits: u64,
+    pub max_depth: u32,
+    pub node_limit_hits: u64,
+    pub depth_limit_hits: u64,
+    pub tablebase_hits: u64,
+}
+
+impl SearchStatistics {
+    /// Records that a node at the given distance to the root was visited
+    ///
+    /// # Arguments
+    /// * `ply` - The distance to the root of the search
+    pub fn visit(&mut self, ply: usize) {
+        self.nodes += 1;
+        self.max_depth = self.max_depth.max(ply as u32);
+    }
+}
+
+/// Combines the statistics of multiple searches, e.g. of multiple threads
+impl AddAssign for SearchStatistics {
+    fn add_assign(&mut self, other: Self) {
+        self.nodes += other.nodes;
+        self.quiescence_nodes += other.quiescence_nodes;
+        self.beta_cutoffs += other.beta_cutoffs;
+        self.tt_hits += other.tt_hits;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.node_limit_hits += other.node_limit_hits;
+        self.depth_limit_hits += other.depth_limit_hits;
+        self.tablebase_hits += other.tablebase_hits;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine() {
+        let mut first = SearchStatistics::default();
+        first.visit(0);
+        first.visit(3);
+        first.beta_cutoffs = 1;
+
+        let mut second = SearchStatistics::default();
+        second.visit(5);
+        second.quiescence_nodes = 1;
+        second.tt_hits = 2;
+
+        assert_eq!(first.nodes, 2);
+        assert_eq!(first.max_depth, 3);
+
+        // The counters are added up, the maximum depth is the deepest of both
+        first += second;
+        assert_eq!(
+            first,
+            SearchStatistics {
+                nodes: 3,
+                quiescence_nodes: 1,
+                beta_cutoffs: 1,
+                tt_hits: 2,
+                max_depth: 5,
+                ..SearchStatistics::default()
+            }
+        );
+    }
+}
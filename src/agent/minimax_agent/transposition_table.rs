@@ -0,0 +1,274 @@
+//! # Contains the [TranspositionTable] struct
+//!
+//! The [TranspositionTable] stores the results of already searched positions of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//!
+//! The table is kept across moves, so the results of previous searches can be reused.
+//! Because of alpha-beta pruning, not every stored value is exact. Each [TranspositionEntry] is marked with an [EntryFlag].
+//!
+//! The table is split into shards guarded by their own lock, so multiple search threads can share it.
+//!
+//! To verify that [key collisions](https://www.chessprogramming.org/Transposition_Table#KeyCollisions) do not corrupt the search,
+//! entries can be stored together with a [fingerprint](crate::game::ultimate_board::UltimateBoard::get_fingerprint) of the position.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// The default maximum number of entries of a [TranspositionTable]
+pub const DEFAULT_CAPACITY: usize = 1 << 20;
+
+/// The number of independently locked shards of a [TranspositionTable]
+const NUM_SHARDS: usize = 64;
+
+/// # Enum representing the kind of value stored in a [TranspositionEntry]
+///
+/// See [Transposition table](https://www.chessprogramming.org/Transposition_Table#What_Information_is_Stored)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryFlag {
+    /// The value is the exact value of the position
+    Exact,
+    /// The value is a lower bound, the search failed high
+    LowerBound,
+    /// The value is an upper bound, the search failed low
+    UpperBound,
+}
+
+impl EntryFlag {
+    /// Determines the flag of a search result
+    ///
+    /// # Arguments
+    /// * `value` - The value returned by the search
+    /// * `alpha` - The alpha value the search was started with
+    /// * `beta` - The beta value the search was started with
+    /// # Returns
+    /// The flag of the search result
+    pub fn from_bounds(value: f64, alpha: f64, beta: f64) -> Self {
+        if value <= alpha {
+            EntryFlag::UpperBound
+        } else if value >= beta {
+            EntryFlag::LowerBound
+        } else {
+            EntryFlag::Exact
+        }
+    }
+}
+
+/// # Struct representing an entry of the [TranspositionTable]
+///
+/// # Fields
+/// * `depth` - The remaining depth the position was searched with
+/// * `value` - The value of the position
+/// * `flag` - Whether the value is exact or a bound
+/// * `best_move` - The best move found in the position, if any
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TranspositionEntry {
+    pub depth: u32,
+    pub value: f64,
+    pub flag: EntryFlag,
+    pub best_move: Option<u8>,
+}
+
+/// # Struct representing a stored [TranspositionEntry]
+///
+/// # Fields
+/// * `entry` - The stored entry
+/// * `fingerprint` - The fingerprint of the position, None if the entry was stored without verification
+#[derive(Clone, Copy)]
+struct Slot {
+    entry: TranspositionEntry,
+    fingerprint: Option<u64>,
+}
+
+/// # Struct representing a [transposition table](https://www.chessprogramming.org/Transposition_Table)
+///
+/// The entries are keyed by the [Zobrist hash](crate::game::ultimate_board::UltimateBoard::get_hash) of the position.
+///
+/// Entries of deeper searches are preferred over entries of shallower searches.
+/// If a shard of the table is full, new positions are not stored in it until the table is [cleared](TranspositionTable::clear).
+///
+/// Entries stored with [TranspositionTable::store_verified] are only returned by [TranspositionTable::probe_verified]
+/// if the fingerprints match. Mismatches are key collisions, which are counted and logged.
+///
+/// The table can be shared between threads, each shard is locked separately.
+pub struct TranspositionTable {
+    shards: Vec<Mutex<HashMap<u64, Slot>>>,
+    shard_capacity: usize,
+    collisions: AtomicU64,
+}
+
+impl TranspositionTable {
+    /// Creates a new, empty [TranspositionTable]
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of entries
+    pub fn new(capacity: usize) -> Self {
+        TranspositionTable {
+            shards: (0..NUM_SHARDS)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            shard_capacity: capacity.div_ceil(NUM_SHARDS),
+            collisions: AtomicU64::new(0),
+        }
+    }
+
+    /// Locks the shard containing the given hash
+    fn shard(&self, hash: u64) -> MutexGuard<'_, HashMap<u64, Slot>> {
+        // A panicking search thread does not leave an entry in an inconsistent state
+        self.shards[hash as usize % NUM_SHARDS]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Looks up the entry of a position
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// # Returns
+    /// The entry of the position, None if the position is not stored
+    pub fn probe(&self, hash: u64) -> Option<TranspositionEntry> {
+        self.shard(hash).get(&hash).map(|slot| slot.entry)
+    }
+
+    /// Looks up the entry of a position and verifies its fingerprint
+    ///
+    /// If the entry was stored with a different fingerprint, it belongs to another position with the same hash.
+    /// The collision is counted, see [TranspositionTable::get_collisions], and no entry is returned.
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// * `fingerprint` - The fingerprint of the position
+    /// # Returns
+    /// The entry of the position, None if the position is not stored
+    pub fn probe_verified(&self, hash: u64, fingerprint: u64) -> Option<TranspositionEntry> {
+        let slot = self.shard(hash).get(&hash).copied()?;
+
+        match slot.fingerprint {
+            Some(stored_fingerprint) if stored_fingerprint != fingerprint => {
+                self.collisions.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            _ => Some(slot.entry),
+        }
+    }
+
+    /// Stores the entry of a position
+    ///
+    /// An existing entry is only replaced if the new entry was searched at least as deep.
+    ///
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// * `entry` - The entry to store
+    pub fn store(&self, hash: u64, entry: TranspositionEntry) {
+        self.insert(
+            hash,
+            Slot {
+                entry,
+                fingerprint: None,
+            },
+        );
+    }
+
+    /// Stores the entry of a position together with its fingerprint
+    ///
+    /// See [TranspositionTable::store] and [TranspositionTable::probe_verified].
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// * `fingerprint` - The fingerprint of the position
+    /// * `entry` - The entry to store
+    pub fn store_verified(&self, hash: u64, fingerprint: u64, entry: TranspositionEntry) {
+        self.insert(
+            hash,
+            Slot {
+                entry,
+                fingerprint: Some(fingerprint),
+            },
+        );
+    }
+
+    /// Stores a slot, replacing an existing slot only if the new entry was searched at least as deep
+    fn insert(&self, hash: u64, slot: Slot) {
+        let mut shard = self.shard(hash);
+
+        let is_full = shard.len() >= self.shard_capacity;
+
+        match shard.get_mut(&hash) {
+            Some(existing) => {
+                if slot.entry.depth >= existing.entry.depth {
+                    *existing = slot;
+                }
+            }
+            None => {
+                if !is_full {
+                    shard.insert(hash, slot);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of key collisions detected by [TranspositionTable::probe_verified]
+    ///
+    /// The counter is kept when the table is [cleared](TranspositionTable::clear).
+    pub fn get_collisions(&self) -> u64 {
+        self.collisions.load(Ordering::Relaxed)
+    }
+
+    /// Checks if the table has reached its capacity
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.shard_capacity * NUM_SHARDS
+    }
+
+    /// Returns the number of stored entries
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap_or_else(|e| e.into_inner()).len())
+            .sum()
+    }
+
+    /// Checks if the table contains no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all entries from the table
+    pub fn clear(&mut self) {
+        for shard in self.shards.iter_mut() {
+            shard.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification() {
+        let table = TranspositionTable::new(64);
+
+        let entry = TranspositionEntry {
+            depth: 3,
+            value: 1.,
+            flag: EntryFlag::Exact,
+            best_move: Some(40),
+        };
+
+        table.store_verified(1, 10, entry);
+
+        assert_eq!(table.probe(1), Some(entry));
+        assert_eq!(table.probe_verified(1, 10), Some(entry));
+        assert_eq!(table.get_collisions(), 0);
+
+        assert_eq!(table.probe_verified(1, 11), None);
+        assert_eq!(table.get_collisions(), 1);
+
+        // Entries stored without a fingerprint cannot be verified
+        table.store(2, entry);
+        assert_eq!(table.probe_verified(2, 11), Some(entry));
+        assert_eq!(table.get_collisions(), 1);
+    }
+}
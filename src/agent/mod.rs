@@ -4,16 +4,42 @@
 //!
 //! The trait has a single method [act](Agent::act) that is called to get the agent's move.
 //!
-//! The agent should return the index of the field to play on.
+//! The agent should return the index of the field to play on, or an [AgentError](agent_error::AgentError) if it cannot play.
 //!
 //! The index is the human index (0-80).
+pub mod action;
+pub mod agent_error;
 pub mod benched;
+pub mod coach_agent;
+pub mod ensemble_agent;
+pub mod epsilon_greedy_agent;
+pub mod greedy_agent;
 pub mod human_agent;
+pub mod hybrid_agent;
+pub mod logging_agent;
 pub mod minimax_agent;
 pub mod monte_carlo_tree_agent;
+#[cfg(feature = "onnx")]
+pub mod neural_net_agent;
 pub mod random_agent;
 pub mod random_start;
+pub mod registry;
+pub mod remote_agent;
+pub mod scripted_agent;
+pub mod softmax_agent;
+pub mod symmetry_agent;
+pub mod tablebase_agent;
+pub mod time_limited_agent;
+#[cfg(feature = "net")]
+pub mod websocket_agent;
 
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::minimax_agent::search_statistics::SearchStatistics;
+use crate::agent::monte_carlo_tree_agent::mcts_report::MctsReport;
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
 
@@ -24,32 +50,200 @@ pub trait Agent: Send + Sync {
     /// The agent should return the index of the field to play on.
     /// The index is the human index (0-80) over all boards.
     ///
-    /// The game will panic if None is returned or if the index is out of bounds.
-    /// In case of a panic, relevant information about the state of the game will be printed to the console.
+    /// If the agent cannot play or can not find a move, it should return an [AgentError].
+    /// The [Game](crate::game::Game) lets an agent that returns an error or an illegal move forfeit the game.
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `player` - The player of the agent
+    /// * `turn` - The number of moves made so far by both players
+    /// # Returns
+    /// The index of the field to play on, the reason if the agent cannot play
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError>;
+
+    /// Called by the [Game](crate::game::Game) to get the agent's [action](Action) in its turn.
     ///
-    /// It is recommended to return None if the agent cannot play or if the agent can not find a move.
+    /// Besides a move, the agent can request other actions, e.g. to take back its last move.
+    /// The default implementation returns the move of [act](Agent::act).
+    /// Agents wrapping other agents that request actions should forward the call.
     /// # Arguments
     /// * `board` - The current state of the board
+    /// * `player` - The player of the agent
+    /// * `turn` - The number of moves made so far by both players
     /// # Returns
-    /// The index of the field to play on
-    fn act(&mut self, board: UltimateBoard, player: Player, turn: u32) -> Option<u8>;
+    /// The requested action, the reason if the agent cannot play
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        self.act(board, player, turn).map(Action::Move)
+    }
+
+    /// Called by the [Game](crate::game::Game) if an action other than a move requested by the agent could not be performed.
+    ///
+    /// The agent is asked for an action on the same board again afterwards.
+    /// The default implementation ignores the failure.
+    /// Agents wrapping other agents that request actions should forward the notification.
+    /// # Arguments
+    /// * `action` - The requested action
+    /// * `reason` - Why the action could not be performed
+    fn action_failed(&mut self, _action: &Action, _reason: &str) {}
+
+    /// Called by a [CoachAgent](coach_agent::CoachAgent) after it has reviewed the move of the agent.
+    ///
+    /// Agents playing for a human can show the review to the player.
+    /// The default implementation ignores the review.
+    /// Agents wrapping other agents should forward the review.
+    /// # Arguments
+    /// * `review` - The [report](coach_agent::CoachReport) of the review as text
+    fn move_reviewed(&mut self, _review: &str) {}
 
     /// Returns the information of the agent
     ///
     /// # Returns
     /// The information of the agent
     fn get_info(&self) -> AgentInfo;
+
+    /// Called by the [Game](crate::game::Game) before the first move with the metadata of the game.
+    ///
+    /// The default implementation ignores the metadata.
+    /// Agents wrapping other agents should forward the metadata.
+    /// # Arguments
+    /// * `metadata` - The metadata of the game
+    fn set_game_metadata(&mut self, _metadata: &GameMetadata) {}
+
+    /// Called by the [Game](crate::game::Game) after the opponent of the agent has made a move.
+    ///
+    /// The default implementation ignores the move.
+    /// Agents wrapping other agents should forward the notification.
+    /// # Arguments
+    /// * `board` - The board after the move of the opponent
+    /// * `index` - The index of the field the opponent played on
+    fn opponent_moved(&mut self, _board: UltimateBoard, _index: u8) {}
+
+    /// Called by the [Game](crate::game::Game) before the first move of a new game.
+    ///
+    /// Agents can reset the state of previous games, e.g. transposition tables or search trees.
+    /// The default implementation does nothing.
+    /// Agents wrapping other agents should forward the notification.
+    /// # Arguments
+    /// * `player` - The player of the agent in the new game
+    fn new_game(&mut self, _player: Player) {}
+
+    /// Called by the [Game](crate::game::Game) after the game has ended.
+    ///
+    /// Agents can stop background work or learn from the result.
+    /// The default implementation does nothing.
+    /// Agents wrapping other agents should forward the notification.
+    /// # Arguments
+    /// * `board` - The board at the end of the game
+    /// * `result` - The result of the game
+    fn game_over(&mut self, _board: UltimateBoard, _result: GameResult) {}
+
+    /// Called by the [Game](crate::game::Game) before [act](Agent::act) if the game is played with a [time control](crate::game::clock::TimeControl).
+    ///
+    /// Agents can display the clocks or plan their thinking time.
+    /// The default implementation ignores the clock.
+    /// Agents wrapping other agents should forward the clock.
+    /// # Arguments
+    /// * `clock` - The clocks of both players at the start of the move
+    fn clock_updated(&mut self, _clock: &Clock) {}
+}
+
+/// Trait representing an agent that can search in the background
+///
+/// The search is started with [start_thinking](AnytimeAgent::start_thinking) and runs until it is [stopped](AnytimeAgent::stop),
+/// the best move found so far can be queried at any time.
+/// This allows time controls, pondering and user interfaces that do not block while the agent thinks.
+///
+/// Calling [act](Agent::act) stops a running background search.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use hausarbeit::agent::AnytimeAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::game::ultimate_board::UltimateBoard;
+///
+/// let mut agent = MonteCarloTreeAgent::new(1_000_000);
+/// agent.start_thinking(UltimateBoard::new());
+///
+/// std::thread::sleep(Duration::from_millis(100));
+/// let preview = agent.best_move_so_far();
+///
+/// assert!(agent.stop().is_some());
+/// ```
+pub trait AnytimeAgent: Agent {
+    /// Starts searching the given board in the background
+    ///
+    /// A running background search is stopped first.
+    /// If the game is over, no search is started.
+    /// # Arguments
+    /// * `board` - The board to search, the agent plays for the current player of the board
+    fn start_thinking(&mut self, board: UltimateBoard);
+
+    /// Returns the best move found by the running background search so far
+    ///
+    /// # Returns
+    /// The best move so far, None if no search is running or the search has not found a move yet
+    fn best_move_so_far(&self) -> Option<u8>;
+
+    /// Stops the background search and waits until it has finished
+    ///
+    /// # Returns
+    /// The best move found by the search, None if no search was running
+    fn stop(&mut self) -> Option<u8>;
+}
+
+/// Trait for agents that can be cloned behind a [Box]
+///
+/// The trait is implemented for every [Agent] implementing [Clone].
+/// It allows to create the agents of many games from one configured prototype, also if the prototype is a boxed trait object.
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::CloneAgent;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::random_agent::RandomAgent;
+/// use hausarbeit::game::Game;
+///
+/// let prototype: Box<dyn CloneAgent> = Box::new(MonteCarloTreeAgent::new(1000));
+///
+/// let games: Vec<Game> = (0..10)
+///     .map(|_| Game::new(prototype.clone(), Box::new(RandomAgent::new())))
+///     .collect();
+/// ```
+pub trait CloneAgent: Agent {
+    /// Clones the agent into a new [Box]
+    fn clone_agent(&self) -> Box<dyn CloneAgent>;
+}
+
+impl<A: Agent + Clone + 'static> CloneAgent for A {
+    fn clone_agent(&self) -> Box<dyn CloneAgent> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn CloneAgent> {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_agent()
+    }
 }
 
 /// # Struct representing the information of an agent
 ///
 /// The information contains the name of the agent, the player, the turn number, and the configuration of the agent.
+/// Searching agents may add the [statistics](SearchStatistics) and the evaluation of their last search.
 #[derive(Clone, Debug)]
 pub struct AgentInfo {
     name: String,
     player: Player,
     turn_num: u32,
     config: String,
+    statistics: Option<SearchStatistics>,
+    mcts_report: Option<MctsReport>,
+    evaluation: Option<f64>,
 }
 
 impl AgentInfo {
@@ -69,6 +263,65 @@ impl AgentInfo {
             player,
             turn_num,
             config,
+            statistics: None,
+            mcts_report: None,
+            evaluation: None,
         }
     }
+
+    /// Adds the statistics of the last search to the [AgentInfo]
+    ///
+    /// # Arguments
+    /// * `statistics` - The statistics of the last search
+    ///
+    /// # Returns
+    /// The [AgentInfo] containing the statistics
+    pub fn with_statistics(mut self, statistics: SearchStatistics) -> AgentInfo {
+        self.statistics = Some(statistics);
+        self
+    }
+
+    /// Returns the statistics of the last search, if the agent reports them
+    pub fn get_statistics(&self) -> Option<&SearchStatistics> {
+        self.statistics.as_ref()
+    }
+
+    /// Adds the report of the last search of a [MonteCarloTreeAgent](monte_carlo_tree_agent::MonteCarloTreeAgent) to the [AgentInfo]
+    ///
+    /// # Arguments
+    /// * `report` - The report of the last search
+    ///
+    /// # Returns
+    /// The [AgentInfo] containing the report
+    pub fn with_mcts_report(mut self, report: MctsReport) -> AgentInfo {
+        self.mcts_report = Some(report);
+        self
+    }
+
+    /// Returns the report of the last search, if the agent is a [MonteCarloTreeAgent](monte_carlo_tree_agent::MonteCarloTreeAgent)
+    pub fn get_mcts_report(&self) -> Option<&MctsReport> {
+        self.mcts_report.as_ref()
+    }
+
+    /// Adds the evaluation of the chosen move to the [AgentInfo]
+    ///
+    /// # Arguments
+    /// * `evaluation` - The value of the chosen move from the perspective of the player of the agent
+    ///
+    /// # Returns
+    /// The [AgentInfo] containing the evaluation
+    pub fn with_evaluation(mut self, evaluation: f64) -> AgentInfo {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    /// Returns the evaluation of the chosen move, if the agent reports it
+    pub fn get_evaluation(&self) -> Option<f64> {
+        self.evaluation
+    }
+
+    /// Returns the name of the agent
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
 }
@@ -0,0 +1,148 @@
+//! # Contains the [Evaluator] trait and implementations
+//!
+//! An [Evaluator] is a policy/value function as used by [AlphaZero](https://www.chessprogramming.org/AlphaZero):
+//! it estimates the probabilities of the moves of a position and the value of the position.
+//! The [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent) uses the value instead of playouts
+//! and the probabilities as the priors of the children,
+//! see [MctsConfig::with_evaluator](crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig::with_evaluator).
+//!
+//! Neural networks, e.g. loaded with tract or candle, can be used by implementing the trait.
+//!
+//! The following evaluators are provided:
+//! * [HeuristicEvaluator] - A reference implementation deriving the priors and the value from a [Heuristic]
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{is_proven, Heuristic};
+
+/// # Trait representing a policy/value function
+pub trait Evaluator: Send + Sync {
+    /// Evaluates a board
+    ///
+    /// # Arguments
+    /// * `board` - The board to evaluate, the game is not over
+    /// # Returns
+    /// The prior probabilities of the possible moves, which should sum up to 1, moves without a probability have the prior 0,
+    /// and the value of the board for the player to move, between -1 for a loss and 1 for a win
+    fn evaluate(&self, board: UltimateBoard) -> (Vec<(u8, f32)>, f32);
+
+    /// Returns the name of the evaluator, used in the configuration of the agent
+    fn get_name(&self) -> String;
+}
+
+/// Evaluator deriving the priors and the value from a [Heuristic]
+///
+/// The value is `tanh(value / scale)` of the value of the heuristic for the player to move, or ±1 if the result is proven.
+/// The priors are the softmax of the values of the boards after the moves, `exp(value / scale)` normalized over the moves.
+///
+/// This is a reference implementation of the interface, a heuristic is usually better used
+/// for [progressive bias](crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig::with_progressive_bias).
+pub struct HeuristicEvaluator<H> {
+    heuristic: H,
+    scale: f64,
+}
+
+impl<H: Heuristic> HeuristicEvaluator<H> {
+    /// Creates a new [HeuristicEvaluator]
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic, it evaluates the boards [for](Heuristic::evaluate_for) the player to move
+    /// * `scale` - The value of the heuristic that corresponds to a value of about 0.76, must be positive
+    ///
+    /// # Example
+    /// ```
+    /// use hausarbeit::agent::monte_carlo_tree_agent::evaluator::HeuristicEvaluator;
+    /// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+    /// use hausarbeit::game::player::Player;
+    /// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+    ///
+    /// let agent = MonteCarloTreeAgent::new(1000).with_evaluator(HeuristicEvaluator::new(CustomHeuristic::new(Player::One), 0.5));
+    /// ```
+    pub fn new(heuristic: H, scale: f64) -> HeuristicEvaluator<H> {
+        assert!(scale > 0., "Scale must be positive");
+        HeuristicEvaluator { heuristic, scale }
+    }
+}
+
+impl<H: Heuristic> Evaluator for HeuristicEvaluator<H> {
+    fn evaluate(&self, board: UltimateBoard) -> (Vec<(u8, f32)>, f32) {
+        let player = board.get_current_player();
+
+        let values: Vec<(u8, f64)> = board
+            .get_possible_moves()
+            .map(|index| {
+                let mut board_copy = board;
+                board_copy.make_move(index);
+                (
+                    index,
+                    self.heuristic.evaluate_for(board_copy, player) / self.scale,
+                )
+            })
+            .collect();
+
+        let max_value = values
+            .iter()
+            .map(|&(_, value)| value)
+            .fold(f64::MIN, f64::max);
+
+        let weights: Vec<f64> = values
+            .iter()
+            .map(|&(_, value)| (value - max_value).exp())
+            .collect();
+        let weight_sum: f64 = weights.iter().sum();
+
+        let priors = values
+            .iter()
+            .zip(weights)
+            .map(|(&(index, _), weight)| {
+                let prior = if weight_sum.is_finite() && weight_sum > 0. {
+                    weight / weight_sum
+                } else {
+                    // The values are not finite
+                    1. / values.len() as f64
+                };
+                (index, prior as f32)
+            })
+            .collect();
+
+        let value = self.heuristic.evaluate_for(board, player);
+        let value = if is_proven(value) {
+            value.signum()
+        } else {
+            (value / self.scale).tanh()
+        };
+
+        (priors, value as f32)
+    }
+
+    fn get_name(&self) -> String {
+        format!("heuristic {} {}", self.heuristic.get_name(), self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::player::Player;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_heuristic_evaluator() {
+        let evaluator = HeuristicEvaluator::new(CustomHeuristic::new(Player::One), 0.5);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let (priors, value) = evaluator.evaluate(board);
+
+        let mut moves: Vec<u8> = priors.iter().map(|&(index, _)| index).collect();
+        let mut possible_moves: Vec<u8> = board.get_possible_moves().collect();
+        moves.sort();
+        possible_moves.sort();
+        assert_eq!(moves, possible_moves);
+
+        let prior_sum: f32 = priors.iter().map(|&(_, prior)| prior).sum();
+        assert!((prior_sum - 1.).abs() < 1e-5);
+        assert!(priors.iter().all(|&(_, prior)| prior > 0.));
+
+        assert!((-1. ..=1.).contains(&value));
+    }
+}
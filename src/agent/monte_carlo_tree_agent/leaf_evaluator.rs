@@ -0,0 +1,65 @@
+//! # Contains the [LeafEvaluator] trait and implementations
+//!
+//! A [LeafEvaluator] replaces the playouts of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent),
+//! see [MonteCarloTreeAgent::with_leaf_evaluator](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_leaf_evaluator).
+//!
+//! The leaves are evaluated in batches, so expensive evaluations, e.g. by a neural network, can process multiple boards at once.
+//!
+//! The following evaluators are provided:
+//! * [HeuristicLeafEvaluator] - Maps the value of a [Heuristic] to a win probability
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{win_probability, Heuristic};
+
+/// # Trait representing the evaluation of batches of leaves
+pub trait LeafEvaluator: Send + Sync {
+    /// Evaluates a batch of boards
+    ///
+    /// # Arguments
+    /// * `boards` - The boards to evaluate, the games are not over
+    /// # Returns
+    /// The probability that the player to move wins the game for every board, in the order of the boards
+    fn evaluate_batch(&self, boards: &[UltimateBoard]) -> Vec<f64>;
+
+    /// Returns the name of the evaluator, used in the configuration of the agent
+    fn get_name(&self) -> String;
+}
+
+/// Evaluator mapping the value of a [Heuristic] to a win probability
+///
+/// The value of the heuristic for the player to move is mapped to the win probability `p = 1 / (1 + exp(-value / scale))`,
+/// proven wins and losses to 1 and 0, see [win_probability].
+pub struct HeuristicLeafEvaluator<H> {
+    heuristic: H,
+    scale: f64,
+}
+
+impl<H: Heuristic> HeuristicLeafEvaluator<H> {
+    /// Creates a new [HeuristicLeafEvaluator]
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic, it evaluates the boards [for](Heuristic::evaluate_for) the player to move
+    /// * `scale` - The value of the heuristic that corresponds to a win probability of about 73%, must be positive
+    pub fn new(heuristic: H, scale: f64) -> HeuristicLeafEvaluator<H> {
+        assert!(scale > 0., "Scale must be positive");
+        HeuristicLeafEvaluator { heuristic, scale }
+    }
+}
+
+impl<H: Heuristic> LeafEvaluator for HeuristicLeafEvaluator<H> {
+    fn evaluate_batch(&self, boards: &[UltimateBoard]) -> Vec<f64> {
+        boards
+            .iter()
+            .map(|&board| {
+                let value = self
+                    .heuristic
+                    .evaluate_for(board, board.get_current_player());
+
+                win_probability(value, self.scale)
+            })
+            .collect()
+    }
+
+    fn get_name(&self) -> String {
+        format!("heuristic {} {}", self.heuristic.get_name(), self.scale)
+    }
+}
@@ -0,0 +1,623 @@
+//! # Contains the [MctsConfig] struct
+//!
+//! The [MctsConfig] struct contains all options of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+//! A configuration is created with [MctsConfig::new] and customized with the `with_*` methods,
+//! before it is passed to [MonteCarloTreeAgent::from_config](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::from_config).
+use crate::agent::monte_carlo_tree_agent::evaluator::Evaluator;
+use crate::agent::monte_carlo_tree_agent::leaf_evaluator::LeafEvaluator;
+use crate::agent::monte_carlo_tree_agent::playout_policy::PlayoutPolicy;
+use crate::agent::monte_carlo_tree_agent::selection_formula::SelectionFormula;
+use crate::agent::monte_carlo_tree_agent::{DEFAULT_DRAW_REWARD, DEFAULT_EXPLORATION_CONSTANT};
+use crate::game::player::Player;
+use crate::game::tablebase::Tablebase;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{win_probability, Heuristic};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// # Struct containing the configuration of a [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent)
+///
+/// All optional features are disabled by default.
+///
+/// The configuration is [displayed](Display) with all its options, which is used as the configuration of the agent in the benchmarks.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use hausarbeit::agent::monte_carlo_tree_agent::mcts_config::MctsConfig;
+/// use hausarbeit::agent::monte_carlo_tree_agent::playout_policy::DecisivePolicy;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+///
+/// let config = MctsConfig::new(10000)
+///     .with_time_limit(Duration::from_millis(500))
+///     .with_playout_policy(DecisivePolicy::new(0.1))
+///     .with_threads(4);
+///
+/// let agent = MonteCarloTreeAgent::from_config(config);
+/// ```
+#[derive(Clone)]
+pub struct MctsConfig {
+    /// The number of iterations of the search
+    ///
+    /// If a time limit is set, this is the maximum number of iterations.
+    pub iterations: u32,
+    /// The time budget per move, None if the search always completes all [iterations](MctsConfig::iterations)
+    pub time_limit: Option<Duration>,
+    /// The exploration constant `c` of the selection formula
+    pub exploration_constant: f64,
+    /// The reward of a draw, between the reward of a loss (0) and a win (1)
+    pub draw_reward: f64,
+    /// The value of unvisited children, None if unvisited children are selected first
+    pub first_play_urgency: Option<f64>,
+    /// The formula valuing the children during the selection, None for [Ucb1](crate::agent::monte_carlo_tree_agent::selection_formula::Ucb1)
+    pub selection_formula: Option<Arc<dyn SelectionFormula>>,
+    /// The RAVE equivalence parameter, None if RAVE is disabled
+    pub rave: Option<f64>,
+    /// The policy choosing the moves of the playouts, None for the [UniformPolicy](crate::agent::monte_carlo_tree_agent::playout_policy::UniformPolicy)
+    pub playout_policy: Option<Arc<dyn PlayoutPolicy>>,
+    /// The evaluator replacing the playouts, None if playouts are used
+    pub leaf_evaluator: Option<Arc<dyn LeafEvaluator>>,
+    /// The policy/value function providing the priors and replacing the playouts, None if it is not used
+    pub evaluator: Option<Arc<dyn Evaluator>>,
+    /// The early playout termination, None if the playouts are played to the end of the game
+    pub playout_cutoff: Option<PlayoutCutoff>,
+    /// The progressive bias, None if progressive bias is disabled
+    pub progressive_bias: Option<ProgressiveBias>,
+    /// The coefficient and exponent of the progressive widening, None if all children are expanded at once
+    pub progressive_widening: Option<(f64, f64)>,
+    /// The maximum number of nodes of the tree, None if the tree is not limited
+    pub max_nodes: Option<usize>,
+    /// The maximum distance of a node to the root, None if the depth of the tree is not limited
+    pub max_depth: Option<u32>,
+    /// The number of nodes of the tree after which the search stops, None if the search is not stopped by the size of the tree
+    pub node_budget: Option<usize>,
+    /// The temperature of the sampling of the move, None if the best move is chosen
+    pub temperature: Option<f64>,
+    /// The number of trees searched in parallel
+    pub threads: u32,
+    /// The number of playouts per simulated node, None if leaf parallelization is disabled
+    pub leaf_playouts: Option<u32>,
+    /// The seed of the random number generator, None for a random seed
+    pub seed: Option<u64>,
+    /// Whether the agent keeps searching while the opponent is thinking
+    pub pondering: bool,
+    /// The tablebase marking the results of positions close to the end of the game as proven, None if no tablebase is used
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+impl MctsConfig {
+    /// Creates a new [MctsConfig] with all optional features disabled
+    ///
+    /// # Arguments
+    /// * `iterations` - The number of iterations to determine the number of simulations
+    pub fn new(iterations: u32) -> MctsConfig {
+        MctsConfig {
+            iterations,
+            time_limit: None,
+            exploration_constant: DEFAULT_EXPLORATION_CONSTANT,
+            draw_reward: DEFAULT_DRAW_REWARD,
+            first_play_urgency: None,
+            selection_formula: None,
+            rave: None,
+            playout_policy: None,
+            leaf_evaluator: None,
+            evaluator: None,
+            playout_cutoff: None,
+            progressive_bias: None,
+            progressive_widening: None,
+            max_nodes: None,
+            max_depth: None,
+            node_budget: None,
+            temperature: None,
+            threads: 1,
+            leaf_playouts: None,
+            seed: None,
+            pondering: false,
+            tablebase: None,
+        }
+    }
+
+    /// Sets a time budget per move
+    ///
+    /// The search stops once the time budget is exceeded or all [iterations](MctsConfig::iterations) are completed.
+    /// The elapsed time is checked before every iteration, so the budget is exceeded by at most one iteration.
+    /// If multiple trees are [searched in parallel](MctsConfig::with_threads), the budget applies to every tree.
+    /// # Arguments
+    /// * `time_limit` - The time budget per move
+    /// # Returns
+    /// The configuration with the time budget set
+    pub fn with_time_limit(mut self, time_limit: Duration) -> MctsConfig {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Sets the exploration constant `c` of the UCT formula
+    ///
+    /// Higher values favor exploring rarely visited children, lower values favor exploiting children with a high win rate.
+    /// The default is [DEFAULT_EXPLORATION_CONSTANT].
+    /// # Arguments
+    /// * `exploration_constant` - The exploration constant, must not be negative
+    /// # Returns
+    /// The configuration with the exploration constant set
+    pub fn with_exploration_constant(mut self, exploration_constant: f64) -> MctsConfig {
+        assert!(
+            exploration_constant >= 0.,
+            "Exploration constant must not be negative"
+        );
+        self.exploration_constant = exploration_constant;
+        self
+    }
+
+    /// Sets the reward of a draw
+    ///
+    /// A simulation is rewarded with 1 if the player wins, 0 if the player loses and the draw reward if the game is drawn.
+    /// The win rates of the selection and the choice of the best move are the mean rewards,
+    /// so with a draw reward of 0 a certain draw is as bad as a loss.
+    /// The default is [DEFAULT_DRAW_REWARD].
+    /// # Arguments
+    /// * `draw_reward` - The reward of a draw, must be between 0 and 1
+    /// # Returns
+    /// The configuration with the draw reward set
+    pub fn with_draw_reward(mut self, draw_reward: f64) -> MctsConfig {
+        assert!(
+            (0. ..=1.).contains(&draw_reward),
+            "Draw reward must be between 0 and 1"
+        );
+        self.draw_reward = draw_reward;
+        self
+    }
+
+    /// Sets the First Play Urgency (FPU) of unvisited children
+    ///
+    /// By default all children of a node are visited with a playout each when the node is expanded.
+    /// This wastes simulations in positions with many moves.
+    /// With an FPU, only a single child is visited on expansion and the unvisited children are valued with the given value,
+    /// so visited children whose UCT value is higher are selected before the unvisited children.
+    /// A selected unvisited child is visited with a playout before its own children are created.
+    /// Values around the expected win rate, e.g. 0.5 to 1.5 for the default exploration constant, are typical.
+    /// # Arguments
+    /// * `first_play_urgency` - The UCT value of unvisited children, must not be negative
+    /// # Returns
+    /// The configuration with the First Play Urgency set
+    pub fn with_first_play_urgency(mut self, first_play_urgency: f64) -> MctsConfig {
+        assert!(
+            first_play_urgency >= 0.,
+            "First Play Urgency must not be negative"
+        );
+        self.first_play_urgency = Some(first_play_urgency);
+        self
+    }
+
+    /// Sets the formula valuing the children during the selection
+    ///
+    /// By default the UCT formula [Ucb1](crate::agent::monte_carlo_tree_agent::selection_formula::Ucb1) is used.
+    /// # Arguments
+    /// * `formula` - The formula
+    /// # Returns
+    /// The configuration with the selection formula set
+    pub fn with_selection_formula(
+        mut self,
+        formula: impl SelectionFormula + 'static,
+    ) -> MctsConfig {
+        self.selection_formula = Some(Arc::new(formula));
+        self
+    }
+
+    /// Enables [Rapid Action Value Estimation](https://www.chessprogramming.org/Monte-Carlo_Tree_Search#RAVE) (RAVE)
+    ///
+    /// Every node additionally collects all-moves-as-first (AMAF) statistics: a simulation counts for a child
+    /// if the player to move played the move of the child at any point of the simulation.
+    /// The AMAF win rate is blended with the win rate of the child using the schedule
+    /// `beta = sqrt(k / (3 * visits + k))`, so the AMAF statistics dominate while a child has only few visits.
+    /// # Arguments
+    /// * `equivalence` - The equivalence parameter `k`, must be positive
+    /// # Returns
+    /// The configuration with RAVE enabled
+    pub fn with_rave(mut self, equivalence: f64) -> MctsConfig {
+        assert!(equivalence > 0., "RAVE equivalence must be positive");
+        self.rave = Some(equivalence);
+        self
+    }
+
+    /// Sets the policy choosing the moves of the playouts
+    ///
+    /// By default the [UniformPolicy](crate::agent::monte_carlo_tree_agent::playout_policy::UniformPolicy) is used.
+    /// # Arguments
+    /// * `policy` - The policy
+    /// # Returns
+    /// The configuration with the playout policy set
+    pub fn with_playout_policy(mut self, policy: impl PlayoutPolicy + 'static) -> MctsConfig {
+        self.playout_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Evaluates the leaves with the given evaluator instead of playouts
+    ///
+    /// All children expanded in an iteration are collected and evaluated in a single [batch](LeafEvaluator::evaluate_batch).
+    /// The win probability `p` of the evaluator is counted as `p` wins and `1 - p` losses.
+    /// Leaves whose game is over are not evaluated, their result is used instead.
+    /// # Arguments
+    /// * `evaluator` - The evaluator
+    /// # Returns
+    /// The configuration with the leaf evaluator set
+    pub fn with_leaf_evaluator(mut self, evaluator: impl LeafEvaluator + 'static) -> MctsConfig {
+        self.leaf_evaluator = Some(Arc::new(evaluator));
+        self
+    }
+
+    /// Uses a policy/value function as in [AlphaZero](https://www.chessprogramming.org/AlphaZero)
+    ///
+    /// When a node is expanded, the evaluator is called with the board of the node
+    /// and the probabilities of the moves are used as the priors of the children.
+    /// The priors replace the priors of the [progressive bias](MctsConfig::with_progressive_bias),
+    /// they are used by the [Puct](crate::agent::monte_carlo_tree_agent::selection_formula::Puct) formula and to order the children.
+    ///
+    /// The leaves are scored with the value `v` of the evaluator instead of playouts,
+    /// which is counted as `(v + 1) / 2` wins and `(1 - v) / 2` losses.
+    /// If a [leaf evaluator](MctsConfig::with_leaf_evaluator) is set as well, the leaf evaluator scores the leaves.
+    /// # Arguments
+    /// * `evaluator` - The evaluator
+    /// # Returns
+    /// The configuration with the evaluator set
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator + 'static) -> MctsConfig {
+        self.evaluator = Some(Arc::new(evaluator));
+        self
+    }
+
+    /// Enables early playout termination
+    ///
+    /// Playouts are stopped after the given number of random plies and the position is scored with the heuristic instead of playing to the end of the game.
+    /// The value of the heuristic is mapped to a win probability `p = 1 / (1 + exp(-value / scale))`,
+    /// which is counted as `p` wins and `1 - p` losses.
+    /// This trades the accuracy of the playouts for more iterations per second.
+    ///
+    /// The heuristic evaluates the board [for](Heuristic::evaluate_for) the player the agent plays for.
+    /// # Arguments
+    /// * `plies` - The number of random plies before the playout is stopped
+    /// * `heuristic` - The heuristic used to score the position
+    /// * `scale` - The value of the heuristic that corresponds to a win probability of about 73%, must be positive
+    /// # Returns
+    /// The configuration with early playout termination enabled
+    pub fn with_playout_cutoff(
+        mut self,
+        plies: u32,
+        heuristic: impl Heuristic + 'static,
+        scale: f64,
+    ) -> MctsConfig {
+        self.playout_cutoff = Some(PlayoutCutoff {
+            plies,
+            evaluation: HeuristicEvaluation::new(heuristic, scale),
+        });
+        self
+    }
+
+    /// Enables [progressive bias](https://www.chessprogramming.org/Monte-Carlo_Tree_Search) from heuristic priors
+    ///
+    /// When a node is expanded, every child is scored with the heuristic, mapped to a win probability as in [MctsConfig::with_playout_cutoff].
+    /// The term `weight * prior / (visits + 1)` is added to the UCT value of the child,
+    /// so the heuristic guides the selection while a child has only few visits and its influence decays as the statistics become reliable.
+    ///
+    /// The heuristic evaluates the board [for](Heuristic::evaluate_for) the player the agent plays for.
+    /// # Arguments
+    /// * `heuristic` - The heuristic used to score the children
+    /// * `scale` - The value of the heuristic that corresponds to a win probability of about 73%, must be positive
+    /// * `weight` - The weight of the progressive bias, must not be negative
+    /// # Returns
+    /// The configuration with progressive bias enabled
+    pub fn with_progressive_bias(
+        mut self,
+        heuristic: impl Heuristic + 'static,
+        scale: f64,
+        weight: f64,
+    ) -> MctsConfig {
+        assert!(weight >= 0., "Weight must not be negative");
+        self.progressive_bias = Some(ProgressiveBias {
+            evaluation: HeuristicEvaluation::new(heuristic, scale),
+            weight,
+        });
+        self
+    }
+
+    /// Enables [progressive widening](https://www.chessprogramming.org/Monte-Carlo_Tree_Search) of the expansion
+    ///
+    /// By default, a leaf expands all children at once and runs a playout for each of them.
+    /// With progressive widening, a node with `n` visits only expands the first `ceil(coefficient * (n + 1)^exponent)` children,
+    /// further children are expanded with a playout as soon as the number of visits allows it.
+    ///
+    /// The children are ordered by their [prior](MctsConfig::with_progressive_bias) if progressive bias is enabled, otherwise randomly.
+    /// # Arguments
+    /// * `coefficient` - The number of children expanded at the first visit, must be positive
+    /// * `exponent` - The growth of the number of expanded children, must be between 0 and 1
+    /// # Returns
+    /// The configuration with progressive widening enabled
+    pub fn with_progressive_widening(mut self, coefficient: f64, exponent: f64) -> MctsConfig {
+        assert!(coefficient > 0., "Coefficient must be positive");
+        assert!(
+            (0. ..=1.).contains(&exponent),
+            "Exponent must be between 0 and 1"
+        );
+        self.progressive_widening = Some((coefficient, exponent));
+        self
+    }
+
+    /// Limits the number of nodes of the tree
+    ///
+    /// Once expanding a leaf would exceed the limit, the leaf is no longer expanded.
+    /// Instead, a single playout is run from the leaf itself, so the search continues without using more memory.
+    /// How often the limit was hit is reported in the [statistics](crate::agent::minimax_agent::search_statistics::SearchStatistics::node_limit_hits) of [Agent::get_info](crate::agent::Agent::get_info).
+    ///
+    /// If multiple trees are [searched in parallel](MctsConfig::with_threads), the limit applies to every tree.
+    /// # Arguments
+    /// * `max_nodes` - The maximum number of nodes of the tree, must be positive
+    /// # Returns
+    /// The configuration with the node limit set
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> MctsConfig {
+        assert!(max_nodes > 0, "Node limit must be positive");
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limits the depth of the tree
+    ///
+    /// Nodes at the given distance to the root are no longer expanded.
+    /// Instead, a playout is run from the node itself, so the selection never follows a line deeper than the limit.
+    /// This bounds the length of the paths in positions where the search keeps following a single forced line.
+    /// How often the limit was hit is reported in the [statistics](crate::agent::minimax_agent::search_statistics::SearchStatistics::depth_limit_hits) of [Agent::get_info](crate::agent::Agent::get_info).
+    /// # Arguments
+    /// * `max_depth` - The maximum distance of a node to the root, must be positive
+    /// # Returns
+    /// The configuration with the depth limit set
+    pub fn with_max_depth(mut self, max_depth: u32) -> MctsConfig {
+        assert!(max_depth > 0, "Depth limit must be positive");
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets a node budget per move
+    ///
+    /// The search stops once the tree contains the given number of nodes or all [iterations](MctsConfig::iterations) are completed.
+    /// Unlike the [node limit](MctsConfig::with_max_nodes), which keeps searching without growing the tree,
+    /// the budget ends the search, so both the memory and the time per move are bounded.
+    /// The budget is checked before every iteration, so it is exceeded by at most the children of a single expansion.
+    ///
+    /// If multiple trees are [searched in parallel](MctsConfig::with_threads), the budget applies to every tree.
+    /// # Arguments
+    /// * `node_budget` - The number of nodes after which the search stops, must be positive
+    /// # Returns
+    /// The configuration with the node budget set
+    pub fn with_node_budget(mut self, node_budget: usize) -> MctsConfig {
+        assert!(node_budget > 0, "Node budget must be positive");
+        self.node_budget = Some(node_budget);
+        self
+    }
+
+    /// Samples the move instead of choosing the best move
+    ///
+    /// The move is sampled proportionally to `visits^(1 / temperature)` of the children of the root.
+    /// Low temperatures approach choosing the most visited move, a temperature of 1 samples proportionally to the visits.
+    /// This generates diverse games, e.g. for self-play training data or to avoid repeated tournament games.
+    ///
+    /// A move proven to win is always chosen, moves proven to lose are only sampled if all moves are lost.
+    /// # Arguments
+    /// * `temperature` - The temperature, must be positive
+    /// # Returns
+    /// The configuration with sampled move selection
+    pub fn with_temperature(mut self, temperature: f64) -> MctsConfig {
+        assert!(temperature > 0., "Temperature must be positive");
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the number of trees searched in parallel
+    ///
+    /// Every tree is searched independently with the full number of iterations on the [rayon] thread pool.
+    /// The statistics of the children of the roots are merged before the best move is chosen.
+    /// # Arguments
+    /// * `threads` - The number of trees, must be positive
+    /// # Returns
+    /// The configuration with the number of trees set
+    pub fn with_threads(mut self, threads: u32) -> MctsConfig {
+        assert!(threads > 0, "Number of threads must be positive");
+        self.threads = threads;
+        self
+    }
+
+    /// Runs multiple playouts in parallel for every simulated node (leaf parallelization)
+    ///
+    /// The playouts of a node run on the [rayon] thread pool and all their results are backpropagated together.
+    /// Unlike [root parallelization](MctsConfig::with_threads), a single tree is searched,
+    /// so the memory used by the search does not grow with the number of threads.
+    /// Every node then counts as visited once per playout.
+    /// # Arguments
+    /// * `playouts` - The number of playouts per simulated node, must be positive
+    /// # Returns
+    /// The configuration with leaf parallelization enabled
+    pub fn with_leaf_playouts(mut self, playouts: u32) -> MctsConfig {
+        assert!(playouts > 0, "Number of leaf playouts must be positive");
+        self.leaf_playouts = Some(playouts);
+        self
+    }
+
+    /// Seeds the random number generator of the agent
+    ///
+    /// The generator is used for the playouts, the order of the children and the [sampling](MctsConfig::with_temperature) of the move.
+    /// Agents with the same seed and configuration play the same moves, which allows deterministic tests of the search.
+    /// Without a seed, the generator is seeded from the operating system.
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The configuration with the seed set
+    pub fn with_seed(mut self, seed: u64) -> MctsConfig {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Enables or disables pondering
+    ///
+    /// After a move has been made, the tree of the position after the move is searched in a background thread
+    /// while the opponent is thinking, for at most the number of [iterations](MctsConfig::iterations) of a move.
+    /// When the move of the opponent arrives, the subtree of the move becomes the root of the next search,
+    /// so the simulations of the pondering are kept.
+    ///
+    /// Pondering is stopped when the agent is [notified](crate::agent::Agent::opponent_moved) about the move of the opponent
+    /// or at the latest when the next move is requested.
+    /// If multiple trees are [searched in parallel](MctsConfig::with_threads), only the first tree continues the pondered tree.
+    /// Pondering is disabled by default.
+    /// # Arguments
+    /// * `pondering` - Whether the agent should ponder
+    /// # Returns
+    /// The configuration with pondering enabled or disabled
+    pub fn with_pondering(mut self, pondering: bool) -> MctsConfig {
+        self.pondering = pondering;
+        self
+    }
+
+    /// Probes a tablebase when expanding nodes
+    ///
+    /// Children whose positions are stored in the [Tablebase] are marked as proven with their exact result,
+    /// so they are not simulated and their results are propagated towards the root like the results of finished games.
+    /// # Arguments
+    /// * `tablebase` - The tablebase
+    /// # Returns
+    /// The configuration probing the tablebase
+    pub fn with_tablebase(mut self, tablebase: Arc<Tablebase>) -> MctsConfig {
+        self.tablebase = Some(tablebase);
+        self
+    }
+}
+
+impl Display for MctsConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        /// Formats an optional option, `none` if it is disabled
+        fn option<T: Display>(value: Option<T>) -> String {
+            value.map_or("none".to_string(), |value| value.to_string())
+        }
+
+        write!(
+            f,
+            "max_nodes: {}, c: {}, threads: {}, leaf playouts: {}, time limit: {}, seed: {}, fpu: {}, formula: {}, rave: {}, playout: {}, evaluator: {}, network: {}, cutoff: {}, bias: {}, widening: {}, node limit: {}, max depth: {}, node budget: {}, temperature: {}, draw reward: {}, ponder: {}, tablebase: {}",
+            self.iterations,
+            self.exploration_constant,
+            self.threads,
+            option(self.leaf_playouts),
+            option(self.time_limit.map(|time_limit| format!("{}ms", time_limit.as_millis()))),
+            option(self.seed),
+            option(self.first_play_urgency),
+            self.selection_formula
+                .as_ref()
+                .map_or("ucb1".to_string(), |formula| formula.get_name()),
+            option(self.rave),
+            self.playout_policy
+                .as_ref()
+                .map_or("uniform".to_string(), |policy| policy.get_name()),
+            option(self.leaf_evaluator.as_ref().map(|evaluator| evaluator.get_name())),
+            option(self.evaluator.as_ref().map(|evaluator| evaluator.get_name())),
+            option(self.playout_cutoff.as_ref().map(|cutoff| cutoff.plies)),
+            option(self.progressive_bias.as_ref().map(|bias| bias.weight)),
+            option(
+                self.progressive_widening
+                    .map(|(coefficient, exponent)| format!("{}*n^{}", coefficient, exponent))
+            ),
+            option(self.max_nodes),
+            option(self.max_depth),
+            option(self.node_budget),
+            option(self.temperature),
+            self.draw_reward,
+            self.pondering,
+            option(
+                self.tablebase
+                    .as_ref()
+                    .map(|tablebase| tablebase.get_max_empty_squares())
+            ),
+        )
+    }
+}
+
+/// # Struct representing a heuristic whose values are mapped to win probabilities
+///
+/// The value of the heuristic is mapped to a win probability `p = 1 / (1 + exp(-value / scale))`.
+#[derive(Clone)]
+pub struct HeuristicEvaluation {
+    /// The heuristic used to score the position
+    evaluate: Arc<dyn Fn(UltimateBoard, Player) -> f64 + Send + Sync>,
+    /// The value of the heuristic mapped to a win probability of `1 / (1 + e^-1)`
+    scale: f64,
+}
+
+impl HeuristicEvaluation {
+    /// Creates a new [HeuristicEvaluation]
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic used to score the position
+    /// * `scale` - The value of the heuristic mapped to a win probability of `1 / (1 + e^-1)`, must be positive
+    fn new(heuristic: impl Heuristic + 'static, scale: f64) -> Self {
+        assert!(scale > 0., "Scale must be positive");
+        HeuristicEvaluation {
+            evaluate: Arc::new(move |board, player| heuristic.evaluate_for(board, player)),
+            scale,
+        }
+    }
+
+    /// Scores the position with the heuristic
+    ///
+    /// # Arguments
+    /// * `board` - The board to score
+    /// * `player` - The player of the agent
+    /// # Returns
+    /// The probability that the player of the agent wins the game
+    pub(super) fn win_probability(&self, board: UltimateBoard, player: Player) -> f64 {
+        win_probability((self.evaluate)(board, player), self.scale)
+    }
+}
+
+/// # Struct representing the configuration of the early playout termination
+///
+/// See [MctsConfig::with_playout_cutoff].
+#[derive(Clone)]
+pub struct PlayoutCutoff {
+    /// The number of random plies before the playout is stopped
+    pub(super) plies: u32,
+    /// The heuristic used to score the position
+    pub(super) evaluation: HeuristicEvaluation,
+}
+
+/// # Struct representing the configuration of the progressive bias
+///
+/// See [MctsConfig::with_progressive_bias].
+#[derive(Clone)]
+pub struct ProgressiveBias {
+    /// The heuristic used to score the children
+    pub(super) evaluation: HeuristicEvaluation,
+    /// The weight of the progressive bias
+    pub(super) weight: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::monte_carlo_tree_agent::playout_policy::DecisivePolicy;
+
+    #[test]
+    fn test_display() {
+        let config = MctsConfig::new(100)
+            .with_time_limit(Duration::from_millis(50))
+            .with_playout_policy(DecisivePolicy::new(0.5))
+            .with_progressive_widening(1., 0.5);
+
+        let display = config.to_string();
+
+        assert!(display.starts_with("max_nodes: 100, c: "));
+        assert!(display.contains("time limit: 50ms"));
+        assert!(display.contains("playout: decisive 0.5"));
+        assert!(display.contains("widening: 1*n^0.5"));
+        assert!(display.contains("formula: ucb1"));
+        assert!(display.contains("rave: none"));
+
+        // Every option is displayed, also if it is disabled
+        assert_eq!(
+            display.matches(", ").count(),
+            MctsConfig::new(100).to_string().matches(", ").count()
+        );
+    }
+}
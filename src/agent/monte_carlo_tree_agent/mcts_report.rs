@@ -0,0 +1,41 @@
+//! # Contains the [MctsReport] struct
+//!
+//! The [MctsReport] struct contains the diagnostics of a search of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+//! It is available with [MonteCarloTreeAgent::get_last_report](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::get_last_report)
+//! and is part of the [AgentInfo](crate::agent::AgentInfo) of the agent, so it can be recorded by the [BenchedAgent](crate::agent::benched::BenchedAgent).
+use crate::game::game_result::GameResult;
+
+/// # Struct containing the diagnostics of a search
+///
+/// If multiple trees are searched in parallel, the statistics of the children of the roots are merged
+/// and the principal variation of the tree that visited its first move the most is reported.
+///
+/// # Fields
+/// * `best_move` - The index of the field to play on
+/// * `children` - The children of the root, the most visited child first
+/// * `principal_variation` - The most visited line, starting at the root
+/// * `deepest_line` - The line to the deepest expanded node of the tree, starting at the root
+/// * `simulations` - The total number of simulations through the children of the root
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsReport {
+    pub best_move: u8,
+    pub children: Vec<MctsChild>,
+    pub principal_variation: Vec<u8>,
+    pub deepest_line: Vec<u8>,
+    pub simulations: f64,
+}
+
+/// # Struct containing the statistics of a single child of the root
+///
+/// # Fields
+/// * `move_index` - The index of the move leading to the child
+/// * `visits` - The number of simulations through the child, fractional if playouts are [cut off](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_playout_cutoff)
+/// * `win_rate` - The mean reward of the simulations for the player of the agent, draws count as the [draw reward](crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig::draw_reward)
+/// * `proven` - The proven result of the child, None if the result is not proven
+#[derive(Clone, Debug, PartialEq)]
+pub struct MctsChild {
+    pub move_index: u8,
+    pub visits: f64,
+    pub win_rate: f64,
+    pub proven: Option<GameResult>,
+}
@@ -3,17 +3,50 @@
 //! The [MonteCarloTreeAgent] struct represents an agent that uses the Monte Carlo Tree Search algorithm to evaluate the best move.
 //!
 //! The agent uses a [Tree] to store the game states and the statistics of the nodes.
-use crate::agent::monte_carlo_tree_agent::tree::{Node, Tree};
-use crate::agent::{Agent, AgentInfo};
+use crate::agent::agent_error::AgentError;
+use crate::agent::minimax_agent::search_statistics::SearchStatistics;
+use crate::agent::monte_carlo_tree_agent::evaluator::Evaluator;
+use crate::agent::monte_carlo_tree_agent::leaf_evaluator::LeafEvaluator;
+use crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig;
+use crate::agent::monte_carlo_tree_agent::mcts_report::{MctsChild, MctsReport};
+use crate::agent::monte_carlo_tree_agent::playout_policy::{PlayoutPolicy, UniformPolicy};
+use crate::agent::monte_carlo_tree_agent::selection_formula::{
+    SelectionFormula, SelectionStatistics, Ucb1,
+};
+use crate::agent::monte_carlo_tree_agent::tree::{NodeId, Tree};
+use crate::agent::monte_carlo_tree_agent::tree_dump::TreeFormat;
+use crate::agent::{Agent, AgentInfo, AnytimeAgent};
 use crate::game::game_result::GameResult;
 use crate::game::player::Player;
+use crate::game::tablebase::Tablebase;
 use crate::game::ultimate_board::UltimateBoard;
-use rand::distributions::Uniform;
+use crate::heuristic::Heuristic;
+use rand::distributions::WeightedIndex;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use rand_distr::Distribution;
-use std::cell::RefCell;
-use std::rc::Rc;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
+pub mod evaluator;
+pub mod leaf_evaluator;
+pub mod mcts_config;
+pub mod mcts_report;
+pub mod playout_policy;
+pub mod selection_formula;
 pub mod tree;
+pub mod tree_dump;
+
+/// The default exploration constant of the [UCT](https://www.chessprogramming.org/UCT) formula
+pub const DEFAULT_EXPLORATION_CONSTANT: f64 = std::f64::consts::SQRT_2;
+
+/// The default reward of a draw, halfway between a loss and a win
+pub const DEFAULT_DRAW_REWARD: f64 = 0.5;
 
 /// # Struct representing an agent that uses the Monte Carlo Tree Search algorithm to evaluate the best move
 ///
@@ -21,260 +54,2564 @@ pub mod tree;
 ///
 /// The agent uses the number of iterations to determine the number of simulations.
 ///
+/// The children of a node are selected using the [UCT](https://www.chessprogramming.org/UCT) formula
+/// with a configurable [exploration constant](MonteCarloTreeAgent::with_exploration_constant).
+/// Other [formulas](SelectionFormula) can be used with [MonteCarloTreeAgent::with_selection_formula].
+/// Unvisited children are selected first, unless a [First Play Urgency](MonteCarloTreeAgent::with_first_play_urgency) is set.
+///
+/// The agent uses [MCTS-Solver](https://www.chessprogramming.org/Monte-Carlo_Tree_Search) semantics:
+/// nodes whose result is certain are marked as proven, proven results are propagated towards the root
+/// and children proven to be lost are no longer selected.
+///
+/// The moves of the playouts are chosen by a [PlayoutPolicy], see [MonteCarloTreeAgent::with_playout_policy].
+///
+/// Instead of playouts, the leaves can be evaluated in batches by a [LeafEvaluator], see [MonteCarloTreeAgent::with_leaf_evaluator].
+/// A policy/value function, e.g. a neural network, can be plugged in as an [Evaluator], see [MonteCarloTreeAgent::with_evaluator].
+///
+/// Playouts can be [cut off](MonteCarloTreeAgent::with_playout_cutoff) after a number of plies and scored with a [Heuristic].
+///
+/// Heuristic knowledge can guide the selection with [progressive bias](MonteCarloTreeAgent::with_progressive_bias).
+///
+/// With [progressive widening](MonteCarloTreeAgent::with_progressive_widening) only the most promising children of a node are expanded,
+/// more children are added as the number of visits grows.
+///
+/// The size of the tree can be [limited](MonteCarloTreeAgent::with_max_nodes) to bound the memory used by long searches.
+/// The depth of the tree can be [limited](MonteCarloTreeAgent::with_max_depth) as well,
+/// and a [node budget](MonteCarloTreeAgent::with_node_budget) stops the search once the tree has grown large enough.
+/// The line to the deepest node is part of the [report](MctsReport::deepest_line).
+///
+/// For self-play, the move can be [sampled](MonteCarloTreeAgent::with_temperature) by the visit counts instead of choosing the best move.
+///
+/// [Rapid Action Value Estimation](https://www.chessprogramming.org/Monte-Carlo_Tree_Search#RAVE)
+/// can be enabled with [MonteCarloTreeAgent::with_rave].
+///
+/// Multiple independent trees can be searched in parallel with [MonteCarloTreeAgent::with_threads]
+/// ([root parallelization](https://www.chessprogramming.org/Parallel_Search#RootParallelization)).
+/// The playouts of a single tree can be run in parallel with [MonteCarloTreeAgent::with_leaf_playouts] (leaf parallelization).
+///
+/// The random decisions of the search can be made reproducible with [MonteCarloTreeAgent::with_seed].
+///
+/// With [pondering](MonteCarloTreeAgent::with_pondering), the agent keeps searching while the opponent is thinking
+/// and continues the subtree of the move of the opponent.
+/// The agent can also search in the background as an [AnytimeAgent].
+///
+/// All options are collected in a [MctsConfig], which can be passed to [MonteCarloTreeAgent::from_config].
+/// The search can be limited by [time](MonteCarloTreeAgent::with_time_limit) instead of the number of iterations.
+///
 /// The agent uses the [Player] and the turn to determine the best move.
 pub struct MonteCarloTreeAgent {
-    iterations: u32,
+    /// The configuration of the search
+    config: MctsConfig,
+    /// The random number generator of the search
+    rng: ChaCha20Rng,
     player: Player,
     turn: u32,
+    /// The statistics of the last search, reported by [get_info](Agent::get_info)
+    last_statistics: Option<SearchStatistics>,
+    last_report: Option<MctsReport>,
+    last_tree: Option<Tree<NodeInfo>>,
+    /// The running pondering thread, if any
+    ponder: Option<Ponder>,
+    /// The running [anytime](AnytimeAgent) search, if any
+    thinking: Option<Thinking>,
+    /// The pondered tree of the current position, the root of the next search
+    pondered_tree: Option<Tree<NodeInfo>>,
 }
 
 impl MonteCarloTreeAgent {
-    /// Creates a new [MonteCarloTreeAgent]
+    /// Creates a new [MonteCarloTreeAgent] with the default configuration
     ///
+    /// See [MctsConfig::new] for the defaults.
     /// # Arguments
     /// * `iterations` - The number of iterations to determine the number of simulations
     pub fn new(iterations: u32) -> Self {
+        MonteCarloTreeAgent::from_config(MctsConfig::new(iterations))
+    }
+
+    /// Creates a new [MonteCarloTreeAgent] from the given configuration
+    ///
+    /// # Arguments
+    /// * `config` - The configuration of the search
+    pub fn from_config(config: MctsConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+            None => ChaCha20Rng::from_entropy(),
+        };
+
         MonteCarloTreeAgent {
-            iterations,
+            config,
+            rng,
             player: Player::default(),
             turn: 0,
+            last_statistics: None,
+            last_report: None,
+            last_tree: None,
+            ponder: None,
+            thinking: None,
+            pondered_tree: None,
         }
     }
 
-    fn tree_root(&self, board: UltimateBoard) -> Option<u8> {
-        let tree = Tree::new(Node::new(NodeInfo::new(board)));
+    /// Returns the configuration of the agent
+    pub fn get_config(&self) -> &MctsConfig {
+        &self.config
+    }
+
+    /// Sets a time budget per move, see [MctsConfig::with_time_limit]
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.config = self.config.with_time_limit(time_limit);
+        self
+    }
+
+    /// Sets the exploration constant `c` of the UCT formula, see [MctsConfig::with_exploration_constant]
+    pub fn with_exploration_constant(mut self, exploration_constant: f64) -> Self {
+        self.config = self.config.with_exploration_constant(exploration_constant);
+        self
+    }
+
+    /// Sets the reward of a draw, see [MctsConfig::with_draw_reward]
+    pub fn with_draw_reward(mut self, draw_reward: f64) -> Self {
+        self.config = self.config.with_draw_reward(draw_reward);
+        self
+    }
+
+    /// Sets the First Play Urgency (FPU) of unvisited children, see [MctsConfig::with_first_play_urgency]
+    pub fn with_first_play_urgency(mut self, first_play_urgency: f64) -> Self {
+        self.config = self.config.with_first_play_urgency(first_play_urgency);
+        self
+    }
+
+    /// Sets the formula valuing the children during the selection, see [MctsConfig::with_selection_formula]
+    pub fn with_selection_formula(mut self, formula: impl SelectionFormula + 'static) -> Self {
+        self.config = self.config.with_selection_formula(formula);
+        self
+    }
+
+    /// Enables Rapid Action Value Estimation (RAVE), see [MctsConfig::with_rave]
+    pub fn with_rave(mut self, equivalence: f64) -> Self {
+        self.config = self.config.with_rave(equivalence);
+        self
+    }
+
+    /// Sets the policy choosing the moves of the playouts, see [MctsConfig::with_playout_policy]
+    pub fn with_playout_policy(mut self, policy: impl PlayoutPolicy + 'static) -> Self {
+        self.config = self.config.with_playout_policy(policy);
+        self
+    }
+
+    /// Evaluates the leaves with the given evaluator instead of playouts, see [MctsConfig::with_leaf_evaluator]
+    pub fn with_leaf_evaluator(mut self, evaluator: impl LeafEvaluator + 'static) -> Self {
+        self.config = self.config.with_leaf_evaluator(evaluator);
+        self
+    }
+
+    /// Enables early playout termination, see [MctsConfig::with_playout_cutoff]
+    pub fn with_playout_cutoff(
+        mut self,
+        plies: u32,
+        heuristic: impl Heuristic + 'static,
+        scale: f64,
+    ) -> Self {
+        self.config = self.config.with_playout_cutoff(plies, heuristic, scale);
+        self
+    }
+
+    /// Enables progressive bias from heuristic priors, see [MctsConfig::with_progressive_bias]
+    pub fn with_progressive_bias(
+        mut self,
+        heuristic: impl Heuristic + 'static,
+        scale: f64,
+        weight: f64,
+    ) -> Self {
+        self.config = self.config.with_progressive_bias(heuristic, scale, weight);
+        self
+    }
+
+    /// Enables progressive widening of the expansion, see [MctsConfig::with_progressive_widening]
+    pub fn with_progressive_widening(mut self, coefficient: f64, exponent: f64) -> Self {
+        self.config = self.config.with_progressive_widening(coefficient, exponent);
+        self
+    }
+
+    /// Limits the number of nodes of the tree, see [MctsConfig::with_max_nodes]
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.config = self.config.with_max_nodes(max_nodes);
+        self
+    }
+
+    /// Samples the move instead of choosing the best move, see [MctsConfig::with_temperature]
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.config = self.config.with_temperature(temperature);
+        self
+    }
+
+    /// Sets the number of trees searched in parallel, see [MctsConfig::with_threads]
+    pub fn with_threads(mut self, threads: u32) -> Self {
+        self.config = self.config.with_threads(threads);
+        self
+    }
+
+    /// Runs multiple playouts in parallel for every simulated node (leaf parallelization), see [MctsConfig::with_leaf_playouts]
+    pub fn with_leaf_playouts(mut self, playouts: u32) -> Self {
+        self.config = self.config.with_leaf_playouts(playouts);
+        self
+    }
+
+    /// Seeds the random number generator of the agent, see [MctsConfig::with_seed]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.config = self.config.with_seed(seed);
+        self.rng = ChaCha20Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Limits the depth of the tree, see [MctsConfig::with_max_depth]
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.config = self.config.with_max_depth(max_depth);
+        self
+    }
+
+    /// Sets a node budget per move, see [MctsConfig::with_node_budget]
+    pub fn with_node_budget(mut self, node_budget: usize) -> Self {
+        self.config = self.config.with_node_budget(node_budget);
+        self
+    }
+
+    /// Uses a policy/value function instead of playouts, see [MctsConfig::with_evaluator]
+    pub fn with_evaluator(mut self, evaluator: impl Evaluator + 'static) -> Self {
+        self.config = self.config.with_evaluator(evaluator);
+        self
+    }
+
+    /// Enables or disables pondering, see [MctsConfig::with_pondering]
+    pub fn with_pondering(mut self, pondering: bool) -> Self {
+        self.config = self.config.with_pondering(pondering);
+        self
+    }
+
+    /// Probes a tablebase when expanding nodes, see [MctsConfig::with_tablebase]
+    pub fn with_tablebase(mut self, tablebase: Arc<Tablebase>) -> Self {
+        self.config = self.config.with_tablebase(tablebase);
+        self
+    }
+
+    /// Returns the diagnostics of the last search
+    ///
+    /// # Returns
+    /// The report of the last search, None if no move was searched yet or the game was over
+    pub fn get_last_report(&self) -> Option<&MctsReport> {
+        self.last_report.as_ref()
+    }
+
+    /// Exports the tree of the last search for visualization
+    ///
+    /// Every node is labeled with its move, its number of visits and its win rate for the player of the agent.
+    /// If multiple trees were [searched in parallel](MonteCarloTreeAgent::with_threads), the tree of the principal variation is exported.
+    ///
+    /// # Example
+    /// ```
+    /// use hausarbeit::agent::Agent;
+    /// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+    /// use hausarbeit::agent::monte_carlo_tree_agent::tree_dump::TreeFormat;
+    /// use hausarbeit::game::player::Player;
+    /// use hausarbeit::game::ultimate_board::UltimateBoard;
+    ///
+    /// let mut agent = MonteCarloTreeAgent::new(100);
+    /// agent.act(&UltimateBoard::new(), Player::One, 0).unwrap();
+    ///
+    /// let dot = agent.dump_tree(2, TreeFormat::Dot).unwrap();
+    /// assert!(dot.starts_with("digraph"));
+    /// ```
+    /// # Arguments
+    /// * `max_depth` - The depth of the deepest exported nodes, 0 only exports the root
+    /// * `format` - The format of the export
+    /// # Returns
+    /// The exported tree, None if no move was searched yet
+    pub fn dump_tree(&self, max_depth: usize, format: TreeFormat) -> Option<String> {
+        self.last_tree
+            .as_ref()
+            .map(|tree| tree_dump::dump(tree, max_depth, format, self.config.draw_reward))
+    }
 
-        let root = tree.get_root().unwrap();
+    /// Starts pondering on the position after the move of the agent
+    ///
+    /// The position is searched in a background thread until the pondering is [stopped](MonteCarloTreeAgent::stop_pondering).
+    /// # Arguments
+    /// * `board` - The board the move was chosen on
+    /// * `best_move` - The move of the agent
+    fn start_pondering(&mut self, board: UltimateBoard, best_move: u8) {
+        let mut board = board;
+        board.make_move(best_move);
 
-        for _ in 0..self.iterations {
-            let _ = self.tree_search(root.clone());
+        if board.get_game_status() != GameResult::Continue {
+            return;
         }
 
-        let best_child = root
-            .borrow()
+        let searcher = self.background_searcher();
+
+        let seed = self.rng.gen();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+            let mut tree = Tree::new(NodeInfo::new(board));
+            let mut statistics = SearchStatistics::default();
+
+            for _ in 0..searcher.config.iterations {
+                if thread_stop.load(Ordering::Relaxed)
+                    || tree.get(tree.get_root()).get_data().proven.is_some()
+                {
+                    break;
+                }
+
+                searcher.tree_search(&mut tree, &mut statistics, &mut rng);
+            }
+
+            (tree, statistics)
+        });
+
+        self.ponder = Some(Ponder {
+            stop,
+            handle: Some(handle),
+        });
+    }
+
+    /// Stops the pondering thread, if any, and keeps the pondered tree of the current position
+    ///
+    /// Blocks until the thread has finished.
+    /// # Arguments
+    /// * `board` - The current board, after the move of the opponent
+    fn stop_pondering(&mut self, board: UltimateBoard) {
+        let Some((tree, _)) = self.ponder.take().and_then(Ponder::finish) else {
+            return;
+        };
+
+        // The subtree of the move of the opponent becomes the root of the next search
+        self.pondered_tree = tree
+            .get(tree.get_root())
             .get_children()
-            .iter()
+            .find(|&child| tree.get(child).get_data().get_board() == board)
             .map(|child| {
-                let child_stats = child.borrow().get_data().stats;
-                let uct = child_stats.wins() as f64 / child_stats.total() as f64;
-                (child, uct)
-            })
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(child, _)| child)
-            .unwrap()
-            .clone();
-
-        best_child.clone().borrow().get_data().get_move_index()
+                let mut subtree = tree.subtree(child);
+                subtree
+                    .get_mut(subtree.get_root())
+                    .get_data_mut()
+                    .move_index = None;
+                subtree
+            });
     }
 
-    fn tree_search(&self, root: Rc<RefCell<Node<NodeInfo>>>) -> Stats {
-        let mut root_stats = Stats::default();
+    /// Searches the given board and chooses the best move
+    ///
+    /// The statistics, the report and the tree of the search are stored for [Agent::get_info] and [MonteCarloTreeAgent::dump_tree].
+    /// # Arguments
+    /// * `board` - The board to search
+    /// # Returns
+    /// The best move, None if the game is over
+    fn tree_root(&mut self, board: UltimateBoard) -> Option<u8> {
+        // The tree of the previous search is dropped before the next search allocates its tree
+        self.last_tree = None;
 
-        if root.borrow().get_data().get_board().get_game_status() != GameResult::Continue {
-            return root_stats;
-        } else if root.borrow().is_leaf() {
-            let board = root.borrow().get_data().get_board();
-            for possible_move in board.get_possible_moves() {
-                let mut board_copy = board;
-                board_copy.make_move(possible_move);
-                let stats = self.playout(board_copy);
+        self.stop_pondering(board);
+        self.thinking = None;
 
-                let mut node_info = NodeInfo::withMove(board_copy, possible_move);
-                node_info.apply_stats(stats);
+        // Every tree gets its own generator, so the trees do not depend on the scheduling of the threads
+        let mut trees: Vec<(Option<Tree<NodeInfo>>, u64)> = (0..self.config.threads)
+            .map(|_| (None, self.rng.gen()))
+            .collect();
 
-                root_stats.merge(stats);
+        // The first tree continues the pondered tree
+        trees[0].0 = self.pondered_tree.take();
 
-                root.borrow_mut().append(Node::new(node_info));
+        let search = |(tree, seed): (Option<Tree<NodeInfo>>, u64)| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            match tree {
+                Some(tree) => self.search_tree_from(tree, &mut rng),
+                None => self.search_tree(board, &mut rng),
             }
+        };
+
+        let searched_tree = if self.config.threads > 1 {
+            trees
+                .into_par_iter()
+                .map(search)
+                .reduce_with(SearchedTree::merge)
+                .unwrap()
         } else {
-            let root_visits = root.borrow().get_data().stats.total();
+            search(trees.pop().unwrap())
+        };
+
+        self.choose_move(searched_tree)
+    }
+
+    /// Chooses the best move among the children of the root of a searched tree
+    ///
+    /// The statistics, the report and the tree of the search are stored for [Agent::get_info] and [MonteCarloTreeAgent::dump_tree].
+    /// # Arguments
+    /// * `searched_tree` - The outcome of the search
+    /// # Returns
+    /// The best move, None if the root has no children
+    fn choose_move(&mut self, mut searched_tree: SearchedTree) -> Option<u8> {
+        self.last_statistics = Some(searched_tree.statistics);
+        self.last_tree = searched_tree.tree.take();
 
-            let best_child = root
-                .borrow()
-                .get_children()
+        let best_move = match self.config.temperature {
+            Some(temperature) => self.sample_move(&searched_tree.children, temperature),
+            None => searched_tree
+                .children
                 .iter()
-                .map(|child| {
-                    let uct = child.borrow().get_data().uct_value(root_visits);
-                    (child, uct)
+                .map(|&(move_index, stats, proven)| {
+                    let value = match proven {
+                        Some(GameResult::Win(player)) if player == self.player => f64::INFINITY,
+                        Some(GameResult::Win(_)) => f64::NEG_INFINITY,
+                        _ => stats.win_rate(true, self.config.draw_reward),
+                    };
+                    (move_index, value)
                 })
                 .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-                .map(|(child, _)| child)
-                .unwrap()
-                .clone();
+                .map(|(move_index, _)| move_index),
+        };
+
+        self.last_report = best_move
+            .map(|best_move| searched_tree.into_report(best_move, self.config.draw_reward));
+
+        best_move
+    }
 
-            let stats = self.tree_search(best_child.clone());
+    /// Samples a move proportionally to the visits of the children of the root raised to `1 / temperature`
+    ///
+    /// # Arguments
+    /// * `children` - The children of the root
+    /// * `temperature` - The temperature of the sampling
+    /// # Returns
+    /// The sampled move, None if there are no children
+    fn sample_move(&mut self, children: &[RootChild], temperature: f64) -> Option<u8> {
+        let won = Some(GameResult::Win(self.player));
+        let lost = Some(GameResult::Win(self.player.get_opponent()));
 
-            root_stats.merge(stats);
+        if let Some(&(move_index, _, _)) = children.iter().find(|(_, _, proven)| *proven == won) {
+            return Some(move_index);
         }
 
-        root.borrow_mut().map(|data: NodeInfo| {
-            let mut res = data;
-            res.apply_stats(root_stats);
-            res
-        });
+        let candidates: Vec<&RootChild> = if children.iter().all(|(_, _, proven)| *proven == lost) {
+            children.iter().collect()
+        } else {
+            children
+                .iter()
+                .filter(|(_, _, proven)| *proven != lost)
+                .collect()
+        };
+
+        let weights = candidates
+            .iter()
+            .map(|(_, stats, _)| stats.total().powf(1. / temperature));
 
-        root_stats
+        match WeightedIndex::new(weights) {
+            Ok(distribution) => Some(candidates[distribution.sample(&mut self.rng)].0),
+            // All weights are zero or too large, e.g. for very low temperatures
+            Err(_) => candidates
+                .iter()
+                .max_by(|a, b| a.1.total().total_cmp(&b.1.total()))
+                .map(|(move_index, _, _)| *move_index),
+        }
     }
 
-    fn playout(&self, mut board: UltimateBoard) -> Stats {
-        let mut stats = Stats::default();
+    /// Searches a new tree for the given board
+    ///
+    /// # Arguments
+    /// * `board` - The board to search
+    /// * `rng` - The random number generator of the search
+    /// # Returns
+    /// The children of the root, the principal variation and the statistics of the search
+    fn search_tree(&self, board: UltimateBoard, rng: &mut ChaCha20Rng) -> SearchedTree {
+        self.search_tree_from(Tree::new(NodeInfo::new(board)), rng)
+    }
 
-        while board.get_game_status() == GameResult::Continue {
-            let possible_moves: Vec<_> = board.get_possible_moves().collect();
+    /// Continues the search of the given tree
+    ///
+    /// The search stops early once the result of the root is proven.
+    /// # Arguments
+    /// * `tree` - The tree to search, a new tree or a [pondered](MonteCarloTreeAgent::with_pondering) tree
+    /// * `rng` - The random number generator of the search
+    /// # Returns
+    /// The children of the root, the principal variation and the statistics of the search
+    fn search_tree_from(&self, mut tree: Tree<NodeInfo>, rng: &mut ChaCha20Rng) -> SearchedTree {
+        let root = tree.get_root();
 
-            let next_move = possible_moves
-                [Uniform::from(0..possible_moves.len()).sample(&mut rand::thread_rng())];
+        let mut statistics = SearchStatistics::default();
 
-            board.make_move(next_move);
-        }
+        let start = Instant::now();
 
-        match board.get_game_status() {
-            GameResult::Win(player) => {
-                if player == self.player {
-                    stats.wins += 1;
-                } else {
-                    stats.losses += 1;
-                }
+        for _ in 0..self.config.iterations {
+            if tree.get(root).get_data().proven.is_some() {
+                break;
             }
-            GameResult::Draw => {
-                stats.draws += 1;
+
+            if let Some(time_limit) = self.config.time_limit {
+                if start.elapsed() >= time_limit {
+                    break;
+                }
             }
-            _ => unreachable!(),
-        }
 
-        stats
-    }
-}
+            if let Some(node_budget) = self.config.node_budget {
+                if tree.len() >= node_budget {
+                    break;
+                }
+            }
 
-impl Agent for MonteCarloTreeAgent {
-    fn act(&mut self, board: UltimateBoard, player: Player, turn: u32) -> Option<u8> {
-        self.player = player;
-        self.turn = turn;
+            self.tree_search(&mut tree, &mut statistics, rng);
+        }
 
-        self.tree_root(board)
+        SearchedTree::from_tree(tree, statistics)
     }
 
-    fn get_info(&self) -> AgentInfo {
-        AgentInfo::new(
-            "MCTS".to_string(),
-            self.player,
-            self.turn,
-            format!("max_nodes: {}", self.iterations),
-        )
+    /// Creates a single threaded copy of the agent for a search in a background thread
+    fn background_searcher(&self) -> MonteCarloTreeAgent {
+        MonteCarloTreeAgent {
+            config: MctsConfig {
+                time_limit: None,
+                pondering: false,
+                ..self.config.clone()
+            },
+            rng: ChaCha20Rng::from_entropy(),
+            player: self.player,
+            turn: self.turn,
+            last_statistics: None,
+            last_report: None,
+            last_tree: None,
+            ponder: None,
+            thinking: None,
+            pondered_tree: None,
+        }
     }
-}
-
-/// # Struct representing the information of a node in the tree
-///
-/// The information contains the board, the move index, and the statistics of the node.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-struct NodeInfo {
-    board: UltimateBoard,
-    move_index: Option<u8>,
-    stats: Stats,
-}
 
-impl NodeInfo {
-    /// Creates a new [NodeInfo]
+    /// Follows the most visited children starting at the root
     ///
     /// # Arguments
-    /// * `board` - The board of the node
-    fn new(board: UltimateBoard) -> Self {
-        NodeInfo {
-            board,
-            move_index: None,
-            stats: Stats::default(),
+    /// * `tree` - The searched tree
+    /// # Returns
+    /// The moves of the most visited line
+    fn principal_variation(tree: &Tree<NodeInfo>) -> Vec<u8> {
+        let mut principal_variation = vec![];
+
+        let mut node = tree.get_root();
+
+        while let Some(child) =
+            MonteCarloTreeAgent::expanded_children(tree, node).max_by(|&a, &b| {
+                let visits = |child: NodeId| tree.get(child).get_data().stats.total();
+                visits(a).total_cmp(&visits(b))
+            })
+        {
+            principal_variation.push(tree.get(child).get_data().get_move_index().unwrap());
+            node = child;
         }
+
+        principal_variation
     }
 
-    /// Creates a new [NodeInfo] with a move index
+    /// Finds the deepest expanded node of the tree
     ///
+    /// The tree is traversed depth-first with an explicit stack, so deep trees do not overflow the call stack.
     /// # Arguments
-    /// * `board` - The board of the node
-    fn withMove(board: UltimateBoard, move_index: u8) -> Self {
-        NodeInfo {
-            board,
-            move_index: Some(move_index),
-            stats: Stats::default(),
-        }
-    }
+    /// * `tree` - The searched tree
+    /// # Returns
+    /// The moves leading to the deepest node, the first found if multiple nodes are equally deep
+    fn deepest_line(tree: &Tree<NodeInfo>) -> Vec<u8> {
+        let mut deepest_line = vec![];
+        let mut line = vec![];
 
-    /// Gets the board of the node
-    pub fn get_board(&self) -> UltimateBoard {
-        self.board
-    }
+        let mut stack = vec![(tree.get_root(), 0)];
 
-    /// Gets the move index of the node
-    pub fn get_move_index(&self) -> Option<u8> {
-        self.move_index
+        // Every node is stored with the length of the line to its parent
+        while let Some((node, parent_depth)) = stack.pop() {
+            // The nodes are visited depth-first, so the line only has to be cut back to the parent
+            line.truncate(parent_depth);
+            if let Some(move_index) = tree.get(node).get_data().get_move_index() {
+                line.push(move_index);
+            }
+
+            if line.len() > deepest_line.len() {
+                deepest_line.clone_from(&line);
+            }
+
+            stack.extend(
+                MonteCarloTreeAgent::expanded_children(tree, node)
+                    .rev()
+                    .map(|child| (child, line.len())),
+            );
+        }
+
+        deepest_line
     }
 
-    /// Applies the statistics to the node
+    /// Merges the statistics of the children of the roots of two trees
     ///
+    /// A result proven in one of the trees is kept, as proven results are exact.
     /// # Arguments
-    /// * `stats` - The statistics to apply
-    fn apply_stats(&mut self, stats: Stats) {
-        self.stats.wins += stats.wins;
-        self.stats.draws += stats.draws;
-        self.stats.losses += stats.losses;
+    /// * `lhs` - The children of the first root
+    /// * `rhs` - The children of the second root
+    /// # Returns
+    /// The children of both roots with the sum of their statistics
+    fn merge_root_stats(mut lhs: Vec<RootChild>, rhs: Vec<RootChild>) -> Vec<RootChild> {
+        for (move_index, stats, proven) in rhs {
+            match lhs.iter_mut().find(|(index, _, _)| *index == move_index) {
+                Some((_, existing, existing_proven)) => {
+                    existing.merge(stats);
+                    *existing_proven = existing_proven.or(proven);
+                }
+                None => lhs.push((move_index, stats, proven)),
+            }
+        }
+
+        lhs
     }
 
-    /// Calculates the UCT value of the node
+    /// Runs a single iteration of the search on the tree
     ///
-    /// # Arguments
-    /// * `parent_visits` - The number of visits of the parent node
+    /// Children are selected starting at the root until a proven node or a node that can be [widened](MonteCarloTreeAgent::with_progressive_widening) is reached.
+    /// New children of the node are expanded with a playout each, a proven node uses its proven result as the result of the simulation.
+    /// Afterwards the statistics of all nodes on the path are updated and the nodes are proven if the results of their children allow it.
     ///
+    /// The iteration is not recursive, so the depth of the tree is not limited by the size of the stack.
+    /// # Arguments
+    /// * `tree` - The tree to search
+    /// * `statistics` - The statistics of the search
+    /// * `rng` - The random number generator of the search
     /// # Returns
-    /// The UCT value of the node
-    fn uct_value(&self, parent_visits: u32) -> f64 {
-        let wins = self.stats.wins() as f64;
-        let visits = self.stats.total() as f64;
+    /// The simulations made during the iteration
+    fn tree_search(
+        &self,
+        tree: &mut Tree<NodeInfo>,
+        statistics: &mut SearchStatistics,
+        rng: &mut ChaCha20Rng,
+    ) -> Vec<Simulation> {
+        let mut path = vec![tree.get_root()];
+        let mut moves = vec![];
 
-        wins / visits + ((2. * (parent_visits as f64).ln()) / wins)
-    }
-}
+        let mut node = tree.get_root();
 
-/// # Struct representing the statistics of a node in the tree
-///
-/// The statistics contain the number of wins, draws, and losses.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
-struct Stats {
-    wins: u32,
-    draws: u32,
-    losses: u32,
-}
+        while tree.get(node).get_data().proven.is_none() && !self.can_widen(tree, node) {
+            node = self.select_child(tree, node);
 
-impl Stats {
-    /// Gets the total number of games
-    fn total(&self) -> u32 {
-        self.wins + self.draws + self.losses
-    }
-    /// Gets the number of wins
-    fn wins(&self) -> u32 {
-        self.wins
-    }
-    /// Gets the number of draws
-    fn draws(&self) -> u32 {
-        self.draws
-    }
-    /// Gets the number of losses
-    fn losses(&self) -> u32 {
-        self.losses
-    }
+            path.push(node);
+            moves.push(tree.get(node).get_data().get_move_index().unwrap());
+        }
 
-    /// Merges this instance with another instance, adding the statistics
-    pub fn merge(&mut self, other: Stats) {
-        self.wins += other.wins;
-        self.draws += other.draws;
-        self.losses += other.losses;
-    }
-}
+        statistics.max_depth = statistics.max_depth.max(moves.len() as u32);
 
-pub fn run() {
-    let mut agent = MonteCarloTreeAgent::new(1000);
-    let board = UltimateBoard::new();
-    let player = Player::One;
-    let turn = 0;
+        let simulations = if let Some(result) = tree.get(node).get_data().proven {
+            vec![Simulation {
+                stats: Stats::from_result(result, self.player),
+                moves,
+            }]
+        } else if node != tree.get_root() && tree.get(node).get_data().stats.total() == 0. {
+            // An unvisited child selected with the First Play Urgency
+            self.simulate(tree.get(node).get_data().get_board(), moves, rng)
+        } else if self
+            .config
+            .max_depth
+            .is_some_and(|max_depth| moves.len() >= max_depth as usize)
+        {
+            statistics.depth_limit_hits += 1;
+
+            self.simulate(tree.get(node).get_data().get_board(), moves, rng)
+        } else {
+            self.expand(tree, node, &moves, statistics, rng)
+        };
+
+        let mut stats = Stats::default();
+        for simulation in simulations.iter() {
+            stats.merge(simulation.stats);
+        }
 
-    agent.act(board, player, turn);
+        for (depth, &node) in path.iter().enumerate().rev() {
+            tree.get_mut(node).get_data_mut().apply_stats(stats);
+
+            if self.config.rave.is_some() {
+                MonteCarloTreeAgent::update_amaf(tree, node, &simulations, depth);
+            }
+
+            if tree.get(node).get_data().proven.is_none() {
+                tree.get_mut(node).get_data_mut().proven = MonteCarloTreeAgent::solve(tree, node);
+            }
+        }
+
+        simulations
+    }
+
+    /// Expands the next children of the node with a playout each
+    ///
+    /// The children of a leaf are created first, ordered for the [progressive widening](MonteCarloTreeAgent::with_progressive_widening).
+    /// With a [First Play Urgency](MonteCarloTreeAgent::with_first_play_urgency), only the first expanded child gets a playout.
+    /// If the children of a leaf would exceed the [node limit](MonteCarloTreeAgent::with_max_nodes),
+    /// a single playout is run from the leaf instead.
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node to expand, must be able to [widen](MonteCarloTreeAgent::can_widen)
+    /// * `moves` - The moves from the root of the tree to the node
+    /// * `statistics` - The statistics of the search
+    /// # Returns
+    /// The simulations of the expanded children
+    fn expand(
+        &self,
+        tree: &mut Tree<NodeInfo>,
+        node: NodeId,
+        moves: &[u8],
+        statistics: &mut SearchStatistics,
+        rng: &mut ChaCha20Rng,
+    ) -> Vec<Simulation> {
+        if tree.get(node).is_leaf() {
+            let board = tree.get(node).get_data().get_board();
+
+            if self.config.max_nodes.is_some_and(|max_nodes| {
+                tree.len() + board.get_possible_moves().count() > max_nodes
+            }) {
+                statistics.node_limit_hits += 1;
+
+                return self.simulate(board, moves.to_vec(), rng);
+            }
+
+            let policy = self
+                .config
+                .evaluator
+                .as_ref()
+                .map(|evaluator| evaluator.evaluate(board).0);
+
+            let own_perspective = board.get_current_player() == self.player;
+
+            let mut children: Vec<NodeInfo> = board
+                .get_possible_moves()
+                .map(|possible_move| {
+                    let mut board_copy = board;
+                    board_copy.make_move(possible_move);
+
+                    let mut node_info = NodeInfo::with_move(board_copy, possible_move);
+
+                    if let Some(tablebase) = &self.config.tablebase {
+                        node_info.proven = tablebase.probe(board_copy);
+                    }
+
+                    if let Some(policy) = &policy {
+                        let probability = policy
+                            .iter()
+                            .find(|(index, _)| *index == possible_move)
+                            .map_or(0., |&(_, probability)| probability as f64);
+
+                        // The prior is stored for the player of the agent, like the priors of the progressive bias
+                        node_info.prior = if own_perspective {
+                            probability
+                        } else {
+                            1. - probability
+                        };
+                    } else if let Some(bias) = &self.config.progressive_bias {
+                        node_info.prior = bias.evaluation.win_probability(board_copy, self.player);
+                    }
+
+                    node_info
+                })
+                .collect();
+
+            // The order decides which children are expanded and visited first
+            if self.config.progressive_widening.is_some()
+                || self.config.first_play_urgency.is_some()
+            {
+                children.shuffle(rng);
+                children.sort_by(|a, b| {
+                    let order = b.prior.total_cmp(&a.prior);
+                    if own_perspective {
+                        order
+                    } else {
+                        order.reverse()
+                    }
+                });
+            }
+
+            tree.append_children(node, children);
+        }
+
+        let children = tree.get(node).get_children();
+        let expanded = tree.get(node).get_data().expanded;
+        let limit = self
+            .widening_limit(tree.get(node).get_data().stats.total(), children.len())
+            .max(expanded + 1);
+
+        let simulated = if self.config.first_play_urgency.is_some() {
+            expanded + 1
+        } else {
+            limit
+        };
+
+        let simulated_children = children.start + expanded..children.start + simulated;
+
+        let leaves = simulated_children
+            .clone()
+            .map(|child| {
+                let data = tree.get(child).get_data();
+
+                let mut child_moves = moves.to_vec();
+                child_moves.push(data.get_move_index().unwrap());
+
+                (data.get_board(), child_moves)
+            })
+            .collect();
+
+        let mut simulations = vec![];
+
+        for (child, child_simulations) in simulated_children.zip(self.simulate_batch(leaves, rng)) {
+            let data = tree.get_mut(child).get_data_mut();
+
+            for simulation in child_simulations {
+                data.apply_stats(simulation.stats);
+
+                simulations.push(simulation);
+            }
+        }
+
+        tree.get_mut(node).get_data_mut().expanded = limit;
+
+        simulations
+    }
+
+    /// Calculates the number of children a node may expand
+    ///
+    /// # Arguments
+    /// * `visits` - The number of visits of the node
+    /// * `children` - The number of children of the node
+    /// # Returns
+    /// The number of children, all children if progressive widening is disabled
+    fn widening_limit(&self, visits: f64, children: usize) -> usize {
+        match self.config.progressive_widening {
+            Some((coefficient, exponent)) => {
+                ((coefficient * (visits + 1.).powf(exponent)).ceil() as usize).clamp(1, children)
+            }
+            None => children,
+        }
+    }
+
+    /// Checks if new children of the node should be expanded
+    ///
+    /// A node is widened if it is a leaf, if the number of visits allows more children or if all expanded children are proven to be lost.
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node to check, must not be proven
+    /// # Returns
+    /// True if the node should be widened, false if a child should be selected
+    fn can_widen(&self, tree: &Tree<NodeInfo>, node: NodeId) -> bool {
+        if tree.get(node).is_leaf() {
+            return true;
+        }
+
+        let data = tree.get(node).get_data();
+        let children = tree.get(node).get_children().len();
+
+        if data.expanded == children {
+            return false;
+        }
+
+        let lost = GameResult::Win(data.get_board().get_current_player().get_opponent());
+
+        data.expanded < self.widening_limit(data.stats.total(), children)
+            || MonteCarloTreeAgent::expanded_children(tree, node)
+                .all(|child| tree.get(child).get_data().proven == Some(lost))
+    }
+
+    /// Gets the children of the node that were expanded
+    ///
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node
+    /// # Returns
+    /// The ids of the expanded children
+    fn expanded_children(tree: &Tree<NodeInfo>, node: NodeId) -> Range<NodeId> {
+        let children = tree.get(node).get_children();
+
+        children.start..children.start + tree.get(node).get_data().expanded
+    }
+
+    /// Tries to prove the result of a node using the proven results of its children
+    ///
+    /// The node is won for the player to move if any child is won for them.
+    /// Otherwise the result is only proven if all children are proven, it is the best of their results for the player to move.
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node to prove
+    /// # Returns
+    /// The proven result of the node, None if the result is not proven
+    fn solve(tree: &Tree<NodeInfo>, node: NodeId) -> Option<GameResult> {
+        let player = tree.get(node).get_data().get_board().get_current_player();
+
+        let results: Vec<Option<GameResult>> = tree
+            .get(node)
+            .get_children()
+            .map(|child| tree.get(child).get_data().proven)
+            .collect();
+
+        if results.contains(&Some(GameResult::Win(player))) {
+            Some(GameResult::Win(player))
+        } else if results.is_empty() || results.contains(&None) {
+            None
+        } else if results.contains(&Some(GameResult::Draw)) {
+            Some(GameResult::Draw)
+        } else {
+            Some(GameResult::Win(player.get_opponent()))
+        }
+    }
+
+    /// Updates the all-moves-as-first statistics of the children of the node
+    ///
+    /// A simulation counts for a child if the player to move in the node played the move of the child during the simulation.
+    /// As every square is played at most once per game, it does not matter when the move was played.
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node whose children are updated
+    /// * `simulations` - The simulations made below the node
+    /// * `depth` - The number of moves from the root of the tree to the node
+    fn update_amaf(
+        tree: &mut Tree<NodeInfo>,
+        node: NodeId,
+        simulations: &[Simulation],
+        depth: usize,
+    ) {
+        let played_moves: Vec<u128> = simulations
+            .iter()
+            .map(|simulation| {
+                simulation.moves[depth..]
+                    .iter()
+                    .step_by(2)
+                    .fold(0, |mask, &index| mask | 1 << index)
+            })
+            .collect();
+
+        for child in tree.get(node).get_children() {
+            let data = tree.get_mut(child).get_data_mut();
+            let move_index = data.get_move_index().unwrap();
+
+            for (simulation, mask) in simulations.iter().zip(played_moves.iter()) {
+                if mask & 1 << move_index != 0 {
+                    data.amaf.merge(simulation.stats);
+                }
+            }
+        }
+    }
+
+    /// Selects the child of the node with the highest UCT value
+    ///
+    /// The win rates are calculated from the perspective of the player to move in the node.
+    /// Only the expanded children are considered.
+    /// Children proven to be lost for the player to move are never selected, as the node would be proven or widened otherwise.
+    /// The visited children are valued by the [selection formula](MonteCarloTreeAgent::with_selection_formula),
+    /// unvisited children by the [First Play Urgency](MonteCarloTreeAgent::with_first_play_urgency).
+    /// If [progressive bias](MonteCarloTreeAgent::with_progressive_bias) is enabled, the bias of the children is added to their value.
+    /// # Arguments
+    /// * `tree` - The tree containing the node
+    /// * `node` - The node to select a child of, must have expanded children
+    /// # Returns
+    /// The selected child
+    fn select_child(&self, tree: &Tree<NodeInfo>, node: NodeId) -> NodeId {
+        let data = tree.get(node).get_data();
+        let parent_visits = data.stats.total();
+        let player = data.get_board().get_current_player();
+        let own_perspective = player == self.player;
+
+        let formula = self
+            .config
+            .selection_formula
+            .as_deref()
+            .unwrap_or(&Ucb1 as &dyn SelectionFormula);
+
+        let children = MonteCarloTreeAgent::expanded_children(tree, node);
+
+        let prior_sum: f64 = children
+            .clone()
+            .map(|child| tree.get(child).get_data().own_prior(own_perspective))
+            .sum();
+
+        let children_count = children.len() as f64;
+
+        children
+            .map(|child| {
+                let data = tree.get(child).get_data();
+
+                let uct = if data.proven == Some(GameResult::Win(player.get_opponent())) {
+                    f64::NEG_INFINITY
+                } else {
+                    // Without priors, all children have the same prior
+                    let prior = if prior_sum > 0. {
+                        data.own_prior(own_perspective) / prior_sum
+                    } else {
+                        1. / children_count
+                    };
+
+                    let value = match data.selection_statistics(
+                        parent_visits,
+                        own_perspective,
+                        self.config.draw_reward,
+                        self.config.rave,
+                        prior,
+                    ) {
+                        Some(statistics) => {
+                            formula.value(statistics, self.config.exploration_constant)
+                        }
+                        None => self.config.first_play_urgency.unwrap_or(f64::INFINITY),
+                    };
+
+                    value
+                        + self.config.progressive_bias.as_ref().map_or(0., |bias| {
+                            data.progressive_bias(bias.weight, own_perspective)
+                        })
+                };
+                (child, uct)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(child, _)| child)
+            .unwrap()
+    }
+
+    /// Simulates a single node, see [MonteCarloTreeAgent::simulate_batch]
+    ///
+    /// # Arguments
+    /// * `board` - The board of the node
+    /// * `moves` - The moves from the root of the tree to the board
+    /// * `rng` - The random number generator of the search
+    /// # Returns
+    /// The simulations of the node
+    fn simulate(
+        &self,
+        board: UltimateBoard,
+        moves: Vec<u8>,
+        rng: &mut ChaCha20Rng,
+    ) -> Vec<Simulation> {
+        self.simulate_batch(vec![(board, moves)], rng)
+            .pop()
+            .unwrap()
+    }
+
+    /// Simulates a batch of nodes
+    ///
+    /// With a [leaf evaluator](MonteCarloTreeAgent::with_leaf_evaluator), all nodes whose game is not over are evaluated in a single batch.
+    /// Without a leaf evaluator, the value of the [evaluator](MonteCarloTreeAgent::with_evaluator) is used if set.
+    /// Otherwise every node is simulated with one or, with [leaf parallelization](MonteCarloTreeAgent::with_leaf_playouts), multiple playouts.
+    /// # Arguments
+    /// * `leaves` - The boards of the nodes and the moves from the root of the tree to the boards
+    /// * `rng` - The random number generator of the search
+    /// # Returns
+    /// The simulations of every node, in the order of the nodes
+    fn simulate_batch(
+        &self,
+        leaves: Vec<(UltimateBoard, Vec<u8>)>,
+        rng: &mut ChaCha20Rng,
+    ) -> Vec<Vec<Simulation>> {
+        let value_evaluator = self.config.evaluator.as_deref().map(ValueEvaluator);
+
+        let leaf_evaluator = self.config.leaf_evaluator.as_deref().or(value_evaluator
+            .as_ref()
+            .map(|value| value as &dyn LeafEvaluator));
+
+        if let Some(evaluator) = leaf_evaluator {
+            let boards: Vec<UltimateBoard> = leaves
+                .iter()
+                .map(|(board, _)| *board)
+                .filter(|board| board.get_game_status() == GameResult::Continue)
+                .collect();
+
+            let mut probabilities = if boards.is_empty() {
+                vec![]
+            } else {
+                evaluator.evaluate_batch(&boards)
+            }
+            .into_iter();
+
+            return leaves
+                .into_iter()
+                .map(|(board, moves)| {
+                    let stats = match board.get_game_status() {
+                        GameResult::Continue => {
+                            let probability = probabilities.next().unwrap();
+
+                            let probability = if board.get_current_player() == self.player {
+                                probability
+                            } else {
+                                1. - probability
+                            };
+
+                            Stats {
+                                wins: probability,
+                                draws: 0.,
+                                losses: 1. - probability,
+                            }
+                        }
+                        result => Stats::from_result(result, self.player),
+                    };
+
+                    vec![Simulation { stats, moves }]
+                })
+                .collect();
+        }
+
+        leaves
+            .into_iter()
+            .map(|(board, moves)| match self.config.leaf_playouts {
+                Some(playouts) => {
+                    let seeds: Vec<u64> = (0..playouts).map(|_| rng.gen()).collect();
+
+                    seeds
+                        .into_par_iter()
+                        .map(|seed| {
+                            self.playout(
+                                board,
+                                moves.clone(),
+                                &mut ChaCha20Rng::seed_from_u64(seed),
+                            )
+                        })
+                        .collect()
+                }
+                None => vec![self.playout(board, moves, rng)],
+            })
+            .collect()
+    }
+
+    /// Plays moves chosen by the [playout policy](MonteCarloTreeAgent::with_playout_policy) until the game is over
+    ///
+    /// If [early playout termination](MonteCarloTreeAgent::with_playout_cutoff) is enabled,
+    /// the playout stops after the configured number of plies and the position is scored with the heuristic.
+    /// # Arguments
+    /// * `board` - The board to start the playout from
+    /// * `moves` - The moves from the root of the tree to the board, the moves of the playout are appended
+    /// # Returns
+    /// The result of the simulation
+    fn playout(
+        &self,
+        mut board: UltimateBoard,
+        mut moves: Vec<u8>,
+        rng: &mut ChaCha20Rng,
+    ) -> Simulation {
+        let policy = self
+            .config
+            .playout_policy
+            .as_deref()
+            .unwrap_or(&UniformPolicy as &dyn PlayoutPolicy);
+
+        let mut plies = 0;
+
+        while board.get_game_status() == GameResult::Continue {
+            if let Some(cutoff) = self
+                .config
+                .playout_cutoff
+                .as_ref()
+                .filter(|c| plies == c.plies)
+            {
+                let probability = cutoff.evaluation.win_probability(board, self.player);
+
+                let stats = Stats {
+                    wins: probability,
+                    draws: 0.,
+                    losses: 1. - probability,
+                };
+
+                return Simulation { stats, moves };
+            }
+
+            let next_move = policy.choose_move(board, rng);
+
+            board.make_move(next_move);
+            moves.push(next_move);
+            plies += 1;
+        }
+
+        Simulation {
+            stats: Stats::from_result(board.get_game_status(), self.player),
+            moves,
+        }
+    }
+}
+
+/// Creates a fresh agent with the same configuration
+///
+/// The trees and the running searches are not shared with the clone.
+impl Clone for MonteCarloTreeAgent {
+    fn clone(&self) -> Self {
+        MonteCarloTreeAgent::from_config(self.config.clone())
+    }
+}
+
+impl Agent for MonteCarloTreeAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let best_move = self.tree_root(*board).ok_or(AgentError::NoMove)?;
+
+        if self.config.pondering {
+            self.start_pondering(*board, best_move);
+        }
+
+        Ok(best_move)
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, _index: u8) {
+        self.stop_pondering(board);
+    }
+
+    /// Drops the trees of the previous game
+    fn new_game(&mut self, _player: Player) {
+        self.ponder = None;
+        self.thinking = None;
+        self.pondered_tree = None;
+        self.last_tree = None;
+    }
+
+    fn game_over(&mut self, _board: UltimateBoard, _result: GameResult) {
+        self.ponder = None;
+        self.thinking = None;
+        self.pondered_tree = None;
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let mut info = AgentInfo::new(
+            "MCTS".to_string(),
+            self.player,
+            self.turn,
+            self.config.to_string(),
+        );
+
+        if let Some(statistics) = self.last_statistics {
+            info = info.with_statistics(statistics);
+        }
+
+        if let Some(report) = &self.last_report {
+            info = info.with_mcts_report(report.clone());
+        }
+
+        info
+    }
+}
+
+/// # Struct representing the information of a node in the tree
+///
+/// The information contains the board, the move index, the statistics and the proven result of the node.
+/// The all-moves-as-first statistics are only collected if [RAVE](MonteCarloTreeAgent::with_rave) is enabled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NodeInfo {
+    board: UltimateBoard,
+    move_index: Option<u8>,
+    stats: Stats,
+    amaf: Stats,
+    /// The result of the game if both players play perfectly, None if not proven yet
+    proven: Option<GameResult>,
+    /// The win probability of the player of the agent estimated by the heuristic of the [progressive bias](MonteCarloTreeAgent::with_progressive_bias)
+    prior: f64,
+    /// The number of children that were expanded with a playout, see [MonteCarloTreeAgent::with_progressive_widening]
+    expanded: usize,
+}
+
+impl NodeInfo {
+    /// Creates a new [NodeInfo]
+    ///
+    /// # Arguments
+    /// * `board` - The board of the node
+    fn new(board: UltimateBoard) -> Self {
+        NodeInfo {
+            board,
+            move_index: None,
+            stats: Stats::default(),
+            amaf: Stats::default(),
+            proven: NodeInfo::terminal_result(board),
+            prior: 0.,
+            expanded: 0,
+        }
+    }
+
+    /// Creates a new [NodeInfo] with a move index
+    ///
+    /// # Arguments
+    /// * `board` - The board of the node
+    fn with_move(board: UltimateBoard, move_index: u8) -> Self {
+        NodeInfo {
+            board,
+            move_index: Some(move_index),
+            stats: Stats::default(),
+            amaf: Stats::default(),
+            proven: NodeInfo::terminal_result(board),
+            prior: 0.,
+            expanded: 0,
+        }
+    }
+
+    /// Gets the result of the board if the game is over
+    ///
+    /// # Returns
+    /// The result of the game, None if the game continues
+    fn terminal_result(board: UltimateBoard) -> Option<GameResult> {
+        Some(board.get_game_status()).filter(|&result| result != GameResult::Continue)
+    }
+
+    /// Gets the board of the node
+    pub fn get_board(&self) -> UltimateBoard {
+        self.board
+    }
+
+    /// Gets the move index of the node
+    pub fn get_move_index(&self) -> Option<u8> {
+        self.move_index
+    }
+
+    /// Applies the statistics to the node
+    ///
+    /// # Arguments
+    /// * `stats` - The statistics to apply
+    fn apply_stats(&mut self, stats: Stats) {
+        self.stats.wins += stats.wins;
+        self.stats.draws += stats.draws;
+        self.stats.losses += stats.losses;
+    }
+
+    /// Collects the statistics of the node used by the [selection formula](SelectionFormula)
+    ///
+    /// With [RAVE](MonteCarloTreeAgent::with_rave), the win rate is blended with the all-moves-as-first win rate.
+    /// # Arguments
+    /// * `parent_visits` - The number of visits of the parent node
+    /// * `own_perspective` - Whether the win rate is calculated for the player of the agent, otherwise for the opponent
+    /// * `draw_reward` - The reward of a draw
+    /// * `rave` - The RAVE equivalence parameter, None if RAVE is disabled
+    /// * `prior` - The normalized prior of the node
+    ///
+    /// # Returns
+    /// The statistics of the node, None if the node is unvisited
+    fn selection_statistics(
+        &self,
+        parent_visits: f64,
+        own_perspective: bool,
+        draw_reward: f64,
+        rave: Option<f64>,
+        prior: f64,
+    ) -> Option<SelectionStatistics> {
+        let visits = self.stats.total();
+
+        if visits == 0. {
+            return None;
+        }
+
+        let mut win_rate = self.stats.win_rate(own_perspective, draw_reward);
+
+        if let Some(equivalence) = rave.filter(|_| self.amaf.total() > 0.) {
+            let beta = (equivalence / (3. * visits + equivalence)).sqrt();
+
+            win_rate =
+                (1. - beta) * win_rate + beta * self.amaf.win_rate(own_perspective, draw_reward);
+        }
+
+        Some(SelectionStatistics {
+            visits,
+            parent_visits,
+            win_rate,
+            variance: self.stats.variance(own_perspective, draw_reward),
+            prior,
+        })
+    }
+
+    /// Gets the prior of the node for a player
+    ///
+    /// # Arguments
+    /// * `own_perspective` - Whether the prior is used for the player of the agent, otherwise for the opponent
+    /// # Returns
+    /// The prior of the node
+    fn own_prior(&self, own_perspective: bool) -> f64 {
+        if own_perspective {
+            self.prior
+        } else {
+            1. - self.prior
+        }
+    }
+
+    /// Calculates the progressive bias of the node
+    ///
+    /// The bias is `weight * prior / (visits + 1)`, see [MonteCarloTreeAgent::with_progressive_bias].
+    /// # Arguments
+    /// * `weight` - The weight of the progressive bias
+    /// * `own_perspective` - Whether the prior is used for the player of the agent, otherwise for the opponent
+    /// # Returns
+    /// The progressive bias of the node
+    fn progressive_bias(&self, weight: f64, own_perspective: bool) -> f64 {
+        weight * self.own_prior(own_perspective) / (self.stats.total() + 1.)
+    }
+}
+
+/// # Struct representing the statistics of a node in the tree
+///
+/// The statistics contain the number of wins, draws, and losses.
+/// The numbers are fractional, as a [cut off playout](MonteCarloTreeAgent::with_playout_cutoff) counts as a partial win and a partial loss.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+struct Stats {
+    wins: f64,
+    draws: f64,
+    losses: f64,
+}
+
+impl Stats {
+    /// Creates the statistics of a single finished game
+    ///
+    /// # Arguments
+    /// * `result` - The result of the game, must not be [GameResult::Continue]
+    /// * `player` - The player of the agent
+    fn from_result(result: GameResult, player: Player) -> Self {
+        let mut stats = Stats::default();
+
+        match result {
+            GameResult::Win(winner) => {
+                if winner == player {
+                    stats.wins += 1.;
+                } else {
+                    stats.losses += 1.;
+                }
+            }
+            GameResult::Draw => {
+                stats.draws += 1.;
+            }
+            _ => unreachable!(),
+        }
+
+        stats
+    }
+
+    /// Gets the total number of games
+    fn total(&self) -> f64 {
+        self.wins + self.draws + self.losses
+    }
+    /// Gets the number of wins
+    fn wins(&self) -> f64 {
+        self.wins
+    }
+    /// Gets the number of draws
+    fn draws(&self) -> f64 {
+        self.draws
+    }
+    /// Gets the number of losses
+    fn losses(&self) -> f64 {
+        self.losses
+    }
+
+    /// Gets the mean reward of the given side
+    ///
+    /// A win is rewarded with 1, a draw with the draw reward and a loss with 0.
+    /// # Arguments
+    /// * `own_perspective` - Whether the rewards of the player of the agent are counted, otherwise the rewards of the opponent
+    /// * `draw_reward` - The reward of a draw
+    fn win_rate(&self, own_perspective: bool, draw_reward: f64) -> f64 {
+        let wins = if own_perspective {
+            self.wins()
+        } else {
+            self.losses()
+        };
+
+        (wins + draw_reward * self.draws()) / self.total()
+    }
+
+    /// Gets the variance of the rewards of the given side
+    ///
+    /// # Arguments
+    /// * `own_perspective` - Whether the rewards of the player of the agent are counted, otherwise the rewards of the opponent
+    /// * `draw_reward` - The reward of a draw
+    fn variance(&self, own_perspective: bool, draw_reward: f64) -> f64 {
+        let wins = if own_perspective {
+            self.wins()
+        } else {
+            self.losses()
+        };
+
+        let mean = self.win_rate(own_perspective, draw_reward);
+        let mean_square = (wins + draw_reward * draw_reward * self.draws()) / self.total();
+
+        mean_square - mean * mean
+    }
+
+    /// Merges this instance with another instance, adding the statistics
+    pub fn merge(&mut self, other: Stats) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+}
+
+/// The move, the statistics and the proven result of a child of the root
+type RootChild = (u8, Stats, Option<GameResult>);
+
+/// # Struct representing the outcome of the search of a single tree
+struct SearchedTree {
+    /// The children of the root
+    children: Vec<RootChild>,
+    /// The most visited line, starting at the root
+    principal_variation: Vec<u8>,
+    /// The line to the deepest expanded node, starting at the root
+    deepest_line: Vec<u8>,
+    /// The statistics of the search
+    statistics: SearchStatistics,
+    /// The searched tree, the tree of the principal variation if multiple trees were merged
+    tree: Option<Tree<NodeInfo>>,
+}
+
+impl SearchedTree {
+    /// Collects the outcome of the search of a tree
+    ///
+    /// # Arguments
+    /// * `tree` - The searched tree
+    /// * `statistics` - The statistics of the search
+    /// # Returns
+    /// The children of the root, the principal variation and the statistics of the search
+    fn from_tree(tree: Tree<NodeInfo>, mut statistics: SearchStatistics) -> SearchedTree {
+        let root = tree.get_root();
+
+        statistics.nodes = tree.len() as u64;
+
+        let children = MonteCarloTreeAgent::expanded_children(&tree, root)
+            .filter_map(|child| {
+                let data = tree.get(child).get_data();
+                Some((data.get_move_index()?, data.stats, data.proven))
+            })
+            .collect();
+
+        SearchedTree {
+            children,
+            principal_variation: MonteCarloTreeAgent::principal_variation(&tree),
+            deepest_line: MonteCarloTreeAgent::deepest_line(&tree),
+            statistics,
+            tree: Some(tree),
+        }
+    }
+
+    /// Gets the number of visits of a child of the root
+    ///
+    /// # Arguments
+    /// * `move_index` - The move of the child
+    /// # Returns
+    /// The number of visits, 0 if the child was not expanded
+    fn visits(&self, move_index: u8) -> f64 {
+        self.children
+            .iter()
+            .find(|(index, _, _)| *index == move_index)
+            .map_or(0., |(_, stats, _)| stats.total())
+    }
+
+    /// Merges the outcomes of two independently searched trees
+    ///
+    /// The principal variation and the tree of the tree that visited its first move the most are kept,
+    /// as well as the longer of the deepest lines.
+    /// # Arguments
+    /// * `other` - The outcome of the other tree
+    /// # Returns
+    /// The merged outcome
+    fn merge(self, other: SearchedTree) -> SearchedTree {
+        let first_visits = |tree: &SearchedTree| {
+            tree.principal_variation
+                .first()
+                .map_or(0., |&move_index| tree.visits(move_index))
+        };
+
+        let (principal_variation, tree) = if first_visits(&other) > first_visits(&self) {
+            (other.principal_variation, other.tree)
+        } else {
+            (self.principal_variation, self.tree)
+        };
+
+        let deepest_line = if other.deepest_line.len() > self.deepest_line.len() {
+            other.deepest_line
+        } else {
+            self.deepest_line
+        };
+
+        let mut statistics = self.statistics;
+        statistics += other.statistics;
+
+        SearchedTree {
+            children: MonteCarloTreeAgent::merge_root_stats(self.children, other.children),
+            principal_variation,
+            deepest_line,
+            statistics,
+            tree,
+        }
+    }
+
+    /// Creates the report of the search
+    ///
+    /// # Arguments
+    /// * `best_move` - The chosen move
+    /// * `draw_reward` - The reward of a draw
+    /// # Returns
+    /// The report, the most visited child first
+    fn into_report(self, best_move: u8, draw_reward: f64) -> MctsReport {
+        let mut children: Vec<MctsChild> = self
+            .children
+            .into_iter()
+            .map(|(move_index, stats, proven)| MctsChild {
+                move_index,
+                visits: stats.total(),
+                win_rate: stats.win_rate(true, draw_reward),
+                proven,
+            })
+            .collect();
+
+        children.sort_by(|a, b| b.visits.total_cmp(&a.visits));
+
+        MctsReport {
+            best_move,
+            simulations: children.iter().map(|child| child.visits).sum(),
+            children,
+            principal_variation: self.principal_variation,
+            deepest_line: self.deepest_line,
+        }
+    }
+}
+
+/// # Struct using the value of an [Evaluator] as a [LeafEvaluator]
+///
+/// The value `v` between -1 and 1 is mapped to the win probability `(v + 1) / 2`.
+struct ValueEvaluator<'a>(&'a dyn Evaluator);
+
+impl LeafEvaluator for ValueEvaluator<'_> {
+    fn evaluate_batch(&self, boards: &[UltimateBoard]) -> Vec<f64> {
+        boards
+            .iter()
+            .map(|&board| (self.0.evaluate(board).1 as f64 + 1.) / 2.)
+            .collect()
+    }
+
+    fn get_name(&self) -> String {
+        self.0.get_name()
+    }
+}
+
+impl AnytimeAgent for MonteCarloTreeAgent {
+    /// Starts searching a new tree, for at most the configured number of [iterations](MctsConfig::iterations)
+    fn start_thinking(&mut self, board: UltimateBoard) {
+        self.ponder = None;
+        self.pondered_tree = None;
+        self.thinking = None;
+
+        if board.get_game_status() != GameResult::Continue {
+            return;
+        }
+
+        self.player = board.get_current_player();
+
+        let searcher = self.background_searcher();
+
+        let seed = self.rng.gen();
+
+        let best_move = Arc::new(Mutex::new(None));
+        let thread_best_move = Arc::clone(&best_move);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+
+        let handle = std::thread::spawn(move || {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+            let mut tree = Tree::new(NodeInfo::new(board));
+            let mut statistics = SearchStatistics::default();
+
+            for iteration in 0..searcher.config.iterations {
+                if thread_stop.load(Ordering::Relaxed)
+                    || tree.get(tree.get_root()).get_data().proven.is_some()
+                {
+                    break;
+                }
+
+                searcher.tree_search(&mut tree, &mut statistics, &mut rng);
+
+                // The most visited move is published regularly, as following the principal variation is not free
+                if iteration % 64 == 0 {
+                    *thread_best_move.lock().unwrap() =
+                        MonteCarloTreeAgent::principal_variation(&tree)
+                            .first()
+                            .copied();
+                }
+            }
+
+            (tree, statistics)
+        });
+
+        self.thinking = Some(Thinking {
+            best_move,
+            ponder: Ponder {
+                stop,
+                handle: Some(handle),
+            },
+        });
+    }
+
+    /// Returns the most visited move of the tree, updated every 64 iterations
+    fn best_move_so_far(&self) -> Option<u8> {
+        *self.thinking.as_ref()?.best_move.lock().unwrap()
+    }
+
+    /// Chooses the best move of the tree like [act](Agent::act)
+    fn stop(&mut self) -> Option<u8> {
+        let (tree, statistics) = self.thinking.take()?.ponder.finish()?;
+
+        self.choose_move(SearchedTree::from_tree(tree, statistics))
+    }
+}
+
+/// # Struct representing a running [anytime](AnytimeAgent) search
+struct Thinking {
+    /// The most visited move of the tree, updated by the thread
+    best_move: Arc<Mutex<Option<u8>>>,
+    /// The thread of the search returning the searched tree
+    ponder: Ponder,
+}
+
+/// # Struct representing a running pondering thread
+///
+/// The thread is stopped when the struct is dropped.
+struct Ponder {
+    /// Flag to stop the search of the thread
+    stop: Arc<AtomicBool>,
+    /// The handle of the thread returning the pondered tree and the statistics of the search, None once the thread has been joined
+    handle: Option<JoinHandle<(Tree<NodeInfo>, SearchStatistics)>>,
+}
+
+impl Ponder {
+    /// Stops the thread and waits until it has finished
+    ///
+    /// # Returns
+    /// The pondered tree and the statistics of the search, None if the thread panicked
+    fn finish(mut self) -> Option<(Tree<NodeInfo>, SearchStatistics)> {
+        self.stop.store(true, Ordering::Relaxed);
+
+        self.handle.take()?.join().ok()
+    }
+}
+
+impl Drop for Ponder {
+    /// Stops the thread and waits until it has finished
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// # Struct representing the result of a single simulation
+///
+/// The moves are used to update the all-moves-as-first statistics of [RAVE](MonteCarloTreeAgent::with_rave).
+struct Simulation {
+    /// The result of the simulation from the perspective of the player of the agent
+    stats: Stats,
+    /// The moves from the root of the tree to the end of the game
+    moves: Vec<u8>,
+}
+
+pub fn run() {
+    let mut agent = MonteCarloTreeAgent::new(1000);
+    let board = UltimateBoard::new();
+    let player = Player::One;
+    let turn = 0;
+
+    let _ = agent.act(&board, player, turn);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use rand::prelude::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn node_with_stats(wins: u32, draws: u32, losses: u32) -> NodeInfo {
+        let mut node_info = NodeInfo::new(UltimateBoard::new());
+        node_info.apply_stats(Stats {
+            wins: wins as f64,
+            draws: draws as f64,
+            losses: losses as f64,
+        });
+        node_info
+    }
+
+    #[test]
+    fn test_uct_value() {
+        let node_info = node_with_stats(6, 0, 4);
+
+        let exploration = (100f64.ln() / 10.).sqrt();
+
+        let uct_value = |node_info: &NodeInfo, c: f64, own_perspective: bool| {
+            let statistics = node_info
+                .selection_statistics(100., own_perspective, 0.5, None, 1.)
+                .unwrap();
+            Ucb1.value(statistics, c)
+        };
+
+        assert_eq!(uct_value(&node_info, 0., true), 0.6);
+        assert_eq!(uct_value(&node_info, 2., true), 0.6 + 2. * exploration);
+        assert_eq!(uct_value(&node_info, 2., false), 0.4 + 2. * exploration);
+
+        assert_eq!(
+            node_with_stats(0, 0, 0).selection_statistics(100., true, 0.5, None, 1.),
+            None
+        );
+    }
+
+    #[test]
+    fn test_draw_reward() {
+        let stats = node_with_stats(2, 4, 4).stats;
+
+        assert_eq!(stats.win_rate(true, 0.), 0.2);
+        assert_eq!(stats.win_rate(true, 0.5), 0.4);
+        assert_eq!(stats.win_rate(false, 0.5), 0.6);
+        assert_eq!(stats.win_rate(false, 1.), 0.8);
+
+        // The rewards 1, 1, 0.5, 0.5, 0.5, 0.5, 0, 0, 0, 0 around the mean 0.4
+        assert!((stats.variance(true, 0.5) - 0.14).abs() < 1e-12);
+        assert!((stats.variance(true, 0.) - 0.16).abs() < 1e-12);
+
+        // A certain draw is preferred to an uncertain result with fewer wins than losses
+        let draw = node_with_stats(0, 10, 0).stats;
+        let risky = node_with_stats(4, 0, 6).stats;
+        assert!(draw.win_rate(true, 0.5) > risky.win_rate(true, 0.5));
+        assert!(draw.win_rate(true, 0.) < risky.win_rate(true, 0.));
+    }
+
+    #[test]
+    fn test_selection_formula() {
+        use crate::agent::monte_carlo_tree_agent::selection_formula::{Puct, Ucb1Tuned};
+
+        let board = UltimateBoard::new();
+
+        for mut agent in [
+            MonteCarloTreeAgent::new(100).with_selection_formula(Ucb1Tuned),
+            MonteCarloTreeAgent::new(100).with_selection_formula(Puct),
+        ] {
+            assert!(agent
+                .act(&board, Player::One, 0)
+                .is_ok_and(|index| board.is_legal_move(index)));
+        }
+    }
+
+    #[test]
+    fn test_evaluator() {
+        use crate::agent::monte_carlo_tree_agent::evaluator::HeuristicEvaluator;
+        use crate::agent::monte_carlo_tree_agent::selection_formula::Puct;
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let agent = MonteCarloTreeAgent::new(200)
+            .with_evaluator(HeuristicEvaluator::new(
+                CustomHeuristic::new(Player::One),
+                0.5,
+            ))
+            .with_selection_formula(Puct);
+
+        let searched_tree = agent.search_tree(board, &mut ChaCha20Rng::seed_from_u64(0));
+        let tree = searched_tree.tree.unwrap();
+
+        // The priors of the children of the root are the probabilities of the policy of Player Two
+        let prior_sum: f64 = tree
+            .get(tree.get_root())
+            .get_children()
+            .map(|child| tree.get(child).get_data().own_prior(false))
+            .sum();
+        assert!((prior_sum - 1.).abs() < 1e-5);
+
+        // The leaves are scored with the value instead of playouts
+        assert!(searched_tree
+            .children
+            .iter()
+            .any(|(_, stats, _)| stats.wins().fract() != 0.));
+    }
+
+    #[test]
+    fn test_first_play_urgency() {
+        let board = UltimateBoard::new();
+
+        // Without FPU every move of the root is tried before any move is tried twice
+        let searched_tree =
+            MonteCarloTreeAgent::new(200).search_tree(board, &mut ChaCha20Rng::seed_from_u64(0));
+        assert!(searched_tree
+            .children
+            .iter()
+            .all(|(_, stats, _)| stats.total() > 0.));
+
+        // A visited move keeps a higher value than an FPU of 0, so most moves are never tried
+        let searched_tree = MonteCarloTreeAgent::new(200)
+            .with_first_play_urgency(0.)
+            .search_tree(board, &mut ChaCha20Rng::seed_from_u64(0));
+        assert!(searched_tree
+            .children
+            .iter()
+            .any(|(_, stats, _)| stats.total() == 0.));
+    }
+
+    #[test]
+    fn test_rave() {
+        let mut node_info = node_with_stats(1, 0, 3);
+        node_info.amaf = Stats {
+            wins: 75.,
+            draws: 0.,
+            losses: 25.,
+        };
+
+        // beta = sqrt(12 / (3 * 4 + 12))
+        let beta = 0.5f64.sqrt();
+        assert_eq!(
+            node_info
+                .selection_statistics(100., true, 0.5, Some(12.), 1.)
+                .unwrap()
+                .win_rate,
+            (1. - beta) * 0.25 + beta * 0.75
+        );
+
+        let mut tree = Tree::new(NodeInfo::new(UltimateBoard::new()));
+        tree.append_children(
+            tree.get_root(),
+            [0, 1, 2].map(|move_index| NodeInfo::with_move(UltimateBoard::new(), move_index)),
+        );
+
+        let win = Stats {
+            wins: 1.,
+            draws: 0.,
+            losses: 0.,
+        };
+
+        // The node is at depth 1, only every second move from there on is made by the player to move in the node
+        let simulations = [
+            Simulation {
+                stats: win,
+                moves: vec![9, 0, 1, 2],
+            },
+            Simulation {
+                stats: win,
+                moves: vec![9, 2, 0, 10],
+            },
+        ];
+
+        let root = tree.get_root();
+        MonteCarloTreeAgent::update_amaf(&mut tree, root, &simulations, 1);
+
+        let amaf: Vec<f64> = tree
+            .get(root)
+            .get_children()
+            .map(|child| tree.get(child).get_data().amaf.total())
+            .collect();
+        assert_eq!(amaf, vec![1., 0., 2.]);
+
+        let board = UltimateBoard::new();
+        assert!(MonteCarloTreeAgent::new(20)
+            .with_rave(100.)
+            .tree_root(board)
+            .is_some_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_playout_cutoff() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let mut agent = MonteCarloTreeAgent::new(50).with_playout_cutoff(
+            2,
+            CustomHeuristic::new(Player::One),
+            0.5,
+        );
+
+        let simulation = agent.playout(board, vec![40], &mut ChaCha20Rng::seed_from_u64(0));
+        assert_eq!(simulation.moves.len(), 3);
+        assert!((simulation.stats.total() - 1.).abs() < 1e-9);
+        assert!(simulation.stats.wins() > 0. && simulation.stats.losses() > 0.);
+
+        let cutoff = agent.config.playout_cutoff.as_ref().unwrap();
+        // X has the next move on the empty board
+        assert!(
+            cutoff
+                .evaluation
+                .win_probability(UltimateBoard::new(), Player::One)
+                > 0.5
+        );
+        assert!(
+            cutoff
+                .evaluation
+                .win_probability(UltimateBoard::new(), Player::Two)
+                < 0.5
+        );
+
+        assert!(agent
+            .tree_root(board)
+            .is_some_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_progressive_bias() {
+        let node_info = NodeInfo {
+            prior: 0.75,
+            ..node_with_stats(2, 0, 1)
+        };
+
+        assert_eq!(node_info.progressive_bias(2., true), 0.375);
+        assert_eq!(node_info.progressive_bias(2., false), 0.125);
+
+        let mut agent = MonteCarloTreeAgent::new(50)
+            .with_exploration_constant(0.)
+            .with_progressive_bias(CustomHeuristic::new(Player::One), 0.5, 10.);
+
+        let mut tree = Tree::new(NodeInfo {
+            expanded: 2,
+            ..node_with_stats(5, 0, 5)
+        });
+        tree.append_children(
+            tree.get_root(),
+            [
+                NodeInfo {
+                    move_index: Some(0),
+                    prior: 0.2,
+                    ..node_with_stats(3, 0, 2)
+                },
+                NodeInfo {
+                    move_index: Some(1),
+                    prior: 0.8,
+                    ..node_with_stats(2, 0, 3)
+                },
+            ],
+        );
+
+        // The bias outweighs the lower win rate of the second child
+        let selected = agent.select_child(&tree, tree.get_root());
+        assert_eq!(tree.get(selected).get_data().get_move_index(), Some(1));
+
+        let board = UltimateBoard::new();
+        assert!(agent
+            .tree_root(board)
+            .is_some_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_progressive_widening() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let agent = MonteCarloTreeAgent::new(0).with_progressive_widening(1., 0.5);
+
+        let mut tree = Tree::new(NodeInfo::new(UltimateBoard::new()));
+        let root = tree.get_root();
+
+        // The first visit creates all children but only expands a single one
+        assert_eq!(
+            agent
+                .tree_search(&mut tree, &mut SearchStatistics::default(), &mut rng)
+                .len(),
+            1
+        );
+        assert_eq!(tree.get(root).get_children().len(), 81);
+        assert_eq!(tree.get(root).get_data().expanded, 1);
+
+        for _ in 0..15 {
+            agent.tree_search(&mut tree, &mut SearchStatistics::default(), &mut rng);
+        }
+
+        // At most ceil(sqrt(n + 1)) children are expanded after n visits
+        let visits = tree.get(root).get_data().stats.total();
+        let expanded = tree.get(root).get_data().expanded;
+        assert!(expanded > 1 && expanded <= (visits + 1.).sqrt().ceil() as usize);
+
+        for child in tree.get(root).get_children() {
+            let expanded = MonteCarloTreeAgent::expanded_children(&tree, root).contains(&child);
+            assert_eq!(tree.get(child).get_data().stats.total() > 0., expanded);
+        }
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let mut agent = MonteCarloTreeAgent::new(500).with_max_nodes(200);
+
+        let statistics = agent
+            .search_tree(UltimateBoard::new(), &mut ChaCha20Rng::seed_from_u64(0))
+            .statistics;
+        assert!(statistics.nodes <= 200);
+        assert!(statistics.node_limit_hits > 0);
+
+        let board = UltimateBoard::new();
+        assert!(agent
+            .act(&board, Player::One, 0)
+            .is_ok_and(|index| board.is_legal_move(index)));
+
+        let info = agent.get_info();
+        assert!(info.get_statistics().unwrap().node_limit_hits > 0);
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let agent = MonteCarloTreeAgent::new(2000).with_max_depth(2);
+
+        let searched_tree = agent.search_tree(board, &mut ChaCha20Rng::seed_from_u64(0));
+        assert_eq!(searched_tree.statistics.max_depth, 2);
+        assert!(searched_tree.statistics.depth_limit_hits > 0);
+        assert_eq!(searched_tree.deepest_line.len(), 2);
+        assert!(searched_tree.principal_variation.len() <= 2);
+
+        let agent = MonteCarloTreeAgent::new(2000).with_node_budget(500);
+
+        let searched_tree = agent.search_tree(board, &mut ChaCha20Rng::seed_from_u64(0));
+        assert!(searched_tree.statistics.nodes < 600);
+
+        // The deepest line is a line of the tree
+        let mut board_copy = board;
+        for &index in searched_tree.deepest_line.iter() {
+            assert!(board_copy.is_legal_move(index));
+            board_copy.make_move(index);
+        }
+        assert_eq!(
+            searched_tree.deepest_line.len() as u32,
+            searched_tree.statistics.max_depth + 1
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let mut agent = MonteCarloTreeAgent::new(200).with_threads(2);
+        assert_eq!(agent.get_last_report(), None);
+
+        let best_move = agent.act(&board, Player::Two, 1).unwrap();
+        let report = agent.get_last_report().unwrap().clone();
+
+        assert_eq!(report.best_move, best_move);
+        assert_eq!(report.children.len(), board.get_possible_moves().count());
+        assert!(report
+            .children
+            .windows(2)
+            .all(|pair| pair[0].visits >= pair[1].visits));
+        assert_eq!(
+            report.simulations,
+            report
+                .children
+                .iter()
+                .map(|child| child.visits)
+                .sum::<f64>()
+        );
+
+        // The principal variation is a legal line starting at the most visited child
+        let mut line_board = board;
+        for &index in report.principal_variation.iter() {
+            assert!(line_board.is_legal_move(index));
+            line_board.make_move(index);
+        }
+        assert!(report.principal_variation.len() > 1);
+
+        assert_eq!(agent.get_info().get_mcts_report(), Some(&report));
+    }
+
+    #[test]
+    fn test_temperature() {
+        let stats = |visits: u32| Stats {
+            wins: visits as f64,
+            draws: 0.,
+            losses: 0.,
+        };
+
+        let mut agent = MonteCarloTreeAgent::new(0).with_temperature(1.);
+
+        let children = vec![
+            (0, stats(1), None),
+            (1, stats(99), None),
+            (2, stats(1000), Some(GameResult::Win(Player::Two))),
+        ];
+
+        // The proven loss is never sampled, the rarely visited move is sampled sometimes
+        let samples: Vec<u8> = (0..1000)
+            .map(|_| agent.sample_move(&children, 1.).unwrap())
+            .collect();
+        assert!(!samples.contains(&2));
+        assert!(samples.contains(&0) && samples.contains(&1));
+
+        // Low temperatures choose the most visited move
+        assert_eq!(agent.sample_move(&children, 0.01), Some(1));
+
+        let mut children = children;
+        children.push((3, stats(0), Some(GameResult::Win(Player::One))));
+        assert_eq!(agent.sample_move(&children, 1.), Some(3));
+    }
+
+    #[test]
+    fn test_leaf_playouts() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let agent = MonteCarloTreeAgent::new(0).with_leaf_playouts(8);
+
+        let mut tree = Tree::new(NodeInfo::new(UltimateBoard::new()));
+        let root = tree.get_root();
+
+        let simulations = agent.tree_search(&mut tree, &mut SearchStatistics::default(), &mut rng);
+        assert_eq!(simulations.len(), 81 * 8);
+        assert_eq!(tree.get(root).get_data().stats.total(), (81 * 8) as f64);
+
+        for child in tree.get(root).get_children() {
+            assert_eq!(tree.get(child).get_data().stats.total(), 8.);
+        }
+    }
+
+    #[test]
+    fn test_dump_tree() {
+        let mut agent = MonteCarloTreeAgent::new(200).with_seed(0);
+        assert_eq!(agent.dump_tree(1, TreeFormat::Json), None);
+
+        let board = UltimateBoard::new();
+        agent.act(&board, Player::One, 0).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&agent.dump_tree(1, TreeFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["move_index"], serde_json::Value::Null);
+        let children = json["children"].as_array().unwrap();
+        assert_eq!(children.len(), 81);
+        assert_eq!(
+            json["visits"].as_f64().unwrap(),
+            children
+                .iter()
+                .map(|child| child["visits"].as_f64().unwrap())
+                .sum::<f64>()
+        );
+        assert!(json["children"][0]["children"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        let dot = agent.dump_tree(0, TreeFormat::Dot).unwrap();
+        assert_eq!(dot.matches("[label=").count(), 1);
+        assert!(!dot.contains("->"));
+    }
+
+    #[test]
+    fn test_leaf_evaluator() {
+        use std::sync::{Arc, Mutex};
+
+        struct CountingEvaluator {
+            batches: Arc<Mutex<Vec<usize>>>,
+        }
+
+        impl LeafEvaluator for CountingEvaluator {
+            fn evaluate_batch(&self, boards: &[UltimateBoard]) -> Vec<f64> {
+                self.batches.lock().unwrap().push(boards.len());
+                boards
+                    .iter()
+                    .map(|board| match board.get_current_player() {
+                        Player::One => 0.25,
+                        Player::Two => 0.75,
+                    })
+                    .collect()
+            }
+
+            fn get_name(&self) -> String {
+                "counting".to_string()
+            }
+        }
+
+        let batches = Arc::new(Mutex::new(vec![]));
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let agent = MonteCarloTreeAgent::new(0).with_leaf_evaluator(CountingEvaluator {
+            batches: batches.clone(),
+        });
+
+        let mut tree = Tree::new(NodeInfo::new(UltimateBoard::new()));
+        let simulations = agent.tree_search(&mut tree, &mut SearchStatistics::default(), &mut rng);
+
+        // All children of the root are evaluated in one batch, Player Two is to move in the children
+        assert_eq!(*batches.lock().unwrap(), vec![81]);
+        assert!(simulations
+            .iter()
+            .all(|simulation| simulation.stats.wins() == 0.25));
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let search = |seed: u64| {
+            let mut agent = MonteCarloTreeAgent::new(200)
+                .with_temperature(1.)
+                .with_threads(2)
+                .with_seed(seed);
+            agent.act(&board, Player::Two, 1).unwrap();
+            agent.get_last_report().unwrap().clone()
+        };
+
+        assert_eq!(search(3), search(3));
+        assert_ne!(search(3), search(4));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let config = MctsConfig::new(200).with_temperature(1.).with_seed(3);
+
+        let search = |mut agent: MonteCarloTreeAgent| {
+            agent.act(&board, Player::Two, 1).unwrap();
+            agent.get_last_report().unwrap().clone()
+        };
+
+        // The seed of the configuration is used, as with the builder of the agent
+        assert_eq!(
+            search(MonteCarloTreeAgent::from_config(config.clone())),
+            search(
+                MonteCarloTreeAgent::new(200)
+                    .with_temperature(1.)
+                    .with_seed(3)
+            )
+        );
+
+        let agent = MonteCarloTreeAgent::from_config(config.clone());
+        assert_eq!(agent.get_config().seed, Some(3));
+        assert_eq!(agent.get_info().config, config.to_string());
+    }
+
+    #[test]
+    fn test_pondering() {
+        let mut agent = MonteCarloTreeAgent::new(500).with_pondering(true);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let best_move = agent.act(&board, Player::Two, 1).unwrap();
+        board.make_move(best_move);
+
+        // The pondering thread searches the position while the opponent thinks
+        std::thread::sleep(Duration::from_millis(100));
+
+        let reply = board.get_possible_moves().next().unwrap();
+        board.make_move(reply);
+        agent.opponent_moved(board, reply);
+
+        assert!(agent.ponder.is_none());
+        let pondered_tree = agent.pondered_tree.as_ref().unwrap();
+        let root = pondered_tree.get(pondered_tree.get_root()).get_data();
+        assert_eq!(root.get_board(), board);
+        assert_eq!(root.get_move_index(), None);
+        assert!(root.stats.total() > 0.);
+
+        assert!(agent
+            .act(&board, Player::Two, 3)
+            .is_ok_and(|index| board.is_legal_move(index)));
+        assert!(agent.pondered_tree.is_none());
+
+        // The simulations of the pondering are kept
+        assert!(agent.get_last_report().unwrap().simulations > 500.);
+    }
+
+    #[test]
+    fn test_anytime() {
+        let mut agent = MonteCarloTreeAgent::new(u32::MAX);
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        agent.start_thinking(board);
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(agent
+            .best_move_so_far()
+            .is_some_and(|index| board.is_legal_move(index)));
+        assert!(agent.stop().is_some_and(|index| board.is_legal_move(index)));
+        assert!(agent.get_last_report().unwrap().simulations > 0.);
+        assert_eq!(agent.stop(), None);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let mut agent =
+            MonteCarloTreeAgent::new(u32::MAX).with_time_limit(Duration::from_millis(50));
+
+        let start = Instant::now();
+        agent.act(&UltimateBoard::new(), Player::One, 0).unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(agent.get_last_report().unwrap().simulations > 0.);
+    }
+
+    #[test]
+    fn test_solver() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        let winning_moves = |board: UltimateBoard| -> Vec<u8> {
+            let player = board.get_current_player();
+
+            board
+                .get_possible_moves()
+                .filter(|&index| {
+                    let mut board_copy = board;
+                    board_copy.make_move(index);
+                    board_copy.get_game_status() == GameResult::Win(player)
+                })
+                .collect()
+        };
+
+        // Play random games until the player to move can win the game immediately
+        let (board, winning_moves) = 'search: loop {
+            let mut board = UltimateBoard::new();
+
+            while board.get_game_status() == GameResult::Continue {
+                let moves = winning_moves(board);
+
+                if !moves.is_empty() {
+                    break 'search (board, moves);
+                }
+
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+        };
+
+        let mut agent = MonteCarloTreeAgent::new(1000);
+        let player = board.get_current_player();
+
+        // The root is proven after the first iteration
+        let index = agent.act(&board, player, 0).unwrap();
+        assert!(winning_moves.contains(&index));
+
+        let root_stats = agent
+            .search_tree(board, &mut ChaCha20Rng::seed_from_u64(0))
+            .children;
+        assert_eq!(
+            root_stats
+                .iter()
+                .map(|(_, stats, _)| stats.total())
+                .sum::<f64>(),
+            board.get_possible_moves().count() as f64
+        );
+
+        let mut tree = Tree::new(NodeInfo {
+            expanded: 2,
+            ..NodeInfo::new(board)
+        });
+        let mut lost_board = board;
+        lost_board.make_move(board.get_possible_moves().next().unwrap());
+        tree.append_children(
+            tree.get_root(),
+            [
+                NodeInfo {
+                    proven: Some(GameResult::Win(player.get_opponent())),
+                    ..NodeInfo::new(lost_board)
+                },
+                NodeInfo {
+                    proven: Some(GameResult::Draw),
+                    ..NodeInfo::new(lost_board)
+                },
+            ],
+        );
+        assert_eq!(
+            MonteCarloTreeAgent::solve(&tree, tree.get_root()),
+            Some(GameResult::Draw)
+        );
+
+        let selected = agent.select_child(&tree, tree.get_root());
+        assert_eq!(tree.get(selected).get_data().proven, Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn test_root_parallel() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let mut agent = MonteCarloTreeAgent::new(50).with_threads(4);
+
+        let root_stats = agent
+            .search_tree(board, &mut ChaCha20Rng::seed_from_u64(0))
+            .children;
+        assert_eq!(root_stats.len(), board.get_possible_moves().count());
+
+        let merged = MonteCarloTreeAgent::merge_root_stats(root_stats.clone(), root_stats.clone());
+        for ((index, stats, _), (merged_index, merged_stats, _)) in root_stats.iter().zip(merged) {
+            assert_eq!(*index, merged_index);
+            assert_eq!(stats.total() * 2., merged_stats.total());
+        }
+
+        assert!(agent
+            .tree_root(board)
+            .is_some_and(|index| board.is_legal_move(index)));
+    }
+
+    #[test]
+    fn test_select_child() {
+        let agent = |exploration_constant: f64| {
+            MonteCarloTreeAgent::new(0).with_exploration_constant(exploration_constant)
+        };
+
+        let mut tree = Tree::new(NodeInfo {
+            expanded: 2,
+            ..node_with_stats(55, 0, 55)
+        });
+        tree.append_children(
+            tree.get_root(),
+            [
+                // Often visited with a high win rate for the agent
+                NodeInfo {
+                    move_index: Some(0),
+                    ..node_with_stats(70, 0, 30)
+                },
+                // Rarely visited with a lower win rate for the agent
+                NodeInfo {
+                    move_index: Some(1),
+                    ..node_with_stats(4, 0, 6)
+                },
+            ],
+        );
+
+        let selected = |agent: MonteCarloTreeAgent, tree: &Tree<NodeInfo>| {
+            let child = agent.select_child(tree, tree.get_root());
+            tree.get(child).get_data().get_move_index()
+        };
+
+        // The agent is the player to move at the root
+        assert_eq!(selected(agent(0.), &tree), Some(0));
+        assert_eq!(selected(agent(10.), &tree), Some(1));
+
+        // The opponent is the player to move at the root, so the win rates are reversed
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        tree.get_mut(tree.get_root()).get_data_mut().board = board;
+
+        assert_eq!(selected(agent(0.), &tree), Some(1));
+    }
+
+    #[test]
+    fn test_tablebase() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut tablebase = Tablebase::new(10);
+
+        // A position with few empty squares won by the player to move
+        let board = loop {
+            let mut board = UltimateBoard::new();
+            while board.get_game_status() == GameResult::Continue
+                && board.count_empty_squares() > 10
+            {
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            if tablebase.solve(board) == Some(GameResult::Win(board.get_current_player())) {
+                break board;
+            }
+        };
+        let player = board.get_current_player();
+
+        let tablebase = Arc::new(tablebase);
+        let mut agent = MonteCarloTreeAgent::new(100)
+            .with_tablebase(tablebase.clone())
+            .with_seed(0);
+
+        let best_move = agent.act(&board, player, 0).unwrap();
+
+        // The winning move is proven without playouts to the end of the game
+        let report = agent.get_last_report().unwrap();
+        assert!(report
+            .children
+            .iter()
+            .any(|child| child.proven == Some(GameResult::Win(player))));
+
+        let mut board_copy = board;
+        board_copy.make_move(best_move);
+        assert_eq!(tablebase.probe(board_copy), Some(GameResult::Win(player)));
+    }
 }
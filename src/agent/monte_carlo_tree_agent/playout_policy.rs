@@ -0,0 +1,287 @@
+//! # Contains the [PlayoutPolicy] trait and implementations
+//!
+//! A [PlayoutPolicy] chooses the moves of the playouts of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+//!
+//! The following policies are provided:
+//! * [UniformPolicy] - Chooses a possible move uniformly at random (default)
+//! * [DecisivePolicy] - Plays a move winning the game if there is one, with a small chance of a random move instead
+//! * [AntiDecisivePolicy] - Plays a move winning the game if there is one, otherwise blocks the wins of the opponent
+//! * [HeuristicPolicy] - Samples the moves weighted by the evaluation of a [Heuristic]
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::Heuristic;
+use rand::distributions::{Uniform, WeightedIndex};
+use rand::{Rng, RngCore};
+use rand_distr::Distribution;
+
+/// # Trait representing the choice of the moves of a playout
+///
+/// The policy is called for every move of a playout, so it should be fast.
+pub trait PlayoutPolicy: Send + Sync {
+    /// Chooses the next move of a playout
+    ///
+    /// # Arguments
+    /// * `board` - The current state of the board, the game is not over
+    /// * `rng` - The random number generator of the playout
+    /// # Returns
+    /// The index of the chosen move, must be a possible move
+    fn choose_move(&self, board: UltimateBoard, rng: &mut dyn RngCore) -> u8;
+
+    /// Returns the name of the policy, used in the configuration of the agent
+    fn get_name(&self) -> String;
+}
+
+/// Policy choosing a possible move uniformly at random
+///
+/// This is the default policy of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformPolicy;
+
+impl PlayoutPolicy for UniformPolicy {
+    fn choose_move(&self, board: UltimateBoard, rng: &mut dyn RngCore) -> u8 {
+        let possible_moves: Vec<_> = board.get_possible_moves().collect();
+
+        choose(&possible_moves, rng).unwrap()
+    }
+
+    fn get_name(&self) -> String {
+        "uniform".to_string()
+    }
+}
+
+/// Policy playing a move winning the game if there is one
+///
+/// With the probability epsilon, or if no move wins the game, a random move is chosen instead.
+/// Playouts with decisive moves are closer to the games of real players, who do not miss an immediate win.
+#[derive(Clone, Copy, Debug)]
+pub struct DecisivePolicy {
+    epsilon: f64,
+}
+
+impl DecisivePolicy {
+    /// Creates a new [DecisivePolicy]
+    ///
+    /// # Arguments
+    /// * `epsilon` - The probability of choosing a random move although a move wins the game, must be between 0 and 1
+    pub fn new(epsilon: f64) -> DecisivePolicy {
+        assert!(
+            (0. ..=1.).contains(&epsilon),
+            "Epsilon must be between 0 and 1"
+        );
+        DecisivePolicy { epsilon }
+    }
+}
+
+impl PlayoutPolicy for DecisivePolicy {
+    fn choose_move(&self, board: UltimateBoard, rng: &mut dyn RngCore) -> u8 {
+        if rng.gen::<f64>() >= self.epsilon {
+            let winning_move = board
+                .get_game_winning_moves(board.get_current_player())
+                .next();
+
+            if let Some(index) = winning_move {
+                return index;
+            }
+        }
+
+        UniformPolicy.choose_move(board, rng)
+    }
+
+    fn get_name(&self) -> String {
+        format!("decisive {}", self.epsilon)
+    }
+}
+
+/// Policy playing decisive and anti-decisive moves
+///
+/// The moves are chosen in the following order:
+/// 1. A move winning the game
+/// 2. A random move blocking a small board whose win would win the game for the opponent
+/// 3. A random move blocking a small board the opponent could win
+/// 4. A random move
+///
+/// Only the boards the player to move may play on are considered,
+/// see [UltimateBoard::get_game_winning_moves] and [UltimateBoard::get_board_winning_moves].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AntiDecisivePolicy;
+
+impl PlayoutPolicy for AntiDecisivePolicy {
+    fn choose_move(&self, board: UltimateBoard, rng: &mut dyn RngCore) -> u8 {
+        let player = board.get_current_player();
+
+        if let Some(index) = board.get_game_winning_moves(player).next() {
+            return index;
+        }
+
+        let opponent = player.get_opponent();
+
+        let blocking_moves: Vec<_> = board.get_game_winning_moves(opponent).collect();
+        if let Some(index) = choose(&blocking_moves, rng) {
+            return index;
+        }
+
+        let blocking_moves: Vec<_> = board.get_board_winning_moves(opponent).collect();
+        if let Some(index) = choose(&blocking_moves, rng) {
+            return index;
+        }
+
+        UniformPolicy.choose_move(board, rng)
+    }
+
+    fn get_name(&self) -> String {
+        "anti-decisive".to_string()
+    }
+}
+
+/// Chooses a move uniformly at random
+///
+/// # Arguments
+/// * `moves` - The moves to choose from
+/// * `rng` - The random number generator
+/// # Returns
+/// The chosen move, None if there are no moves
+fn choose(moves: &[u8], rng: &mut dyn RngCore) -> Option<u8> {
+    if moves.is_empty() {
+        None
+    } else {
+        Some(moves[Uniform::from(0..moves.len()).sample(rng)])
+    }
+}
+
+/// Policy sampling the moves weighted by the evaluation of a [Heuristic]
+///
+/// Every possible move is evaluated for the player making it, the move is sampled with the weight `exp(value / temperature)`.
+/// Low temperatures favor the best moves, high temperatures approach the [UniformPolicy].
+#[derive(Clone)]
+pub struct HeuristicPolicy<H> {
+    heuristic: H,
+    temperature: f64,
+}
+
+impl<H: Heuristic> HeuristicPolicy<H> {
+    /// Creates a new [HeuristicPolicy]
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic, it evaluates the moves [for](Heuristic::evaluate_for) the player making them
+    /// * `temperature` - The temperature of the sampling, must be positive
+    ///
+    /// # Example
+    /// ```
+    /// use hausarbeit::agent::monte_carlo_tree_agent::playout_policy::HeuristicPolicy;
+    /// use hausarbeit::game::player::Player;
+    /// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+    ///
+    /// let policy = HeuristicPolicy::new(CustomHeuristic::new(Player::One), 0.1);
+    /// ```
+    pub fn new(heuristic: H, temperature: f64) -> HeuristicPolicy<H> {
+        assert!(temperature > 0., "Temperature must be positive");
+        HeuristicPolicy {
+            heuristic,
+            temperature,
+        }
+    }
+}
+
+impl<H: Heuristic> PlayoutPolicy for HeuristicPolicy<H> {
+    fn choose_move(&self, board: UltimateBoard, rng: &mut dyn RngCore) -> u8 {
+        let player = board.get_current_player();
+
+        let (possible_moves, values): (Vec<u8>, Vec<f64>) = board
+            .get_possible_moves()
+            .map(|index| {
+                let mut board_copy = board;
+                board_copy.make_move(index);
+                (index, self.heuristic.evaluate_for(board_copy, player))
+            })
+            .unzip();
+
+        let max_value = values.iter().copied().fold(f64::MIN, f64::max);
+
+        let weights = values
+            .iter()
+            .map(|value| ((value - max_value) / self.temperature).exp());
+
+        match WeightedIndex::new(weights) {
+            Ok(distribution) => possible_moves[distribution.sample(rng)],
+            // The values are not finite
+            Err(_) => UniformPolicy.choose_move(board, rng),
+        }
+    }
+
+    fn get_name(&self) -> String {
+        format!(
+            "heuristic {} {}",
+            self.heuristic.get_name(),
+            self.temperature
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_result::GameResult;
+    use crate::game::player::Player;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_policies() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        let policies: Vec<Box<dyn PlayoutPolicy>> = vec![
+            Box::new(UniformPolicy),
+            Box::new(DecisivePolicy::new(0.)),
+            Box::new(AntiDecisivePolicy),
+            Box::new(HeuristicPolicy::new(CustomHeuristic::new(Player::One), 1.)),
+        ];
+
+        for policy in policies.iter() {
+            let mut board = UltimateBoard::new();
+
+            while board.get_game_status() == GameResult::Continue {
+                let index = policy.choose_move(board, &mut rng);
+                assert!(board.is_legal_move(index));
+                board.make_move(index);
+            }
+        }
+
+        // The decisive policies do not miss a win of the game
+        let mut board = UltimateBoard::new();
+        for turn in 0.. {
+            let player = board.get_current_player();
+            let index = if turn % 2 == 0 {
+                DecisivePolicy::new(0.).choose_move(board, &mut rng)
+            } else {
+                AntiDecisivePolicy.choose_move(board, &mut rng)
+            };
+
+            let can_win = board.get_possible_moves().any(|index| {
+                let mut board_copy = board;
+                board_copy.make_move(index);
+                board_copy.get_game_status() == GameResult::Win(player)
+            });
+
+            board.make_move(index);
+
+            if can_win {
+                assert_eq!(board.get_game_status(), GameResult::Win(player));
+            }
+
+            if board.get_game_status() != GameResult::Continue {
+                break;
+            }
+        }
+
+        // The anti-decisive policy blocks the opponent
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 38, 22, 42, 60, 58] {
+            board.make_move(index);
+        }
+        // Player One has to play on board 4, where Player Two threatens to win with the squares 1 and 3
+        let mut blocking_moves: Vec<_> = board.get_board_winning_moves(Player::Two).collect();
+        blocking_moves.sort();
+        assert_eq!(blocking_moves, vec![37, 39]);
+        assert!(blocking_moves.contains(&AntiDecisivePolicy.choose_move(board, &mut rng)));
+    }
+}
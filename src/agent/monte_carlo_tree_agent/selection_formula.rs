@@ -0,0 +1,141 @@
+//! # Contains the [SelectionFormula] trait and implementations
+//!
+//! A [SelectionFormula] values the children of a node during the selection of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent),
+//! the child with the highest value is selected.
+//!
+//! The following formulas are provided:
+//! * [Ucb1] - The [UCT](https://www.chessprogramming.org/UCT) formula (default)
+//! * [Ucb1Tuned] - UCB1 with an exploration term bounded by the variance of the rewards
+//! * [Puct] - The predictor UCT formula of AlphaZero, weighting the exploration with the priors of the children
+//!
+//! Unvisited children are not valued by the formula, see [MonteCarloTreeAgent::with_first_play_urgency](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_first_play_urgency).
+
+/// # Struct containing the statistics of a visited child used to value it
+///
+/// The rewards are the results of the simulations for the player choosing the child:
+/// 1 for a win, the [draw reward](crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig::with_draw_reward) for a draw and 0 for a loss.
+///
+/// # Fields
+/// * `visits` - The number of visits of the child, positive
+/// * `parent_visits` - The number of visits of the parent
+/// * `win_rate` - The mean reward of the child, blended with the all-moves-as-first win rate if [RAVE](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_rave) is enabled
+/// * `variance` - The variance of the rewards of the child
+/// * `prior` - The prior probability of the child, the priors of all expanded children of the parent sum up to 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelectionStatistics {
+    pub visits: f64,
+    pub parent_visits: f64,
+    pub win_rate: f64,
+    pub variance: f64,
+    pub prior: f64,
+}
+
+/// # Trait representing the formula valuing the children during the selection
+pub trait SelectionFormula: Send + Sync {
+    /// Values a visited child
+    ///
+    /// # Arguments
+    /// * `statistics` - The statistics of the child
+    /// * `exploration_constant` - The [exploration constant](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_exploration_constant) `c`
+    /// # Returns
+    /// The value of the child, the child with the highest value is selected
+    fn value(&self, statistics: SelectionStatistics, exploration_constant: f64) -> f64;
+
+    /// Returns the name of the formula, used in the configuration of the agent
+    fn get_name(&self) -> String;
+}
+
+/// The UCB1 formula `win rate + c * sqrt(ln(parent visits) / visits)`
+///
+/// This is the default formula of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ucb1;
+
+impl SelectionFormula for Ucb1 {
+    fn value(&self, statistics: SelectionStatistics, exploration_constant: f64) -> f64 {
+        statistics.win_rate
+            + exploration_constant * (statistics.parent_visits.ln() / statistics.visits).sqrt()
+    }
+
+    fn get_name(&self) -> String {
+        "ucb1".to_string()
+    }
+}
+
+/// The UCB1-Tuned formula `win rate + sqrt(ln(parent visits) / visits * min(1/4, V))`
+///
+/// `V = variance + sqrt(2 * ln(parent visits) / visits)` is an upper confidence bound of the variance of the rewards,
+/// so children with consistent results are explored less than with [Ucb1].
+/// `1/4` is the maximum variance of rewards between 0 and 1.
+///
+/// The formula does not use the exploration constant.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ucb1Tuned;
+
+impl SelectionFormula for Ucb1Tuned {
+    fn value(&self, statistics: SelectionStatistics, _exploration_constant: f64) -> f64 {
+        let log_ratio = statistics.parent_visits.ln() / statistics.visits;
+
+        let variance_bound = statistics.variance + (2. * log_ratio).sqrt();
+
+        statistics.win_rate + (log_ratio * variance_bound.min(0.25)).sqrt()
+    }
+
+    fn get_name(&self) -> String {
+        "ucb1-tuned".to_string()
+    }
+}
+
+/// The PUCT formula `win rate + c * prior * sqrt(parent visits) / (1 + visits)`
+///
+/// The exploration of a child is proportional to its prior, so children the priors consider unlikely are rarely explored.
+/// The priors are the heuristic win probabilities of [progressive bias](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::with_progressive_bias),
+/// normalized over the children, without progressive bias all children have the same prior.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Puct;
+
+impl SelectionFormula for Puct {
+    fn value(&self, statistics: SelectionStatistics, exploration_constant: f64) -> f64 {
+        statistics.win_rate
+            + exploration_constant * statistics.prior * statistics.parent_visits.sqrt()
+                / (1. + statistics.visits)
+    }
+
+    fn get_name(&self) -> String {
+        "puct".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formulas() {
+        let statistics = SelectionStatistics {
+            visits: 10.,
+            parent_visits: 100.,
+            win_rate: 0.6,
+            variance: 0.24,
+            prior: 0.5,
+        };
+
+        let exploration = (100f64.ln() / 10.).sqrt();
+
+        assert_eq!(Ucb1.value(statistics, 2.), 0.6 + 2. * exploration);
+        assert_eq!(
+            Ucb1Tuned.value(statistics, 2.),
+            0.6 + exploration * 0.25f64.sqrt()
+        );
+        assert_eq!(Puct.value(statistics, 2.), 0.6 + 2. * 0.5 * 10. / 11.);
+
+        // Consistent results are explored less by UCB1-Tuned
+        let consistent = SelectionStatistics {
+            visits: 1000.,
+            parent_visits: 10000.,
+            variance: 0.,
+            ..statistics
+        };
+        assert!(Ucb1Tuned.value(consistent, 2.) < Ucb1.value(consistent, 0.5f64.sqrt()));
+    }
+}
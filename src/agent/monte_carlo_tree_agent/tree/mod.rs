@@ -3,37 +3,134 @@
 //! The [Tree] struct represents a tree data structure.
 //!
 //! The [Node] struct represents a node in a tree data structure.
-use std::cell::RefCell;
-use std::rc::Rc;
+//!
+//! The nodes are stored in an arena: a single [Vec] owns all nodes and the nodes refer to each other by their [NodeId].
+//! This avoids an allocation per node and keeps the nodes close together in memory.
+use std::ops::Range;
+
+/// The index of a [Node] in the arena of a [Tree]
+pub type NodeId = usize;
 
 /// # Struct representing a tree data structure
 ///
-/// The tree might have a root node.
+/// The tree always has a root node.
 /// The root node might have children.
 /// The number of children is not limited.
-pub struct Tree<T: Clone> {
-    root: Option<Rc<RefCell<Node<T>>>>,
+///
+/// All children of a node are appended at once, so they are stored next to each other in the arena.
+pub struct Tree<T> {
+    nodes: Vec<Node<T>>,
 }
 
-impl<T: Clone> Tree<T> {
+impl<T> Tree<T> {
+    /// The id of the root of every tree
+    pub const ROOT: NodeId = 0;
+
     /// Creates a new tree
     ///
-    /// The root of this tree is set to the given node.
+    /// The root of this tree is set to a node containing the given data.
     ///
     /// # Arguments
-    /// * `root` - The root node of the tree
-    pub fn new(root: Node<T>) -> Self {
+    /// * `root` - The data of the root node of the tree
+    pub fn new(root: T) -> Self {
         Tree {
-            root: Some(Rc::new(RefCell::new(root))),
+            nodes: vec![Node::new(root)],
         }
     }
 
     /// Gets the root of the tree
     ///
     /// # Returns
-    /// The root of the tree
-    pub fn get_root(&self) -> Option<Rc<RefCell<Node<T>>>> {
-        self.root.clone()
+    /// The id of the root of the tree
+    pub fn get_root(&self) -> NodeId {
+        Tree::<T>::ROOT
+    }
+
+    /// Gets a node of the tree
+    ///
+    /// # Arguments
+    /// * `id` - The id of the node
+    /// # Returns
+    /// The node
+    pub fn get(&self, id: NodeId) -> &Node<T> {
+        &self.nodes[id]
+    }
+
+    /// Gets a mutable reference to a node of the tree
+    ///
+    /// # Arguments
+    /// * `id` - The id of the node
+    /// # Returns
+    /// The node
+    pub fn get_mut(&mut self, id: NodeId) -> &mut Node<T> {
+        &mut self.nodes[id]
+    }
+
+    /// Appends children to a leaf
+    ///
+    /// # Arguments
+    /// * `parent` - The id of the node to append the children to, must be a leaf
+    /// * `children` - The data of the children
+    pub fn append_children(&mut self, parent: NodeId, children: impl IntoIterator<Item = T>) {
+        debug_assert!(
+            self.nodes[parent].is_leaf(),
+            "Children are only appended to leaves"
+        );
+
+        let start = self.nodes.len();
+        self.nodes.extend(children.into_iter().map(Node::new));
+
+        self.nodes[parent].children = start..self.nodes.len();
+    }
+
+    /// Gets the number of nodes of the tree
+    ///
+    /// # Returns
+    /// The number of nodes, including the root
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Copies the subtree of a node into a new tree
+    ///
+    /// The node becomes the root of the new tree, the other nodes of the tree are not copied.
+    ///
+    /// # Arguments
+    /// * `root` - The id of the root of the subtree
+    /// # Returns
+    /// The new tree
+    pub fn subtree(&self, root: NodeId) -> Tree<T>
+    where
+        T: Clone,
+    {
+        let mut subtree = Tree::new(self.nodes[root].data.clone());
+
+        // The nodes are copied breadth-first, so the children of a node stay next to each other
+        let mut queue = std::collections::VecDeque::from([(root, Tree::<T>::ROOT)]);
+
+        while let Some((node, copy)) = queue.pop_front() {
+            let children = self.nodes[node].get_children();
+
+            let start = subtree.nodes.len();
+            for child in children {
+                queue.push_back((child, subtree.nodes.len()));
+                subtree
+                    .nodes
+                    .push(Node::new(self.nodes[child].data.clone()));
+            }
+
+            subtree.nodes[copy].children = start..subtree.nodes.len();
+        }
+
+        subtree
+    }
+
+    /// Checks if the tree only consists of its root
+    ///
+    /// # Returns
+    /// True if the root has no children, false otherwise
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
     }
 }
 
@@ -42,46 +139,46 @@ impl<T: Clone> Tree<T> {
 /// The node might have children.
 /// The number of children is not limited.
 ///
-/// The data that is stored in the node is of type T and must implement the Clone trait.
-pub struct Node<T: Clone> {
+/// The data that is stored in the node is of type T.
+pub struct Node<T> {
     data: T,
-    children: Vec<Rc<RefCell<Node<T>>>>,
+    children: Range<NodeId>,
 }
 
-impl<T: Clone> Node<T> {
-    /// Creates a new node
+impl<T> Node<T> {
+    /// Creates a new node without children
     ///
     /// # Arguments
     /// * `data` - The data of the node
-    pub fn new(data: T) -> Self {
+    fn new(data: T) -> Self {
         Node {
             data,
-            children: Vec::new(),
+            children: 0..0,
         }
     }
 
-    /// Appends a child to the node
+    /// Gets the children of the node
     ///
-    /// # Arguments
-    /// * `child` - The child to append
-    pub fn append(&mut self, child: Node<T>) {
-        self.children.push(Rc::new(RefCell::new(child)));
+    /// # Returns
+    /// The ids of the children of the node
+    pub fn get_children(&self) -> Range<NodeId> {
+        self.children.clone()
     }
 
-    /// Gets the children of the node
+    /// Gets the data of the node
     ///
     /// # Returns
-    /// The children of the node
-    pub fn get_children(&self) -> &Vec<Rc<RefCell<Node<T>>>> {
-        &self.children
+    /// The data of the node
+    pub fn get_data(&self) -> &T {
+        &self.data
     }
 
-    /// Gets the data of the node
+    /// Gets a mutable reference to the data of the node
     ///
     /// # Returns
     /// The data of the node
-    pub fn get_data(&self) -> T {
-        self.data.clone()
+    pub fn get_data_mut(&mut self) -> &mut T {
+        &mut self.data
     }
 
     /// Checks if the node is a leaf
@@ -91,15 +188,51 @@ impl<T: Clone> Node<T> {
     pub fn is_leaf(&self) -> bool {
         self.children.is_empty()
     }
+}
 
-    /// Maps a function over the data of the node
-    ///
-    /// # Arguments
-    /// * `f` - The function to map over the data
-    pub fn map<F>(&mut self, f: F)
-    where
-        F: Fn(T) -> T,
-    {
-        self.data = f(self.data.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_children() {
+        let mut tree = Tree::new(0);
+        tree.append_children(tree.get_root(), [1, 2]);
+        tree.append_children(2, [3, 4, 5]);
+
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.get(Tree::<i32>::ROOT).get_children(), 1..3);
+        assert!(tree.get(1).is_leaf());
+
+        let data: Vec<i32> = tree
+            .get(2)
+            .get_children()
+            .map(|child| *tree.get(child).get_data())
+            .collect();
+        assert_eq!(data, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_subtree() {
+        let mut tree = Tree::new(0);
+        tree.append_children(tree.get_root(), [1, 2]);
+        tree.append_children(1, [3]);
+        tree.append_children(2, [4, 5]);
+        tree.append_children(5, [6]);
+
+        let subtree = tree.subtree(2);
+
+        assert_eq!(subtree.len(), 4);
+        assert_eq!(*subtree.get(subtree.get_root()).get_data(), 2);
+
+        let children = subtree.get(subtree.get_root()).get_children();
+        let data: Vec<i32> = children
+            .clone()
+            .map(|child| *subtree.get(child).get_data())
+            .collect();
+        assert_eq!(data, vec![4, 5]);
+
+        let grandchild = subtree.get(children.end - 1).get_children().start;
+        assert_eq!(*subtree.get(grandchild).get_data(), 6);
     }
 }
@@ -0,0 +1,127 @@
+//! # Contains the [TreeFormat] enum
+//!
+//! The tree of the last search of the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent)
+//! can be exported with [MonteCarloTreeAgent::dump_tree](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent::dump_tree)
+//! to inspect why the agent prefers or avoids a move.
+//!
+//! Every node is labeled with the move leading to it, its number of visits and its win rate for the player of the agent.
+//! Only the expanded children of a node are exported.
+use crate::agent::monte_carlo_tree_agent::tree::{NodeId, Tree};
+use crate::agent::monte_carlo_tree_agent::{MonteCarloTreeAgent, NodeInfo};
+use crate::game::game_result::GameResult;
+use serde::Serialize;
+
+/// # Enum representing the formats the tree can be exported to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeFormat {
+    /// A directed graph in the [DOT](https://graphviz.org/doc/info/lang.html) language of Graphviz
+    Dot,
+    /// A nested JSON object, the children of a node are stored in its `children` array
+    Json,
+}
+
+/// # Struct representing a node of the exported tree
+#[derive(Serialize)]
+struct DumpNode {
+    /// The move leading to the node, None for the root
+    move_index: Option<u8>,
+    /// The number of simulations through the node
+    visits: f64,
+    /// The mean reward of the simulations for the player of the agent
+    win_rate: f64,
+    /// The proven result of the node
+    proven: Option<GameResult>,
+    /// The exported children of the node
+    children: Vec<DumpNode>,
+}
+
+/// Exports the tree in the given format
+///
+/// # Arguments
+/// * `tree` - The tree to export
+/// * `max_depth` - The depth of the deepest exported nodes, 0 only exports the root
+/// * `format` - The format of the export
+/// * `draw_reward` - The reward of a draw used for the win rates
+/// # Returns
+/// The exported tree
+pub(super) fn dump(
+    tree: &Tree<NodeInfo>,
+    max_depth: usize,
+    format: TreeFormat,
+    draw_reward: f64,
+) -> String {
+    let root = collect(tree, tree.get_root(), max_depth, draw_reward);
+
+    match format {
+        TreeFormat::Dot => {
+            let mut dot = "digraph mcts {\n".to_string();
+            write_dot(&root, &mut 0, &mut dot);
+            dot += "}\n";
+            dot
+        }
+        TreeFormat::Json => serde_json::to_string_pretty(&root).unwrap(),
+    }
+}
+
+/// Collects a node and its descendants up to the given depth
+///
+/// # Arguments
+/// * `tree` - The tree containing the node
+/// * `node` - The node to collect
+/// * `depth` - The remaining depth
+/// * `draw_reward` - The reward of a draw used for the win rates
+/// # Returns
+/// The exported node
+fn collect(tree: &Tree<NodeInfo>, node: NodeId, depth: usize, draw_reward: f64) -> DumpNode {
+    let data = tree.get(node).get_data();
+
+    let children = if depth == 0 {
+        vec![]
+    } else {
+        MonteCarloTreeAgent::expanded_children(tree, node)
+            .map(|child| collect(tree, child, depth - 1, draw_reward))
+            .collect()
+    };
+
+    DumpNode {
+        move_index: data.get_move_index(),
+        visits: data.stats.total(),
+        win_rate: data.stats.win_rate(true, draw_reward),
+        proven: data.proven,
+        children,
+    }
+}
+
+/// Writes a node and its descendants as DOT statements
+///
+/// # Arguments
+/// * `node` - The node to write
+/// * `next_id` - The id of the next written node, incremented for every node
+/// * `dot` - The DOT output
+/// # Returns
+/// The id of the written node
+fn write_dot(node: &DumpNode, next_id: &mut usize, dot: &mut String) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+
+    let mut label = match node.move_index {
+        Some(move_index) => format!("move {}", move_index),
+        None => "root".to_string(),
+    };
+    label += &format!(
+        "\\nvisits: {}\\nwin rate: {:.3}",
+        node.visits, node.win_rate
+    );
+    if let Some(proven) = node.proven {
+        label += &format!("\\nproven: {:?}", proven);
+    }
+
+    *dot += &format!("    n{} [label=\"{}\"];\n", id, label);
+
+    for child in node.children.iter() {
+        let child_id = write_dot(child, next_id, dot);
+        *dot += &format!("    n{} -> n{};\n", id, child_id);
+    }
+
+    id
+}
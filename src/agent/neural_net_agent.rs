@@ -0,0 +1,312 @@
+//! # Contains the [NeuralNetAgent] and [OnnxEvaluator] structs
+//!
+//! The [OnnxEvaluator] loads a policy/value network in the [ONNX](https://onnx.ai) format with [tract](https://github.com/sonos/tract)
+//! and implements the [Evaluator] trait, so the network can guide the [MonteCarloTreeAgent].
+//! The value of the network can also be used as a heuristic with the [NnHeuristic](crate::heuristic::nn_heuristic::NnHeuristic).
+//!
+//! The [NeuralNetAgent] plays either greedily from the policy of the network or with a [PUCT](Puct) search.
+//!
+//! The module is only available with the `onnx` feature.
+//!
+//! # Network format
+//! The network has a single input of the shape `[1, 3, 81]`, the board from the perspective of the player to move:
+//! * Plane 0 - 1 for the squares of the player to move, 0 otherwise
+//! * Plane 1 - 1 for the squares of the opponent, 0 otherwise
+//! * Plane 2 - 1 for the possible moves, 0 otherwise
+//!
+//! The squares are indexed by the human index (0-80) of the moves.
+//!
+//! The network has two outputs:
+//! * The policy of the shape `[1, 81]`, the logits of the moves
+//! * The value of the shape `[1, 1]`, the value of the board for the player to move between -1 and 1
+use crate::agent::agent_error::AgentError;
+use crate::agent::monte_carlo_tree_agent::evaluator::Evaluator;
+use crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig;
+use crate::agent::monte_carlo_tree_agent::selection_formula::Puct;
+use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use std::sync::Arc;
+use tract_onnx::prelude::*;
+
+/// The number of values of the input of the network
+const INPUT_SIZE: usize = 3 * 81;
+
+/// Encodes a board as the input of the network
+///
+/// See the [module documentation](self) for the format.
+/// # Arguments
+/// * `board` - The board to encode
+/// # Returns
+/// The values of the input planes, plane after plane
+pub fn encode(board: UltimateBoard) -> Vec<f32> {
+    let player = board.get_current_player();
+
+    let mut input = vec![0.; INPUT_SIZE];
+
+    for (board_index, small_board) in board.get_boards().iter().enumerate() {
+        for square in 0..9 {
+            let index = board_index * 9 + square as usize;
+
+            match small_board.get_square(square) {
+                Some(owner) if owner == player => input[index] = 1.,
+                Some(_) => input[81 + index] = 1.,
+                None => {}
+            }
+        }
+    }
+
+    for index in board.get_possible_moves() {
+        input[162 + index as usize] = 1.;
+    }
+
+    input
+}
+
+/// # Struct representing a policy/value network loaded from an ONNX file
+///
+/// The evaluator is cheap to clone, the clones share the network.
+#[derive(Clone)]
+pub struct OnnxEvaluator {
+    model: Arc<TypedRunnableModel>,
+    name: String,
+}
+
+impl OnnxEvaluator {
+    /// Loads the network from an ONNX file
+    ///
+    /// # Arguments
+    /// * `path` - The path of the ONNX file
+    /// # Returns
+    /// The evaluator, an error if the file could not be read or the network does not have the expected input
+    pub fn load(path: &str) -> TractResult<Self> {
+        let model = tract_onnx::onnx()
+            .model_for_path(path)?
+            .with_input_fact(0, f32::fact([1, 3, 81]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+
+        Ok(OnnxEvaluator {
+            model,
+            name: path.to_string(),
+        })
+    }
+
+    /// Runs the network
+    ///
+    /// # Arguments
+    /// * `board` - The board to evaluate
+    /// # Returns
+    /// The logits of the 81 moves and the value of the board for the player to move
+    fn run(&self, board: UltimateBoard) -> TractResult<(Vec<f32>, f32)> {
+        let input = Tensor::from_shape(&[1, 3, 81], &encode(board))?;
+
+        let outputs = self.model.run(tvec!(input.into()))?;
+
+        let logits: Vec<f32> = outputs[0]
+            .to_plain_array_view::<f32>()?
+            .iter()
+            .copied()
+            .collect();
+        let value = outputs[1]
+            .to_plain_array_view::<f32>()?
+            .iter()
+            .copied()
+            .next()
+            .unwrap_or(0.);
+
+        Ok((logits, value))
+    }
+
+    /// Evaluates a board with the value output of the network only
+    ///
+    /// # Arguments
+    /// * `board` - The board to evaluate
+    /// # Returns
+    /// The value of the board for the player to move between -1 and 1
+    pub fn evaluate_value(&self, board: UltimateBoard) -> f32 {
+        let (_, value) = self.run(board).expect("The network could not be run");
+
+        value.clamp(-1., 1.)
+    }
+}
+
+impl Evaluator for OnnxEvaluator {
+    fn evaluate(&self, board: UltimateBoard) -> (Vec<(u8, f32)>, f32) {
+        let (logits, value) = self.run(board).expect("The network could not be run");
+
+        // Softmax over the possible moves
+        let possible_moves: Vec<u8> = board.get_possible_moves().collect();
+
+        let max_logit = possible_moves
+            .iter()
+            .map(|&index| logits[index as usize])
+            .fold(f32::MIN, f32::max);
+
+        let weights: Vec<f32> = possible_moves
+            .iter()
+            .map(|&index| (logits[index as usize] - max_logit).exp())
+            .collect();
+        let weight_sum: f32 = weights.iter().sum();
+
+        let priors = possible_moves
+            .into_iter()
+            .zip(weights)
+            .map(|(index, weight)| (index, weight / weight_sum))
+            .collect();
+
+        (priors, value.clamp(-1., 1.))
+    }
+
+    fn get_name(&self) -> String {
+        format!("onnx {}", self.name)
+    }
+}
+
+/// # Struct representing an agent playing with a policy/value network
+///
+/// Without a search, the agent plays the move with the highest prior of the policy.
+/// With [NeuralNetAgent::with_search], the network guides a [MonteCarloTreeAgent] using the [Puct] formula,
+/// the value of the network replaces the playouts.
+pub struct NeuralNetAgent {
+    evaluator: OnnxEvaluator,
+    /// The search guided by the network, None if the agent plays greedily from the policy
+    search: Option<MonteCarloTreeAgent>,
+    player: Player,
+    turn: u32,
+}
+
+impl NeuralNetAgent {
+    /// Creates a new [NeuralNetAgent] playing greedily from the policy of the network
+    ///
+    /// # Arguments
+    /// * `evaluator` - The network
+    pub fn new(evaluator: OnnxEvaluator) -> Self {
+        NeuralNetAgent {
+            evaluator,
+            search: None,
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Loads the network from an ONNX file and creates a new [NeuralNetAgent] playing greedily from the policy
+    ///
+    /// # Arguments
+    /// * `path` - The path of the ONNX file
+    /// # Returns
+    /// The agent, an error if the network could not be loaded
+    pub fn load(path: &str) -> TractResult<Self> {
+        Ok(NeuralNetAgent::new(OnnxEvaluator::load(path)?))
+    }
+
+    /// Searches the moves with a PUCT search guided by the network
+    ///
+    /// # Arguments
+    /// * `config` - The configuration of the search, the [evaluator](MctsConfig::with_evaluator) and
+    ///   the [selection formula](MctsConfig::with_selection_formula) are set to the network and [Puct]
+    /// # Returns
+    /// The agent searching the moves
+    pub fn with_search(mut self, config: MctsConfig) -> Self {
+        let config = config
+            .with_evaluator(self.evaluator.clone())
+            .with_selection_formula(Puct);
+
+        self.search = Some(MonteCarloTreeAgent::from_config(config));
+        self
+    }
+}
+
+impl Agent for NeuralNetAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        if let Some(search) = &mut self.search {
+            return search.act(board, player, turn);
+        }
+
+        let (priors, _) = self.evaluator.evaluate(*board);
+
+        priors
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+            .ok_or(AgentError::NoMove)
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        if let Some(search) = &mut self.search {
+            search.opponent_moved(board, index);
+        }
+    }
+
+    fn new_game(&mut self, player: Player) {
+        if let Some(search) = &mut self.search {
+            search.new_game(player);
+        }
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        if let Some(search) = &mut self.search {
+            search.game_over(board, result);
+        }
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        match &self.search {
+            Some(search) => {
+                let info = search.get_info();
+
+                let mut neural_info = AgentInfo::new(
+                    "NN".to_string(),
+                    self.player,
+                    self.turn,
+                    info.config.clone(),
+                );
+
+                if let Some(statistics) = info.get_statistics() {
+                    neural_info = neural_info.with_statistics(*statistics);
+                }
+
+                if let Some(report) = info.get_mcts_report() {
+                    neural_info = neural_info.with_mcts_report(report.clone());
+                }
+
+                neural_info
+            }
+            None => AgentInfo::new(
+                "NN".to_string(),
+                self.player,
+                self.turn,
+                self.evaluator.get_name(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        board.make_move(36);
+
+        let input = encode(board);
+
+        // Player One is to move
+        assert_eq!(input[40], 1.);
+        assert_eq!(input[81 + 36], 1.);
+        assert_eq!(input.iter().take(162).sum::<f32>(), 2.);
+
+        // Player One has to play on board 0
+        let possible_moves: Vec<usize> =
+            (0..81).filter(|&index| input[162 + index] == 1.).collect();
+        assert_eq!(possible_moves, (0..9).collect::<Vec<_>>());
+    }
+}
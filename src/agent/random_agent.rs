@@ -2,12 +2,14 @@
 //! The RandomAgent struct represents an [Agent] that plays randomly.
 //! The agent can be used to test other agent or to play against a human player.
 
+use crate::agent::agent_error::AgentError;
 use crate::agent::{Agent, AgentInfo};
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
-use rand::Rng;
+use rand::seq::IteratorRandom;
 
 /// Struct representing an agent that plays randomly
+#[derive(Clone)]
 pub struct RandomAgent {
     player: Player,
     turn: u32,
@@ -29,13 +31,14 @@ impl RandomAgent {
 }
 
 impl Agent for RandomAgent {
-    fn act(&mut self, board: UltimateBoard, player: Player, turn: u32) -> Option<u8> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
         self.player = player;
         self.turn = turn;
 
-        let possible_moves: Vec<_> = board.get_possible_moves().collect();
-
-        Some(possible_moves[rand::thread_rng().gen_range(0..possible_moves.len())])
+        board
+            .get_possible_moves()
+            .choose(&mut rand::thread_rng())
+            .ok_or(AgentError::NoMove)
     }
 
     fn get_info(&self) -> AgentInfo {
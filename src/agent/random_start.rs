@@ -1,14 +1,45 @@
-//! # Contains the [RandomStartAgent] struct
+//! # Contains the [RandomStartAgent] struct and the [Opening] enum
 //!
-//! The RandomStartAgent struct represents an [Agent] that uses a random agent for the first `depth` turns, then switches to another agent.
+//! The RandomStartAgent struct represents an [Agent] that plays an [Opening] for the first turns, then switches to another agent.
+//! The opening consists of random moves, a fixed sequence of moves or a move of a book of openings.
 //!
 //! For more information see the [RandomStartAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
 use crate::agent::random_agent::RandomAgent;
 use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 
-/// An agent that uses a random agent for the first `depth` turns, then switches to another agent.
+/// # Enum representing the opening played by a [RandomStartAgent]
+///
+/// The moves of an opening are human indices (0-80) of both players in the order they are made.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Opening {
+    /// Random moves for the given number of turns
+    Random(u32),
+    /// The given sequence of moves
+    Moves(Vec<u8>),
+    /// A sequence of moves sampled from the given book of openings
+    Book(Vec<Vec<u8>>),
+}
+
+/// An agent that plays an [Opening] for the first turns, then switches to another agent.
+///
+/// An agent with a sequence of moves or a book plays the next move of an opening that leads to the current board.
+/// If no opening leads to the current board, e.g. because the opponent deviated, the other agent takes over.
+/// In a book, all openings that lead to the current board are equally likely,
+/// so two agents with the same book agree on the opening as long as both are in the book.
+///
+/// For fair matches every opening is played twice, with both agents playing each color once.
+///
+/// See [HybridAgent](crate::agent::hybrid_agent::HybridAgent) for switching between any agents.
 ///
 /// # Example
 /// ```
@@ -18,31 +49,220 @@ use crate::game::ultimate_board::UltimateBoard;
 /// let agent = RandomStartAgent::new(5, MonteCarloTreeAgent::new(1000));
 /// ```
 /// The agent will use a random agent for the first 5 turns, then switch to a MonteCarloTreeAgent.
+///
+/// ```
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::random_start::{Opening, RandomStartAgent};
+///
+/// let book = Opening::Book(vec![vec![40, 36], vec![40, 44], vec![0, 4]]);
+/// let agent = RandomStartAgent::from_opening(book, MonteCarloTreeAgent::new(1000)).with_seed(42);
+/// ```
+/// The agent will play the first two moves of one of the openings, then switch to a MonteCarloTreeAgent.
+#[derive(Clone)]
 pub struct RandomStartAgent<A: Agent> {
-    depth: u32,
+    opening: Opening,
     agent: A,
     random_agent: RandomAgent,
+    rng: ChaCha20Rng,
 }
 
 impl<A: Agent> RandomStartAgent<A> {
+    /// Creates a new [RandomStartAgent] playing random moves for the first turns
+    ///
+    /// # Arguments
+    /// * `depth` - The number of turns with random moves
+    /// * `agent` - The agent playing after the opening
     pub fn new(depth: u32, agent: A) -> RandomStartAgent<A> {
+        RandomStartAgent::from_opening(Opening::Random(depth), agent)
+    }
+
+    /// Creates a new [RandomStartAgent] playing the given opening
+    ///
+    /// The random number generator choosing between the openings of a book is seeded from the operating system.
+    /// # Arguments
+    /// * `opening` - The opening
+    /// * `agent` - The agent playing after the opening
+    pub fn from_opening(opening: Opening, agent: A) -> RandomStartAgent<A> {
         RandomStartAgent {
-            depth,
+            opening,
             agent,
             random_agent: RandomAgent::new(),
+            rng: ChaCha20Rng::from_entropy(),
+        }
+    }
+
+    /// Seeds the random number generator choosing between the openings of a book
+    ///
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The agent with the seeded random number generator
+    pub fn with_seed(mut self, seed: u64) -> RandomStartAgent<A> {
+        self.rng = ChaCha20Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Chooses the next move of an opening that leads to the given board
+    ///
+    /// # Arguments
+    /// * `rng` - The random number generator choosing between the openings
+    /// * `openings` - The openings
+    /// * `board` - The current board
+    /// * `turn` - The current turn
+    /// # Returns
+    /// The next move of a randomly chosen opening, None if no opening leads to the board or all of them have ended
+    fn choose_opening_move(
+        rng: &mut ChaCha20Rng,
+        openings: &[Vec<u8>],
+        board: &UltimateBoard,
+        turn: u32,
+    ) -> Option<u8> {
+        let position = board.get_position();
+
+        openings
+            .iter()
+            .filter(|opening| opening.len() > turn as usize)
+            .filter(|opening| {
+                let mut opening_board = UltimateBoard::new();
+
+                opening[..turn as usize].iter().all(|&index| {
+                    let legal = opening_board.is_legal_move(index);
+                    if legal {
+                        opening_board.make_move(index);
+                    }
+                    legal
+                }) && opening_board.get_position() == position
+            })
+            .map(|opening| opening[turn as usize])
+            .filter(|&index| board.is_legal_move(index))
+            .choose(rng)
+    }
+
+    /// Plays the move of the opening, if the opening has not ended yet
+    ///
+    /// # Arguments
+    /// * `board` - The current board
+    /// * `player` - The player of the agent
+    /// * `turn` - The current turn
+    /// # Returns
+    /// The move of the opening, None if the wrapped agent has to play
+    fn play_opening(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Option<Result<u8, AgentError>> {
+        match &self.opening {
+            Opening::Random(depth) if *depth > turn => {
+                Some(self.random_agent.act(board, player, turn))
+            }
+            Opening::Random(_) => None,
+            Opening::Moves(moves) => RandomStartAgent::<A>::choose_opening_move(
+                &mut self.rng,
+                std::slice::from_ref(moves),
+                board,
+                turn,
+            )
+            .map(Ok),
+            Opening::Book(openings) => {
+                RandomStartAgent::<A>::choose_opening_move(&mut self.rng, openings, board, turn)
+                    .map(Ok)
+            }
         }
     }
 }
 
 impl<A: Agent> Agent for RandomStartAgent<A> {
-    fn act(&mut self, board: UltimateBoard, player: Player, turn: u32) -> Option<u8> {
-        if self.depth > turn {
-            return self.random_agent.act(board, player, turn);
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        match self.play_opening(board, player, turn) {
+            Some(result) => result,
+            None => self.agent.act(board, player, turn),
+        }
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        match self.play_opening(board, player, turn) {
+            Some(result) => result.map(Action::Move),
+            None => self.agent.choose_action(board, player, turn),
         }
-        self.agent.act(board, player, turn)
     }
 
     fn get_info(&self) -> AgentInfo {
         self.agent.get_info()
     }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+    use crate::game::Game;
+
+    #[test]
+    fn test_openings() {
+        let opening = Opening::Moves(vec![40, 36, 4]);
+
+        let mut game = Game::new(
+            Box::new(RandomStartAgent::from_opening(
+                opening.clone(),
+                RandomAgent::new(),
+            )),
+            Box::new(RandomStartAgent::from_opening(opening, RandomAgent::new())),
+        );
+        game.play();
+        assert_eq!(&game.get_record().get_moves()[..3], &[40, 36, 4]);
+
+        // Both agents agree on one of the openings of the book
+        let book = Opening::Book(vec![vec![40, 36], vec![40, 44], vec![0, 4]]);
+        let mut game = Game::new(
+            Box::new(RandomStartAgent::from_opening(book.clone(), RandomAgent::new()).with_seed(1)),
+            Box::new(RandomStartAgent::from_opening(book, RandomAgent::new()).with_seed(2)),
+        );
+        game.play();
+        let moves = game.get_record().get_moves()[..2].to_vec();
+        assert!([vec![40, 36], vec![40, 44], vec![0, 4]].contains(&moves));
+
+        // The other agent takes over after a deviation from the opening
+        let mut agent = RandomStartAgent::from_opening(
+            Opening::Moves(vec![40, 36, 4]),
+            ScriptedAgent::new(vec![40, 37, 9]),
+        );
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        board.make_move(37);
+        assert_eq!(agent.act(&board, Player::One, 2), Ok(9));
+    }
 }
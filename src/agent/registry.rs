@@ -0,0 +1,465 @@
+//! # Contains the [AgentRegistry] and [AgentSpec] structs
+//!
+//! The AgentRegistry struct creates [agents](Agent) from textual specifications like `minimax:d=8,q=1,h=custom` or `mcts:i=10000`.
+//! It allows command line tools and configuration files to refer to agents by name instead of Rust code.
+//!
+//! For more information see the [AgentRegistry] struct.
+use crate::agent::agent_error::AgentError;
+use crate::agent::greedy_agent::GreedyHeuristicAgent;
+use crate::agent::human_agent::HumanAgent;
+use crate::agent::minimax_agent::minimax_config::MiniMaxConfig;
+use crate::agent::minimax_agent::MiniMaxAgent;
+use crate::agent::monte_carlo_tree_agent::mcts_config::MctsConfig;
+use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+use crate::agent::random_agent::RandomAgent;
+use crate::agent::remote_agent::RemoteAgent;
+use crate::agent::softmax_agent::SoftmaxAgent;
+use crate::agent::Agent;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_record::GameRecord;
+use crate::game::player::Player;
+use crate::game::Game;
+use crate::heuristic::custom_heuristic::CustomHeuristic;
+use crate::heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic;
+use crate::heuristic::threat_heuristic::ThreatHeuristic;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A function creating an agent for the given player from a specification
+pub type AgentFactory =
+    dyn Fn(&AgentSpec, Player) -> Result<Box<dyn Agent>, AgentError> + Send + Sync;
+
+/// # Struct representing the specification of an agent
+///
+/// A specification consists of the kind of the agent and optional parameters, e.g. `minimax:d=8,q=1,h=custom`.
+/// The kind is separated from the parameters by a colon, the parameters are separated by commas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentSpec {
+    kind: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl AgentSpec {
+    /// Returns the kind of the agent
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the value of a parameter
+    ///
+    /// # Arguments
+    /// * `key` - The name of the parameter
+    /// # Returns
+    /// The parsed value, None if the parameter is not set, or an error if the value can not be parsed
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, AgentError> {
+        let Some((_, value)) = self.parameters.iter().find(|(name, _)| name == key) else {
+            return Ok(None);
+        };
+
+        value.parse().map(Some).map_err(|_| {
+            AgentError::Other(format!(
+                "Invalid value '{}' for parameter '{}' of agent '{}'",
+                value, key, self.kind
+            ))
+        })
+    }
+
+    /// Returns the value of a parameter or the given default value if the parameter is not set
+    ///
+    /// # Arguments
+    /// * `key` - The name of the parameter
+    /// * `default` - The value used if the parameter is not set
+    /// # Returns
+    /// The parsed value, or an error if the value can not be parsed
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, AgentError> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Checks that only the given parameters are set, to catch misspelled parameters
+    ///
+    /// # Arguments
+    /// * `keys` - The names of the supported parameters
+    /// # Returns
+    /// An error naming the first unsupported parameter
+    pub fn check_parameters(&self, keys: &[&str]) -> Result<(), AgentError> {
+        match self
+            .parameters
+            .iter()
+            .find(|(name, _)| !keys.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(AgentError::Other(format!(
+                "Unknown parameter '{}' of agent '{}', expected one of {:?}",
+                name, self.kind, keys
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for AgentSpec {
+    type Err = AgentError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (kind, parameters) = spec.trim().split_once(':').unwrap_or((spec.trim(), ""));
+
+        if kind.is_empty() {
+            return Err(AgentError::Other(format!(
+                "Missing agent kind in '{}'",
+                spec
+            )));
+        }
+
+        let parameters = parameters
+            .split(',')
+            .filter(|parameter| !parameter.trim().is_empty())
+            .map(|parameter| match parameter.split_once('=') {
+                Some((key, value)) => Ok((key.trim().to_string(), value.trim().to_string())),
+                None => Err(AgentError::Other(format!(
+                    "Invalid parameter '{}' in '{}', expected key=value",
+                    parameter, spec
+                ))),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(AgentSpec {
+            kind: kind.to_string(),
+            parameters,
+        })
+    }
+}
+
+impl Display for AgentSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+
+        for (i, (key, value)) in self.parameters.iter().enumerate() {
+            write!(f, "{}{}={}", if i == 0 { ':' } else { ',' }, key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// # Struct mapping agent kinds to the functions creating the agents
+///
+/// The registry contains the following agents and parameters by default:
+/// * `random` - [RandomAgent]
+/// * `human` - [HumanAgent]
+/// * `remote:addr=<address>` - [RemoteAgent] connected to the given address
+/// * `minimax:d=<depth>,q=<quiescence depth>,h=<heuristic>,seed=<seed>` - [MiniMaxAgent], defaults `d=8,q=1,h=custom`
+/// * `mcts:i=<iterations>,seed=<seed>` - [MonteCarloTreeAgent], default `i=10000`
+/// * `greedy:h=<heuristic>` - [GreedyHeuristicAgent], default `h=custom`
+/// * `softmax:h=<heuristic>,t=<temperature>` - [SoftmaxAgent], defaults `h=custom,t=0.1`
+///
+/// The heuristic is either `custom` for the [CustomHeuristic],
+/// `mcgs` for the [MonteCarloGameSearchHeuristic] with `s=<simulations>`, default `s=10`,
+/// or `threat` for the [ThreatHeuristic].
+/// The seed of the minimax agent also seeds the random games of the [MonteCarloGameSearchHeuristic].
+/// The heuristics evaluate the board for the player the agent is created for.
+///
+/// Further agents can be added with [AgentRegistry::with_agent].
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::registry::AgentRegistry;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::game::Game;
+///
+/// let registry = AgentRegistry::new();
+///
+/// let mut game = Game::new(
+///     registry.create("minimax:d=2,q=0", Player::One).unwrap(),
+///     registry.create("mcts:i=100", Player::Two).unwrap(),
+/// );
+/// game.play();
+/// ```
+pub struct AgentRegistry {
+    factories: BTreeMap<String, Box<AgentFactory>>,
+}
+
+impl Default for AgentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentRegistry {
+    /// Creates a new [AgentRegistry] containing the built-in agents
+    pub fn new() -> AgentRegistry {
+        AgentRegistry::empty()
+            .with_agent("random", |spec, _| {
+                spec.check_parameters(&[])?;
+                Ok(Box::new(RandomAgent::new()))
+            })
+            .with_agent("human", |spec, _| {
+                spec.check_parameters(&[])?;
+                Ok(Box::new(HumanAgent::default()))
+            })
+            .with_agent("remote", |spec, _| {
+                spec.check_parameters(&["addr"])?;
+                let address: String = spec.get("addr")?.ok_or(AgentError::Other(
+                    "Missing parameter 'addr' of agent 'remote'".to_string(),
+                ))?;
+                Ok(Box::new(RemoteAgent::connect(address)?))
+            })
+            .with_agent("minimax", AgentRegistry::create_minimax)
+            .with_agent("mcts", |spec, _| {
+                spec.check_parameters(&["i", "seed"])?;
+                let mut config = MctsConfig::new(spec.get_or("i", 10000)?);
+                if let Some(seed) = spec.get("seed")? {
+                    config = config.with_seed(seed);
+                }
+                Ok(Box::new(MonteCarloTreeAgent::from_config(config)))
+            })
+            .with_agent("greedy", |spec, player| {
+                spec.check_parameters(&["h", "s"])?;
+                Ok(match AgentRegistry::get_heuristic(spec)? {
+                    Heuristic::Custom => {
+                        Box::new(GreedyHeuristicAgent::new(CustomHeuristic::new(player)))
+                    }
+                    Heuristic::MonteCarlo(simulations) => Box::new(GreedyHeuristicAgent::new(
+                        MonteCarloGameSearchHeuristic::new(player, simulations),
+                    )),
+                    Heuristic::Threat => {
+                        Box::new(GreedyHeuristicAgent::new(ThreatHeuristic::new(player)))
+                    }
+                })
+            })
+            .with_agent("softmax", |spec, player| {
+                spec.check_parameters(&["h", "s", "t"])?;
+                let temperature = spec.get_or("t", 0.1)?;
+                if temperature <= 0. {
+                    return Err(AgentError::Other(
+                        "Temperature must be positive".to_string(),
+                    ));
+                }
+                Ok(match AgentRegistry::get_heuristic(spec)? {
+                    Heuristic::Custom => {
+                        Box::new(SoftmaxAgent::new(CustomHeuristic::new(player), temperature))
+                    }
+                    Heuristic::MonteCarlo(simulations) => Box::new(SoftmaxAgent::new(
+                        MonteCarloGameSearchHeuristic::new(player, simulations),
+                        temperature,
+                    )),
+                    Heuristic::Threat => {
+                        Box::new(SoftmaxAgent::new(ThreatHeuristic::new(player), temperature))
+                    }
+                })
+            })
+    }
+
+    /// Creates a new [AgentRegistry] without any agents
+    pub fn empty() -> AgentRegistry {
+        AgentRegistry {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an agent kind to the registry
+    ///
+    /// An existing kind with the same name is replaced.
+    /// # Arguments
+    /// * `kind` - The kind of the agent used in the specifications
+    /// * `factory` - The function creating the agent from the specification and the player
+    /// # Returns
+    /// The registry containing the agent kind
+    pub fn with_agent(
+        mut self,
+        kind: &str,
+        factory: impl Fn(&AgentSpec, Player) -> Result<Box<dyn Agent>, AgentError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> AgentRegistry {
+        self.factories.insert(kind.to_string(), Box::new(factory));
+        self
+    }
+
+    /// Returns the registered agent kinds in alphabetical order
+    pub fn get_kinds(&self) -> Vec<&str> {
+        self.factories.keys().map(String::as_str).collect()
+    }
+
+    /// Creates an agent from its specification
+    ///
+    /// # Arguments
+    /// * `spec` - The specification of the agent, e.g. `mcts:i=10000`
+    /// * `player` - The player the agent plays for
+    /// # Returns
+    /// The agent, or an error if the specification is malformed or the kind is unknown
+    pub fn create(&self, spec: &str, player: Player) -> Result<Box<dyn Agent>, AgentError> {
+        self.create_from_spec(&spec.parse()?, player)
+    }
+
+    /// Creates an agent from its parsed specification
+    ///
+    /// # Arguments
+    /// * `spec` - The specification of the agent
+    /// * `player` - The player the agent plays for
+    /// # Returns
+    /// The agent, or an error if the kind is unknown or a parameter is invalid
+    pub fn create_from_spec(
+        &self,
+        spec: &AgentSpec,
+        player: Player,
+    ) -> Result<Box<dyn Agent>, AgentError> {
+        let factory = self.factories.get(spec.get_kind()).ok_or_else(|| {
+            AgentError::Other(format!(
+                "Unknown agent '{}', expected one of {:?}",
+                spec.get_kind(),
+                self.get_kinds()
+            ))
+        })?;
+
+        factory(spec, player)
+    }
+
+    /// Creates a game between the agents of the given specifications
+    ///
+    /// The specifications are stored in the [metadata](GameMetadata), so the game can be [resumed](AgentRegistry::resume_game) after it was saved.
+    /// # Arguments
+    /// * `spec_one` - The specification of the agent of [Player::One]
+    /// * `spec_two` - The specification of the agent of [Player::Two]
+    /// # Returns
+    /// The game, or an error if an agent can not be created
+    pub fn create_game(&self, spec_one: &str, spec_two: &str) -> Result<Game, AgentError> {
+        let mut metadata = GameMetadata::new();
+        metadata.agent_specs = [spec_one.to_string(), spec_two.to_string()];
+
+        Ok(Game::with_metadata(
+            self.create(spec_one, Player::One)?,
+            self.create(spec_two, Player::Two)?,
+            metadata,
+        ))
+    }
+
+    /// Continues a saved game with the agents of the specifications stored in its metadata
+    ///
+    /// # Arguments
+    /// * `record` - The record of the game, e.g. [loaded](GameRecord::load) from a file
+    /// # Returns
+    /// The game, or an error if the record contains no specifications, an agent can not be created or a move is illegal
+    pub fn resume_game(&self, record: GameRecord) -> Result<Game, AgentError> {
+        let [spec_one, spec_two] = &record.get_metadata().agent_specs;
+        if spec_one.is_empty() || spec_two.is_empty() {
+            return Err(AgentError::Other(
+                "The record does not contain the specifications of the agents".to_string(),
+            ));
+        }
+
+        let agent_one = self.create(spec_one, Player::One)?;
+        let agent_two = self.create(spec_two, Player::Two)?;
+
+        Game::from_record(agent_one, agent_two, record)
+    }
+
+    fn create_minimax(spec: &AgentSpec, player: Player) -> Result<Box<dyn Agent>, AgentError> {
+        spec.check_parameters(&["d", "q", "h", "s", "seed"])?;
+        let mut config = MiniMaxConfig::new(spec.get_or("d", 8)?, spec.get_or("q", 1)?);
+        let seed = spec.get("seed")?;
+        if let Some(seed) = seed {
+            config = config.with_seed(seed);
+        }
+
+        Ok(match AgentRegistry::get_heuristic(spec)? {
+            Heuristic::Custom => Box::new(MiniMaxAgent::from_config(
+                config,
+                CustomHeuristic::new(player),
+            )),
+            Heuristic::MonteCarlo(simulations) => {
+                let mut heuristic = MonteCarloGameSearchHeuristic::new(player, simulations);
+                if let Some(seed) = seed {
+                    heuristic = heuristic.with_seed(seed);
+                }
+                Box::new(MiniMaxAgent::from_config(config, heuristic))
+            }
+            Heuristic::Threat => Box::new(MiniMaxAgent::from_config(
+                config,
+                ThreatHeuristic::new(player),
+            )),
+        })
+    }
+
+    fn get_heuristic(spec: &AgentSpec) -> Result<Heuristic, AgentError> {
+        match spec.get_or("h", "custom".to_string())?.as_str() {
+            "custom" => Ok(Heuristic::Custom),
+            "mcgs" => Ok(Heuristic::MonteCarlo(spec.get_or("s", 10)?)),
+            "threat" => Ok(Heuristic::Threat),
+            heuristic => Err(AgentError::Other(format!(
+                "Unknown heuristic '{}' of agent '{}', expected custom, mcgs or threat",
+                heuristic,
+                spec.get_kind()
+            ))),
+        }
+    }
+}
+
+/// The heuristics available in the specifications
+enum Heuristic {
+    Custom,
+    MonteCarlo(u32),
+    Threat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ultimate_board::UltimateBoard;
+
+    #[test]
+    fn test_specs() {
+        let spec: AgentSpec = "minimax:d=8, q=1,h=custom".parse().unwrap();
+        assert_eq!(spec.get_kind(), "minimax");
+        assert_eq!(spec.get::<u32>("d"), Ok(Some(8)));
+        assert_eq!(spec.get::<u32>("x"), Ok(None));
+        assert!(spec.get::<u32>("h").is_err());
+        assert_eq!(spec.to_string(), "minimax:d=8,q=1,h=custom");
+
+        assert!("minimax:d".parse::<AgentSpec>().is_err());
+        assert!(":d=1".parse::<AgentSpec>().is_err());
+
+        let registry = AgentRegistry::new();
+        let mut agent = registry.create("greedy", Player::One).unwrap();
+        assert_eq!(agent.get_info().get_name(), "GREEDY");
+        assert!(agent.act(&UltimateBoard::new(), Player::One, 0).is_ok());
+        assert!(registry.create("softmax:h=threat", Player::Two).is_ok());
+
+        assert!(registry.create("minimax:depth=8", Player::One).is_err());
+        assert!(registry.create("minimax:h=unknown", Player::One).is_err());
+        assert!(registry.create("alphazero", Player::One).is_err());
+    }
+
+    #[test]
+    fn test_resume_game() {
+        let registry = AgentRegistry::new();
+        let mut game = registry.create_game("random", "greedy").unwrap();
+        game.play();
+
+        // Save the game after the first four moves
+        let mut record = GameRecord::new(game.get_record().get_metadata().clone());
+        for &index in &game.get_record().get_moves()[..4] {
+            record.push_move(index);
+        }
+        let path = std::env::temp_dir().join("hausarbeit_test_resume_game.json");
+        let path = path.to_str().unwrap();
+        record.save(path).unwrap();
+
+        let mut game = registry
+            .resume_game(GameRecord::load(path).unwrap())
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(game.get_record().get_moves(), record.get_moves());
+        assert_eq!(
+            game.get_record().get_metadata().agent_names,
+            ["RAND".to_string(), "GREEDY".to_string()]
+        );
+
+        game.play();
+        assert_eq!(&game.get_record().get_moves()[..4], record.get_moves());
+
+        assert!(registry
+            .resume_game(GameRecord::new(GameMetadata::new()))
+            .is_err());
+    }
+}
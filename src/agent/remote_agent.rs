@@ -0,0 +1,502 @@
+//! # Contains the [RemoteAgent], [AgentServer] and [Relay] structs
+//!
+//! A [RemoteAgent] forwards the moves it is asked for to an engine over TCP,
+//! the [AgentServer] exposes a local [Agent] to remote clients.
+//! The [Relay] plays games between two clients that connect to it, e.g. two human players on different machines.
+//! Engines written in other languages can take part in games and tournaments of this crate
+//! by implementing the server side of the protocol.
+//!
+//! # Protocol
+//! The protocol is line-based, every message is a single line of text terminated by `\n`.
+//! Positions are sent in the format of [UltimateBoard::get_position].
+//!
+//! After the connection is accepted, the server sends its name:
+//! * `name <name>`
+//!
+//! The client then sends any number of the following requests:
+//! * `act <turn> <position>` - The server replies with `move <index>`, or `none` if the agent has no move
+//! * `opponent <index> <position>` - The opponent played on the square with the given index, the position is the board after the move. The server does not reply.
+//! * `quit` - The server closes the connection
+//!
+//! Malformed requests are answered with `error <message>`.
+//!
+//! If the notifications are enabled with [RemoteAgent::with_notifications], the client also sends the following requests,
+//! which the server does not reply to:
+//! * `new <player>` - A new game starts, the server plays `X` or `O`
+//! * `result <result> <position>` - The game is over, the result is `X`, `O` or `draw`, the position is the final board
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::game::Game;
+use std::io::{BufRead, BufReader, Error, ErrorKind, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+/// An agent playing the moves of an engine connected over TCP
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::random_agent::RandomAgent;
+/// use hausarbeit::agent::remote_agent::RemoteAgent;
+/// use hausarbeit::game::Game;
+///
+/// let remote = RemoteAgent::connect("127.0.0.1:7878").unwrap();
+///
+/// let mut game = Game::new(Box::new(remote), Box::new(RandomAgent::new()));
+/// game.play();
+/// ```
+pub struct RemoteAgent {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    name: String,
+    address: String,
+    player: Player,
+    turn: u32,
+    /// Whether the server is informed about new games and results
+    notifications: bool,
+}
+
+impl RemoteAgent {
+    /// Connects to an [AgentServer] or another server implementing the protocol
+    ///
+    /// # Arguments
+    /// * `address` - The address of the server
+    /// # Returns
+    /// The connected agent, an error if the connection failed or the server did not send its name
+    pub fn connect(address: impl ToSocketAddrs) -> Result<RemoteAgent, Error> {
+        RemoteAgent::from_stream(TcpStream::connect(address)?)
+    }
+
+    /// Creates an agent from an established connection, e.g. a client accepted by a [Relay]
+    ///
+    /// # Arguments
+    /// * `writer` - The connection to the server side of the protocol
+    /// # Returns
+    /// The agent, an error if the server did not send its name
+    pub fn from_stream(writer: TcpStream) -> Result<RemoteAgent, Error> {
+        let address = writer.peer_addr()?.to_string();
+        // The requests are short lines, waiting to fill a packet only delays the replies
+        writer.set_nodelay(true)?;
+        let mut reader = BufReader::new(writer.try_clone()?);
+
+        let line = read_line(&mut reader)?;
+        let name = line
+            .strip_prefix("name ")
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Expected the name of the agent"))?
+            .to_string();
+
+        Ok(RemoteAgent {
+            reader,
+            writer,
+            name,
+            address,
+            player: Player::default(),
+            turn: 0,
+            notifications: false,
+        })
+    }
+
+    /// Sends the `new` and `result` notifications to the server
+    ///
+    /// Servers implementing only the basic protocol would reply to the notifications with errors,
+    /// so they are disabled by default.
+    /// # Returns
+    /// The agent sending the notifications
+    pub fn with_notifications(mut self) -> RemoteAgent {
+        self.notifications = true;
+        self
+    }
+
+    /// Sends a notification to the server, if the notifications are enabled
+    ///
+    /// # Arguments
+    /// * `notification` - The request without the line terminator
+    fn notify(&mut self, notification: &str) {
+        if !self.notifications {
+            return;
+        }
+        if let Err(error) = writeln!(self.writer, "{}", notification) {
+            eprintln!("Remote agent {} failed: {}", self.address, error);
+        }
+    }
+
+    /// Requests a move from the server
+    ///
+    /// # Arguments
+    /// * `board` - The current state of the board
+    /// * `turn` - The turn number
+    /// # Returns
+    /// The move of the server, None if the server has no move
+    fn request_move(&mut self, board: UltimateBoard, turn: u32) -> Result<Option<u8>, Error> {
+        writeln!(self.writer, "act {} {}", turn, board.get_position())?;
+
+        let line = read_line(&mut self.reader)?;
+
+        if line == "none" {
+            return Ok(None);
+        }
+
+        line.strip_prefix("move ")
+            .and_then(|index| index.parse().ok())
+            .map(Some)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Invalid reply: {}", line)))
+    }
+}
+
+impl Agent for RemoteAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        self.request_move(*board, turn)
+            .map_err(|error| AgentError::Io(format!("{}: {}", self.address, error)))?
+            .ok_or(AgentError::NoMove)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new(
+            self.name.clone(),
+            self.player,
+            self.turn,
+            self.address.clone(),
+        )
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        if let Err(error) = writeln!(self.writer, "opponent {} {}", index, board.get_position()) {
+            eprintln!("Remote agent {} failed: {}", self.address, error);
+        }
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.player = player;
+        self.notify(&format!("new {}", player_symbol(player)));
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        let result = match result {
+            GameResult::Win(player) => player_symbol(player),
+            _ => "draw",
+        };
+        self.notify(&format!("result {} {}", result, board.get_position()));
+    }
+}
+
+impl Drop for RemoteAgent {
+    fn drop(&mut self) {
+        // The server may already be gone
+        let _ = writeln!(self.writer, "quit");
+    }
+}
+
+/// Server exposing a local [Agent] to [RemoteAgents](RemoteAgent)
+///
+/// The clients are served one after another, so the agent plays one game at a time.
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::remote_agent::AgentServer;
+///
+/// let mut server = AgentServer::new(MonteCarloTreeAgent::new(1000));
+/// server.listen("127.0.0.1:7878").unwrap();
+/// ```
+pub struct AgentServer<A: Agent> {
+    agent: A,
+}
+
+impl<A: Agent> AgentServer<A> {
+    /// Creates a new [AgentServer]
+    ///
+    /// # Arguments
+    /// * `agent` - The agent answering the requests
+    pub fn new(agent: A) -> AgentServer<A> {
+        AgentServer { agent }
+    }
+
+    /// Accepts connections and serves them until an error occurs
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// # Returns
+    /// The error that stopped the server
+    pub fn listen(&mut self, address: impl ToSocketAddrs) -> Result<(), Error> {
+        let listener = TcpListener::bind(address)?;
+
+        for stream in listener.incoming() {
+            if let Err(error) = self.serve(stream?) {
+                eprintln!("Connection failed: {}", error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serves a single connection until the client quits or disconnects
+    ///
+    /// # Arguments
+    /// * `stream` - The connection to the client
+    /// # Returns
+    /// An error if the connection failed
+    pub fn serve(&mut self, stream: TcpStream) -> Result<(), Error> {
+        stream.set_nodelay(true)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+
+        writeln!(writer, "name {}", self.agent.get_info().get_name())?;
+
+        loop {
+            let line = match read_line(&mut reader) {
+                Ok(line) => line,
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+                Err(error) => return Err(error),
+            };
+
+            let (command, arguments) = line.split_once(' ').unwrap_or((&line, ""));
+
+            match command {
+                "act" => match parse_request(arguments) {
+                    Some((turn, board)) if board.get_game_status() == GameResult::Continue => {
+                        match self.agent.act(&board, board.get_current_player(), turn) {
+                            Ok(index) => writeln!(writer, "move {}", index)?,
+                            Err(_) => writeln!(writer, "none")?,
+                        }
+                    }
+                    _ => writeln!(writer, "error invalid position")?,
+                },
+                "opponent" => match parse_request(arguments) {
+                    Some((index, board)) if index < 81 => {
+                        self.agent.opponent_moved(board, index as u8)
+                    }
+                    _ => writeln!(writer, "error invalid move")?,
+                },
+                "new" => match parse_player(arguments) {
+                    Some(player) => self.agent.new_game(player),
+                    None => writeln!(writer, "error invalid player")?,
+                },
+                "result" => match parse_result(arguments) {
+                    Some((result, board)) => self.agent.game_over(board, result),
+                    None => writeln!(writer, "error invalid result")?,
+                },
+                "quit" => return Ok(()),
+                _ => writeln!(writer, "error unknown command {}", command)?,
+            }
+        }
+    }
+}
+
+/// Server playing games between two clients implementing the server side of the protocol
+///
+/// The clients connect to the relay, e.g. with [AgentServer::serve], instead of waiting for a connection.
+/// The first client to connect plays [Player::One].
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::remote_agent::Relay;
+///
+/// let relay = Relay::bind("0.0.0.0:7878").unwrap();
+/// loop {
+///     println!("Result: {:?}", relay.play_game().unwrap());
+/// }
+/// ```
+pub struct Relay {
+    listener: TcpListener,
+}
+
+impl Relay {
+    /// Creates a new [Relay] listening on the given address
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// # Returns
+    /// The relay, an error if the address can not be bound
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Relay, Error> {
+        Ok(Relay {
+            listener: TcpListener::bind(address)?,
+        })
+    }
+
+    /// Returns the address the relay is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.listener.local_addr()
+    }
+
+    /// Waits for two clients and plays a game between them
+    ///
+    /// The clients are informed about the start and the result of the game, see [RemoteAgent::with_notifications],
+    /// and disconnected afterwards.
+    /// # Returns
+    /// The result of the game, an error if a client could not be accepted
+    pub fn play_game(&self) -> Result<GameResult, Error> {
+        let mut agents = Vec::with_capacity(2);
+        while agents.len() < 2 {
+            let (stream, _) = self.listener.accept()?;
+            match RemoteAgent::from_stream(stream) {
+                Ok(agent) => agents.push(agent.with_notifications()),
+                Err(error) => eprintln!("Connection failed: {}", error),
+            }
+        }
+
+        let agent_two = agents.pop().unwrap();
+        let agent_one = agents.pop().unwrap();
+        let mut game = Game::new(Box::new(agent_one), Box::new(agent_two));
+
+        Ok(game.play())
+    }
+}
+
+/// Returns the symbol of the player used in the protocol
+fn player_symbol(player: Player) -> &'static str {
+    match player {
+        Player::One => "X",
+        Player::Two => "O",
+    }
+}
+
+/// Parses the symbol of a player
+fn parse_player(symbol: &str) -> Option<Player> {
+    match symbol {
+        "X" => Some(Player::One),
+        "O" => Some(Player::Two),
+        _ => None,
+    }
+}
+
+/// Parses the arguments of a `result` request
+///
+/// # Arguments
+/// * `arguments` - The arguments of the request
+/// # Returns
+/// The result and the final board, None if the arguments are malformed
+fn parse_result(arguments: &str) -> Option<(GameResult, UltimateBoard)> {
+    let (result, position) = arguments.split_once(' ')?;
+    let result = match result {
+        "draw" => GameResult::Draw,
+        symbol => GameResult::Win(parse_player(symbol)?),
+    };
+
+    Some((result, UltimateBoard::from_position(position)?))
+}
+
+/// Reads a line without the line terminator
+///
+/// # Arguments
+/// * `reader` - The reader of the connection
+/// # Returns
+/// The line, an error of the kind [ErrorKind::UnexpectedEof] if the connection was closed
+fn read_line(reader: &mut impl BufRead) -> Result<String, Error> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Connection closed"));
+    }
+
+    Ok(line.trim_end().to_string())
+}
+
+/// Parses the arguments of a request consisting of a number and a position
+///
+/// # Arguments
+/// * `arguments` - The arguments of the request
+/// # Returns
+/// The number and the board, None if the arguments are malformed
+fn parse_request(arguments: &str) -> Option<(u32, UltimateBoard)> {
+    let (number, position) = arguments.split_once(' ')?;
+
+    Some((
+        number.parse().ok()?,
+        UltimateBoard::from_position(position)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::random_agent::RandomAgent;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_remote_agent() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = std::thread::spawn(move || {
+            let mut server = AgentServer::new(RandomAgent::new());
+            server.serve(listener.accept().unwrap().0)
+        });
+
+        let remote = RemoteAgent::connect(address).unwrap();
+        assert_eq!(remote.get_info().get_name(), "RAND");
+
+        let mut game = Game::new(Box::new(remote), Box::new(RandomAgent::new()));
+        assert_ne!(game.play(), GameResult::Continue);
+
+        // The server stops when the remote agent is dropped with the game
+        drop(game);
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_relay() {
+        let relay = Relay::bind("127.0.0.1:0").unwrap();
+        let address = relay.local_addr().unwrap();
+
+        let clients: Vec<_> = (0..2)
+            .map(|_| {
+                let stream = TcpStream::connect(address).unwrap();
+                std::thread::spawn(move || {
+                    let log = Arc::new(Mutex::new(Vec::new()));
+                    let agent = ResultAgent {
+                        agent: RandomAgent::new(),
+                        log: log.clone(),
+                    };
+                    AgentServer::new(agent).serve(stream).unwrap();
+                    let log = log.lock().unwrap();
+                    log.clone()
+                })
+            })
+            .collect();
+
+        let result = relay.play_game().unwrap();
+        assert_ne!(result, GameResult::Continue);
+
+        // Both clients are told their player and the result
+        let logs: Vec<_> = clients.into_iter().map(|c| c.join().unwrap()).collect();
+        for (player, log) in Player::iter().zip(logs) {
+            assert_eq!(
+                log,
+                vec![format!("new {:?}", player), format!("{:?}", result)]
+            );
+        }
+    }
+
+    /// Records the players of the new games and the results
+    struct ResultAgent {
+        agent: RandomAgent,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Agent for ResultAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            self.agent.act(board, player, turn)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+
+        fn new_game(&mut self, player: Player) {
+            self.log.lock().unwrap().push(format!("new {:?}", player));
+        }
+
+        fn game_over(&mut self, _board: UltimateBoard, result: GameResult) {
+            self.log.lock().unwrap().push(format!("{:?}", result));
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! # Contains the [ScriptedAgent] struct
+//!
+//! The ScriptedAgent struct represents an [Agent] that replays a fixed list of moves.
+//! It is used to reproduce games, e.g. from a [GameRecord] of a bug report, and to write deterministic tests.
+//!
+//! For more information see the [ScriptedAgent] struct.
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::game_record::GameRecord;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+
+/// An agent that plays the moves of a script verbatim
+///
+/// The script contains the moves of both players in the order they are made,
+/// so two scripted agents with the same script replay a whole game.
+/// In turn `n` the agent plays the `n`-th move of the script.
+///
+/// If the scripted move is illegal or the script has ended, the agent returns an [AgentError].
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::Agent;
+/// use hausarbeit::agent::scripted_agent::ScriptedAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::game::ultimate_board::UltimateBoard;
+///
+/// let mut agent = ScriptedAgent::new(vec![40, 36]);
+///
+/// assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(40));
+/// ```
+#[derive(Clone)]
+pub struct ScriptedAgent {
+    script: Vec<u8>,
+    player: Player,
+    turn: u32,
+}
+
+impl ScriptedAgent {
+    /// Creates a new [ScriptedAgent]
+    ///
+    /// # Arguments
+    /// * `script` - The moves of both players in the order they are made, as human indices (0-80)
+    pub fn new(script: Vec<u8>) -> ScriptedAgent {
+        ScriptedAgent {
+            script,
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Creates a new [ScriptedAgent] replaying the moves of a recorded game
+    ///
+    /// # Arguments
+    /// * `record` - The record of the game
+    pub fn from_record(record: &GameRecord) -> ScriptedAgent {
+        ScriptedAgent::new(record.get_moves().to_vec())
+    }
+}
+
+impl Agent for ScriptedAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let Some(&index) = self.script.get(turn as usize) else {
+            return Err(AgentError::Other(format!(
+                "The script has no move for turn {}",
+                turn
+            )));
+        };
+
+        if !board.is_legal_move(index) {
+            return Err(AgentError::IllegalMove(index));
+        }
+
+        Ok(index)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new(
+            "SCRIPT".to_string(),
+            self.player,
+            self.turn,
+            format!("{} moves", self.script.len()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::random_agent::RandomAgent;
+    use crate::game::Game;
+
+    #[test]
+    fn test_replay() {
+        let mut game = Game::new(Box::new(RandomAgent::new()), Box::new(RandomAgent::new()));
+        let result = game.play();
+        let record = game.get_record();
+
+        let mut replay = Game::new(
+            Box::new(ScriptedAgent::from_record(record)),
+            Box::new(ScriptedAgent::from_record(record)),
+        );
+
+        assert_eq!(replay.play(), result);
+        assert_eq!(replay.get_record().get_moves(), record.get_moves());
+
+        // The first board can not be played on after the move to the center
+        let mut agent = ScriptedAgent::new(vec![40, 0]);
+        let mut board = UltimateBoard::new();
+        board.make_move(agent.act(&board, Player::One, 0).unwrap());
+        assert_eq!(
+            agent.act(&board, Player::Two, 1),
+            Err(AgentError::IllegalMove(0))
+        );
+        assert!(agent.act(&board, Player::Two, 2).is_err());
+    }
+}
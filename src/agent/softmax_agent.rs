@@ -0,0 +1,192 @@
+//! # Contains the [SoftmaxAgent] struct
+//!
+//! The SoftmaxAgent struct represents an [Agent] that samples its moves from a softmax over the heuristic evaluations of the moves.
+//! It is a weak but not trivial opponent for quality tests and fitness functions, stronger than the [RandomAgent](crate::agent::random_agent::RandomAgent).
+//!
+//! For more information see the [SoftmaxAgent] struct.
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::Heuristic;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// An agent that samples a move with a probability proportional to the softmax of its one-ply evaluation
+///
+/// Every legal move is made and the resulting board is evaluated with the heuristic.
+/// The move with the value `v` is played with a probability proportional to `exp(v / temperature)`.
+/// With a low temperature the agent almost always plays the best evaluated move, with a high temperature it plays almost uniformly random.
+///
+/// The boards are evaluated [for](Heuristic::evaluate_for) the player of the agent, so the agent can play both sides with one heuristic.
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::softmax_agent::SoftmaxAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let agent = SoftmaxAgent::new(CustomHeuristic::new(Player::One), 0.5).with_seed(42);
+/// ```
+#[derive(Clone)]
+pub struct SoftmaxAgent<H> {
+    heuristic: H,
+    temperature: f64,
+    rng: ChaCha20Rng,
+    player: Player,
+    turn: u32,
+}
+
+impl<H: Heuristic> SoftmaxAgent<H> {
+    /// Creates a new [SoftmaxAgent] with a random number generator seeded from the operating system
+    ///
+    /// # Arguments
+    /// * `heuristic` - The heuristic used to evaluate the moves
+    /// * `temperature` - The temperature of the softmax, must be positive
+    pub fn new(heuristic: H, temperature: f64) -> SoftmaxAgent<H> {
+        assert!(temperature > 0., "Temperature must be positive");
+        SoftmaxAgent {
+            heuristic,
+            temperature,
+            rng: ChaCha20Rng::from_entropy(),
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Seeds the random number generator
+    ///
+    /// Agents with the same seed play the same moves in the same positions.
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The agent with the seeded random number generator
+    pub fn with_seed(mut self, seed: u64) -> SoftmaxAgent<H> {
+        self.rng = ChaCha20Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Calculates the probabilities of the legal moves
+    ///
+    /// # Arguments
+    /// * `board` - The board to choose a move on
+    /// * `player` - The player to choose the move for
+    /// # Returns
+    /// The legal moves and their probabilities, empty if the game is over
+    pub fn get_probabilities(&self, board: UltimateBoard, player: Player) -> Vec<(u8, f64)> {
+        let evaluations: Vec<(u8, f64)> = board
+            .get_possible_moves()
+            .map(|index| {
+                let mut new_board = board;
+                new_board.make_move(index);
+                (index, self.heuristic.evaluate_for(new_board, player))
+            })
+            .collect();
+
+        let best_value = evaluations
+            .iter()
+            .map(|&(_, value)| value)
+            .fold(f64::MIN, f64::max);
+
+        // Subtracting the best value keeps the exponents finite, also for won or lost positions
+        let weights: Vec<(u8, f64)> = evaluations
+            .into_iter()
+            .map(|(index, value)| (index, ((value - best_value) / self.temperature).exp()))
+            .collect();
+        let total: f64 = weights.iter().map(|&(_, weight)| weight).sum();
+
+        weights
+            .into_iter()
+            .map(|(index, weight)| (index, weight / total))
+            .collect()
+    }
+}
+
+impl<H: Heuristic> Agent for SoftmaxAgent<H> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let probabilities = self.get_probabilities(*board, player);
+
+        let distribution =
+            WeightedIndex::new(probabilities.iter().map(|&(_, probability)| probability))
+                .map_err(|_| AgentError::NoMove)?;
+
+        Ok(probabilities[distribution.sample(&mut self.rng)].0)
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new(
+            "SOFTMAX".to_string(),
+            self.player,
+            self.turn,
+            format!(
+                "{} (temperature {})",
+                self.heuristic.get_name(),
+                self.temperature
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_probabilities() {
+        let board = UltimateBoard::new();
+        let heuristic = CustomHeuristic::new(Player::One);
+
+        let cold = SoftmaxAgent::new(heuristic.clone(), 1e-6);
+        let probabilities = cold.get_probabilities(board, Player::One);
+        assert_eq!(probabilities.len(), 81);
+        assert!((probabilities.iter().map(|&(_, p)| p).sum::<f64>() - 1.).abs() < 1e-9);
+
+        // With a low temperature only the best evaluated moves are played
+        let best_value = board
+            .get_possible_moves()
+            .map(|index| {
+                let mut new_board = board;
+                new_board.make_move(index);
+                heuristic.evaluate(new_board)
+            })
+            .fold(f64::MIN, f64::max);
+        let mut agent = cold.with_seed(0);
+        let index = agent.act(&board, Player::One, 0).unwrap();
+        let mut new_board = board;
+        new_board.make_move(index);
+        assert_eq!(heuristic.evaluate(new_board), best_value);
+
+        // With a high temperature all moves are almost equally likely
+        let hot = SoftmaxAgent::new(heuristic, 1e9);
+        assert!(hot
+            .get_probabilities(board, Player::One)
+            .iter()
+            .all(|&(_, p)| (p - 1. / 81.).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_other_player() {
+        // Player Two has won the boards 0 and 1 and wins the game with the move 20 on board 2
+        let squares = format!(
+            "OOO......OOO......OO.......XX.......XX.......X........{}",
+            ".".repeat(27)
+        );
+        let board = UltimateBoard::from_position(&format!("{} 2 O", squares)).unwrap();
+
+        // The heuristic was created for Player One, but the agent plays for Player Two
+        let mut agent = SoftmaxAgent::new(CustomHeuristic::new(Player::One), 1e-3).with_seed(0);
+
+        let probabilities = agent.get_probabilities(board, Player::Two);
+        let (_, winning) = probabilities
+            .iter()
+            .find(|&&(index, _)| index == 20)
+            .unwrap();
+        assert!(*winning > 0.99);
+        assert_eq!(agent.act(&board, Player::Two, 0), Ok(20));
+    }
+}
@@ -0,0 +1,150 @@
+//! # Contains the [SymmetryAgent] struct
+//!
+//! The SymmetryAgent struct represents an [Agent] that lets another agent play on a rotated or reflected board.
+//! Playing an agent against its transformed self is a consistency test: both agents see equivalent positions,
+//! so differences in their play point to evaluation bugs that depend on the orientation of the board.
+//!
+//! For more information see the [SymmetryAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::{UltimateBoard, NUM_SYMMETRIES};
+
+/// An agent that transforms the board by a fixed symmetry before passing it to another agent
+///
+/// The move of the other agent is transformed back to the original board.
+/// The opponent moves and the final board are transformed as well, so the other agent only sees the transformed game.
+///
+/// The symmetries are numbered as in [UltimateBoard::transform].
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+/// use hausarbeit::agent::symmetry_agent::SymmetryAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::game::Game;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let mut game = Game::new(
+///     Box::new(MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::One))),
+///     Box::new(SymmetryAgent::new(4, MiniMaxAgent::new(2, 0, CustomHeuristic::new(Player::Two)))),
+/// );
+/// game.play();
+/// ```
+/// The second agent plays on the horizontally reflected board.
+#[derive(Clone)]
+pub struct SymmetryAgent<A> {
+    symmetry: usize,
+    agent: A,
+}
+
+impl<A: Agent> SymmetryAgent<A> {
+    /// Creates a new [SymmetryAgent]
+    ///
+    /// # Arguments
+    /// * `symmetry` - The index of the symmetry, must be less than [NUM_SYMMETRIES]
+    /// * `agent` - The agent playing on the transformed board
+    pub fn new(symmetry: usize, agent: A) -> SymmetryAgent<A> {
+        assert!(
+            symmetry < NUM_SYMMETRIES,
+            "Symmetry must be less than {}",
+            NUM_SYMMETRIES
+        );
+        SymmetryAgent { symmetry, agent }
+    }
+}
+
+impl<A: Agent> Agent for SymmetryAgent<A> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        let index = self
+            .agent
+            .act(&board.transform(self.symmetry), player, turn)?;
+
+        Ok(UltimateBoard::transform_move(
+            index,
+            UltimateBoard::inverse_symmetry(self.symmetry),
+        ))
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        let action = self
+            .agent
+            .choose_action(&board.transform(self.symmetry), player, turn)?;
+
+        Ok(match action {
+            Action::Move(index) => Action::Move(UltimateBoard::transform_move(
+                index,
+                UltimateBoard::inverse_symmetry(self.symmetry),
+            )),
+            action => action,
+        })
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        let info = self.agent.get_info();
+
+        AgentInfo {
+            name: format!("Symmetry({})", info.name),
+            config: format!("{} (symmetry {})", info.config, self.symmetry),
+            ..info
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(
+            board.transform(self.symmetry),
+            UltimateBoard::transform_move(index, self.symmetry),
+        );
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board.transform(self.symmetry), result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+
+    #[test]
+    fn test_symmetry() {
+        // The move 20 on the board rotated by 90 degrees is the move 0 on the original board
+        let mut agent = SymmetryAgent::new(1, ScriptedAgent::new(vec![20]));
+        assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(0));
+
+        for symmetry in 0..NUM_SYMMETRIES {
+            let mut agent = SymmetryAgent::new(symmetry, ScriptedAgent::new(vec![40]));
+            assert_eq!(agent.act(&UltimateBoard::new(), Player::One, 0), Ok(40));
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! # Contains the [TablebaseAgent] struct
+//!
+//! The TablebaseAgent struct represents an [Agent] that plays perfectly once the position is stored in a [Tablebase],
+//! and uses another agent before.
+//!
+//! For more information see the [TablebaseAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::tablebase::Tablebase;
+use crate::game::ultimate_board::UltimateBoard;
+use std::sync::Arc;
+
+/// An agent that plays the moves of a [Tablebase] for the stored positions, and the moves of another agent otherwise.
+///
+/// If the position is lost, the tablebase move is not better than any other move,
+/// so the move is chosen by the other agent, who may still profit from mistakes of the opponent.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::tablebase_agent::TablebaseAgent;
+/// use hausarbeit::game::tablebase::Tablebase;
+///
+/// let tablebase = Arc::new(Tablebase::generate(8, 10, 0));
+///
+/// let agent = TablebaseAgent::new(tablebase, MonteCarloTreeAgent::new(1000));
+/// ```
+#[derive(Clone)]
+pub struct TablebaseAgent<A: Agent> {
+    tablebase: Arc<Tablebase>,
+    agent: A,
+}
+
+impl<A: Agent> TablebaseAgent<A> {
+    /// Creates a new [TablebaseAgent]
+    ///
+    /// # Arguments
+    /// * `tablebase` - The tablebase probed before every move
+    /// * `agent` - The agent playing the positions not stored in the tablebase
+    pub fn new(tablebase: Arc<Tablebase>, agent: A) -> TablebaseAgent<A> {
+        TablebaseAgent { tablebase, agent }
+    }
+
+    /// Returns the move of the tablebase, unless the position is lost for the player
+    ///
+    /// # Arguments
+    /// * `board` - The current board
+    /// * `player` - The player of the agent
+    /// # Returns
+    /// The best move of the tablebase, None if the wrapped agent has to play
+    fn tablebase_move(&self, board: &UltimateBoard, player: Player) -> Option<u8> {
+        match self.tablebase.best_move(*board) {
+            Some((index, result)) if result != player.get_opponent().into() => Some(index),
+            _ => None,
+        }
+    }
+}
+
+impl<A: Agent> Agent for TablebaseAgent<A> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        match self.tablebase_move(board, player) {
+            Some(index) => Ok(index),
+            None => self.agent.act(board, player, turn),
+        }
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        match self.tablebase_move(board, player) {
+            Some(index) => Ok(Action::Move(index)),
+            None => self.agent.choose_action(board, player, turn),
+        }
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        self.agent.get_info()
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        self.agent.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        self.agent.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        self.agent.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.agent.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.agent.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        self.agent.action_failed(action, reason);
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        self.agent.move_reviewed(review);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::random_agent::RandomAgent;
+    use rand::seq::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_tablebase_agent() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut tablebase = Tablebase::new(10);
+        let mut boards = vec![];
+
+        while boards.len() < 10 {
+            let mut board = UltimateBoard::new();
+            while board.get_game_status() == GameResult::Continue
+                && board.count_empty_squares() > 10
+            {
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            if tablebase.solve(board) == Some(GameResult::Win(board.get_current_player())) {
+                boards.push(board);
+            }
+        }
+
+        let tablebase = Arc::new(tablebase);
+
+        // The agent never loses a won position, whatever the opponent plays
+        for mut board in boards {
+            let player = board.get_current_player();
+            let mut agent = TablebaseAgent::new(tablebase.clone(), RandomAgent::new());
+            let mut opponent = RandomAgent::new();
+
+            while board.get_game_status() == GameResult::Continue {
+                let index = if board.get_current_player() == player {
+                    agent.act(&board, player, 0)
+                } else {
+                    opponent.act(&board, player.get_opponent(), 0)
+                };
+                board.make_move(index.unwrap());
+            }
+
+            assert_eq!(board.get_game_status(), GameResult::Win(player));
+        }
+    }
+}
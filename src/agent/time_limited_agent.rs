@@ -0,0 +1,298 @@
+//! # Contains the [TimeLimitedAgent] struct
+//!
+//! The TimeLimitedAgent struct represents an [Agent] that enforces a hard deadline for every move of another agent.
+//! It is used in tournaments, so a single slow configuration cannot stall all games.
+//!
+//! For more information see the [TimeLimitedAgent] struct.
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
+use crate::agent::random_agent::RandomAgent;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::clock::Clock;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// An agent that plays the move of another agent if it is found within the time budget, and the move of a fallback agent otherwise
+///
+/// The other agent acts on a worker thread.
+/// If the budget is exceeded, the [stop token](TimeLimitedAgent::with_stop_token) is set, if any,
+/// and the agent waits for the aborted search during the [grace period](TimeLimitedAgent::with_grace_period).
+/// Without a result in time, the move of the fallback agent is played, by default a [RandomAgent].
+///
+/// A search that cannot be stopped keeps running on the worker thread.
+/// Until it has finished, the other agent is not asked for moves and is not notified about the moves of the opponent,
+/// so the fallback agent plays these moves as well.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use hausarbeit::agent::minimax_agent::MiniMaxAgent;
+/// use hausarbeit::agent::time_limited_agent::TimeLimitedAgent;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+///
+/// let agent = MiniMaxAgent::new(20, 1, CustomHeuristic::new(Player::One));
+/// let stop_token = agent.get_stop_token();
+///
+/// let agent = TimeLimitedAgent::new(Duration::from_millis(500), agent)
+///     .with_stop_token(stop_token)
+///     .with_fallback(MiniMaxAgent::new(1, 0, CustomHeuristic::new(Player::One)));
+/// ```
+pub struct TimeLimitedAgent<A: Agent + 'static> {
+    agent: Arc<Mutex<A>>,
+    budget: Duration,
+    grace_period: Duration,
+    stop_token: Option<Arc<AtomicBool>>,
+    fallback: Box<dyn Agent>,
+    info: AgentInfo,
+    timeouts: u32,
+}
+
+impl<A: Agent + 'static> TimeLimitedAgent<A> {
+    /// Creates a new [TimeLimitedAgent] falling back to a [RandomAgent]
+    ///
+    /// # Arguments
+    /// * `budget` - The time the agent has for every move
+    /// * `agent` - The agent to limit
+    pub fn new(budget: Duration, agent: A) -> TimeLimitedAgent<A> {
+        let info = agent.get_info();
+
+        TimeLimitedAgent {
+            agent: Arc::new(Mutex::new(agent)),
+            budget,
+            grace_period: Duration::from_millis(100),
+            stop_token: None,
+            fallback: Box::new(RandomAgent::new()),
+            info,
+            timeouts: 0,
+        }
+    }
+
+    /// Sets the stop token of the limited agent
+    ///
+    /// The token is set to true once the budget is exceeded,
+    /// see [MiniMaxAgent::get_stop_token](crate::agent::minimax_agent::MiniMaxAgent::get_stop_token).
+    /// # Arguments
+    /// * `stop_token` - The flag that aborts the search of the limited agent
+    /// # Returns
+    /// The agent with the stop token
+    pub fn with_stop_token(mut self, stop_token: Arc<AtomicBool>) -> TimeLimitedAgent<A> {
+        self.stop_token = Some(stop_token);
+        self
+    }
+
+    /// Sets the time to wait for the result of an aborted search, 100 ms by default
+    ///
+    /// # Arguments
+    /// * `grace_period` - The time to wait after the stop token has been set
+    /// # Returns
+    /// The agent with the grace period
+    pub fn with_grace_period(mut self, grace_period: Duration) -> TimeLimitedAgent<A> {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Sets the agent playing the moves the limited agent does not find in time
+    ///
+    /// The fallback agent should be fast, e.g. a [RandomAgent] or a [GreedyHeuristicAgent](crate::agent::greedy_agent::GreedyHeuristicAgent).
+    /// # Arguments
+    /// * `fallback` - The fallback agent
+    /// # Returns
+    /// The agent with the fallback agent
+    pub fn with_fallback(mut self, fallback: impl Agent + 'static) -> TimeLimitedAgent<A> {
+        self.fallback = Box::new(fallback);
+        self
+    }
+
+    /// Returns how often the fallback agent had to play a move
+    pub fn get_timeouts(&self) -> u32 {
+        self.timeouts
+    }
+
+    /// Asks the limited agent on the worker thread and the fallback agent if the budget is exceeded
+    ///
+    /// # Arguments
+    /// * `board` - The current board
+    /// * `player` - The player of the agent
+    /// * `turn` - The current turn
+    /// * `request` - Asks the limited agent for its move or action
+    /// # Returns
+    /// The result of the limited agent, the move of the fallback agent if the budget is exceeded
+    fn limit<T: From<u8> + Send + 'static>(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+        request: fn(&mut A, &UltimateBoard, Player, u32) -> Result<T, AgentError>,
+    ) -> Result<T, AgentError> {
+        let (sender, receiver) = mpsc::channel();
+
+        // The worker only acts if the previous search has finished
+        if self.agent.try_lock().is_ok() {
+            let worker_agent = self.agent.clone();
+            let board = *board;
+
+            std::thread::spawn(move || {
+                let mut agent = worker_agent.lock().unwrap();
+                let result = request(&mut agent, &board, player, turn);
+                let info = agent.get_info();
+                // The agent is released before sending, so it is not busy once the result has arrived
+                drop(agent);
+                // The receiver is gone if the deadline has passed
+                let _ = sender.send((result, info));
+            });
+        }
+
+        let mut result = receiver.recv_timeout(self.budget);
+
+        if result.is_err() {
+            if let Some(stop_token) = &self.stop_token {
+                stop_token.store(true, Ordering::Relaxed);
+                result = receiver.recv_timeout(self.grace_period);
+            }
+        }
+
+        match result {
+            Ok((result, info)) => {
+                self.info = info;
+                result
+            }
+            Err(_) => {
+                self.timeouts += 1;
+                self.fallback.act(board, player, turn).map(T::from)
+            }
+        }
+    }
+}
+
+impl<A: Agent + 'static> Agent for TimeLimitedAgent<A> {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.limit(board, player, turn, |agent, board, player, turn| {
+            agent.act(board, player, turn)
+        })
+    }
+
+    fn choose_action(
+        &mut self,
+        board: &UltimateBoard,
+        player: Player,
+        turn: u32,
+    ) -> Result<Action, AgentError> {
+        self.limit(board, player, turn, |agent, board, player, turn| {
+            agent.choose_action(board, player, turn)
+        })
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo {
+            config: format!(
+                "{} (budget {:?}, fallback {}, timeouts {})",
+                self.info.config,
+                self.budget,
+                self.fallback.get_info().get_name(),
+                self.timeouts
+            ),
+            ..self.info.clone()
+        }
+    }
+
+    fn set_game_metadata(&mut self, metadata: &GameMetadata) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.set_game_metadata(metadata);
+        }
+        self.fallback.set_game_metadata(metadata);
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.opponent_moved(board, index);
+        }
+        self.fallback.opponent_moved(board, index);
+    }
+
+    fn new_game(&mut self, player: Player) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.new_game(player);
+        }
+        self.fallback.new_game(player);
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.game_over(board, result);
+        }
+        self.fallback.game_over(board, result);
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.clock_updated(clock);
+        }
+        self.fallback.clock_updated(clock);
+    }
+
+    fn action_failed(&mut self, action: &Action, reason: &str) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.action_failed(action, reason);
+        }
+    }
+
+    fn move_reviewed(&mut self, review: &str) {
+        if let Ok(mut agent) = self.agent.try_lock() {
+            agent.move_reviewed(review);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scripted_agent::ScriptedAgent;
+    use std::time::Instant;
+
+    /// Agent taking a fixed time for every move
+    struct SlowAgent(Duration);
+
+    impl Agent for SlowAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            _player: Player,
+            _turn: u32,
+        ) -> Result<u8, AgentError> {
+            std::thread::sleep(self.0);
+            board.get_possible_moves().next().ok_or(AgentError::NoMove)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            AgentInfo::new("SLOW".to_string(), Player::One, 0, "".to_string())
+        }
+    }
+
+    #[test]
+    fn test_deadline() {
+        let board = UltimateBoard::new();
+
+        let mut agent = TimeLimitedAgent::new(Duration::from_secs(5), ScriptedAgent::new(vec![40]));
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(40));
+        assert_eq!(agent.get_timeouts(), 0);
+
+        let mut agent =
+            TimeLimitedAgent::new(Duration::from_millis(50), SlowAgent(Duration::from_secs(1)))
+                .with_fallback(ScriptedAgent::new(vec![40, 40]));
+
+        let start = Instant::now();
+        assert_eq!(agent.act(&board, Player::One, 0), Ok(40));
+        assert!(start.elapsed() < Duration::from_millis(900));
+
+        // The previous search is still running
+        assert_eq!(agent.act(&board, Player::One, 1), Ok(40));
+        assert_eq!(agent.get_timeouts(), 2);
+    }
+}
@@ -0,0 +1,536 @@
+//! # Contains the [WebSocketAgent] and [PlayServer] structs
+//!
+//! The [WebSocketAgent] plays the moves of a client connected over a WebSocket, e.g. a board in a browser.
+//! The [PlayServer] lets such a client play games against any [Agent] of this crate.
+//!
+//! This module requires the `net` feature.
+//!
+//! # Messages
+//! All messages are JSON objects with a `type` field, the boards are sent as [BoardExport].
+//!
+//! The server sends [ServerMessages](ServerMessage):
+//! * `{"type": "turn", "board": {..}, "player": "One"}` - The client has to move
+//! * `{"type": "update", "board": {..}}` - The opponent of the client has moved
+//! * `{"type": "game_over", "board": {..}, "result": {"Win": "One"}}` - The game is over
+//! * `{"type": "error", "message": ".."}` - The last message of the client was invalid
+//!
+//! The client sends [ClientMessages](ClientMessage):
+//! * `{"type": "move", "index": 40}` - The move of the client, only accepted after a `turn` message
+//!
+//! # Reconnects
+//! If the connection is lost, the server waits for the client to connect again and resends the last message
+//! other than an error, so a reloaded page continues the game where it stopped.
+//! The server only waits for the client while it has to move, at most until its [timeout](WebSocketAgent::with_timeout)
+//! or its clock runs out.
+use crate::agent::agent_error::AgentError;
+use crate::agent::{Agent, AgentInfo};
+use crate::game::board_export::BoardExport;
+use crate::game::clock::Clock;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+/// The default time the client has for a move, see [WebSocketAgent::with_timeout]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// The interval in which the server checks for a client to connect
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The time a message may take to be sent before the connection is considered lost
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// # Enum representing the messages sent to the client
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The client has to move on the board as the given player
+    Turn { board: BoardExport, player: Player },
+    /// The opponent of the client has moved
+    Update { board: BoardExport },
+    /// The game is over
+    GameOver {
+        board: BoardExport,
+        result: GameResult,
+    },
+    /// The last message of the client was invalid
+    Error { message: String },
+}
+
+/// # Enum representing the messages sent by the client
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// The client plays on the square with the given index
+    Move { index: u8 },
+}
+
+/// # Struct representing the connection to the client, shared by the agent and the server
+///
+/// The listener does not block, so the server only waits for a client as long as it has to move.
+struct Connection {
+    listener: TcpListener,
+    socket: Option<WebSocket<TcpStream>>,
+    /// The last message sent to the client, resent after a reconnect
+    last_message: Option<String>,
+}
+
+impl Connection {
+    /// Creates a new [Connection] accepting clients on the given address
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// # Returns
+    /// The connection, an error if the address could not be bound
+    fn bind(address: impl ToSocketAddrs) -> Result<Connection, Error> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Connection {
+            listener,
+            socket: None,
+            last_message: None,
+        })
+    }
+
+    /// Returns the connected socket, waiting for a client to connect if there is none
+    ///
+    /// A newly connected client is sent the last message.
+    /// # Arguments
+    /// * `deadline` - The time until which the server waits for a client
+    /// # Returns
+    /// The socket, [AgentError::Timeout] if no client connected in time, [AgentError::Io] if the listener failed
+    fn connected(&mut self, deadline: Instant) -> Result<&mut WebSocket<TcpStream>, AgentError> {
+        while self.socket.is_none() {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                    remaining(deadline)?;
+                    thread::sleep(ACCEPT_INTERVAL);
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+
+            // The stream may inherit the non-blocking mode of the listener
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(remaining(deadline)?))?;
+            stream.set_write_timeout(Some(SEND_TIMEOUT))?;
+
+            let Ok(mut socket) = tungstenite::accept(stream) else {
+                continue;
+            };
+
+            if let Some(message) = &self.last_message {
+                if socket.send(Message::text(message.clone())).is_err() {
+                    continue;
+                }
+            }
+
+            self.socket = Some(socket);
+        }
+
+        Ok(self.socket.as_mut().unwrap())
+    }
+
+    /// Sends a message to the client
+    ///
+    /// If no client is connected or the connection is lost, the message is sent when a client connects again.
+    /// # Arguments
+    /// * `message` - The message
+    fn send(&mut self, message: &ServerMessage) {
+        let text = serde_json::to_string(message).unwrap();
+
+        self.last_message = Some(text.clone());
+        self.send_text(text);
+    }
+
+    /// Receives the next valid message of the client, reconnecting if the connection is lost
+    ///
+    /// Invalid messages are answered with an error.
+    /// # Arguments
+    /// * `deadline` - The time until which the server waits for the message
+    /// # Returns
+    /// The message, [AgentError::Timeout] if the client did not send a valid message in time,
+    /// [AgentError::Io] if the connection could not be used
+    fn receive(&mut self, deadline: Instant) -> Result<ClientMessage, AgentError> {
+        loop {
+            let socket = self.connected(deadline)?;
+            socket
+                .get_mut()
+                .set_read_timeout(Some(remaining(deadline)?))?;
+
+            let error = match socket.read() {
+                Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                    Ok(message) => return Ok(message),
+                    Err(error) => error.to_string(),
+                },
+                Err(tungstenite::Error::Io(error))
+                    if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    return Err(AgentError::Timeout);
+                }
+                Ok(Message::Close(_)) | Err(_) => {
+                    self.socket = None;
+                    continue;
+                }
+                Ok(_) => continue,
+            };
+
+            self.send_error(error);
+        }
+    }
+
+    /// Sends an error to the client, the error is not resent after a reconnect
+    ///
+    /// # Arguments
+    /// * `message` - The description of the error
+    fn send_error(&mut self, message: String) {
+        self.send_text(serde_json::to_string(&ServerMessage::Error { message }).unwrap());
+    }
+
+    /// Sends a text to the connected client, if any, dropping the connection if it is lost
+    fn send_text(&mut self, text: String) {
+        if let Some(socket) = &mut self.socket {
+            if socket.send(Message::text(text)).is_err() {
+                self.socket = None;
+            }
+        }
+    }
+}
+
+/// Returns the time left until a deadline
+///
+/// # Returns
+/// The remaining time, [AgentError::Timeout] if the deadline has passed
+fn remaining(deadline: Instant) -> Result<Duration, AgentError> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or(AgentError::Timeout)
+}
+
+/// An agent playing the moves of a client connected over a WebSocket
+///
+/// The agent waits for the client to connect when it has to move,
+/// and for a reconnect whenever the connection is lost, so [act](Agent::act) blocks until the client moves.
+/// If the client does not move in time, [act](Agent::act) returns [AgentError::Timeout].
+/// The client has [DEFAULT_TIMEOUT] for a move, or less if its [clock](Agent::clock_updated) runs out earlier.
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::websocket_agent::WebSocketAgent;
+/// use hausarbeit::game::Game;
+///
+/// let client = WebSocketAgent::bind("127.0.0.1:8080").unwrap();
+///
+/// let mut game = Game::new(Box::new(client), Box::new(MonteCarloTreeAgent::new(1000)));
+/// game.play();
+/// ```
+pub struct WebSocketAgent {
+    connection: Arc<Mutex<Connection>>,
+    address: SocketAddr,
+    timeout: Duration,
+    clock: Option<Clock>,
+    player: Player,
+    turn: u32,
+}
+
+impl WebSocketAgent {
+    /// Creates a new [WebSocketAgent] accepting clients on the given address
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// # Returns
+    /// The agent, an error if the address could not be bound
+    pub fn bind(address: impl ToSocketAddrs) -> Result<WebSocketAgent, Error> {
+        let connection = Connection::bind(address)?;
+        let address = connection.listener.local_addr()?;
+
+        Ok(WebSocketAgent::with_connection(
+            Arc::new(Mutex::new(connection)),
+            address,
+        ))
+    }
+
+    /// Creates a new [WebSocketAgent] using an existing connection
+    ///
+    /// # Arguments
+    /// * `connection` - The connection to the client
+    /// * `address` - The address the connection accepts clients on
+    fn with_connection(connection: Arc<Mutex<Connection>>, address: SocketAddr) -> WebSocketAgent {
+        WebSocketAgent {
+            connection,
+            address,
+            timeout: DEFAULT_TIMEOUT,
+            clock: None,
+            player: Player::default(),
+            turn: 0,
+        }
+    }
+
+    /// Sets the time the client has for a move
+    ///
+    /// If the game is played with a time control, the client has at most its remaining time.
+    /// # Arguments
+    /// * `timeout` - The maximum time to wait for a move, including the time to connect
+    /// # Returns
+    /// The agent with the timeout set
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the address the agent accepts clients on
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.address)
+    }
+}
+
+impl Agent for WebSocketAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        let timeout = match &self.clock {
+            Some(clock) => self.timeout.min(clock.get_remaining(player)),
+            None => self.timeout,
+        };
+        let deadline = Instant::now() + timeout;
+
+        let mut connection = self.connection.lock().unwrap();
+
+        connection.send(&ServerMessage::Turn {
+            board: (*board).into(),
+            player,
+        });
+
+        loop {
+            match connection.receive(deadline)? {
+                ClientMessage::Move { index } if board.is_legal_move(index) => return Ok(index),
+                ClientMessage::Move { index } => {
+                    connection.send_error(format!("Illegal move {}", index))
+                }
+            }
+        }
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new(
+            "WS".to_string(),
+            self.player,
+            self.turn,
+            self.local_addr()
+                .map_or("".to_string(), |address| address.to_string()),
+        )
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, _index: u8) {
+        self.connection
+            .lock()
+            .unwrap()
+            .send(&ServerMessage::Update {
+                board: board.into(),
+            });
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+        self.connection
+            .lock()
+            .unwrap()
+            .send(&ServerMessage::GameOver {
+                board: board.into(),
+                result,
+            });
+    }
+
+    fn clock_updated(&mut self, clock: &Clock) {
+        self.clock = Some(*clock);
+    }
+}
+
+/// Server letting a WebSocket client play games against the agents of this crate
+///
+/// The games are played one after another on the same address,
+/// the client is sent the result at the end of every game.
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+/// use hausarbeit::agent::websocket_agent::PlayServer;
+/// use hausarbeit::game::player::Player;
+///
+/// let server = PlayServer::bind("127.0.0.1:8080").unwrap();
+///
+/// loop {
+///     server.play(MonteCarloTreeAgent::new(1000), Player::One);
+/// }
+/// ```
+pub struct PlayServer {
+    connection: Arc<Mutex<Connection>>,
+    address: SocketAddr,
+    timeout: Duration,
+}
+
+impl PlayServer {
+    /// Creates a new [PlayServer] accepting clients on the given address
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// # Returns
+    /// The server, an error if the address could not be bound
+    pub fn bind(address: impl ToSocketAddrs) -> Result<PlayServer, Error> {
+        let agent = WebSocketAgent::bind(address)?;
+
+        Ok(PlayServer {
+            connection: agent.connection,
+            address: agent.address,
+            timeout: agent.timeout,
+        })
+    }
+
+    /// Sets the time the client has for a move, see [WebSocketAgent::with_timeout]
+    ///
+    /// # Arguments
+    /// * `timeout` - The maximum time to wait for a move
+    /// # Returns
+    /// The server with the timeout set
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the address the server accepts clients on
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.address)
+    }
+
+    /// Plays a game between the client and an agent
+    ///
+    /// If the client does not move in time, it forfeits the game.
+    /// # Arguments
+    /// * `agent` - The opponent of the client
+    /// * `client_player` - The player of the client
+    /// # Returns
+    /// The result of the game
+    pub fn play(&self, agent: impl Agent + 'static, client_player: Player) -> GameResult {
+        let client = WebSocketAgent::with_connection(self.connection.clone(), self.address)
+            .with_timeout(self.timeout);
+
+        let mut game = match client_player {
+            Player::One => Game::new(Box::new(client), Box::new(agent)),
+            Player::Two => Game::new(Box::new(agent), Box::new(client)),
+        };
+
+        // The client is sent the result by the game over notification
+        game.play()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::random_agent::RandomAgent;
+    use crate::game::clock::TimeControl;
+
+    #[test]
+    fn test_play_server() {
+        let server = PlayServer::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+
+        let game = std::thread::spawn(move || server.play(RandomAgent::new(), Player::Two));
+
+        let connect = || tungstenite::connect(&url).unwrap().0;
+        let mut socket = connect();
+        let mut reconnected = false;
+
+        let result = loop {
+            let Message::Text(text) = socket.read().unwrap() else {
+                continue;
+            };
+
+            match serde_json::from_str(&text).unwrap() {
+                ServerMessage::Turn { board, player } => {
+                    assert_eq!(player, Player::Two);
+
+                    // The client loses the connection once and receives the turn again
+                    if !reconnected {
+                        reconnected = true;
+                        drop(socket);
+                        socket = connect();
+                        continue;
+                    }
+
+                    let illegal = serde_json::to_string(&ClientMessage::Move { index: 81 });
+                    socket.send(Message::text(illegal.unwrap())).unwrap();
+                    let Message::Text(text) = socket.read().unwrap() else {
+                        panic!();
+                    };
+                    assert!(matches!(
+                        serde_json::from_str(&text).unwrap(),
+                        ServerMessage::Error { .. }
+                    ));
+
+                    let index = board.possible_moves[0];
+                    let message = serde_json::to_string(&ClientMessage::Move { index });
+                    socket.send(Message::text(message.unwrap())).unwrap();
+                }
+                ServerMessage::Update { board } => assert_eq!(board.current_player, Player::Two),
+                ServerMessage::GameOver { result, .. } => break result,
+                ServerMessage::Error { message } => panic!("{}", message),
+            }
+        };
+
+        assert!(reconnected);
+        assert_eq!(game.join().unwrap(), result);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let board = UltimateBoard::new();
+        let mut agent = WebSocketAgent::bind("127.0.0.1:0")
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+
+        // The notifications do not wait for a client
+        agent.opponent_moved(board, 40);
+        agent.game_over(board, GameResult::Draw);
+
+        // No client connects
+        let start = Instant::now();
+        assert_eq!(agent.act(&board, Player::One, 0), Err(AgentError::Timeout));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // The client connects, but does not move
+        let url = format!("ws://{}", agent.local_addr().unwrap());
+        let client = std::thread::spawn(move || {
+            let mut socket = tungstenite::connect(&url).unwrap().0;
+            socket.read().unwrap()
+        });
+        agent.clock_updated(&Clock::from_time_control(TimeControl::new(
+            Duration::from_millis(500),
+            Duration::ZERO,
+        )));
+        agent = agent.with_timeout(DEFAULT_TIMEOUT);
+
+        let start = Instant::now();
+        assert_eq!(agent.act(&board, Player::One, 0), Err(AgentError::Timeout));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let Message::Text(text) = client.join().unwrap() else {
+            panic!();
+        };
+        assert!(matches!(
+            serde_json::from_str(&text).unwrap(),
+            ServerMessage::Turn { .. }
+        ));
+    }
+}
@@ -82,6 +82,29 @@ const PARTIAL_WIN_POSITIONS: [u16; 24] = [
     0b100000001,
 ];
 
+/// Rotates and reflects the squares of a board in the internal representation
+///
+/// The outer squares of the internal representation form a ring in clockwise order,
+/// so a rotation by 90 degrees shifts the ring by two squares and the reflection at the diagonal through square 0 reverses it.
+/// # Arguments
+/// * `squares` - The squares in the internal representation
+/// * `symmetry` - The number of clockwise rotations, plus 4 if the squares are reflected first
+/// # Returns
+/// The transformed squares
+fn transform_squares(squares: u16, symmetry: u8) -> u16 {
+    let center = squares & 0b100000000;
+    let mut ring = squares & 0b11111111;
+
+    if symmetry >= 4 {
+        ring = (0..8)
+            .filter(|i| ring & 1 << i != 0)
+            .fold(0, |reflected, i| reflected | 1 << ((8 - i) % 8));
+    }
+
+    let shift = 2 * (symmetry % 4);
+    center | ((ring << shift | ring >> (8 - shift)) & 0b11111111)
+}
+
 /// Rows of the board in the internal representation
 const ROWS: [[u8; 3]; 3] = [[0, 1, 2], [7, 8, 3], [6, 5, 4]];
 
@@ -171,6 +194,38 @@ impl Board {
         self.board[player as usize] |= BitBoard::new(1 << translated_index);
     }
 
+    /// Check if the square at the given index is not set by any player
+    ///
+    /// The index is the human index (0-8)
+    /// # Arguments
+    /// * `index` - The index of the square
+    /// # Returns
+    /// True if the square is empty, false otherwise
+    pub fn is_empty_square(&self, index: u8) -> bool {
+        let bit = BitBoard::new(1 << Self::from_human_to_bit(index));
+
+        (self.board[0] | self.board[1]) & bit == BitBoard::EMPTY
+    }
+
+    /// Get the player occupying the square at the given index
+    ///
+    /// The index is the human index (0-8)
+    /// # Arguments
+    /// * `index` - The index of the square
+    /// # Returns
+    /// The player occupying the square, None if the square is empty
+    pub fn get_square(&self, index: u8) -> Option<Player> {
+        let bit = BitBoard::new(1 << Self::from_human_to_bit(index));
+
+        if self.board[Player::One as usize] & bit != BitBoard::EMPTY {
+            Some(Player::One)
+        } else if self.board[Player::Two as usize] & bit != BitBoard::EMPTY {
+            Some(Player::Two)
+        } else {
+            None
+        }
+    }
+
     /// Set the unique id of the board.
     /// # Arguments
     /// * `unique_id` - The unique id to set
@@ -187,6 +242,51 @@ impl Board {
         first | (second << 9)
     }
 
+    /// Get the smallest key of the board and its seven rotations and reflections
+    ///
+    /// Symmetric boards have the same canonical key, so they can share an evaluation.
+    /// # Returns
+    /// The canonical key of the board
+    pub fn to_canonical_key(&self) -> u32 {
+        Board::canonical_key(self.to_key())
+    }
+
+    /// Get the board with the [canonical key](Board::to_canonical_key) of the board
+    ///
+    /// The unique id is kept.
+    /// # Returns
+    /// The canonical board
+    pub fn to_canonical(&self) -> Board {
+        let key = self.to_canonical_key();
+
+        Board::from_bitboards(
+            [
+                BitBoard::new((key & 0b111111111) as u16),
+                BitBoard::new((key >> 9) as u16),
+            ],
+            self.unique_id,
+        )
+    }
+
+    /// Get the smallest key of all rotations and reflections of the board with the given key
+    ///
+    /// # Arguments
+    /// * `key` - The [key](Board::to_key) of a board
+    /// # Returns
+    /// The canonical key
+    pub fn canonical_key(key: u32) -> u32 {
+        let first = (key & 0b111111111) as u16;
+        let second = (key >> 9) as u16;
+
+        (0..8)
+            .map(|symmetry| {
+                transform_squares(first, symmetry) as u32
+                    | (transform_squares(second, symmetry) as u32) << 9
+            })
+            .min()
+            .unwrap()
+    }
+
     /// Check if the game has been won
     /// # Returns
     /// The result of the game
@@ -252,7 +352,54 @@ impl Board {
         diff
     }
 
-    /// Check if the center square is occupied by a player
+    /// Count the threats of a player
+    ///
+    /// A threat is a win position in which the player occupies two squares and the third square is empty.
+    /// # Arguments
+    /// * `player` - The player to count the threats for
+    /// # Returns
+    /// The number of threats of the player
+    pub fn count_threats(&self, player: Player) -> u8 {
+        let mut threats = 0;
+
+        for i in WIN_POSITIONS.iter() {
+            let bit = BitBoard::new(*i);
+
+            let player_bit = self.board[player as usize] & bit;
+            let opponent_bit = self.board[player.get_opponent() as usize] & bit;
+
+            if player_bit.into_iter().count() == 2 && opponent_bit == BitBoard::EMPTY {
+                threats += 1;
+            }
+        }
+
+        threats
+    }
+
+    /// Get the empty squares completing a win position for a player
+    ///
+    /// # Arguments
+    /// * `player` - The player to get the winning squares for
+    /// # Returns
+    /// The winning squares in the internal representation
+    pub fn get_winning_squares(&self, player: Player) -> BitBoard {
+        let empty_squares = !(self.board[0] | self.board[1]);
+        let mut winning_squares = BitBoard::EMPTY;
+
+        for i in WIN_POSITIONS.iter() {
+            let bit = BitBoard::new(*i);
+
+            let player_bit = self.board[player as usize] & bit;
+            let empty_bit = empty_squares & bit;
+
+            if player_bit.into_iter().count() == 2 && empty_bit != BitBoard::EMPTY {
+                winning_squares |= empty_bit;
+            }
+        }
+
+        winning_squares
+    }
+
     /// # Arguments
     /// * `player` - The player to check for
     /// # Returns
@@ -448,6 +595,40 @@ mod test {
         assert_eq!(board.board[0], BitBoard::new(1));
         board.set(1, Player::Two);
         assert_eq!(board.board[1], BitBoard::new(2));
+
+        board.set(5, Player::Two);
+        assert_eq!(board.get_square(0), Some(Player::One));
+        assert_eq!(board.get_square(5), Some(Player::Two));
+        assert_eq!(board.get_square(4), None);
+    }
+
+    #[test]
+    fn test_count_threats() {
+        let mut board = Board::new(0);
+        board.set(0, Player::One);
+        board.set(1, Player::One);
+        assert_eq!(board.count_threats(Player::One), 1);
+        board.set(4, Player::One);
+        assert_eq!(board.count_threats(Player::One), 3);
+        board.set(2, Player::Two);
+        assert_eq!(board.count_threats(Player::One), 2);
+        assert_eq!(board.count_threats(Player::Two), 0);
+    }
+
+    #[test]
+    fn test_winning_squares() {
+        let mut board = Board::new(0);
+        board.set(0, Player::One);
+        board.set(4, Player::One);
+        board.set(8, Player::Two);
+        assert_eq!(board.get_winning_squares(Player::One), BitBoard::EMPTY);
+
+        board.set(1, Player::One);
+        assert_eq!(
+            board.get_winning_squares(Player::One),
+            BitBoard::new(1 << Board::from_human_to_bit(2) | 1 << Board::from_human_to_bit(7))
+        );
+        assert_eq!(board.get_winning_squares(Player::Two), BitBoard::EMPTY);
     }
 
     #[test]
@@ -456,4 +637,44 @@ mod test {
         let count = iter.count();
         assert_eq!(count, usize::pow(3, 9));
     }
+
+    #[test]
+    fn test_canonical_key() {
+        // A corner and an edge next to it, all eight variants are symmetric
+        let mut variants = vec![];
+        for (corner, edge) in [
+            (0, 1),
+            (0, 3),
+            (2, 1),
+            (2, 5),
+            (6, 3),
+            (6, 7),
+            (8, 5),
+            (8, 7),
+        ] {
+            let mut board = Board::new(0);
+            board.set(corner, Player::One);
+            board.set(edge, Player::Two);
+            variants.push(board.to_canonical_key());
+        }
+        assert!(variants.iter().all(|&key| key == variants[0]));
+
+        // The canonical board is a variant of the board
+        let mut board = Board::new(3);
+        board.set(8, Player::One);
+        board.set(4, Player::Two);
+        let canonical = board.to_canonical();
+        assert_eq!(canonical.get_square(0), Some(Player::One));
+        assert_eq!(canonical.get_square(4), Some(Player::Two));
+        assert_eq!(canonical.to_key(), board.to_canonical_key());
+
+        // The two diagonals are symmetric
+        let mut other = Board::new(0);
+        other.set(2, Player::One);
+        other.set(4, Player::Two);
+        assert_eq!(other.to_canonical_key(), board.to_canonical_key());
+        other.set(6, Player::One);
+        board.set(0, Player::One);
+        assert_eq!(other.to_canonical_key(), board.to_canonical_key());
+    }
 }
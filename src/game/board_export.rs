@@ -0,0 +1,85 @@
+//! # Contains the [BoardExport] struct
+//!
+//! The [BoardExport] struct is a serializable snapshot of an [UltimateBoard],
+//! it is used to exchange boards with other programs, e.g. as JSON with a browser.
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use serde::{Deserialize, Serialize};
+
+/// # Struct containing everything a user interface needs to display a board
+///
+/// All indices are human indices, the squares are indexed from 0 to 80 and the small boards from 0 to 8.
+///
+/// # Example
+/// ```
+/// use hausarbeit::game::board_export::BoardExport;
+/// use hausarbeit::game::ultimate_board::UltimateBoard;
+///
+/// let json = serde_json::to_string(&BoardExport::from(UltimateBoard::new())).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoardExport {
+    /// The position of the board, see [UltimateBoard::get_position]
+    pub position: String,
+    /// The player occupying each square, None for an empty square
+    pub squares: Vec<Option<Player>>,
+    /// The status of each small board
+    pub board_status: [GameResult; 9],
+    /// The small board the player to move has to play on, None if the player can play on any board
+    pub next_board: Option<u8>,
+    /// The player to move
+    pub current_player: Player,
+    /// The legal moves of the player to move, in ascending order
+    pub possible_moves: Vec<u8>,
+    /// The status of the game
+    pub game_status: GameResult,
+}
+
+impl From<UltimateBoard> for BoardExport {
+    fn from(board: UltimateBoard) -> Self {
+        let boards = board.get_boards();
+
+        let mut possible_moves: Vec<u8> = if board.get_game_status() == GameResult::Continue {
+            board.get_possible_moves().collect()
+        } else {
+            vec![]
+        };
+        possible_moves.sort();
+
+        BoardExport {
+            position: board.get_position(),
+            squares: (0..81)
+                .map(|index| boards[index / 9].get_square((index % 9) as u8))
+                .collect(),
+            board_status: board.get_board_status(),
+            next_board: board.get_next_board_index(),
+            current_player: board.get_current_player(),
+            possible_moves,
+            game_status: board.get_game_status(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+
+        let export = BoardExport::from(board);
+        assert_eq!(export.squares[40], Some(Player::One));
+        assert_eq!(export.next_board, Some(4));
+        assert_eq!(export.current_player, Player::Two);
+        assert_eq!(
+            export.possible_moves,
+            (36..45).filter(|&i| i != 40).collect::<Vec<_>>()
+        );
+
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(serde_json::from_str::<BoardExport>(&json).unwrap(), export);
+    }
+}
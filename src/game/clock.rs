@@ -0,0 +1,141 @@
+//! # Contains the [TimeControl] and [Clock] structs
+//! The TimeControl struct describes the thinking time of the players of a [Game](crate::game::Game),
+//! the Clock struct keeps track of the remaining time of both players while the game is played.
+//!
+//! If the thinking time of a player runs out, the flag falls and the player forfeits the game,
+//! see [Game::with_time_control](crate::game::Game::with_time_control).
+
+use crate::game::player::Player;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// # Struct representing the time control of a game
+///
+/// # Fields
+/// * `initial` - The thinking time of each player at the start of the game
+/// * `increment` - The time added to the clock of a player after each of their moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub initial: Duration,
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    /// Creates a new time control
+    ///
+    /// # Arguments
+    /// * `initial` - The thinking time of each player at the start of the game
+    /// * `increment` - The time added to the clock of a player after each of their moves
+    pub fn new(initial: Duration, increment: Duration) -> Self {
+        TimeControl { initial, increment }
+    }
+}
+
+/// # Struct representing the clocks of both players
+///
+/// The clock only stores the remaining time, the [Game](crate::game::Game) measures the time of each move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clock {
+    time_control: TimeControl,
+    remaining: [Duration; 2],
+}
+
+impl Clock {
+    /// Creates the clocks at the start of a game
+    ///
+    /// # Arguments
+    /// * `time_control` - The time control of the game
+    pub fn from_time_control(time_control: TimeControl) -> Self {
+        Clock {
+            time_control,
+            remaining: [time_control.initial; 2],
+        }
+    }
+
+    /// Get the time control of the game
+    pub fn get_time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    /// Get the remaining time of the player
+    pub fn get_remaining(&self, player: Player) -> Duration {
+        self.remaining[player as usize]
+    }
+
+    /// Sets the remaining time of the player, e.g. when a saved game is resumed
+    ///
+    /// # Arguments
+    /// * `player` - The player whose clock is set
+    /// * `remaining` - The remaining time of the player
+    pub fn set_remaining(&mut self, player: Player, remaining: Duration) {
+        self.remaining[player as usize] = remaining;
+    }
+
+    /// Subtracts the time a move took from the clock of the player and adds the increment
+    ///
+    /// # Arguments
+    /// * `player` - The player who made the move
+    /// * `elapsed` - The time the move took
+    /// # Returns
+    /// False if the flag of the player fell, i.e. the move took longer than the remaining time
+    pub fn record_move(&mut self, player: Player, elapsed: Duration) -> bool {
+        let remaining = &mut self.remaining[player as usize];
+
+        match remaining.checked_sub(elapsed) {
+            Some(left) => {
+                *remaining = left + self.time_control.increment;
+                true
+            }
+            None => {
+                *remaining = Duration::ZERO;
+                false
+            }
+        }
+    }
+}
+
+impl Display for Clock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "X {} | O {}",
+            format_time(self.remaining[0]),
+            format_time(self.remaining[1])
+        )
+    }
+}
+
+/// Formats a duration as minutes and seconds, e.g. `4:05`
+///
+/// The seconds are rounded up, so the clock only shows `0:00` once the time has run out.
+pub fn format_time(duration: Duration) -> String {
+    let seconds = duration.as_millis().div_ceil(1000);
+
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock() {
+        let mut clock = Clock::from_time_control(TimeControl::new(
+            Duration::from_secs(60),
+            Duration::from_secs(2),
+        ));
+
+        assert!(clock.record_move(Player::One, Duration::from_millis(5500)));
+        assert_eq!(
+            clock.get_remaining(Player::One),
+            Duration::from_millis(56500)
+        );
+        assert_eq!(clock.get_remaining(Player::Two), Duration::from_secs(60));
+        assert_eq!(clock.to_string(), "X 0:57 | O 1:00");
+
+        assert!(!clock.record_move(Player::Two, Duration::from_secs(61)));
+        assert_eq!(clock.get_remaining(Player::Two), Duration::ZERO);
+        assert_eq!(format_time(Duration::from_secs(3725)), "62:05");
+    }
+}
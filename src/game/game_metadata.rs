@@ -0,0 +1,74 @@
+//! # Contains the [GameMetadata] struct
+//! The GameMetadata struct contains information identifying a [Game](crate::game::Game).
+//!
+//! The metadata is stored in the [GameRecord](crate::game::game_record::GameRecord) and passed to the agents,
+//! so benchmark results can be matched to a specific game.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+/// Counter used to generate unique game ids within a process
+static NEXT_GAME_ID: AtomicU64 = AtomicU64::new(0);
+
+/// # Struct representing the metadata of a game
+///
+/// # Fields
+/// * `game_id` - The id of the game, unique within a process if generated
+/// * `tags` - Arbitrary tags describing the game
+/// * `agent_names` - The names of the agents of [Player::One](crate::game::player::Player::One) and [Player::Two](crate::game::player::Player::Two)
+/// * `start_time` - The time the game was started, None if the game has not been started yet
+/// * `agent_specs` - The [specifications](crate::agent::registry::AgentSpec) the agents were created from, empty if unknown
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameMetadata {
+    pub game_id: u64,
+    pub tags: Vec<String>,
+    pub agent_names: [String; 2],
+    pub start_time: Option<SystemTime>,
+    /// Missing in records written before the specifications were recorded
+    #[serde(default)]
+    pub agent_specs: [String; 2],
+}
+
+impl GameMetadata {
+    /// Creates new metadata with a generated game id
+    ///
+    /// # Returns
+    /// The new metadata
+    pub fn new() -> Self {
+        Self::with_id(NEXT_GAME_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Creates new metadata with the given game id
+    ///
+    /// # Arguments
+    /// * `game_id` - The id of the game
+    /// # Returns
+    /// The new metadata
+    pub fn with_id(game_id: u64) -> Self {
+        GameMetadata {
+            game_id,
+            tags: vec![],
+            agent_names: [String::new(), String::new()],
+            start_time: None,
+            agent_specs: [String::new(), String::new()],
+        }
+    }
+
+    /// Adds a tag to the metadata
+    ///
+    /// # Arguments
+    /// * `tag` - The tag to add
+    /// # Returns
+    /// The metadata with the tag added
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+}
+
+impl Default for GameMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
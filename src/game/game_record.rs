@@ -0,0 +1,186 @@
+//! # Contains the [GameRecord] struct
+//! The GameRecord struct records a [Game](crate::game::Game) as it is played.
+//!
+//! The record contains the [GameMetadata], the moves made and the result of the game.
+//!
+//! Records can be saved as JSON and loaded again, e.g. to resume an interrupted game with [Game::from_record](crate::game::Game::from_record).
+
+use crate::agent::agent_error::AgentError;
+use crate::game::game_metadata::GameMetadata;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::time::Duration;
+
+/// # Struct representing the forfeit of a game by an agent
+///
+/// # Fields
+/// * `player` - The player whose agent forfeited the game
+/// * `reason` - The reason of the forfeit, e.g. the error of the agent
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Forfeit {
+    pub player: Player,
+    pub reason: String,
+}
+
+/// # Struct representing the record of a game
+///
+/// # Fields
+/// * `metadata` - The metadata of the game
+/// * `moves` - The moves made in the game in order, as human indices (0-80)
+/// * `result` - The result of the game, [GameResult::Continue] if the game has not finished
+/// * `forfeit` - The forfeit that ended the game, None if the game was played to the end
+/// * `remaining_time` - The remaining time of both players after the last move, None if the game is played without a time control
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    metadata: GameMetadata,
+    moves: Vec<u8>,
+    result: GameResult,
+    /// Missing in records written before forfeits were recorded
+    #[serde(default)]
+    forfeit: Option<Forfeit>,
+    /// Missing in records written before the clocks were recorded
+    #[serde(default)]
+    remaining_time: Option<[Duration; 2]>,
+}
+
+impl GameRecord {
+    /// Creates a new, empty record
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the game
+    pub fn new(metadata: GameMetadata) -> Self {
+        GameRecord {
+            metadata,
+            moves: vec![],
+            result: GameResult::Continue,
+            forfeit: None,
+            remaining_time: None,
+        }
+    }
+
+    /// Get the metadata of the game
+    pub fn get_metadata(&self) -> &GameMetadata {
+        &self.metadata
+    }
+
+    /// Get the mutable metadata of the game
+    pub fn get_metadata_mut(&mut self) -> &mut GameMetadata {
+        &mut self.metadata
+    }
+
+    /// Get the moves made in the game
+    pub fn get_moves(&self) -> &[u8] {
+        &self.moves
+    }
+
+    /// Get the result of the game
+    pub fn get_result(&self) -> GameResult {
+        self.result
+    }
+
+    /// Replays the recorded moves on a new board
+    ///
+    /// # Returns
+    /// The board before the first move and the board after each move,
+    /// or [AgentError::IllegalMove] if the record contains an illegal move
+    pub fn replay(&self) -> Result<Vec<UltimateBoard>, AgentError> {
+        let mut board = UltimateBoard::new();
+        let mut boards = vec![board];
+
+        for &index in &self.moves {
+            if !board.is_legal_move(index) {
+                return Err(AgentError::IllegalMove(index));
+            }
+            board.make_move(index);
+            boards.push(board);
+        }
+
+        Ok(boards)
+    }
+
+    /// Records a move
+    ///
+    /// # Arguments
+    /// * `index` - The move that was made
+    pub fn push_move(&mut self, index: u8) {
+        self.moves.push(index);
+    }
+
+    /// Removes the last recorded move, e.g. when it is taken back
+    ///
+    /// # Returns
+    /// The removed move, None if no move was recorded
+    pub fn pop_move(&mut self) -> Option<u8> {
+        self.moves.pop()
+    }
+
+    /// Sets the result of the game
+    ///
+    /// # Arguments
+    /// * `result` - The result of the game
+    pub fn set_result(&mut self, result: GameResult) {
+        self.result = result;
+    }
+
+    /// Get the forfeit that ended the game
+    ///
+    /// # Returns
+    /// The forfeit, None if the game was not forfeited
+    pub fn get_forfeit(&self) -> Option<&Forfeit> {
+        self.forfeit.as_ref()
+    }
+
+    /// Get the remaining time of both players after the last move
+    ///
+    /// # Returns
+    /// The remaining time of [Player::One] and [Player::Two], None if the game is played without a time control
+    pub fn get_remaining_time(&self) -> Option<[Duration; 2]> {
+        self.remaining_time
+    }
+
+    /// Records the remaining time of both players
+    ///
+    /// # Arguments
+    /// * `remaining_time` - The remaining time of [Player::One] and [Player::Two]
+    pub fn set_remaining_time(&mut self, remaining_time: [Duration; 2]) {
+        self.remaining_time = Some(remaining_time);
+    }
+
+    /// Saves the record as JSON
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file to write
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+
+        Ok(())
+    }
+
+    /// Loads a record saved with [GameRecord::save]
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file to read
+    /// # Returns
+    /// The record, or an error if the file can not be read or is not a record
+    pub fn load(path: &str) -> std::io::Result<GameRecord> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Records that an agent forfeited the game
+    ///
+    /// The result of the game is set to the win of the opponent.
+    /// # Arguments
+    /// * `player` - The player whose agent forfeited the game
+    /// * `reason` - The reason of the forfeit
+    pub fn set_forfeit(&mut self, player: Player, reason: String) {
+        self.result = GameResult::Win(player.get_opponent());
+        self.forfeit = Some(Forfeit { player, reason });
+    }
+}
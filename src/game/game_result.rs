@@ -3,9 +3,10 @@
 //! The enum can be used to determine the winner of a game, if it's a draw or if the game is still ongoing.
 
 use crate::game::player::Player;
+use serde::{Deserialize, Serialize};
 
 /// Enum representing the possible game results
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GameResult {
     Win(Player),
     Draw,
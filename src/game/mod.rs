@@ -2,23 +2,41 @@
 //! The Game struct represents a game of Ultimate Tic Tac Toe.
 //! The game is played by two [agents](Agent).
 
+use crate::agent::action::Action;
+use crate::agent::agent_error::AgentError;
 use crate::agent::Agent;
+use clock::{Clock, TimeControl};
+use game_metadata::GameMetadata;
+use game_record::GameRecord;
 use game_result::GameResult;
 use player::Player;
+use std::time::{Instant, SystemTime};
 use ultimate_board::UltimateBoard;
 
 pub mod bitboard;
 pub mod board;
+pub mod board_export;
+pub mod clock;
+pub mod game_metadata;
+pub mod game_record;
 pub mod game_result;
+pub mod notation;
 pub mod player;
+pub mod tablebase;
 pub mod ultimate_board;
 
 /// Struct representing a game of Ultimate Tic Tac Toe
 ///
 /// The game is played by two [agents](Agent).
+///
+/// The moves and the [metadata](GameMetadata) of the game are recorded in a [GameRecord].
+///
+/// The thinking time of the agents can be limited with a [TimeControl], see [Game::with_time_control].
 pub struct Game {
     agents: Vec<Box<dyn Agent>>,
     board: UltimateBoard,
+    record: GameRecord,
+    clock: Option<Clock>,
 }
 
 impl Game {
@@ -29,37 +47,235 @@ impl Game {
     /// # Returns
     /// A new game
     pub fn new(agent_one: Box<dyn Agent>, agent_two: Box<dyn Agent>) -> Self {
+        Self::with_metadata(agent_one, agent_two, GameMetadata::new())
+    }
+
+    /// Creates a new game with the provided agents and metadata.
+    ///
+    /// The agent names in the metadata are filled in from the [info](crate::agent::AgentInfo) of the agents if they are empty.
+    /// # Arguments
+    /// * `agent_one` - The agent of [Player::One]
+    /// * `agent_two` - The agent of [Player::Two]
+    /// * `metadata` - The metadata of the game
+    /// # Returns
+    /// A new game
+    pub fn with_metadata(
+        agent_one: Box<dyn Agent>,
+        agent_two: Box<dyn Agent>,
+        mut metadata: GameMetadata,
+    ) -> Self {
+        let agents = vec![agent_one, agent_two];
+
+        for (name, agent) in metadata.agent_names.iter_mut().zip(agents.iter()) {
+            if name.is_empty() {
+                *name = agent.get_info().get_name().to_string();
+            }
+        }
+
         Game {
-            agents: vec![agent_one, agent_two],
+            agents,
             board: UltimateBoard::new(),
+            record: GameRecord::new(metadata),
+            clock: None,
+        }
+    }
+
+    /// Creates a game continuing the given record
+    ///
+    /// The moves of the record are replayed, the game continues with the player to move.
+    /// The result and the forfeit of the record are discarded.
+    /// # Arguments
+    /// * `agent_one` - The agent of [Player::One]
+    /// * `agent_two` - The agent of [Player::Two]
+    /// * `record` - The record of the game to continue, e.g. [loaded](GameRecord::load) from a file
+    /// # Returns
+    /// The game, or [AgentError::IllegalMove] if the record contains an illegal move
+    pub fn from_record(
+        agent_one: Box<dyn Agent>,
+        agent_two: Box<dyn Agent>,
+        record: GameRecord,
+    ) -> Result<Self, AgentError> {
+        let mut game = Self::with_metadata(agent_one, agent_two, record.get_metadata().clone());
+        game.restore(&record)?;
+
+        Ok(game)
+    }
+
+    /// Replaces the board and the moves with the ones of the record
+    ///
+    /// The game is not changed if the record contains an illegal move.
+    fn restore(&mut self, record: &GameRecord) -> Result<(), AgentError> {
+        let boards = record.replay()?;
+
+        self.board = *boards.last().unwrap();
+        self.record = GameRecord::new(record.get_metadata().clone());
+        for &index in record.get_moves() {
+            self.record.push_move(index);
+        }
+        if let Some(remaining_time) = record.get_remaining_time() {
+            self.record.set_remaining_time(remaining_time);
+        }
+        self.restore_clock();
+
+        Ok(())
+    }
+
+    /// Returns the boards before each recorded move
+    fn get_history(&self) -> Vec<UltimateBoard> {
+        let mut boards = self.record.replay().unwrap();
+        boards.pop();
+        boards
+    }
+
+    /// Plays the game with the given time control
+    ///
+    /// The time of each [act](Agent::act) call is subtracted from the clock of the agent.
+    /// An agent whose time runs out forfeits the game with [AgentError::Timeout].
+    /// The agents are informed about the clocks before each move, see [Agent::clock_updated].
+    /// A game continuing a record with the remaining time of the players starts with that time, see [Game::from_record].
+    /// # Arguments
+    /// * `time_control` - The time control of the game
+    /// # Returns
+    /// The game with the time control
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.clock = Some(Clock::from_time_control(time_control));
+        self.restore_clock();
+        self
+    }
+
+    /// Sets the clocks to the remaining time of the record, if the record contains them
+    fn restore_clock(&mut self) {
+        if let (Some(clock), Some(remaining_time)) =
+            (self.clock.as_mut(), self.record.get_remaining_time())
+        {
+            for player in [Player::One, Player::Two] {
+                clock.set_remaining(player, remaining_time[player as usize]);
+            }
         }
     }
 
     /// Plays the game until a result is reached.
+    ///
+    /// If an agent returns an [error](crate::agent::agent_error::AgentError) or an illegal move, it forfeits the game
+    /// and the opponent wins. The reason is recorded as the [forfeit](GameRecord::get_forfeit) of the record.
+    ///
+    /// The agents are asked for their [action](Agent::choose_action) in their turn.
+    /// If an agent requests [Action::Undo], its last move and the reply of the opponent are taken back
+    /// and the agent is asked for a move on the earlier board again.
+    /// Both agents are notified with [Agent::new_game], as after loading a game.
+    /// If the agent has not made a move yet, it is [notified](Agent::action_failed) and asked again.
+    ///
+    /// If an agent requests [Action::Save], the record is saved to the given file and the agent is asked again.
+    /// If an agent requests [Action::Load], the board and the moves are restored from the given file
+    /// and the game continues with the same agents, see [Game::from_record].
+    /// With a time control, the clocks are set to the remaining time of the loaded record and sent to both agents.
+    /// Failing to save or load does not end the game, the agent is [notified](Agent::action_failed) and asked again.
+    ///
+    /// A game created with [Game::from_record] continues with the player to move and keeps the start time of the record.
     /// # Returns
     /// The result of the game
     pub fn play(&mut self) -> GameResult {
         let mut game_result = self.board.get_game_status();
-        let mut active_agent = Player::One;
 
-        let mut turn = 0;
+        // A resumed game keeps the time it was started
+        self.record
+            .get_metadata_mut()
+            .start_time
+            .get_or_insert_with(SystemTime::now);
+
+        self.start_agents();
+
+        let mut turn = self.record.get_moves().len() as u32;
+        let mut active_agent = Game::get_player_to_move(turn);
+        // The boards before each move, to take back moves
+        let mut history = self.get_history();
 
         while game_result == GameResult::Continue {
-            let current_move =
-                self.agents[active_agent as usize].act(self.board, active_agent, turn);
-
-            if current_move.is_none() {
-                eprintln!("Agent {:?} returned None instead of a move", active_agent);
-                eprintln!("{}", self.board);
-                eprintln!("{:?}", self.board);
-                eprintln!("{:?}", self.board.get_possible_moves().collect::<Vec<u8>>());
-                self.agents[active_agent as usize].act(self.board, active_agent, turn);
-                panic!();
+            let agent = &mut self.agents[active_agent as usize];
+            if let Some(clock) = &self.clock {
+                agent.clock_updated(clock);
             }
 
+            let start = Instant::now();
+            let mut current_move = agent
+                .choose_action(&self.board, active_agent, turn)
+                .and_then(|action| match action {
+                    Action::Move(index) if !self.board.is_legal_move(index) => {
+                        Err(AgentError::IllegalMove(index))
+                    }
+                    action => Ok(action),
+                });
+
+            if let Some(clock) = self.clock.as_mut() {
+                if !clock.record_move(active_agent, start.elapsed()) {
+                    current_move = Err(AgentError::Timeout);
+                }
+                self.record.set_remaining_time([
+                    clock.get_remaining(Player::One),
+                    clock.get_remaining(Player::Two),
+                ]);
+            }
+
+            let current_move = match current_move {
+                Ok(Action::Move(index)) => index,
+                Ok(Action::Undo) if turn < 2 => {
+                    agent.action_failed(&Action::Undo, "there is no move to take back");
+                    continue;
+                }
+                Ok(Action::Undo) => {
+                    history.truncate(history.len() - 1);
+                    self.board = history.pop().unwrap();
+                    self.record.pop_move();
+                    self.record.pop_move();
+                    turn -= 2;
+                    self.start_agents();
+                    continue;
+                }
+                Ok(Action::Save(path)) => {
+                    if let Err(error) = self.record.save(&path) {
+                        agent.action_failed(&Action::Save(path), &error.to_string());
+                    }
+                    continue;
+                }
+                Ok(Action::Load(path)) => {
+                    let loaded = GameRecord::load(&path)
+                        .map_err(AgentError::from)
+                        .and_then(|record| self.restore(&record));
+                    if let Err(error) = loaded {
+                        self.agents[active_agent as usize]
+                            .action_failed(&Action::Load(path), &error.to_string());
+                        continue;
+                    }
+
+                    self.start_agents();
+                    if let Some(clock) = &self.clock {
+                        for agent in self.agents.iter_mut() {
+                            agent.clock_updated(clock);
+                        }
+                    }
+
+                    turn = self.record.get_moves().len() as u32;
+                    active_agent = Game::get_player_to_move(turn);
+                    history = self.get_history();
+                    game_result = self.board.get_game_status();
+                    continue;
+                }
+                Err(error) => {
+                    eprintln!("Agent {:?} forfeits: {}", active_agent, error);
+                    self.record.set_forfeit(active_agent, error.to_string());
+                    game_result = self.record.get_result();
+                    break;
+                }
+            };
+
             turn += 1;
 
-            self.board.make_move(current_move.unwrap());
+            history.push(self.board);
+            self.board.make_move(current_move);
+            self.record.push_move(current_move);
+
+            self.agents[active_agent.get_opponent() as usize]
+                .opponent_moved(self.board, current_move);
 
             //println!("{}", self.board);
 
@@ -68,10 +284,530 @@ impl Game {
             active_agent = active_agent.get_opponent();
         }
 
+        if self.record.get_forfeit().is_none() {
+            self.record.set_result(game_result);
+        }
+
+        for agent in self.agents.iter_mut() {
+            agent.game_over(self.board, game_result);
+        }
+
         game_result
     }
 
+    /// Tells both agents that a game starts on the current board
+    ///
+    /// Called before the first move and after the board has been changed by an [Action],
+    /// so the agents drop the searches and trees of moves that are not on the board anymore.
+    fn start_agents(&mut self) {
+        for (agent, player) in self.agents.iter_mut().zip([Player::One, Player::Two]) {
+            agent.new_game(player);
+            agent.set_game_metadata(self.record.get_metadata());
+        }
+    }
+
+    /// Returns the player to move after the given number of moves
+    fn get_player_to_move(turn: u32) -> Player {
+        match turn % 2 {
+            0 => Player::One,
+            _ => Player::Two,
+        }
+    }
+
     pub fn get_board(&self) -> &UltimateBoard {
         &self.board
     }
+
+    /// Get the clocks of the game
+    /// # Returns
+    /// The clocks, None if the game is played without a time control
+    pub fn get_clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Get the record of the game
+    /// # Returns
+    /// The record containing the metadata, the moves and the result of the game
+    pub fn get_record(&self) -> &GameRecord {
+        &self.record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::epsilon_greedy_agent::EpsilonGreedyAgent;
+    use crate::agent::hybrid_agent::HybridAgent;
+    use crate::agent::logging_agent::{AnnotationLog, LoggingAgent};
+    use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+    use crate::agent::random_agent::RandomAgent;
+    use crate::agent::random_start::RandomStartAgent;
+    use crate::agent::scripted_agent::ScriptedAgent;
+    use crate::agent::symmetry_agent::SymmetryAgent;
+    use crate::agent::time_limited_agent::TimeLimitedAgent;
+    use crate::agent::{AgentInfo, CloneAgent};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Agent recording the lifecycle notifications it receives
+    struct LifecycleAgent {
+        agent: RandomAgent,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Agent for LifecycleAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            self.agent.act(board, player, turn)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+
+        fn new_game(&mut self, player: Player) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("new {:?}", player));
+        }
+
+        fn game_over(&mut self, board: UltimateBoard, result: GameResult) {
+            assert_eq!(board.get_game_status(), result);
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("over {:?}", result));
+        }
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let events = Arc::new(Mutex::new(vec![]));
+
+        let mut game = Game::new(
+            Box::new(LifecycleAgent {
+                agent: RandomAgent::new(),
+                events: events.clone(),
+            }),
+            Box::new(RandomAgent::new()),
+        );
+        let result = game.play();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["new One".to_string(), format!("over {:?}", result)]
+        );
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut metadata = GameMetadata::with_id(42).with_tag("test");
+        metadata.agent_names[1] = "opponent".to_string();
+
+        let mut game = Game::with_metadata(
+            Box::new(RandomAgent::new()),
+            Box::new(RandomAgent::new()),
+            metadata,
+        );
+        assert_eq!(game.get_record().get_metadata().start_time, None);
+        game.play();
+
+        // The empty agent name is filled in from the info of the agent
+        let metadata = game.get_record().get_metadata();
+        assert_eq!(metadata.game_id, 42);
+        assert_eq!(metadata.tags, vec!["test".to_string()]);
+        assert_eq!(
+            metadata.agent_names,
+            ["RAND".to_string(), "opponent".to_string()]
+        );
+        assert!(metadata.start_time.is_some());
+
+        // Generated ids are unique
+        assert_ne!(GameMetadata::new().game_id, GameMetadata::new().game_id);
+    }
+
+    #[test]
+    fn test_clone_agent() {
+        let prototype: Box<dyn CloneAgent> = Box::new(ScriptedAgent::new(vec![40, 36]));
+
+        let mut game = Game::new(prototype.clone(), prototype.clone());
+        game.play();
+
+        assert_eq!(&game.get_record().get_moves()[..2], &[40, 36]);
+    }
+
+    /// Agent making a request like [Action::Undo] once in the given turn
+    struct RequestAgent {
+        agent: Box<dyn Agent>,
+        request: Option<(u32, Action)>,
+        /// The reasons of the failed actions
+        failures: Arc<Mutex<Vec<String>>>,
+        /// The clocks the agent was informed about
+        clocks: Arc<Mutex<Vec<Clock>>>,
+    }
+
+    impl RequestAgent {
+        fn new(script: Vec<u8>, turn: u32, request: Action) -> Self {
+            RequestAgent::wrap(ScriptedAgent::new(script), turn, request)
+        }
+
+        fn wrap(agent: impl Agent + 'static, turn: u32, request: Action) -> Self {
+            RequestAgent {
+                agent: Box::new(agent),
+                request: Some((turn, request)),
+                failures: Arc::new(Mutex::new(vec![])),
+                clocks: Arc::new(Mutex::new(vec![])),
+            }
+        }
+    }
+
+    impl Agent for RequestAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            self.agent.act(board, player, turn)
+        }
+
+        fn choose_action(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<Action, AgentError> {
+            if matches!(self.request, Some((request_turn, _)) if request_turn == turn) {
+                return Ok(self.request.take().unwrap().1);
+            }
+            self.act(board, player, turn).map(Action::Move)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+
+        fn action_failed(&mut self, _action: &Action, reason: &str) {
+            self.failures.lock().unwrap().push(reason.to_string());
+        }
+
+        fn clock_updated(&mut self, clock: &Clock) {
+            self.clocks.lock().unwrap().push(*clock);
+        }
+    }
+
+    #[test]
+    fn test_undo() {
+        let script = vec![0, 1, 9, 4, 36];
+        let mut game = Game::new(
+            Box::new(RequestAgent::new(script.clone(), 2, Action::Undo)),
+            Box::new(ScriptedAgent::new(script.clone())),
+        );
+        game.play();
+
+        // The taken back moves are not recorded
+        assert_eq!(&game.get_record().get_moves()[..5], &script[..]);
+
+        // There is no move to take back in the first turn, the agent is told so and asked again
+        let agent = RequestAgent::new(script.clone(), 0, Action::Undo);
+        let failures = agent.failures.clone();
+        let mut game = Game::new(
+            Box::new(agent),
+            Box::new(ScriptedAgent::new(script.clone())),
+        );
+        game.play();
+        assert_eq!(&game.get_record().get_moves()[..5], &script[..]);
+        assert_eq!(failures.lock().unwrap().len(), 1);
+    }
+
+    /// Agent recording the simulations of every search of a [MonteCarloTreeAgent]
+    struct SimulationsAgent {
+        agent: MonteCarloTreeAgent,
+        /// The turn and the number of simulations of every search
+        searches: Arc<Mutex<Vec<(u32, f64)>>>,
+    }
+
+    impl Agent for SimulationsAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            let index = self.agent.act(board, player, turn);
+            let simulations = self.agent.get_last_report().unwrap().simulations;
+            self.searches.lock().unwrap().push((turn, simulations));
+            index
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+
+        fn opponent_moved(&mut self, board: UltimateBoard, index: u8) {
+            self.agent.opponent_moved(board, index);
+        }
+
+        fn new_game(&mut self, player: Player) {
+            self.agent.new_game(player);
+        }
+    }
+
+    #[test]
+    fn test_undo_notifies_agents() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut game = Game::new(
+            Box::new(RequestAgent::wrap(RandomAgent::new(), 2, Action::Undo)),
+            Box::new(LifecycleAgent {
+                agent: RandomAgent::new(),
+                events: events.clone(),
+            }),
+        );
+        let result = game.play();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "new Two".to_string(),
+                "new Two".to_string(),
+                format!("over {:?}", result)
+            ]
+        );
+
+        // The pondering opponent continues its search after the first reply,
+        // but not after the reply to a move that was taken back
+        let searches = Arc::new(Mutex::new(vec![]));
+        let mut game = Game::new(
+            Box::new(SimulationsAgent {
+                agent: MonteCarloTreeAgent::new(200).with_pondering(true),
+                searches: searches.clone(),
+            }),
+            Box::new(RequestAgent::wrap(
+                SlowAgent {
+                    agent: RandomAgent::new(),
+                    delay: Duration::from_millis(100),
+                },
+                3,
+                Action::Undo,
+            )),
+        );
+        game.play();
+
+        let searches = searches.lock().unwrap();
+        let repeated: Vec<f64> = searches
+            .iter()
+            .filter(|&&(turn, _)| turn == 2)
+            .map(|&(_, simulations)| simulations)
+            .collect();
+        assert_eq!(repeated.len(), 2);
+        assert!(repeated[1] < repeated[0]);
+    }
+
+    #[test]
+    fn test_undo_through_wrappers() {
+        let script = vec![0, 1, 9, 4, 36];
+        let wrappers: Vec<fn(RequestAgent) -> Box<dyn Agent>> = vec![
+            |agent| {
+                let log = Arc::new(Mutex::new(AnnotationLog::json_lines(Vec::new())));
+                Box::new(LoggingAgent::new(log, agent))
+            },
+            |agent| Box::new(RandomStartAgent::new(0, agent)),
+            |agent| Box::new(SymmetryAgent::new(0, agent)),
+            |agent| Box::new(EpsilonGreedyAgent::new(0., agent)),
+            |agent| Box::new(HybridAgent::new(agent)),
+            |agent| Box::new(TimeLimitedAgent::new(Duration::from_secs(10), agent)),
+        ];
+
+        for wrap in wrappers {
+            // The wrapped agent takes back its move
+            let mut game = Game::new(
+                wrap(RequestAgent::new(script.clone(), 2, Action::Undo)),
+                Box::new(ScriptedAgent::new(script.clone())),
+            );
+            game.play();
+            assert_eq!(&game.get_record().get_moves()[..5], &script[..]);
+
+            // The wrapped agent is told that there is no move to take back
+            let agent = RequestAgent::new(script.clone(), 0, Action::Undo);
+            let failures = agent.failures.clone();
+            let mut game = Game::new(wrap(agent), Box::new(ScriptedAgent::new(script.clone())));
+            game.play();
+            assert_eq!(failures.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let script = vec![0, 1, 9, 4, 36];
+        let path = std::env::temp_dir().join("hausarbeit_test_save_and_load.json");
+        let path = path.to_str().unwrap().to_string();
+
+        // The game is saved before the third move
+        let mut game = Game::new(
+            Box::new(RequestAgent::new(
+                script.clone(),
+                2,
+                Action::Save(path.clone()),
+            )),
+            Box::new(ScriptedAgent::new(script.clone())),
+        );
+        game.play();
+        let record = GameRecord::load(&path).unwrap();
+        assert_eq!(record.get_moves(), &script[..2]);
+
+        // The saved game is continued with the third move
+        let mut game = Game::from_record(
+            Box::new(ScriptedAgent::new(script.clone())),
+            Box::new(ScriptedAgent::new(script.clone())),
+            record,
+        )
+        .unwrap();
+        assert_eq!(game.get_board().get_next_board_index(), Some(1));
+        game.play();
+        assert_eq!(&game.get_record().get_moves()[..5], &script[..]);
+
+        // Loading during the game goes back to the saved position
+        let mut game = Game::new(
+            Box::new(ScriptedAgent::new(script.clone())),
+            Box::new(RequestAgent::new(
+                script.clone(),
+                3,
+                Action::Load(path.clone()),
+            )),
+        );
+        game.play();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&game.get_record().get_moves()[..3], &script[..3]);
+
+        // A failed load is reported to the agent and the game goes on
+        let agent = RequestAgent::new(script.clone(), 3, Action::Load(path.clone()));
+        let failures = agent.failures.clone();
+        let mut game = Game::new(
+            Box::new(ScriptedAgent::new(script.clone())),
+            Box::new(agent),
+        );
+        game.play();
+        assert_eq!(&game.get_record().get_moves()[..5], &script[..]);
+        assert_eq!(failures.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_clock() {
+        let script = vec![0, 1, 9, 4, 36];
+        let path = std::env::temp_dir().join("hausarbeit_test_load_clock.json");
+        let path = path.to_str().unwrap().to_string();
+
+        let remaining_time = [Duration::from_secs(5), Duration::from_secs(7)];
+        let start_time = SystemTime::UNIX_EPOCH;
+        let mut record = GameRecord::new(GameMetadata::new());
+        record.get_metadata_mut().start_time = Some(start_time);
+        record.push_move(0);
+        record.push_move(1);
+        record.set_remaining_time(remaining_time);
+        record.save(&path).unwrap();
+
+        // A resumed game keeps the clocks and the start time of the record
+        let mut game = Game::from_record(
+            Box::new(ScriptedAgent::new(script.clone())),
+            Box::new(ScriptedAgent::new(script.clone())),
+            GameRecord::load(&path).unwrap(),
+        )
+        .unwrap()
+        .with_time_control(TimeControl::new(Duration::from_secs(60), Duration::ZERO));
+        assert_eq!(
+            game.get_clock().unwrap().get_remaining(Player::Two),
+            remaining_time[1]
+        );
+        game.play();
+        assert_eq!(
+            game.get_record().get_metadata().start_time,
+            Some(start_time)
+        );
+        assert!(game.get_record().get_remaining_time().unwrap()[0] <= remaining_time[0]);
+
+        // Loading during the game sends the recorded clocks to the agents
+        let agent = RequestAgent::new(script.clone(), 3, Action::Load(path.clone()));
+        let clocks = agent.clocks.clone();
+        let mut game = Game::new(
+            Box::new(ScriptedAgent::new(script.clone())),
+            Box::new(agent),
+        )
+        .with_time_control(TimeControl::new(Duration::from_secs(60), Duration::ZERO));
+        game.play();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(clocks.lock().unwrap().iter().any(|clock| {
+            clock.get_remaining(Player::One) == remaining_time[0]
+                && clock.get_remaining(Player::Two) == remaining_time[1]
+        }));
+        assert_eq!(
+            game.get_record().get_metadata().start_time,
+            Some(start_time)
+        );
+    }
+
+    /// Agent thinking for a fixed time before each move
+    struct SlowAgent {
+        agent: RandomAgent,
+        delay: Duration,
+    }
+
+    impl Agent for SlowAgent {
+        fn act(
+            &mut self,
+            board: &UltimateBoard,
+            player: Player,
+            turn: u32,
+        ) -> Result<u8, AgentError> {
+            std::thread::sleep(self.delay);
+            self.agent.act(board, player, turn)
+        }
+
+        fn get_info(&self) -> AgentInfo {
+            self.agent.get_info()
+        }
+    }
+
+    #[test]
+    fn test_time_control() {
+        let mut game = Game::new(
+            Box::new(RandomAgent::new()),
+            Box::new(SlowAgent {
+                agent: RandomAgent::new(),
+                delay: Duration::from_millis(50),
+            }),
+        )
+        .with_time_control(TimeControl::new(Duration::from_millis(20), Duration::ZERO));
+
+        assert_eq!(game.play(), GameResult::Win(Player::One));
+        assert_eq!(
+            game.get_record().get_forfeit().unwrap().reason,
+            AgentError::Timeout.to_string()
+        );
+        assert_eq!(
+            game.get_clock().unwrap().get_remaining(Player::Two),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_forfeit() {
+        // The second move is illegal, as the first board has to be played on
+        let mut game = Game::new(
+            Box::new(ScriptedAgent::new(vec![0, 40])),
+            Box::new(ScriptedAgent::new(vec![0, 40])),
+        );
+
+        assert_eq!(game.play(), GameResult::Win(Player::One));
+
+        let forfeit = game.get_record().get_forfeit().unwrap();
+        assert_eq!(forfeit.player, Player::Two);
+        assert_eq!(forfeit.reason, AgentError::IllegalMove(40).to_string());
+        assert_eq!(game.get_record().get_result(), GameResult::Win(Player::One));
+    }
 }
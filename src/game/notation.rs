@@ -0,0 +1,84 @@
+//! # Contains the algebraic notation of moves
+//!
+//! A move is written as the coordinates of the small board and the coordinates of the square on it, e.g. `b2/c3`.
+//! The columns are named `a` to `c` from left to right and the rows `1` to `3` from top to bottom,
+//! so `b2/c3` is the bottom right square of the center board.
+//!
+//! The rows can also be given as letters with the columns in upper case, e.g. `Bb-Cc` for the same move.
+//! Moves in this notation are unambiguous and can be pasted into bug reports, in contrast to the human indices (0-80).
+
+/// Writes a move in algebraic notation
+///
+/// # Arguments
+/// * `index` - The human index of the move (0-80)
+/// # Returns
+/// The move in algebraic notation, e.g. `b2/c3`
+pub fn to_algebraic(index: u8) -> String {
+    let coordinates = |position: u8| {
+        format!(
+            "{}{}",
+            (b'a' + position % 3) as char,
+            (b'1' + position / 3) as char
+        )
+    };
+
+    format!("{}/{}", coordinates(index / 9), coordinates(index % 9))
+}
+
+/// Parses a move in algebraic notation
+///
+/// Both `b2/c3` and `Bb-Cc` are accepted, the case of the letters is ignored.
+/// # Arguments
+/// * `text` - The move in algebraic notation
+/// # Returns
+/// The human index of the move (0-80), None if the text is not a move in algebraic notation
+pub fn parse_algebraic(text: &str) -> Option<u8> {
+    let (board, square) = text.trim().split_once(['/', '-'])?;
+
+    Some(parse_coordinates(board)? * 9 + parse_coordinates(square)?)
+}
+
+/// Parses the coordinates of a small board or a square, e.g. `b2` or `Bb`
+///
+/// # Returns
+/// The index of the small board or square (0-8)
+fn parse_coordinates(text: &str) -> Option<u8> {
+    let [column, row] = text.trim().as_bytes() else {
+        return None;
+    };
+
+    let column = match column.to_ascii_lowercase() {
+        column @ b'a'..=b'c' => column - b'a',
+        _ => return None,
+    };
+    let row = match row.to_ascii_lowercase() {
+        row @ b'1'..=b'3' => row - b'1',
+        row @ b'a'..=b'c' => row - b'a',
+        _ => return None,
+    };
+
+    Some(row * 3 + column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notation() {
+        assert_eq!(to_algebraic(0), "a1/a1");
+        assert_eq!(to_algebraic(44), "b2/c3");
+        assert_eq!(to_algebraic(80), "c3/c3");
+
+        for index in 0..81 {
+            assert_eq!(parse_algebraic(&to_algebraic(index)), Some(index));
+        }
+
+        assert_eq!(parse_algebraic("Bb-Cc"), Some(44));
+        assert_eq!(parse_algebraic(" B2/c3 "), Some(44));
+        assert_eq!(parse_algebraic("b2"), None);
+        assert_eq!(parse_algebraic("d2/c3"), None);
+        assert_eq!(parse_algebraic("b4/c3"), None);
+        assert_eq!(parse_algebraic("b2/c3x"), None);
+    }
+}
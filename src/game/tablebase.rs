@@ -0,0 +1,370 @@
+//! # Contains the [Tablebase] struct
+//!
+//! A [Tablebase] stores the exact results of positions close to the end of the game,
+//! similar to the [endgame tablebases](https://www.chessprogramming.org/Endgame_Tablebases) of chess.
+//!
+//! A position belongs to the tablebase if at most [max_empty_squares](Tablebase::get_max_empty_squares) squares can still be played,
+//! see [UltimateBoard::count_empty_squares]. The game ends after at most this number of moves,
+//! so such positions can be solved exhaustively.
+//!
+//! Enumerating all positions with few empty squares backwards from the final positions is infeasible,
+//! as almost all of them can not be reached in a game.
+//! Instead the tablebase is generated from seed positions: every seed is solved by a memoized search of all positions reachable from it,
+//! working backwards from the final positions of the search. All solved positions are stored.
+//!
+//! The positions are keyed by their [canonical hash](UltimateBoard::get_canonical_hash), so symmetric positions share an entry.
+//! The tablebase can be probed by the [TablebaseAgent](crate::agent::tablebase_agent::TablebaseAgent),
+//! the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent), the [MonteCarloTreeAgent](crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent)
+//! and the [TablebaseHeuristic](crate::heuristic::tablebase_heuristic::TablebaseHeuristic).
+//!
+//! # File format
+//! The tablebase is stored in a compact binary file:
+//! * the magic bytes `UTTB`
+//! * the maximum number of empty squares as one byte
+//! * the number of entries as a little endian `u64`
+//! * the entries sorted by key, each consisting of the key as a little endian `u64` and the result as one byte
+//!   (0 for a win of [Player::One], 1 for a win of [Player::Two] and 2 for a draw)
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+
+/// The magic bytes at the start of a tablebase file
+const MAGIC: &[u8; 4] = b"UTTB";
+
+/// # Struct representing a table of solved positions
+///
+/// # Example
+/// ```
+/// use hausarbeit::game::tablebase::Tablebase;
+/// use hausarbeit::game::ultimate_board::UltimateBoard;
+///
+/// // Solve the end of 10 random games
+/// let tablebase = Tablebase::generate(8, 10, 0);
+///
+/// // The tablebase does not contain positions with more empty squares
+/// assert_eq!(tablebase.probe(UltimateBoard::new()), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Tablebase {
+    max_empty_squares: u32,
+    entries: HashMap<u64, GameResult>,
+}
+
+impl Tablebase {
+    /// Creates a new empty [Tablebase]
+    ///
+    /// # Arguments
+    /// * `max_empty_squares` - The maximum number of empty squares of the stored positions
+    pub fn new(max_empty_squares: u32) -> Tablebase {
+        Tablebase {
+            max_empty_squares,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Generates a tablebase from the ends of random games
+    ///
+    /// Every game is played with random moves until at most `max_empty_squares` squares are empty,
+    /// the position is then [solved](Tablebase::solve). Games that end earlier are skipped.
+    /// # Arguments
+    /// * `max_empty_squares` - The maximum number of empty squares of the stored positions
+    /// * `games` - The number of random games
+    /// * `seed` - The seed of the random moves
+    /// # Returns
+    /// The generated tablebase
+    pub fn generate(max_empty_squares: u32, games: usize, seed: u64) -> Tablebase {
+        let mut tablebase = Tablebase::new(max_empty_squares);
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+        for _ in 0..games {
+            let mut board = UltimateBoard::new();
+
+            while board.get_game_status() == GameResult::Continue
+                && board.count_empty_squares() > max_empty_squares
+            {
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            tablebase.solve(board);
+        }
+
+        tablebase
+    }
+
+    /// Returns the maximum number of empty squares of the stored positions
+    pub fn get_max_empty_squares(&self) -> u32 {
+        self.max_empty_squares
+    }
+
+    /// Returns the number of stored positions
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no position is stored
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Solves a position and all positions reachable from it and stores their results
+    ///
+    /// # Arguments
+    /// * `board` - The position to solve
+    /// # Returns
+    /// The result of the game if both players play perfectly,
+    /// None if the position has more than [max_empty_squares](Tablebase::get_max_empty_squares) empty squares
+    pub fn solve(&mut self, board: UltimateBoard) -> Option<GameResult> {
+        if board.get_game_status() == GameResult::Continue
+            && board.count_empty_squares() > self.max_empty_squares
+        {
+            return None;
+        }
+
+        Some(self.solve_recursive(board))
+    }
+
+    /// Solves a position with at most [max_empty_squares](Tablebase::get_max_empty_squares) empty squares
+    ///
+    /// The search stops at the first winning move, so the positions after the remaining moves are not solved.
+    /// The recursion depth is bounded by the number of empty squares.
+    /// # Arguments
+    /// * `board` - The position to solve
+    /// # Returns
+    /// The result of the game if both players play perfectly
+    fn solve_recursive(&mut self, board: UltimateBoard) -> GameResult {
+        let status = board.get_game_status();
+        if status != GameResult::Continue {
+            return status;
+        }
+
+        let key = board.get_canonical_hash().0;
+        if let Some(&result) = self.entries.get(&key) {
+            return result;
+        }
+
+        let player = board.get_current_player();
+        let mut best = GameResult::Win(player.get_opponent());
+
+        for index in board.get_possible_moves() {
+            let mut board_copy = board;
+            board_copy.make_move(index);
+
+            match self.solve_recursive(board_copy) {
+                GameResult::Win(winner) if winner == player => {
+                    best = GameResult::Win(player);
+                    break;
+                }
+                GameResult::Draw => best = GameResult::Draw,
+                _ => {}
+            }
+        }
+
+        self.entries.insert(key, best);
+
+        best
+    }
+
+    /// Looks up the result of a position
+    ///
+    /// The result of a finished game is returned without a lookup.
+    /// The result of a position with a different canonical hash may be returned if the hashes collide,
+    /// which is very unlikely for tablebases of practical size.
+    /// # Arguments
+    /// * `board` - The position to look up
+    /// # Returns
+    /// The result of the game if both players play perfectly, None if the position is not stored
+    pub fn probe(&self, board: UltimateBoard) -> Option<GameResult> {
+        let status = board.get_game_status();
+        if status != GameResult::Continue {
+            return Some(status);
+        }
+
+        if board.count_empty_squares() > self.max_empty_squares {
+            return None;
+        }
+
+        self.entries.get(&board.get_canonical_hash().0).copied()
+    }
+
+    /// Returns a move keeping the result of a stored position
+    ///
+    /// The position after the move is either over or stored as well, as the position was solved from it.
+    /// # Arguments
+    /// * `board` - The position to find the move for, the game must not be over
+    /// # Returns
+    /// The move and the result of the game if both players play perfectly, None if the position is not stored
+    pub fn best_move(&self, board: UltimateBoard) -> Option<(u8, GameResult)> {
+        if board.get_game_status() != GameResult::Continue {
+            return None;
+        }
+
+        let result = self.probe(board)?;
+
+        board
+            .get_possible_moves()
+            .find(|&index| {
+                let mut board_copy = board;
+                board_copy.make_move(index);
+                self.probe(board_copy) == Some(result)
+            })
+            .map(|index| (index, result))
+    }
+
+    /// Saves the tablebase to a file
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file
+    /// # Returns
+    /// An error if the file could not be written
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_unstable_by_key(|(&key, _)| key);
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[self.max_empty_squares as u8])?;
+        writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+
+        for (key, result) in entries {
+            writer.write_all(&key.to_le_bytes())?;
+            writer.write_all(&[encode(*result)])?;
+        }
+
+        writer.flush()
+    }
+
+    /// Loads a tablebase from a file written by [Tablebase::save]
+    ///
+    /// # Arguments
+    /// * `path` - The path of the file
+    /// # Returns
+    /// The loaded tablebase, an error if the file could not be read or is not a tablebase
+    pub fn load(path: &str) -> Result<Tablebase, Error> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut header = [0; 13];
+        reader.read_exact(&mut header)?;
+
+        if &header[..4] != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "Not a tablebase file"));
+        }
+
+        let max_empty_squares = header[4] as u32;
+        let len = u64::from_le_bytes(header[5..].try_into().unwrap()) as usize;
+
+        let mut entries = HashMap::with_capacity(len);
+        let mut entry = [0; 9];
+
+        for _ in 0..len {
+            reader.read_exact(&mut entry)?;
+
+            let key = u64::from_le_bytes(entry[..8].try_into().unwrap());
+            let result = decode(entry[8])
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Invalid result"))?;
+
+            entries.insert(key, result);
+        }
+
+        Ok(Tablebase {
+            max_empty_squares,
+            entries,
+        })
+    }
+}
+
+/// Encodes a result of a stored position as a byte
+fn encode(result: GameResult) -> u8 {
+    match result {
+        GameResult::Win(Player::One) => 0,
+        GameResult::Win(Player::Two) => 1,
+        _ => 2,
+    }
+}
+
+/// Decodes a result encoded by [encode]
+///
+/// # Returns
+/// The result, None if the byte is not a valid result
+fn decode(byte: u8) -> Option<GameResult> {
+    match byte {
+        0 => Some(GameResult::Win(Player::One)),
+        1 => Some(GameResult::Win(Player::Two)),
+        2 => Some(GameResult::Draw),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tablebase() {
+        let mut tablebase = Tablebase::new(10);
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut boards = vec![];
+
+        while boards.len() < 10 {
+            let mut board = UltimateBoard::new();
+            while board.get_game_status() == GameResult::Continue
+                && board.count_empty_squares() > 10
+            {
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            if board.get_game_status() == GameResult::Continue {
+                assert!(tablebase.solve(board).is_some());
+                boards.push(board);
+            }
+        }
+
+        assert_eq!(tablebase.solve(UltimateBoard::new()), None);
+        assert_eq!(tablebase.probe(UltimateBoard::new()), None);
+
+        // The stored results are consistent with the results of the positions after the moves
+        for board in boards {
+            let player = board.get_current_player();
+            let result = tablebase.probe(board).unwrap();
+
+            let results: Vec<_> = board
+                .get_possible_moves()
+                .map(|index| {
+                    let mut board_copy = board;
+                    board_copy.make_move(index);
+                    tablebase.probe(board_copy)
+                })
+                .collect();
+
+            if result == GameResult::Win(player) {
+                assert!(results.contains(&Some(result)));
+            } else {
+                assert!(results.iter().all(|result| result.is_some()));
+                assert!(!results.contains(&Some(GameResult::Win(player))));
+                assert!(results.contains(&Some(result)));
+            }
+
+            let (index, best) = tablebase.best_move(board).unwrap();
+            assert_eq!(best, result);
+            let mut board_copy = board;
+            board_copy.make_move(index);
+            assert_eq!(tablebase.probe(board_copy), Some(result));
+        }
+
+        let path = std::env::temp_dir().join("hausarbeit_test_tablebase.uttb");
+        let path = path.to_str().unwrap();
+        tablebase.save(path).unwrap();
+        let loaded = Tablebase::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.get_max_empty_squares(), 10);
+        assert_eq!(loaded.len(), tablebase.len());
+        assert_eq!(loaded.entries, tablebase.entries);
+    }
+}
@@ -6,13 +6,16 @@
 //!
 //! The board contains a [Zobrist hash](https://www.chessprogramming.org/Zobrist_Hashing) used to store the evaluation of a [Heuristic](crate::heuristic::Heuristic) in a [Transposition table](https://www.chessprogramming.org/Transposition_Table).
 
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 
 use once_cell::sync::Lazy;
 use rand_chacha::rand_core::{RngCore, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 
+use crate::game::bitboard::BitBoard;
 use crate::game::board::{Board, BoardSymbol};
 use crate::game::game_result::GameResult;
 use crate::game::game_result::GameResult::Continue;
@@ -73,7 +76,7 @@ const PARTIAL_WIN_POSITIONS: [[u8; 2]; 24] = [
 const NUM_POSITIONS: usize = 9 * 9;
 
 /// Number of Zobrist values needed for the ultimate board
-const NUM_ZOBRIST_VALUES: usize = NUM_POSITIONS * 2 + 9;
+const NUM_ZOBRIST_VALUES: usize = NUM_POSITIONS * 2 + 9 + 1;
 
 /// Values used for [Zobrist hashing](https://www.chessprogramming.org/Zobrist_Hashing)
 ///
@@ -95,6 +98,33 @@ static ZOBRIST_VALUES: Lazy<[u64; NUM_ZOBRIST_VALUES]> = Lazy::new(|| {
 /// Offset of the `next_board_index` hashes in [Zobrist values](ZOBRIST_VALUES)
 pub const ZOBRIST_VALUES_NEXT_BOARD_INDEX_OFFSET: usize = NUM_POSITIONS * 2;
 
+#[allow(rustdoc::private_intra_doc_links)]
+/// Index of the hash toggled by a [null move](UltimateBoard::make_null_move) in [Zobrist values](ZOBRIST_VALUES)
+pub const ZOBRIST_VALUES_NULL_MOVE_INDEX: usize = ZOBRIST_VALUES_NEXT_BOARD_INDEX_OFFSET + 9;
+
+/// The number of symmetries of the [UltimateBoard]
+pub const NUM_SYMMETRIES: usize = 8;
+
+/// The symmetries of a 3x3 board as permutations of the human indices
+///
+/// The symmetries are the identity, the rotations by 90, 180 and 270 degrees clockwise,
+/// the horizontal and vertical reflections and the reflections along both diagonals.
+/// Index `i` is mapped to `SYMMETRIES[symmetry][i]`.
+/// The same permutation is applied to the small boards of the ultimate board and to the squares of each small board.
+const SYMMETRIES: [[u8; 9]; NUM_SYMMETRIES] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8],
+    [2, 5, 8, 1, 4, 7, 0, 3, 6],
+    [8, 7, 6, 5, 4, 3, 2, 1, 0],
+    [6, 3, 0, 7, 4, 1, 8, 5, 2],
+    [2, 1, 0, 5, 4, 3, 8, 7, 6],
+    [6, 7, 8, 3, 4, 5, 0, 1, 2],
+    [0, 3, 6, 1, 4, 7, 2, 5, 8],
+    [8, 5, 2, 7, 4, 1, 6, 3, 0],
+];
+
+/// The inverse of each of the [SYMMETRIES]
+const INVERSE_SYMMETRIES: [usize; NUM_SYMMETRIES] = [0, 3, 2, 1, 4, 5, 6, 7];
+
 /// The indices of the corners of a [UltimateBoard]
 pub const CORNER_INDICES: [usize; 4] = [0, 2, 6, 8];
 
@@ -111,6 +141,8 @@ pub const CENTER_INDEX: usize = 4;
 /// * `board` - The 3x3 board of 3x3 boards
 /// * `next_board` - The index of the next board to play on
 /// * `board_status` - The status of each board
+/// * `won_boards` - The boards won by each player as a [BitBoard]
+/// * `drawn_boards` - The drawn boards as a [BitBoard]
 /// * `game_status` - The status of the game
 /// * `current_player` - The current player
 /// * `hash` - The Zobrist hash of the board
@@ -118,6 +150,8 @@ pub const CENTER_INDEX: usize = 4;
 pub struct UltimateBoard {
     boards: [Board; 9],
     board_status: [GameResult; 9],
+    won_boards: [BitBoard; 2],
+    drawn_boards: BitBoard,
     next_board_index: Option<u8>,
     game_status: GameResult,
     current_player: Player,
@@ -145,6 +179,8 @@ impl UltimateBoard {
             boards,
             next_board_index: None,
             board_status: [Continue; 9],
+            won_boards: [BitBoard::EMPTY; 2],
+            drawn_boards: BitBoard::EMPTY,
             game_status: Continue,
             current_player: Player::One,
             hash: 0,
@@ -194,6 +230,28 @@ impl UltimateBoard {
         self.board_status
     }
 
+    /// Get the small boards won by a player as a [BitBoard]
+    ///
+    /// The bits are ordered like the squares of a [Board] in its internal representation.
+    /// This allows the same bit operations used on small boards to be applied to the meta-board,
+    /// e.g. via [Board::from_bitboards].
+    /// # Arguments
+    /// * `player` - The player to get the won boards for
+    /// # Returns
+    /// The small boards won by the player
+    pub fn won_boards_mask(&self, player: Player) -> BitBoard {
+        self.won_boards[player as usize]
+    }
+
+    /// Get the drawn small boards as a [BitBoard]
+    ///
+    /// The bits are ordered like the squares of a [Board] in its internal representation.
+    /// # Returns
+    /// The drawn small boards
+    pub fn drawn_boards_mask(&self) -> BitBoard {
+        self.drawn_boards
+    }
+
     /// Get the boards that make up the ultimate board
     /// # Returns
     /// The boards
@@ -215,6 +273,154 @@ impl UltimateBoard {
         self.hash
     }
 
+    /// Get the canonical Zobrist hash of the board
+    ///
+    /// Boards that are rotations or reflections of each other have the same canonical hash.
+    /// The canonical hash is the smallest hash of all symmetric boards, so it has to be recomputed for every symmetry.
+    /// # Returns
+    /// The canonical hash and the symmetry transforming the board into the canonical board
+    pub fn get_canonical_hash(&self) -> (u64, usize) {
+        let mut hashes = [0; NUM_SYMMETRIES];
+
+        for (board_index, board) in self.boards.iter().enumerate() {
+            let key = board.to_key();
+
+            for player in Player::iter() {
+                let squares = BitBoard::new(((key >> (9 * player as u32)) & 0b111111111) as u16);
+
+                for bit in squares {
+                    let field_index = Board::from_bit_to_human(bit) as usize;
+
+                    for (hash, symmetry) in hashes.iter_mut().zip(SYMMETRIES.iter()) {
+                        let index = symmetry[board_index] * 9 + symmetry[field_index];
+                        *hash ^= ZOBRIST_VALUES[(index * 2 + player as u8) as usize];
+                    }
+                }
+            }
+        }
+
+        if let Some(next_board_index) = self.next_board_index {
+            for (hash, symmetry) in hashes.iter_mut().zip(SYMMETRIES.iter()) {
+                *hash ^= ZOBRIST_VALUES[symmetry[next_board_index as usize] as usize
+                    + ZOBRIST_VALUES_NEXT_BOARD_INDEX_OFFSET];
+            }
+        }
+
+        // The part of the hash not depending on the squares, e.g. of null moves, is the same for all symmetries
+        let residue = self.hash ^ hashes[0];
+
+        hashes
+            .iter()
+            .enumerate()
+            .map(|(symmetry, hash)| (hash ^ residue, symmetry))
+            .min()
+            .unwrap()
+    }
+
+    /// Transforms a move by the given symmetry
+    ///
+    /// # Arguments
+    /// * `index` - The human index of the move
+    /// * `symmetry` - The index of the symmetry, as returned by [UltimateBoard::get_canonical_hash]
+    /// # Returns
+    /// The index of the move on the transformed board
+    pub fn transform_move(index: u8, symmetry: usize) -> u8 {
+        let permutation = SYMMETRIES[symmetry];
+        permutation[(index / 9) as usize] * 9 + permutation[(index % 9) as usize]
+    }
+
+    /// Transforms the board by the given symmetry
+    ///
+    /// The squares, the next board to play on and the status of the boards are transformed,
+    /// so the moves of the transformed board are the [transformed moves](UltimateBoard::transform_move) of the board.
+    /// # Arguments
+    /// * `symmetry` - The index of the symmetry, 0 for the board itself
+    /// # Returns
+    /// The transformed board
+    pub fn transform(&self, symmetry: usize) -> UltimateBoard {
+        let position = self.get_position();
+        let parts: Vec<&str> = position.split_whitespace().collect();
+
+        let mut squares = ['.'; 81];
+        for (index, symbol) in parts[0].chars().enumerate() {
+            squares[UltimateBoard::transform_move(index as u8, symmetry) as usize] = symbol;
+        }
+
+        let next_board = match self.next_board_index {
+            Some(index) => SYMMETRIES[symmetry][index as usize].to_string(),
+            None => "-".to_string(),
+        };
+
+        UltimateBoard::from_position(&format!(
+            "{} {} {}",
+            squares.iter().collect::<String>(),
+            next_board,
+            parts[2]
+        ))
+        .expect("A transformed position is valid")
+    }
+
+    /// Returns the inverse of the given symmetry
+    ///
+    /// # Arguments
+    /// * `symmetry` - The index of the symmetry
+    /// # Returns
+    /// The index of the symmetry undoing the given symmetry
+    pub fn inverse_symmetry(symmetry: usize) -> usize {
+        INVERSE_SYMMETRIES[symmetry]
+    }
+
+    /// Returns a fingerprint of the board transformed by the given symmetry
+    ///
+    /// The fingerprint is computed from the complete state of the board and is independent of the Zobrist hash.
+    /// It is used to detect [key collisions](https://www.chessprogramming.org/Transposition_Table#KeyCollisions)
+    /// of the transposition table, two different boards with the same hash almost surely have different fingerprints.
+    /// # Arguments
+    /// * `symmetry` - The index of the symmetry, as returned by [UltimateBoard::get_canonical_hash], 0 for the board itself
+    /// # Returns
+    /// The fingerprint of the transformed board
+    pub fn get_fingerprint(&self, symmetry: usize) -> u64 {
+        let permutation = SYMMETRIES[symmetry];
+        let mut keys = [0u32; 9];
+
+        for (board_index, board) in self.boards.iter().enumerate() {
+            let key = board.to_key();
+
+            for player in Player::iter() {
+                let squares = BitBoard::new(((key >> (9 * player as u32)) & 0b111111111) as u16);
+
+                for bit in squares {
+                    let field_index = Board::from_bit_to_human(bit) as usize;
+
+                    keys[permutation[board_index] as usize] |=
+                        1 << (9 * player as u32 + permutation[field_index] as u32);
+                }
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        keys.hash(&mut hasher);
+        self.next_board_index
+            .map(|index| permutation[index as usize])
+            .hash(&mut hasher);
+        (self.current_player as u8).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Count the empty squares of the small boards that are still in play
+    ///
+    /// The squares of won or drawn small boards can not be played, so the game ends after at most this number of moves.
+    /// # Returns
+    /// The number of playable empty squares
+    pub fn count_empty_squares(&self) -> u32 {
+        self.boards
+            .iter()
+            .zip(self.board_status.iter())
+            .filter(|(_, &status)| status == Continue)
+            .map(|(board, _)| 9 - board.to_key().count_ones())
+            .sum()
+    }
+
     /// Get the index of the next board to play on. If the next player can play on any board, the index is None
     /// # Returns
     /// The index of the next board to play on
@@ -228,28 +434,22 @@ impl UltimateBoard {
     /// # Returns
     /// The partial win difference for the player
     pub fn get_partial_wins_difference(&self, player: Player) -> i8 {
-        let mut diff = 0;
-
-        for partial_win in PARTIAL_WIN_POSITIONS.iter() {
-            let mut player_count = 0;
-            let mut opponent_count = 0;
-
-            for &index in partial_win.iter() {
-                match self.board_status[index as usize] {
-                    GameResult::Win(p) if p == player => player_count += 1,
-                    GameResult::Win(_) => opponent_count += 1,
-                    _ => {}
-                }
-            }
-
-            if player_count > 0 && opponent_count == 0 {
-                diff += 1;
-            } else if opponent_count > 0 && player_count == 0 {
-                diff -= 1;
-            }
-        }
+        partial_wins_difference(&self.board_status, player)
+    }
 
-        diff
+    /// Get the key of the meta-board from the perspective of a player
+    ///
+    /// The meta-board consists of the small boards won by each player, drawn and unfinished small boards are empty.
+    /// Like [Board::to_key], the first 9 bits are the [small boards won](UltimateBoard::won_boards_mask) by the player
+    /// and the next 9 bits the small boards won by the opponent.
+    /// # Arguments
+    /// * `player` - The player whose small boards are the first 9 bits
+    /// # Returns
+    /// The key of the meta-board
+    pub fn get_meta_board_key(&self, player: Player) -> u32 {
+        let first: u32 = self.won_boards[player as usize].into();
+        let second: u32 = self.won_boards[player.get_opponent() as usize].into();
+        first | (second << 9)
     }
 
     /// Get the possible moves for the ultimate board
@@ -263,13 +463,100 @@ impl UltimateBoard {
             None => BoardIterator::MultiBoard(
                 self.boards
                     .into_iter()
-                    .zip(self.board_status.into_iter())
+                    .zip(self.board_status)
                     .filter(|(_, status)| *status == Continue)
                     .flat_map(|(board, _)| board.get_possible_moves()),
             ),
         }
     }
 
+    /// Get the indices of the boards the current player may play on
+    /// # Returns
+    /// An iterator of the indices of the boards, empty if the game is over
+    fn playable_boards(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..9).filter(move |&board_index| {
+            self.game_status == Continue
+                && match self.next_board_index {
+                    Some(next_board_index) => next_board_index == board_index,
+                    None => self.board_status[board_index as usize] == Continue,
+                }
+        })
+    }
+
+    /// Get the moves on the boards the current player may play on that win a small board for a player
+    ///
+    /// The player does not have to be the current player,
+    /// the moves of the opponent are the squares the current player has to occupy to block the opponent.
+    /// # Arguments
+    /// * `player` - The player to get the winning moves for
+    /// # Returns
+    /// An iterator of the moves winning a small board
+    pub fn get_board_winning_moves(&self, player: Player) -> impl Iterator<Item = u8> + '_ {
+        self.playable_boards().flat_map(move |board_index| {
+            self.boards[board_index as usize]
+                .get_winning_squares(player)
+                .into_iter()
+                .map(move |bit| board_index * 9 + Board::from_bit_to_human(bit))
+        })
+    }
+
+    /// Get the moves on the boards the current player may play on that win the game for a player
+    ///
+    /// A move wins the game if it wins a small board completing a win position on the meta-board.
+    /// # Arguments
+    /// * `player` - The player to get the winning moves for
+    /// # Returns
+    /// An iterator of the moves winning the game
+    pub fn get_game_winning_moves(&self, player: Player) -> impl Iterator<Item = u8> + '_ {
+        let winning_boards = self.get_meta_winning_boards(player);
+
+        self.get_board_winning_moves(player).filter(move |index| {
+            winning_boards & BitBoard::new(1 << Board::from_human_to_bit(index / 9))
+                != BitBoard::EMPTY
+        })
+    }
+
+    /// Get the unfinished small boards that complete a win position on the meta-board for a player
+    ///
+    /// Drawn small boards block win positions like small boards won by the opponent.
+    /// The bits are ordered like the squares of a [Board] in its internal representation.
+    /// # Arguments
+    /// * `player` - The player to get the winning boards for
+    /// # Returns
+    /// The small boards that win the game for the player if they win them
+    pub fn get_meta_winning_boards(&self, player: Player) -> BitBoard {
+        let opponent = player.get_opponent();
+
+        let mut meta_board = [BitBoard::EMPTY; 2];
+        meta_board[player as usize] = self.won_boards[player as usize];
+        meta_board[opponent as usize] = self.won_boards[opponent as usize] | self.drawn_boards;
+
+        Board::from_bitboards(meta_board, 0).get_winning_squares(player)
+    }
+
+    /// Check if a move is legal without generating all possible moves
+    ///
+    /// A move is legal if the game is not over, the move is made on the next board to play on
+    /// (or on any unfinished board if the player can choose freely) and the square is empty.
+    /// # Arguments
+    /// * `index` - The index of the field to play on
+    /// # Returns
+    /// True if the move is legal, false otherwise
+    pub fn is_legal_move(&self, index: u8) -> bool {
+        if self.game_status != Continue || index as usize >= NUM_POSITIONS {
+            return false;
+        }
+
+        let board_index = index / 9;
+
+        let board_allowed = match self.next_board_index {
+            Some(next_board_index) => next_board_index == board_index,
+            None => self.board_status[board_index as usize] == Continue,
+        };
+
+        board_allowed && self.boards[board_index as usize].is_empty_square(index % 9)
+    }
+
     /// Make a move on the ultimate board
     /// # Arguments
     /// * `index` - The index of the field to play on
@@ -301,6 +588,14 @@ impl UltimateBoard {
         // Update the status of the board
         self.board_status[board_index as usize] = board.check_if_won();
 
+        // Update the meta-board masks
+        let board_bit = BitBoard::new(1 << Board::from_human_to_bit(board_index));
+        match self.board_status[board_index as usize] {
+            GameResult::Win(player) => self.won_boards[player as usize] |= board_bit,
+            GameResult::Draw => self.drawn_boards |= board_bit,
+            Continue => {}
+        }
+
         // Update the status of the game
         self.check_if_won();
 
@@ -325,6 +620,155 @@ impl UltimateBoard {
             _ => None,
         };
     }
+
+    /// Get the position of the board as a single line of text
+    ///
+    /// The position consists of three parts separated by spaces:
+    /// * the 81 squares in human order, `X` for [Player::One], `O` for [Player::Two] and `.` for an empty square
+    /// * the index of the next board to play on, `-` if the player can play on any board
+    /// * the player to move, `X` or `O`
+    ///
+    /// The format is easy to parse in other languages, see [UltimateBoard::from_position].
+    /// # Returns
+    /// The position of the board
+    pub fn get_position(&self) -> String {
+        let mut position: String = (0..81)
+            .map(
+                |index| match self.boards[index / 9].get_square((index % 9) as u8) {
+                    Some(Player::One) => 'X',
+                    Some(Player::Two) => 'O',
+                    None => '.',
+                },
+            )
+            .collect();
+
+        position += &match self.next_board_index {
+            Some(index) => format!(" {}", index),
+            None => " -".to_string(),
+        };
+
+        position += match self.current_player {
+            Player::One => " X",
+            Player::Two => " O",
+        };
+
+        position
+    }
+
+    /// Create an ultimate board from a position returned by [UltimateBoard::get_position]
+    ///
+    /// The status of the boards and the game as well as the hash are recomputed from the squares.
+    /// # Arguments
+    /// * `position` - The position of the board
+    /// # Returns
+    /// The board, None if the position is malformed or the next board can not be played on
+    pub fn from_position(position: &str) -> Option<Self> {
+        let parts: Vec<&str> = position.split_whitespace().collect();
+
+        let [squares, next_board, player] = parts[..] else {
+            return None;
+        };
+
+        if squares.chars().count() != 81 {
+            return None;
+        }
+
+        let mut board = UltimateBoard::new();
+
+        for (index, symbol) in squares.chars().enumerate() {
+            let player = match symbol {
+                'X' => Player::One,
+                'O' => Player::Two,
+                '.' => continue,
+                _ => return None,
+            };
+
+            board.boards[index / 9].set((index % 9) as u8, player);
+            board.hash ^= ZOBRIST_VALUES[index * 2 + player as usize];
+        }
+
+        for (board_index, small_board) in board.boards.iter().enumerate() {
+            let status = small_board.check_if_won();
+            board.board_status[board_index] = status;
+
+            let board_bit = BitBoard::new(1 << Board::from_human_to_bit(board_index as u8));
+            match status {
+                GameResult::Win(player) => board.won_boards[player as usize] |= board_bit,
+                GameResult::Draw => board.drawn_boards |= board_bit,
+                Continue => {}
+            }
+        }
+
+        board.check_if_won();
+
+        board.current_player = match player {
+            "X" => Player::One,
+            "O" => Player::Two,
+            _ => return None,
+        };
+
+        if next_board != "-" {
+            let index: u8 = next_board.parse().ok().filter(|&index| index < 9)?;
+
+            if board.board_status[index as usize] != Continue {
+                return None;
+            }
+
+            board.next_board_index = Some(index);
+            board.hash ^= ZOBRIST_VALUES[index as usize + ZOBRIST_VALUES_NEXT_BOARD_INDEX_OFFSET];
+        }
+
+        Some(board)
+    }
+
+    /// Make a [null move](https://www.chessprogramming.org/Null_Move) on the ultimate board
+    ///
+    /// The current player passes: no square is set and the opponent has to play on the same board(s).
+    /// Null moves are not legal moves, they are only used by search algorithms.
+    ///
+    /// The hash is changed, so the position can not be confused with the position with the other player to move.
+    pub fn make_null_move(&mut self) {
+        // No further moves can be made if the game is over
+        if self.game_status != Continue {
+            panic!("Game is over");
+        }
+
+        self.current_player = self.current_player.get_opponent();
+        self.hash ^= ZOBRIST_VALUES[ZOBRIST_VALUES_NULL_MOVE_INDEX];
+    }
+}
+
+/// Get the partial win difference for a player on the meta-board
+///
+/// A pair of small boards on a line counts for the player if the player won at least one of them and the opponent none.
+/// # Arguments
+/// * `board_status` - The status of the small boards
+/// * `player` - The player to get the partial win difference for
+/// # Returns
+/// The partial win difference for the player
+pub fn partial_wins_difference(board_status: &[GameResult; 9], player: Player) -> i8 {
+    let mut diff = 0;
+
+    for partial_win in PARTIAL_WIN_POSITIONS.iter() {
+        let mut player_count = 0;
+        let mut opponent_count = 0;
+
+        for &index in partial_win.iter() {
+            match board_status[index as usize] {
+                GameResult::Win(p) if p == player => player_count += 1,
+                GameResult::Win(_) => opponent_count += 1,
+                _ => {}
+            }
+        }
+
+        if player_count > 0 && opponent_count == 0 {
+            diff += 1;
+        } else if opponent_count > 0 && player_count == 0 {
+            diff -= 1;
+        }
+    }
+
+    diff
 }
 
 impl Display for UltimateBoard {
@@ -419,4 +863,206 @@ mod test {
             ZOBRIST_VALUES[0] ^ ZOBRIST_VALUES[3] ^ ZOBRIST_VALUES[163]
         );
     }
+
+    #[test]
+    fn test_winning_moves() {
+        use rand::seq::IteratorRandom;
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            let mut board = UltimateBoard::new();
+
+            while board.get_game_status() == Continue {
+                for player in Player::iter() {
+                    let mut player_board = board;
+                    if player != board.get_current_player() {
+                        player_board.make_null_move();
+                    }
+
+                    let boards: Vec<(u8, UltimateBoard)> = player_board
+                        .get_possible_moves()
+                        .map(|index| {
+                            let mut board_copy = player_board;
+                            board_copy.make_move(index);
+                            (index, board_copy)
+                        })
+                        .collect();
+
+                    let mut board_winning: Vec<u8> = boards
+                        .iter()
+                        .filter(|(index, board_copy)| {
+                            board_copy.get_board_status()[(index / 9) as usize]
+                                == GameResult::Win(player)
+                        })
+                        .map(|(index, _)| *index)
+                        .collect();
+                    let mut game_winning: Vec<u8> = boards
+                        .iter()
+                        .filter(|(_, board_copy)| {
+                            board_copy.get_game_status() == GameResult::Win(player)
+                        })
+                        .map(|(index, _)| *index)
+                        .collect();
+
+                    let mut moves: Vec<u8> = board.get_board_winning_moves(player).collect();
+                    moves.sort();
+                    board_winning.sort();
+                    assert_eq!(moves, board_winning);
+
+                    let mut moves: Vec<u8> = board.get_game_winning_moves(player).collect();
+                    moves.sort();
+                    game_winning.sort();
+                    assert_eq!(moves, game_winning);
+                }
+
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+
+            assert_eq!(board.get_board_winning_moves(Player::One).count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_is_legal_move() {
+        let mut board = UltimateBoard::new();
+
+        assert!((0..81).all(|index| board.is_legal_move(index)));
+        assert!(!board.is_legal_move(81));
+
+        board.make_move(4);
+
+        for index in 0..81 {
+            assert_eq!(
+                board.is_legal_move(index),
+                board.get_possible_moves().any(|possible| possible == index)
+            );
+        }
+    }
+
+    #[test]
+    fn test_board_masks() {
+        let mut board = UltimateBoard::new();
+
+        // Player two wins the bottom row of the top left board
+        for index in [0, 8, 72, 7, 63, 6] {
+            board.make_move(index);
+        }
+
+        assert_eq!(board.won_boards_mask(Player::One), BitBoard::EMPTY);
+        assert_eq!(board.won_boards_mask(Player::Two), BitBoard::new(1));
+        assert_eq!(board.drawn_boards_mask(), BitBoard::EMPTY);
+
+        // The top left board is full without a winner
+        let position = format!("XOXXOOOXX{} - O", ".".repeat(72));
+        let board = UltimateBoard::from_position(&position).unwrap();
+
+        assert_eq!(board.get_board_status()[0], GameResult::Draw);
+        assert_eq!(board.won_boards_mask(Player::One), BitBoard::EMPTY);
+        assert_eq!(board.won_boards_mask(Player::Two), BitBoard::EMPTY);
+        assert_eq!(board.drawn_boards_mask(), BitBoard::new(1));
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        let moves = [0, 8, 72, 7, 63, 6];
+
+        let (canonical_hash, canonical_fingerprint) = {
+            let mut board = UltimateBoard::new();
+            for index in moves {
+                board.make_move(index);
+            }
+            let (hash, symmetry) = board.get_canonical_hash();
+            (hash, board.get_fingerprint(symmetry))
+        };
+
+        for symmetry in 0..NUM_SYMMETRIES {
+            let mut board = UltimateBoard::new();
+            for index in moves {
+                board.make_move(UltimateBoard::transform_move(index, symmetry));
+            }
+
+            let (hash, canonical_symmetry) = board.get_canonical_hash();
+            assert_eq!(hash, canonical_hash);
+            assert_eq!(
+                board.get_fingerprint(canonical_symmetry),
+                canonical_fingerprint
+            );
+
+            let inverse = UltimateBoard::inverse_symmetry(symmetry);
+            for index in 0..81 {
+                assert_eq!(
+                    UltimateBoard::transform_move(
+                        UltimateBoard::transform_move(index, symmetry),
+                        inverse
+                    ),
+                    index
+                );
+            }
+        }
+
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        assert_eq!(board.get_canonical_hash(), (board.get_hash(), 0));
+        assert_ne!(board.get_fingerprint(0), canonical_fingerprint);
+    }
+
+    #[test]
+    fn test_position() {
+        use rand::seq::IteratorRandom;
+
+        let mut rng = rand::thread_rng();
+        let mut board = UltimateBoard::new();
+
+        while board.get_game_status() == Continue {
+            let position = board.get_position();
+            let parsed = UltimateBoard::from_position(&position).unwrap();
+
+            assert_eq!(parsed.get_position(), position);
+            assert_eq!(parsed.get_hash(), board.get_hash());
+            assert_eq!(parsed.get_board_status(), board.get_board_status());
+            assert!(parsed.get_possible_moves().eq(board.get_possible_moves()));
+
+            board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+        }
+
+        let parsed = UltimateBoard::from_position(&board.get_position()).unwrap();
+        assert_eq!(parsed.get_game_status(), board.get_game_status());
+
+        assert!(UltimateBoard::from_position("").is_none());
+        assert!(UltimateBoard::from_position(&format!("{} 9 X", ".".repeat(81))).is_none());
+        assert!(UltimateBoard::from_position(&format!("{} - Y", ".".repeat(81))).is_none());
+    }
+
+    #[test]
+    fn test_transform() {
+        use itertools::Itertools;
+        use rand::seq::IteratorRandom;
+
+        let mut rng = rand::thread_rng();
+        let mut board = UltimateBoard::new();
+
+        while board.get_game_status() == Continue {
+            for symmetry in 0..NUM_SYMMETRIES {
+                let transformed = board.transform(symmetry);
+
+                assert_eq!(
+                    transformed.get_canonical_hash().0,
+                    board.get_canonical_hash().0
+                );
+                assert!(transformed.get_possible_moves().sorted().eq(board
+                    .get_possible_moves()
+                    .map(|index| UltimateBoard::transform_move(index, symmetry))
+                    .sorted()));
+                assert_eq!(
+                    transformed
+                        .transform(UltimateBoard::inverse_symmetry(symmetry))
+                        .get_position(),
+                    board.get_position()
+                );
+            }
+
+            board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+        }
+    }
 }
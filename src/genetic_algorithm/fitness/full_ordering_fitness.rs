@@ -9,6 +9,7 @@ use crate::game::game_result::GameResult::Win;
 use crate::game::player::Player::{One, Two};
 use crate::genetic_algorithm::fitness::FitnessFunction;
 use crate::genetic_algorithm::gene::Gene;
+use crate::heuristic::parameterized_heuristic::ParameterizedHeuristic;
 
 /// # Struct representing a full ordering fitness function
 ///
@@ -37,6 +38,12 @@ impl FitnessFunction for FullOrderingFitness {
             .collect();
         let mut genes_with_fitness = HashMap::with_capacity(genes.len());
 
+        // Keep the lookup tables of the genes, including those of their phases, alive until all games are played
+        let _heuristics: Vec<_> = genes
+            .iter()
+            .map(|gene| ParameterizedHeuristic::with_look_up_table(One, gene.get_values()))
+            .collect();
+
         enriched_genes
             .into_par_iter()
             .map(|pair| {
@@ -22,6 +22,9 @@ pub mod full_ordering_fitness;
 pub trait FitnessFunction {
     /// Calculates the fitness of the given genes
     ///
+    /// Implementations should hold the [shared lookup tables](ParameterizedHeuristic::shared_evaluation_cache)
+    /// of the genes while playing, so they are not rebuilt for every game.
+    ///
     /// # Arguments
     /// * `genes` - The genes to calculate the fitness for
     /// # Returns
@@ -30,6 +33,9 @@ pub trait FitnessFunction {
 
     /// Plays a game with the given genes
     ///
+    /// The heuristics use the [shared lookup tables](ParameterizedHeuristic::shared_evaluation_cache) of the genes,
+    /// which are only kept alive while they are in use, see [FitnessFunction::calculate_fitness].
+    ///
     /// # Arguments
     /// * `lhs` - The first gene
     /// * `rhs` - The second gene
@@ -46,12 +52,12 @@ pub trait FitnessFunction {
             Box::new(MiniMaxAgent::new(
                 depth,
                 quiescence_depth,
-                ParameterizedHeuristic::new(One, lhs.get_values()),
+                ParameterizedHeuristic::with_look_up_table(One, lhs.get_values()),
             )),
             Box::new(MiniMaxAgent::new(
                 depth,
                 quiescence_depth,
-                ParameterizedHeuristic::new(Two, rhs.get_values()),
+                ParameterizedHeuristic::with_look_up_table(Two, rhs.get_values()),
             )),
         )
         .play()
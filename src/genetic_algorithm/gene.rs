@@ -62,6 +62,19 @@ impl Gene {
         Gene { values }
     }
 
+    /// Creates a gene with separate values for every phase of the game
+    ///
+    /// Every phase starts with the values of this gene, see [ParameterizedHeuristic::values](crate::heuristic::parameterized_heuristic::ParameterizedHeuristic::values).
+    /// # Arguments
+    /// * `num_phases` - The number of phases of the game
+    /// # Returns
+    /// A new gene with the values repeated for every phase
+    pub fn with_phases(&self, num_phases: usize) -> Self {
+        Gene {
+            values: self.values.repeat(num_phases),
+        }
+    }
+
     /// Returns the values of the gene
     /// # Returns
     /// The values of the gene
@@ -11,7 +11,7 @@ use crate::genetic_algorithm::recombination::two_point_crossover::TwoPointCrosso
 use crate::genetic_algorithm::recombination::Recombination;
 use crate::genetic_algorithm::selection::roulette_wheel_selection::RouletteWheelSelection;
 use crate::genetic_algorithm::selection::Selection;
-use crate::heuristic::parameterized_heuristic::NUM_FEATURES;
+use crate::heuristic::parameterized_heuristic::{NUM_FEATURES, NUM_PHASES};
 use itertools::Itertools;
 use std::time::Instant;
 
@@ -92,11 +92,14 @@ impl GeneticAlgorithm {
     }
 }
 
+/// Runs the genetic algorithm for weights depending on the phase of the game
+///
+/// The weights of all phases of a gene start equal and are separated by the mutation and recombination.
 pub fn run() {
     let mut genes = vec![];
 
     for _ in 0..10 {
-        genes.push(Gene::new(NUM_FEATURES));
+        genes.push(Gene::new(NUM_FEATURES).with_phases(NUM_PHASES));
     }
 
     let mut genetic_algorithm = GeneticAlgorithm::new(
@@ -119,7 +122,7 @@ mod tests {
     use crate::genetic_algorithm::mutation::normal_distribution_mutation::NormalDistributionMutation;
     use crate::genetic_algorithm::recombination::two_point_crossover::TwoPointCrossover;
     use crate::genetic_algorithm::selection::roulette_wheel_selection::RouletteWheelSelection;
-    use crate::heuristic::parameterized_heuristic::NUM_FEATURES;
+    use crate::heuristic::parameterized_heuristic::{NUM_FEATURES, NUM_PHASED_FEATURES};
 
     #[test]
     fn test_genetic_algorithm() {
@@ -140,4 +143,28 @@ mod tests {
 
         genetic_algorithm.run();
     }
+
+    #[test]
+    fn test_phased_genes() {
+        let genes = (0..4)
+            .map(|_| Gene::new(NUM_FEATURES).with_phases(NUM_PHASES))
+            .collect();
+
+        let mut genetic_algorithm = GeneticAlgorithm::new(
+            1,
+            genes,
+            Box::new(FullOrderingFitness::new(2, 1)),
+            Box::new(RouletteWheelSelection {}),
+            Box::new(NormalDistributionMutation::new(0.1)),
+            Box::new(TwoPointCrossover {}),
+        );
+
+        genetic_algorithm.run();
+
+        // The genes keep the weights of every phase
+        assert!(genetic_algorithm
+            .genes
+            .iter()
+            .all(|gene| gene.get_values().len() == NUM_PHASED_FEATURES));
+    }
 }
@@ -0,0 +1,250 @@
+//! # Contains the [CombinedHeuristic] struct
+//! The CombinedHeuristic struct represents a [Heuristic] that sums the weighted evaluations of other heuristics,
+//! e.g. to experiment with blends of the [CustomHeuristic](crate::heuristic::custom_heuristic::CustomHeuristic)
+//! and the [MonteCarloGameSearchHeuristic](crate::heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic)
+//! without writing a new heuristic for every blend.
+//!
+//! The heuristics return values on very different scales, so their evaluations can be normalized before they are weighted,
+//! see [CombinedHeuristic::with_normalization].
+
+use std::sync::Arc;
+
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{is_proven, Heuristic, MAX_ESTIMATE};
+
+/// Object safe part of [Heuristic], so heuristics of different types can be combined
+trait DynHeuristic: Send + Sync {
+    fn evaluate_board(&self, board: UltimateBoard) -> f64;
+    fn evaluate_board_for(&self, board: UltimateBoard, player: Player) -> f64;
+    fn heuristic_name(&self) -> String;
+}
+
+impl<H: Heuristic> DynHeuristic for H {
+    fn evaluate_board(&self, board: UltimateBoard) -> f64 {
+        self.evaluate(board)
+    }
+
+    fn evaluate_board_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        self.evaluate_for(board, player)
+    }
+
+    fn heuristic_name(&self) -> String {
+        self.get_name()
+    }
+}
+
+/// # Struct representing a heuristic of a [CombinedHeuristic]
+///
+/// # Fields
+/// * `heuristic` - The heuristic, shared by the clones of the combined heuristic
+/// * `weight` - The weight of the normalized evaluation
+/// * `scale` - The evaluations are divided by the scale before they are weighted
+#[derive(Clone)]
+struct Component {
+    heuristic: Arc<dyn DynHeuristic>,
+    weight: f64,
+    scale: f64,
+}
+
+/// A [Heuristic] that sums the weighted evaluations of other heuristics
+///
+/// If one of the heuristics evaluates the board as won or lost, i.e. returns [MAX_VALUE](crate::heuristic::MAX_VALUE)
+/// or [MIN_VALUE](crate::heuristic::MIN_VALUE), the combined heuristic returns the same value.
+/// Otherwise the sum is clamped to [-MAX_ESTIMATE](MAX_ESTIMATE) and [MAX_ESTIMATE].
+///
+/// All heuristics should evaluate the board for the same player,
+/// with [Heuristic::evaluate_for] the board is evaluated by all heuristics for the given player.
+///
+/// # Example
+/// ```
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::combined_heuristic::CombinedHeuristic;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+/// use hausarbeit::heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic;
+///
+/// let heuristic = CombinedHeuristic::new()
+///     .with_heuristic(CustomHeuristic::new(Player::One), 0.8)
+///     .with_heuristic(MonteCarloGameSearchHeuristic::new(Player::One, 2), 0.2)
+///     .with_normalization(10);
+/// ```
+#[derive(Clone, Default)]
+pub struct CombinedHeuristic {
+    components: Vec<Component>,
+}
+
+impl CombinedHeuristic {
+    /// Creates a new [CombinedHeuristic] without any heuristics, which evaluates every board with 0
+    pub fn new() -> Self {
+        CombinedHeuristic { components: vec![] }
+    }
+
+    /// Adds a heuristic
+    ///
+    /// The evaluations of the heuristic are not normalized until [CombinedHeuristic::with_normalization] is called.
+    /// # Arguments
+    /// * `heuristic` - The heuristic to add
+    /// * `weight` - The weight of the evaluations of the heuristic
+    /// # Returns
+    /// The combined heuristic including the heuristic
+    pub fn with_heuristic(mut self, heuristic: impl Heuristic + 'static, weight: f64) -> Self {
+        self.components.push(Component {
+            heuristic: Arc::new(heuristic),
+            weight,
+            scale: 1.,
+        });
+        self
+    }
+
+    /// Normalizes the evaluations of the heuristics
+    ///
+    /// The scale of each heuristic is the mean absolute evaluation of positions reached by random moves,
+    /// so the normalized evaluations of all heuristics are around 1 and the weights are comparable.
+    /// The positions are generated with a fixed seed, so the scales are reproducible.
+    /// Won and lost positions are not used.
+    /// # Arguments
+    /// * `num_positions` - The number of positions to evaluate
+    /// # Returns
+    /// The combined heuristic with normalized evaluations
+    pub fn with_normalization(mut self, num_positions: usize) -> Self {
+        let positions = random_positions(num_positions);
+
+        for component in &mut self.components {
+            let values: Vec<f64> = positions
+                .iter()
+                .map(|&board| component.heuristic.evaluate_board(board).abs())
+                .filter(|value| !is_proven(*value))
+                .collect();
+            let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+
+            component.scale = if mean > 0. { mean } else { 1. };
+        }
+
+        self
+    }
+
+    /// Sums the weighted, normalized evaluations of the heuristics
+    ///
+    /// # Arguments
+    /// * `evaluate` - Evaluates the board with a heuristic
+    /// # Returns
+    /// The combined value, a proven value of a heuristic is returned unchanged
+    fn combine(&self, evaluate: impl Fn(&dyn DynHeuristic) -> f64) -> f64 {
+        let mut value = 0.;
+
+        for component in &self.components {
+            let evaluation = evaluate(component.heuristic.as_ref());
+
+            if is_proven(evaluation) {
+                return evaluation;
+            }
+
+            value += component.weight * evaluation / component.scale;
+        }
+
+        value.clamp(-MAX_ESTIMATE, MAX_ESTIMATE)
+    }
+
+    /// Get the scales the evaluations of the heuristics are divided by, in the order the heuristics were added
+    pub fn get_scales(&self) -> Vec<f64> {
+        self.components
+            .iter()
+            .map(|component| component.scale)
+            .collect()
+    }
+}
+
+impl Heuristic for CombinedHeuristic {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.combine(|heuristic| heuristic.evaluate_board(board))
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        self.combine(|heuristic| heuristic.evaluate_board_for(board, player))
+    }
+
+    fn get_name(&self) -> String {
+        let components: Vec<String> = self
+            .components
+            .iter()
+            .map(|component| {
+                format!(
+                    "{}*{}",
+                    component.weight,
+                    component.heuristic.heuristic_name()
+                )
+            })
+            .collect();
+
+        format!("CH({})", components.join("+"))
+    }
+}
+
+/// Generates positions by playing random moves from the empty board
+///
+/// # Arguments
+/// * `num_positions` - The number of positions
+/// # Returns
+/// Positions with up to 40 moves, none of them finished
+fn random_positions(num_positions: usize) -> Vec<UltimateBoard> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut positions = Vec::with_capacity(num_positions);
+
+    while positions.len() < num_positions {
+        let mut board = UltimateBoard::new();
+        let num_moves = positions.len() % 40;
+
+        for _ in 0..num_moves {
+            let moves: Vec<u8> = board.get_possible_moves().collect();
+            board.make_move(*moves.choose(&mut rng).unwrap());
+            if board.get_game_status() != GameResult::Continue {
+                break;
+            }
+        }
+
+        if board.get_game_status() == GameResult::Continue {
+            positions.push(board);
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+
+    #[test]
+    fn test_combined_heuristic() {
+        let custom = CustomHeuristic::new(Player::One);
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 41] {
+            board.make_move(index);
+        }
+
+        let heuristic = CombinedHeuristic::new()
+            .with_heuristic(custom.clone(), 2.)
+            .with_heuristic(CustomHeuristic::new(Player::One), 1.);
+        assert_eq!(heuristic.evaluate(board), 3. * custom.evaluate(board));
+        assert_eq!(heuristic.get_name(), "CH(2*SH+1*SH)");
+
+        // Both heuristics have the same scale, which divides the evaluations
+        let normalized = heuristic.with_normalization(50);
+        let scales = normalized.get_scales();
+        assert_eq!(scales[0], scales[1]);
+        assert!(scales[0] > 0.);
+        let expected = (3. * custom.evaluate(board) / scales[0]).clamp(-MAX_ESTIMATE, MAX_ESTIMATE);
+        assert!((normalized.evaluate(board) - expected).abs() < 1e-9);
+
+        // A won position is passed through
+        let squares = format!("XXX......XXX......XXX......{}", ".".repeat(54));
+        let won = UltimateBoard::from_position(&format!("{} - O", squares)).unwrap();
+        assert_eq!(normalized.evaluate(won), crate::heuristic::MAX_VALUE);
+    }
+}
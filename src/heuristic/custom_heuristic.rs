@@ -1,8 +1,9 @@
 //! # Contains the [CustomHeuristic] and [CustomMiniBoardHeuristic] struct
 //! The CustomHeuristic struct represents a heuristic that uses a custom evaluation function.
 //! The heuristic is used by the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent) to evaluate the best move.
-
-use std::collections::HashMap;
+//!
+//! The small boards and the won small boards are evaluated with lookup tables,
+//! computed by the [CustomMiniBoardHeuristic] and the [CustomMetaBoardHeuristic].
 
 use once_cell::sync::Lazy;
 
@@ -10,14 +11,29 @@ use crate::game::board::Board;
 use crate::game::game_result::GameResult;
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
-use crate::heuristic::{Heuristic, MiniBoardHeuristic, MAX_VALUE, MIN_VALUE};
+use crate::heuristic::{
+    normalize, small_board_index, Heuristic, MetaBoardHeuristic, MiniBoardHeuristic, MAX_VALUE,
+    MIN_VALUE,
+};
 
 /// # Contains the evaluation of all legal [boards](Board) for the [CustomMiniBoardHeuristic].
 ///
 /// The evaluation is calculated from the perspective of [Player::One].
-static SMALL_BOARD_LOOKUP_TABLE: Lazy<HashMap<u32, f64>> =
+static SMALL_BOARD_LOOKUP_TABLE: Lazy<Vec<f64>> =
     Lazy::new(|| CustomMiniBoardHeuristic.initialize());
 
+/// The raw evaluation that is [normalized](normalize) to about 0.75
+const SCALE: f64 = 50.;
+
+/// The raw evaluation of having the next move
+const TEMPO: f64 = 2.;
+
+/// # Contains the evaluation of all configurations of won small boards for the [CustomMetaBoardHeuristic].
+///
+/// The evaluation is calculated from the perspective of [Player::One].
+static META_BOARD_LOOKUP_TABLE: Lazy<Vec<f64>> =
+    Lazy::new(|| CustomMetaBoardHeuristic.initialize());
+
 /// A [Heuristic] that uses a custom evaluation function to evaluate the best move.
 /// # Fields
 /// * `player` - The [Player] for which the heuristic should evaluate the best move.
@@ -28,47 +44,94 @@ pub struct CustomHeuristic {
 
 impl CustomHeuristic {
     pub fn new(player: Player) -> Self {
-        let _ = SMALL_BOARD_LOOKUP_TABLE.get(&0).unwrap();
+        let _ = SMALL_BOARD_LOOKUP_TABLE[0];
+        let _ = META_BOARD_LOOKUP_TABLE[0];
         CustomHeuristic { player }
     }
 }
 
-impl Heuristic for CustomHeuristic {
-    fn evaluate(&self, board: UltimateBoard) -> f64 {
+impl CustomHeuristic {
+    /// Evaluates a small board from the perspective of a player
+    fn get_small_board_value(board: Board, player: Player) -> f64 {
+        SMALL_BOARD_LOOKUP_TABLE[small_board_index(board.to_key())]
+            * (if player == Player::One { 1 } else { -1 }) as f64
+    }
+
+    /// Evaluates the board state for a player using the evaluations of its small boards for the player
+    fn evaluate_position(board: UltimateBoard, small_boards: &[f64; 9], player: Player) -> f64 {
         let mut value = 0.;
 
-        if board.get_game_status() == GameResult::Win(self.player) {
+        if board.get_game_status() == GameResult::Win(player) {
             return MAX_VALUE;
         }
 
-        if board.get_game_status() == GameResult::Win(self.player.get_opponent()) {
+        if board.get_game_status() == GameResult::Win(player.get_opponent()) {
             return MIN_VALUE;
         }
 
         // Reward having more positions set on small boards than the opponent
-        for small_board in board.get_boards() {
-            value += *SMALL_BOARD_LOOKUP_TABLE.get(&small_board.to_key()).unwrap()
-                * (if self.player == Player::One { 1 } else { -1 }) as f64;
+        for small_board_value in small_boards {
+            value += small_board_value;
         }
 
-        // Reward controlLing the center of the board
-        if board.get_board_status()[4] == GameResult::Win(self.player) {
-            value += 10.;
-        }
+        // Reward controlling the center of the board and having more small boards won than the opponent
+        value += META_BOARD_LOOKUP_TABLE[board.get_meta_board_key(player) as usize];
 
-        // Reward having more small boards won than the opponent
-        for board_status in board.get_board_status() {
-            if let GameResult::Win(winner) = board_status {
-                if winner == self.player {
-                    value += 10.;
-                } else {
-                    value -= 10.;
-                }
-            }
-        }
+        // Reward having the next move
+        value += if board.get_current_player() == player {
+            TEMPO
+        } else {
+            -TEMPO
+        };
 
-        value
+        normalize(value, SCALE)
+    }
+}
+
+impl Heuristic for CustomHeuristic {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.evaluate_for(board, self.player)
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        CustomHeuristic::evaluate_position(
+            board,
+            &board
+                .get_boards()
+                .map(|small_board| CustomHeuristic::get_small_board_value(small_board, player)),
+            player,
+        )
     }
+
+    /// The features are the values of the small boards
+    fn initialize_features(&self, board: UltimateBoard, player: Player) -> Option<Vec<f64>> {
+        Some(
+            board
+                .get_boards()
+                .map(|small_board| CustomHeuristic::get_small_board_value(small_board, player))
+                .to_vec(),
+        )
+    }
+
+    /// Only the small board the move was made on is reevaluated
+    fn update_features(
+        &self,
+        features: &mut [f64],
+        _previous: UltimateBoard,
+        board: UltimateBoard,
+        index: u8,
+        player: Player,
+    ) {
+        let board_index = (index / 9) as usize;
+
+        features[board_index] =
+            CustomHeuristic::get_small_board_value(board.get_boards()[board_index], player);
+    }
+
+    fn evaluate_features(&self, board: UltimateBoard, player: Player, features: &[f64]) -> f64 {
+        CustomHeuristic::evaluate_position(board, features.try_into().unwrap(), player)
+    }
+
     fn get_name(&self) -> String {
         "SH".to_string()
     }
@@ -93,3 +156,50 @@ impl MiniBoardHeuristic for CustomMiniBoardHeuristic {
         value
     }
 }
+
+/// A [MetaBoardHeuristic] that uses a custom evaluation function to evaluate the won small boards.
+pub struct CustomMetaBoardHeuristic;
+
+impl MetaBoardHeuristic for CustomMetaBoardHeuristic {
+    fn evaluate(&self, board_status: [GameResult; 9]) -> f64 {
+        let mut value = 0.;
+
+        if board_status[4] == GameResult::Win(Player::One) {
+            value += 10.;
+        }
+
+        for status in board_status {
+            if let GameResult::Win(winner) = status {
+                if winner == Player::One {
+                    value += 10.;
+                } else {
+                    value -= 10.;
+                }
+            }
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo() {
+        let squares = format!("....X....{}", ".".repeat(72));
+        let x_to_move = UltimateBoard::from_position(&format!("{} - X", squares)).unwrap();
+        let o_to_move = UltimateBoard::from_position(&format!("{} - O", squares)).unwrap();
+
+        // Both heuristics prefer having the next move
+        let one = CustomHeuristic::new(Player::One);
+        let two = CustomHeuristic::new(Player::Two);
+        assert!(one.evaluate(x_to_move) > one.evaluate(o_to_move));
+        assert!(two.evaluate(o_to_move) > two.evaluate(x_to_move));
+
+        // The evaluations of the players are consistent
+        assert_eq!(one.evaluate(x_to_move), -two.evaluate(x_to_move));
+        assert_eq!(one.evaluate(o_to_move), -two.evaluate(o_to_move));
+    }
+}
@@ -0,0 +1,488 @@
+//! # Contains the [EvaluationCache] struct
+//!
+//! The [EvaluationCache] contains the evaluations of a [Heuristic](crate::heuristic::Heuristic) that can be reused
+//! by multiple agents and games, e.g. the agents of a tournament that use the same weights.
+//!
+//! The cache consists of two parts:
+//! * The lookup table of the evaluation of all legal small boards up to symmetry, computed once by a [MiniBoardHeuristic]
+//! * An optional lookup table of the evaluation of all configurations of won small boards, computed once by a [MetaBoardHeuristic]
+//! * An optional cache of the evaluations of whole positions, keyed by the [Zobrist hash](crate::game::ultimate_board::UltimateBoard::get_hash)
+//!   and verified by the [fingerprint](crate::game::ultimate_board::UltimateBoard::get_fingerprint) of the position
+//!
+//! With weights for every phase of the game, the cache instead contains the [caches of the phases](EvaluationCache::from_phases),
+//! so the lookup tables of every phase are only built once.
+//!
+//! The cache is shared using an [Arc]. The position cache is split into shards guarded by their own lock,
+//! so agents playing in parallel can use it at the same time.
+use crate::game::player::Player;
+use crate::heuristic::{
+    small_board_index, MetaBoardHeuristic, MiniBoardHeuristic, LOOKUP_TABLE_SIZE,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The number of independently locked shards of the position cache per player
+const NUM_SHARDS: usize = 64;
+
+/// # Struct representing a stored evaluation of a position
+///
+/// # Fields
+/// * `fingerprint` - The fingerprint of the position, to detect key collisions
+/// * `value` - The evaluation of the position
+#[derive(Clone, Copy, Debug)]
+struct PositionEntry {
+    fingerprint: u64,
+    value: f64,
+}
+
+/// The shards of the position cache of a single player
+type PositionShards = Vec<Mutex<HashMap<u64, PositionEntry>>>;
+
+/// # Struct containing evaluations shared by multiple heuristics
+///
+/// All heuristics using the same cache must evaluate the boards the same way, e.g. use the same weights.
+/// The weights the cache was built with can be [recorded](EvaluationCache::with_weights),
+/// so heuristics can verify that they match their own weights.
+/// The small boards are evaluated from the perspective of [Player::One],
+/// the positions are stored separately for the player the heuristic evaluates for.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::heuristic::evaluation_cache::EvaluationCache;
+/// use hausarbeit::heuristic::parameterized_heuristic::{ParameterizedHeuristic, ParameterizedMiniBoardHeuristic};
+///
+/// let values = vec![1.; 12];
+///
+/// let cache = Arc::new(
+///     EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values.clone()))
+///         .with_weights(values.clone())
+///         .with_position_cache(1 << 16),
+/// );
+///
+/// let first = ParameterizedHeuristic::with_evaluation_cache(Player::One, values.clone(), cache.clone());
+/// let second = ParameterizedHeuristic::with_evaluation_cache(Player::Two, values, cache);
+/// ```
+#[derive(Debug)]
+pub struct EvaluationCache {
+    small_board_lookup_table: Vec<f64>,
+    meta_board_lookup_table: Option<Vec<f64>>,
+    weights: Option<Vec<f64>>,
+    phases: Vec<Arc<EvaluationCache>>,
+    positions: Option<[PositionShards; 2]>,
+    shard_capacity: usize,
+}
+
+impl EvaluationCache {
+    /// Creates a new [EvaluationCache] without a position cache
+    ///
+    /// The lookup table of the small boards is [initialized](MiniBoardHeuristic::initialize) with the given heuristic.
+    /// # Arguments
+    /// * `mini_board_heuristic` - The heuristic used to evaluate the small boards
+    pub fn new(mini_board_heuristic: &impl MiniBoardHeuristic) -> Self {
+        EvaluationCache {
+            small_board_lookup_table: mini_board_heuristic.initialize(),
+            meta_board_lookup_table: None,
+            weights: None,
+            phases: vec![],
+            positions: None,
+            shard_capacity: 0,
+        }
+    }
+
+    /// Creates a new [EvaluationCache] of weights for every phase of the game, without a position cache
+    ///
+    /// The cache has no lookup tables of its own, the lookup tables are taken from the caches of the phases.
+    /// # Arguments
+    /// * `phases` - The caches of the weights of the opening, the middlegame and the endgame
+    pub fn from_phases(phases: Vec<Arc<EvaluationCache>>) -> Self {
+        EvaluationCache {
+            small_board_lookup_table: vec![],
+            meta_board_lookup_table: None,
+            weights: None,
+            phases,
+            positions: None,
+            shard_capacity: 0,
+        }
+    }
+
+    /// Adds the lookup table of the meta-board
+    ///
+    /// # Arguments
+    /// * `meta_board_heuristic` - The heuristic used to evaluate the configurations of won small boards
+    /// # Returns
+    /// The cache with the lookup table of the meta-board
+    pub fn with_meta_board_heuristic(
+        mut self,
+        meta_board_heuristic: &impl MetaBoardHeuristic,
+    ) -> Self {
+        self.meta_board_lookup_table = Some(meta_board_heuristic.initialize());
+        self
+    }
+
+    /// Records the weights the lookup tables were built with
+    ///
+    /// # Arguments
+    /// * `weights` - The weights of the heuristics using the cache
+    /// # Returns
+    /// The cache with the weights recorded
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Returns the weights the lookup tables were built with
+    ///
+    /// # Returns
+    /// The weights, None if they were not [recorded](EvaluationCache::with_weights)
+    pub fn get_weights(&self) -> Option<&[f64]> {
+        self.weights.as_deref()
+    }
+
+    /// Returns the caches of the phases of the game
+    ///
+    /// # Returns
+    /// The caches of the opening, the middlegame and the endgame, empty if the cache was not [created from phases](EvaluationCache::from_phases)
+    pub fn get_phases(&self) -> &[Arc<EvaluationCache>] {
+        &self.phases
+    }
+
+    /// Enables the cache of the evaluations of whole positions
+    ///
+    /// If a shard of the cache is full, a new position replaces an arbitrary position of the shard.
+    /// # Arguments
+    /// * `capacity` - The maximum number of positions per player
+    /// # Returns
+    /// The cache with the position cache enabled
+    pub fn with_position_cache(mut self, capacity: usize) -> Self {
+        let shards = || {
+            (0..NUM_SHARDS)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect()
+        };
+
+        self.positions = Some([shards(), shards()]);
+        self.shard_capacity = capacity.div_ceil(NUM_SHARDS);
+        self
+    }
+
+    /// Checks if the cache of the evaluations of whole positions is enabled
+    pub fn has_position_cache(&self) -> bool {
+        self.positions.is_some()
+    }
+
+    /// Returns the evaluation of a small board
+    ///
+    /// # Arguments
+    /// * `key` - The [key](crate::game::board::Board::to_key) of the small board
+    /// # Returns
+    /// The evaluation of the small board from the perspective of [Player::One],
+    /// None if the key has more than 18 bits or the cache has no lookup table of the small boards
+    pub fn get_small_board_value(&self, key: u32) -> Option<f64> {
+        if key as usize >= LOOKUP_TABLE_SIZE {
+            return None;
+        }
+
+        self.small_board_lookup_table
+            .get(small_board_index(key))
+            .copied()
+    }
+
+    /// Returns the evaluation of the meta-board
+    ///
+    /// # Arguments
+    /// * `key` - The [key](crate::game::ultimate_board::UltimateBoard::get_meta_board_key) of the meta-board
+    /// # Returns
+    /// The evaluation of the meta-board from the perspective of the player of the key,
+    /// None if the key has more than 18 bits or the cache has no lookup table of the meta-board
+    pub fn get_meta_board_value(&self, key: u32) -> Option<f64> {
+        self.meta_board_lookup_table
+            .as_ref()?
+            .get(key as usize)
+            .copied()
+    }
+
+    /// Locks the shard of the position cache containing the given hash
+    ///
+    /// # Returns
+    /// The shard, None if the position cache is disabled
+    fn shard(
+        &self,
+        hash: u64,
+        player: Player,
+    ) -> Option<MutexGuard<'_, HashMap<u64, PositionEntry>>> {
+        let shards = &self.positions.as_ref()?[player as usize];
+
+        // A panicking thread does not leave a value in an inconsistent state
+        Some(
+            shards[hash as usize % NUM_SHARDS]
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        )
+    }
+
+    /// Looks up the evaluation of a position
+    ///
+    /// If the evaluation was stored with a different fingerprint, it belongs to another position with the same hash
+    /// and is not returned.
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// * `fingerprint` - The fingerprint of the position
+    /// * `player` - The player the position was evaluated for
+    /// # Returns
+    /// The evaluation of the position, None if the position is not stored or the position cache is disabled
+    pub fn probe_position(&self, hash: u64, fingerprint: u64, player: Player) -> Option<f64> {
+        self.shard(hash, player)?
+            .get(&hash)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| entry.value)
+    }
+
+    /// Stores the evaluation of a position
+    ///
+    /// Nothing is stored if the position cache is disabled.
+    /// If the shard of the position is full, an arbitrary position of the shard is removed first.
+    /// # Arguments
+    /// * `hash` - The hash of the position
+    /// * `fingerprint` - The fingerprint of the position
+    /// * `player` - The player the position was evaluated for
+    /// * `value` - The evaluation of the position
+    pub fn store_position(&self, hash: u64, fingerprint: u64, player: Player, value: f64) {
+        let shard_capacity = self.shard_capacity;
+
+        let Some(mut shard) = self.shard(hash, player) else {
+            return;
+        };
+
+        if shard.len() >= shard_capacity && !shard.contains_key(&hash) {
+            if let Some(&replaced) = shard.keys().next() {
+                shard.remove(&replaced);
+            }
+        }
+
+        shard.insert(hash, PositionEntry { fingerprint, value });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::Board;
+    use crate::game::ultimate_board::UltimateBoard;
+    use crate::heuristic::parameterized_heuristic::{
+        ParameterizedHeuristic, ParameterizedMiniBoardHeuristic, NUM_FEATURES, NUM_PHASED_FEATURES,
+        NUM_PHASES,
+    };
+    use crate::heuristic::Heuristic;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_shared_cache() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+
+        let cache = Arc::new(
+            EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values.clone()))
+                .with_weights(values.clone())
+                .with_position_cache(1024),
+        );
+
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 44, 74] {
+            board.make_move(index);
+        }
+
+        let (hash, fingerprint) = (board.get_hash(), board.get_fingerprint(0));
+
+        for player in Player::iter() {
+            let uncached = ParameterizedHeuristic::new(player, values.clone());
+            let first = ParameterizedHeuristic::with_evaluation_cache(
+                player,
+                values.clone(),
+                cache.clone(),
+            );
+            let second = ParameterizedHeuristic::with_evaluation_cache(
+                player,
+                values.clone(),
+                cache.clone(),
+            );
+
+            assert_eq!(cache.probe_position(hash, fingerprint, player), None);
+            assert_eq!(first.evaluate(board), uncached.evaluate(board));
+            assert_eq!(
+                cache.probe_position(hash, fingerprint, player),
+                Some(uncached.evaluate(board))
+            );
+            assert_eq!(second.evaluate(board), uncached.evaluate(board));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cache_of_other_weights() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+        let mut other_values = values.clone();
+        other_values[3] = 2.;
+
+        let cache = Arc::new(
+            EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(other_values.clone()))
+                .with_weights(other_values),
+        );
+
+        ParameterizedHeuristic::with_evaluation_cache(Player::One, values, cache);
+    }
+
+    #[test]
+    fn test_position_collision() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+        let cache = EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values))
+            .with_position_cache(1024);
+
+        cache.store_position(7, 1, Player::One, 0.5);
+        assert_eq!(cache.probe_position(7, 1, Player::One), Some(0.5));
+        assert_eq!(cache.probe_position(7, 1, Player::Two), None);
+
+        // Another position with the same hash
+        assert_eq!(cache.probe_position(7, 2, Player::One), None);
+        cache.store_position(7, 2, Player::One, -0.5);
+        assert_eq!(cache.probe_position(7, 2, Player::One), Some(-0.5));
+        assert_eq!(cache.probe_position(7, 1, Player::One), None);
+    }
+
+    #[test]
+    fn test_full_position_cache() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+
+        // A single position per shard
+        let cache = EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values))
+            .with_position_cache(NUM_SHARDS);
+
+        cache.store_position(1, 1, Player::One, 0.25);
+        cache.store_position(1 + NUM_SHARDS as u64, 2, Player::One, 0.75);
+
+        assert_eq!(cache.probe_position(1, 1, Player::One), None);
+        assert_eq!(
+            cache.probe_position(1 + NUM_SHARDS as u64, 2, Player::One),
+            Some(0.75)
+        );
+    }
+
+    #[test]
+    fn test_meta_board_lookup_table() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+
+        // X won the top left and the center board, O won the bottom right board
+        let squares = format!(
+            "XXX......{}XXX......{}OOO......",
+            "O........".repeat(3),
+            ".........".repeat(3)
+        );
+        let board = UltimateBoard::from_position(&format!("{} - X", squares)).unwrap();
+        assert_eq!(board.get_meta_board_key(Player::One), 0b000010000_100000001);
+        assert_eq!(board.get_meta_board_key(Player::Two), 0b100000001_000010000);
+
+        for player in Player::iter() {
+            let uncached = ParameterizedHeuristic::new(player, values.clone());
+            let cached = ParameterizedHeuristic::with_look_up_table(player, values.clone());
+
+            assert_eq!(cached.evaluate(board), uncached.evaluate(board));
+        }
+    }
+
+    #[test]
+    fn test_shared_lookup_table() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 8. + 0.5).collect();
+
+        let first = ParameterizedHeuristic::shared_evaluation_cache(&values);
+        let second = ParameterizedHeuristic::shared_evaluation_cache(&values.clone());
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let mut other_values = values.clone();
+        other_values[0] = -other_values[0];
+        let other = ParameterizedHeuristic::shared_evaluation_cache(&other_values);
+        assert!(!Arc::ptr_eq(&first, &other));
+
+        // The heuristic uses the shared lookup tables instead of building new ones
+        let _heuristic = ParameterizedHeuristic::with_look_up_table(Player::One, values);
+        assert_eq!(Arc::strong_count(&first), 3);
+    }
+
+    #[test]
+    fn test_shared_phase_lookup_tables() {
+        let values: Vec<f64> = (0..NUM_PHASED_FEATURES)
+            .map(|i| i as f64 / 16. - 1.)
+            .collect();
+
+        let cache = ParameterizedHeuristic::shared_evaluation_cache(&values);
+        assert_eq!(cache.get_phases().len(), NUM_PHASES);
+        assert_eq!(cache.get_small_board_value(0), None);
+
+        for (phase, weights) in cache.get_phases().iter().zip(values.chunks(NUM_FEATURES)) {
+            assert!(Arc::ptr_eq(
+                phase,
+                &ParameterizedHeuristic::shared_evaluation_cache(weights)
+            ));
+        }
+
+        // The heuristics use the lookup tables of the phases instead of building new ones
+        let _first = ParameterizedHeuristic::with_look_up_table(Player::One, values.clone());
+        let _second = ParameterizedHeuristic::with_look_up_table(Player::Two, values.clone());
+        assert_eq!(Arc::strong_count(&cache), 3);
+        assert!(cache
+            .get_phases()
+            .iter()
+            .all(|phase| Arc::strong_count(phase) == 3));
+
+        // A position cache of phased weights uses the shared lookup tables of the phases
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 44, 74] {
+            board.make_move(index);
+        }
+
+        let position_cache = Arc::new(
+            EvaluationCache::from_phases(cache.get_phases().to_vec())
+                .with_weights(values.clone())
+                .with_position_cache(1024),
+        );
+
+        for player in Player::iter() {
+            let uncached = ParameterizedHeuristic::new(player, values.clone());
+            let cached = ParameterizedHeuristic::with_evaluation_cache(
+                player,
+                values.clone(),
+                position_cache.clone(),
+            );
+
+            assert!((cached.evaluate(board) - uncached.evaluate(board)).abs() < 1e-9);
+            assert!(position_cache
+                .probe_position(board.get_hash(), board.get_fingerprint(0), player)
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn test_symmetric_small_boards() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64 / 4. - 1.).collect();
+        let cache = EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values));
+
+        assert_eq!(
+            cache.small_board_lookup_table.len(),
+            crate::heuristic::SMALL_BOARD_TABLE_SIZE
+        );
+        assert_eq!(cache.get_small_board_value(1 << 18), None);
+
+        // An X in every corner of a board and an O on the edges
+        let mut keys = vec![];
+        for (corner, edge) in [(0, 1), (2, 5), (8, 7), (6, 3)] {
+            let mut board = Board::new(0);
+            board.set(corner, Player::One);
+            board.set(edge, Player::Two);
+            keys.push(board.to_key());
+        }
+
+        let value = cache.get_small_board_value(keys[0]).unwrap();
+        assert!(keys
+            .iter()
+            .all(|&key| cache.get_small_board_value(key) == Some(value)));
+    }
+}
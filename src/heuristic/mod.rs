@@ -4,27 +4,83 @@
 //!
 //! The MiniBoardHeuristic trait represents a heuristic that evaluates a [Board] state.
 //! The heuristic should return a value that represents how good the board state is.
+//!
+//! All heuristics return values on the same normalized scale from [MIN_VALUE] to [MAX_VALUE],
+//! the limits are reserved for proven results, see [normalize] and [is_proven].
+//!
+//! The MetaBoardHeuristic trait represents a heuristic that evaluates the status of the small boards of a [UltimateBoard].
 
+pub mod combined_heuristic;
 pub mod custom_heuristic;
+pub mod evaluation_cache;
 pub mod monte_carlo_game_search_heuristic;
+#[cfg(feature = "onnx")]
+pub mod nn_heuristic;
 pub mod parameterized_heuristic;
+pub mod tablebase_heuristic;
+pub mod threat_heuristic;
 
 use crate::game::bitboard::BitBoard;
 use crate::game::board::{Board, LegalBoardIterator};
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
-use std::collections::HashMap;
+use once_cell::sync::Lazy;
 
-/// The minimum value a heuristic can return
-pub static MIN_VALUE: f64 = f64::MIN;
-/// The maximum value a heuristic can return
-pub static MAX_VALUE: f64 = f64::MAX;
+/// The minimum value a heuristic can return, reserved for proven losses
+pub static MIN_VALUE: f64 = -1.;
+/// The maximum value a heuristic can return, reserved for proven wins
+pub static MAX_VALUE: f64 = 1.;
+/// The maximum absolute value of a board state whose result is not proven
+pub static MAX_ESTIMATE: f64 = 0.99;
+
+/// Maps a raw evaluation onto the normalized scale of the heuristics
+///
+/// The value is `MAX_ESTIMATE * tanh(value / scale)`, so the order of the values is kept
+/// and the result is strictly between [MIN_VALUE] and [MAX_VALUE].
+/// # Arguments
+/// * `value` - The raw evaluation
+/// * `scale` - The raw evaluation that is mapped to about 0.75, must be positive
+/// # Returns
+/// The normalized evaluation
+pub fn normalize(value: f64, scale: f64) -> f64 {
+    (value / scale).tanh() * MAX_ESTIMATE
+}
+
+/// Returns whether a value of a heuristic or a search is a proven win or loss
+///
+/// # Arguments
+/// * `value` - The value
+/// # Returns
+/// True if the value is [MAX_VALUE] or [MIN_VALUE] or beyond
+pub fn is_proven(value: f64) -> bool {
+    value >= MAX_VALUE || value <= MIN_VALUE
+}
+
+/// Maps a value of a heuristic to a win probability
+///
+/// Proven wins and losses have the probability 1 and 0, other values are mapped to `1 / (1 + exp(-value / scale))`.
+/// # Arguments
+/// * `value` - The value of the heuristic
+/// * `scale` - The value that corresponds to a win probability of about 73%, must be positive
+/// # Returns
+/// The win probability
+pub fn win_probability(value: f64, scale: f64) -> f64 {
+    if is_proven(value) {
+        return if value > 0. { 1. } else { 0. };
+    }
+
+    1. / (1. + (-value / scale).exp())
+}
 
 /// # Trait representing a heuristic
 ///
 /// A heuristic is a function that evaluates a [UltimateBoard] state.
 /// The heuristic should return a value that represents how good the board state is for a specified player.
 /// The maximum and minimum values are defined by the constants [MIN_VALUE] and [MAX_VALUE].
-/// The heuristic should return a value between these two values.
+/// The heuristic should return these values for won and lost board states
+/// and a value between [-MAX_ESTIMATE](MAX_ESTIMATE) and [MAX_ESTIMATE] otherwise, e.g. using [normalize].
+/// The board state can also be evaluated for the other player with [Heuristic::evaluate_for].
 /// <p>Implementations may provide customization options.</p>
 pub trait Heuristic: Clone + Send + Sync {
     /// Evaluate the given board state
@@ -35,12 +91,120 @@ pub trait Heuristic: Clone + Send + Sync {
     /// # Returns
     /// The value of the board state, always between [MIN_VALUE] and [MAX_VALUE]
     fn evaluate(&self, board: UltimateBoard) -> f64;
+
+    /// Evaluate the given board state for the given player
+    ///
+    /// In contrast to [Heuristic::evaluate], the board state is not evaluated for the player the heuristic was created for,
+    /// so a single heuristic can evaluate board states for both players, e.g. for the player to move.
+    /// Must return the same value as [Heuristic::evaluate] for the player of the heuristic.
+    /// # Arguments
+    /// * `board` - The board state to evaluate
+    /// * `player` - The player to evaluate the board state for
+    /// # Returns
+    /// The value of the board state for the player, always between [MIN_VALUE] and [MAX_VALUE]
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64;
+
     fn get_name(&self) -> String;
+
+    /// Computes the features of the given board state for the incremental evaluation during a search
+    ///
+    /// Heuristics whose value is computed from features that only change locally with a move can implement this method
+    /// together with [Heuristic::update_features] and [Heuristic::evaluate_features],
+    /// e.g. the evaluations of the small boards. The search then only updates the features a move changed,
+    /// see [IncrementalEvaluation](crate::agent::minimax_agent::incremental_evaluation::IncrementalEvaluation).
+    ///
+    /// The features must not depend on the player to move and the small board the player is sent to,
+    /// as they are not updated for [null moves](UltimateBoard::make_null_move).
+    /// # Arguments
+    /// * `board` - The board state
+    /// * `player` - The player the board state is evaluated for
+    /// # Returns
+    /// The features of the board state, None if incremental evaluation is not supported
+    fn initialize_features(&self, _board: UltimateBoard, _player: Player) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Updates the features after a move
+    ///
+    /// Only called if [Heuristic::initialize_features] returns features. By default, the features are computed from scratch.
+    /// # Arguments
+    /// * `features` - The features of the board state before the move, updated to the features after the move
+    /// * `previous` - The board state before the move
+    /// * `board` - The board state after the move
+    /// * `index` - The index of the move
+    /// * `player` - The player the board state is evaluated for
+    fn update_features(
+        &self,
+        features: &mut [f64],
+        _previous: UltimateBoard,
+        board: UltimateBoard,
+        _index: u8,
+        player: Player,
+    ) {
+        if let Some(new_features) = self.initialize_features(board, player) {
+            features.copy_from_slice(&new_features);
+        }
+    }
+
+    /// Evaluate the given board state for the given player using its features
+    ///
+    /// Must return the same value as [Heuristic::evaluate_for], apart from rounding errors.
+    /// # Arguments
+    /// * `board` - The board state to evaluate
+    /// * `player` - The player to evaluate the board state for
+    /// * `features` - The features of the board state for the player, see [Heuristic::initialize_features]
+    /// # Returns
+    /// The value of the board state, always between [MIN_VALUE] and [MAX_VALUE]
+    fn evaluate_features(&self, board: UltimateBoard, player: Player, _features: &[f64]) -> f64 {
+        self.evaluate_for(board, player)
+    }
 }
 
 /// The number of possible legal small board states
 pub const NUM_SMALL_BOARD_STATES: usize = usize::pow(3, 9);
 
+/// The number of entries of a lookup table, one for every 18 bit [key](Board::to_key) including illegal boards
+pub const LOOKUP_TABLE_SIZE: usize = 1 << 18;
+
+/// The number of legal small board states that are different under rotations and reflections
+pub const NUM_CANONICAL_SMALL_BOARD_STATES: usize = 2862;
+
+/// The number of entries of the lookup table of a [MiniBoardHeuristic],
+/// one for every [canonical](Board::to_canonical_key) small board state and one shared by all illegal boards
+pub const SMALL_BOARD_TABLE_SIZE: usize = NUM_CANONICAL_SMALL_BOARD_STATES + 1;
+
+/// The index in the lookup table of a [MiniBoardHeuristic] for every 18 bit [key](Board::to_key)
+///
+/// Symmetric small boards have the same index, illegal boards have the index 0.
+static SMALL_BOARD_INDICES: Lazy<Vec<u16>> = Lazy::new(|| {
+    let mut indices = vec![0; LOOKUP_TABLE_SIZE];
+    let mut num_indices = 0;
+
+    for (first, second) in LegalBoardIterator::default() {
+        let key = first as usize | (second as usize) << 9;
+        let canonical_key = Board::canonical_key(key as u32) as usize;
+
+        if indices[canonical_key] == 0 {
+            num_indices += 1;
+            indices[canonical_key] = num_indices;
+        }
+
+        indices[key] = indices[canonical_key];
+    }
+
+    indices
+});
+
+/// Returns the index of a small board in the lookup table of a [MiniBoardHeuristic]
+///
+/// # Arguments
+/// * `key` - The [key](Board::to_key) of the small board, must have at most 18 bits
+/// # Returns
+/// The index, the same for all rotations and reflections of the small board
+pub fn small_board_index(key: u32) -> usize {
+    SMALL_BOARD_INDICES[key as usize] as usize
+}
+
 /// # Trait representing a heuristic for small boards
 ///
 /// A MiniBoardHeuristic is a heuristic that evaluates a [Board] state.
@@ -49,7 +213,7 @@ pub const NUM_SMALL_BOARD_STATES: usize = usize::pow(3, 9);
 ///
 /// <div class="warning">
 ///
-/// The return value of [MiniBoardHeuristic::evaluate] must be calculated from the perspective of [Player::One](crate::game::player::Player::One).
+/// The return value of [MiniBoardHeuristic::evaluate] must be calculated from the perspective of [Player::One].
 ///
 /// </div>
 ///
@@ -61,7 +225,7 @@ pub trait MiniBoardHeuristic: Send + Sync {
     ///
     /// <div class="warning">
     ///
-    /// The return value must be calculated from the perspective of [Player::One](crate::game::player::Player::One).
+    /// The return value must be calculated from the perspective of [Player::One].
     ///
     /// </div>
     ///
@@ -75,19 +239,233 @@ pub trait MiniBoardHeuristic: Send + Sync {
     ///
     /// The cache is a lookup table that contains the evaluation of all possible small board states.
     /// The cache is used to speed up the evaluation of the heuristic.
+    /// Only the [canonical](Board::to_canonical_key) small boards are evaluated,
+    /// so symmetric small boards always have the same evaluation.
+    /// It is indexed by the [small board index](small_board_index) of the key of the small board, the entry of illegal boards is 0.
     /// # Returns
-    /// The cache for the heuristic
-    fn initialize(&self) -> HashMap<u32, f64> {
-        let mut cache = HashMap::with_capacity(NUM_SMALL_BOARD_STATES);
+    /// The cache for the heuristic, of length [SMALL_BOARD_TABLE_SIZE]
+    fn initialize(&self) -> Vec<f64> {
+        let mut cache = vec![0.; SMALL_BOARD_TABLE_SIZE];
 
         for (first, second) in LegalBoardIterator::default() {
-            let board = Board::from_bitboards([BitBoard::new(first), BitBoard::new(second)], 0);
+            let key = first as u32 | (second as u32) << 9;
 
-            let index = first as u32 | (second as u32) << 9;
+            if Board::canonical_key(key) == key {
+                let board = Board::from_bitboards([BitBoard::new(first), BitBoard::new(second)], 0);
 
-            cache.insert(index, self.evaluate(board));
+                cache[small_board_index(key)] = self.evaluate(board);
+            }
         }
 
         cache
     }
 }
+
+/// # Trait representing a heuristic for the meta-board
+///
+/// A MetaBoardHeuristic evaluates the status of the small boards of a [UltimateBoard], e.g. the small boards won
+/// or the lines of the meta-board controlled by a player.
+/// There are only 3^9 configurations of won small boards, so the evaluations are precomputed in a lookup table
+/// keyed by the [key of the meta-board](UltimateBoard::get_meta_board_key), like the lookup table of a [MiniBoardHeuristic].
+///
+/// <div class="warning">
+///
+/// The return value of [MetaBoardHeuristic::evaluate] must be calculated from the perspective of [Player::One].
+/// The value for [Player::Two] is looked up with the key from the perspective of [Player::Two].
+///
+/// </div>
+pub trait MetaBoardHeuristic: Send + Sync {
+    /// Evaluate the given status of the small boards
+    ///
+    /// Drawn and unfinished small boards are not distinguished, both are [GameResult::Continue].
+    /// # Arguments
+    /// * `board_status` - The status of the small boards
+    /// # Returns
+    /// The value of the status from the perspective of [Player::One]
+    fn evaluate(&self, board_status: [GameResult; 9]) -> f64;
+
+    /// Initialize the lookup table for the heuristic
+    ///
+    /// The lookup table is indexed directly by the [key of the meta-board](UltimateBoard::get_meta_board_key),
+    /// the entries of illegal keys are 0.
+    /// # Returns
+    /// The evaluations of all configurations of won small boards, of length [LOOKUP_TABLE_SIZE]
+    fn initialize(&self) -> Vec<f64> {
+        let mut cache = vec![0.; LOOKUP_TABLE_SIZE];
+
+        for (first, second) in LegalBoardIterator::default() {
+            let mut board_status = [GameResult::Continue; 9];
+
+            for (index, status) in board_status.iter_mut().enumerate() {
+                let bit = 1 << Board::from_human_to_bit(index as u8);
+
+                if first & bit != 0 {
+                    *status = GameResult::Win(Player::One);
+                } else if second & bit != 0 {
+                    *status = GameResult::Win(Player::Two);
+                }
+            }
+
+            let index = first as usize | (second as usize) << 9;
+
+            cache[index] = self.evaluate(board_status);
+        }
+
+        cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tablebase::Tablebase;
+    use crate::heuristic::combined_heuristic::CombinedHeuristic;
+    use crate::heuristic::custom_heuristic::{
+        CustomHeuristic, CustomMetaBoardHeuristic, CustomMiniBoardHeuristic,
+    };
+    use crate::heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic;
+    use crate::heuristic::parameterized_heuristic::{
+        ParameterizedHeuristic, NUM_FEATURES, NUM_PHASED_FEATURES,
+    };
+    use crate::heuristic::tablebase_heuristic::TablebaseHeuristic;
+    use crate::heuristic::threat_heuristic::ThreatHeuristic;
+    use rand::prelude::IteratorRandom;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use std::sync::Arc;
+
+    /// Asserts that the heuristic only returns the limits of the scale for decided games
+    fn assert_normalized(heuristic: impl Heuristic, seed: u64) {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+
+        for _ in 0..20 {
+            let mut board = UltimateBoard::new();
+
+            loop {
+                for player in Player::iter() {
+                    let values = [
+                        heuristic.evaluate_for(board, player),
+                        heuristic
+                            .initialize_features(board, player)
+                            .map_or(heuristic.evaluate_for(board, player), |features| {
+                                heuristic.evaluate_features(board, player, &features)
+                            }),
+                    ];
+
+                    for value in values {
+                        match board.get_game_status() {
+                            GameResult::Win(winner) if winner == player => {
+                                assert_eq!(value, MAX_VALUE)
+                            }
+                            GameResult::Win(_) => assert_eq!(value, MIN_VALUE),
+                            _ => assert!(
+                                value.abs() <= MAX_ESTIMATE,
+                                "{} evaluated {} with {}",
+                                heuristic.get_name(),
+                                board,
+                                value
+                            ),
+                        }
+                    }
+                }
+
+                if board.get_game_status() != GameResult::Continue {
+                    break;
+                }
+
+                board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalized_scale() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+        // Large weights must not leave the scale either
+        let values: Vec<f64> = (0..NUM_FEATURES)
+            .map(|_| rng.gen_range(-100.0..100.0))
+            .collect();
+        let phased: Vec<f64> = (0..NUM_PHASED_FEATURES)
+            .map(|_| rng.gen_range(-100.0..100.0))
+            .collect();
+
+        assert_normalized(CustomHeuristic::new(Player::One), 0);
+        assert_normalized(ThreatHeuristic::new(Player::One), 1);
+        assert_normalized(ParameterizedHeuristic::new(Player::One, values.clone()), 2);
+        assert_normalized(
+            ParameterizedHeuristic::with_look_up_table(Player::One, phased),
+            3,
+        );
+        assert_normalized(
+            MonteCarloGameSearchHeuristic::new(Player::One, 4).with_seed(0),
+            4,
+        );
+        assert_normalized(
+            CombinedHeuristic::new()
+                .with_heuristic(CustomHeuristic::new(Player::One), 2.)
+                .with_heuristic(ThreatHeuristic::new(Player::One), 1.),
+            5,
+        );
+        assert_normalized(
+            TablebaseHeuristic::new(
+                Player::One,
+                Arc::new(Tablebase::new(0)),
+                ParameterizedHeuristic::new(Player::One, values),
+            ),
+            6,
+        );
+    }
+
+    #[test]
+    fn test_lookup_tables() {
+        let small_boards = CustomMiniBoardHeuristic.initialize();
+        let meta_board = CustomMetaBoardHeuristic.initialize();
+        assert_eq!(small_boards.len(), SMALL_BOARD_TABLE_SIZE);
+        assert_eq!(meta_board.len(), LOOKUP_TABLE_SIZE);
+
+        // Every legal board shares the entry of its canonical board
+        for (first, second) in LegalBoardIterator::default() {
+            let key = first as u32 | (second as u32) << 9;
+            let canonical_key = Board::canonical_key(key);
+            let canonical_board = Board::from_bitboards(
+                [
+                    BitBoard::new(canonical_key as u16 & 0x1FF),
+                    BitBoard::new((canonical_key >> 9) as u16),
+                ],
+                0,
+            );
+            assert_ne!(small_board_index(key), 0);
+            assert_eq!(small_board_index(key), small_board_index(canonical_key));
+            assert_eq!(
+                small_boards[small_board_index(key)],
+                CustomMiniBoardHeuristic.evaluate(canonical_board)
+            );
+        }
+
+        // Both players on the same square is illegal
+        let illegal_key = 1 | 1 << 9;
+        assert_eq!(small_board_index(illegal_key), 0);
+        assert_eq!(small_boards[0], 0.);
+        assert_eq!(meta_board[illegal_key as usize], 0.);
+
+        // X won the center board
+        let mut board_status = [GameResult::Continue; 9];
+        board_status[4] = GameResult::Win(Player::One);
+        let key = 1 << Board::from_human_to_bit(4);
+        assert_eq!(
+            meta_board[key as usize],
+            CustomMetaBoardHeuristic.evaluate(board_status)
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(0., 1.), 0.);
+        assert!(normalize(f64::MAX, 1.) <= MAX_ESTIMATE);
+        assert!(normalize(f64::MIN, 1.) >= -MAX_ESTIMATE);
+        assert!(normalize(1., 1.) < normalize(2., 1.));
+        assert!(!is_proven(normalize(1e9, 1e-9)));
+        assert!(is_proven(MAX_VALUE) && is_proven(MIN_VALUE));
+    }
+}
@@ -1,29 +1,34 @@
 //! # Contains the [MonteCarloGameSearchHeuristic] struct
-//! The MonteCarloGameSearchHeuristic struct represents a [Heuristic] that uses Monte Carlo Tree Search to evaluate the best move.
-//! The heuristic uses random games to evaluate the best move.
+//! The MonteCarloGameSearchHeuristic struct represents a [Heuristic] that evaluates a board with random games.
+//! The heuristic plays random games from the board and evaluates it with the results of the games.
 
 use crate::game::game_result::GameResult;
 use crate::game::player::Player;
 use crate::game::ultimate_board::UltimateBoard;
-use crate::heuristic::Heuristic;
-use rand::prelude::SliceRandom;
+use crate::heuristic::{Heuristic, MAX_ESTIMATE, MAX_VALUE, MIN_VALUE};
+use rand::prelude::IteratorRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
-/// A [Heuristic] that uses Monte Carlo Tree Search to evaluate the best move
+/// A [Heuristic] that evaluates a board with random games
 ///
-/// The heuristic uses random games to evaluate the best move.
-/// The move with the highest win rate and lowest loss rate is chosen.
+/// The heuristic plays `num_simulations` random games from the board.
+/// The win probability of the player, counting draws as half a win, is mapped to the normalized scale,
+/// i.e. the value is the difference of the win and loss rate scaled to [MAX_ESTIMATE].
+/// Won and lost boards are evaluated as [MAX_VALUE] and [MIN_VALUE].
 /// # Fields
-/// * `player` - The [Player] for which the heuristic should evaluate the best move
-/// * `num_simulations` - The number of random games to simulate from each possible move
+/// * `player` - The [Player] for which the heuristic should evaluate the board
+/// * `num_simulations` - The number of random games to simulate from the board
+/// * `seed` - The seed of the random games, None if the games are random
 ///
 /// Note:
-/// * The heuristic is not deterministic.
-/// * The heuristic is not guaranteed to find the best move.
+/// * The heuristic is only deterministic with a [seed](MonteCarloGameSearchHeuristic::with_seed).
 /// * The heuristic is really slow compared to [CustomHeuristic](crate::heuristic::custom_heuristic::CustomHeuristic) while providing worse results.
 #[derive(Clone)]
 pub struct MonteCarloGameSearchHeuristic {
     player: Player,
     num_simulations: u32,
+    seed: Option<u64>,
 }
 
 impl MonteCarloGameSearchHeuristic {
@@ -31,85 +36,105 @@ impl MonteCarloGameSearchHeuristic {
         MonteCarloGameSearchHeuristic {
             player,
             num_simulations,
+            seed: None,
         }
     }
 
+    /// Seeds the random games
+    ///
+    /// The random games of a board are generated from the seed and the [hash](UltimateBoard::get_hash) of the board,
+    /// so the same board is always evaluated with the same value, independent of the order of the evaluations.
+    /// Without a seed, the games are generated with a generator seeded from the operating system.
+    /// # Arguments
+    /// * `seed` - The seed
+    /// # Returns
+    /// The heuristic with the seed set
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Simulates a random game starting from the given board
     ///
     /// The game is played until a result is reached.
-    fn random_game(board: UltimateBoard) -> GameResult {
+    fn random_game(board: UltimateBoard, rng: &mut impl Rng) -> GameResult {
         let mut board = board;
-        let mut game_result = board.get_game_status();
 
-        while game_result == GameResult::Continue {
-            let possible_moves = board.get_possible_moves().collect::<Vec<u8>>();
-            let random_move = possible_moves.choose(&mut rand::thread_rng()).unwrap();
-            board.make_move(*random_move);
-            game_result = board.get_game_status();
+        while board.get_game_status() == GameResult::Continue {
+            let random_move = board.get_possible_moves().choose(rng).unwrap();
+            board.make_move(random_move);
         }
 
-        game_result
+        board.get_game_status()
     }
 }
 
 impl Heuristic for MonteCarloGameSearchHeuristic {
     fn evaluate(&self, board: UltimateBoard) -> f64 {
-        let possible_moves = board.get_possible_moves();
-        let mut results = vec![];
-
-        for current_move in possible_moves {
-            let mut wins = 0;
-            let mut losses = 0;
-            let mut draws = 0;
-
-            for _ in 0..self.num_simulations {
-                let board_copy = board;
-
-                let game_result = MonteCarloGameSearchHeuristic::random_game(board_copy);
-
-                match game_result {
-                    GameResult::Win(player) => {
-                        if player == self.player {
-                            wins += 1;
-                        } else {
-                            losses += 1;
-                        }
-                    }
-                    GameResult::Draw => {
-                        draws += 1;
-                    }
-                    _ => {
-                        panic!("Error: Game should never be in a continue state");
-                    }
-                }
-            }
+        self.evaluate_for(board, self.player)
+    }
 
-            results.push((current_move, wins, losses, draws));
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        match board.get_game_status() {
+            GameResult::Win(winner) if winner == player => return MAX_VALUE,
+            GameResult::Win(_) => return MIN_VALUE,
+            GameResult::Draw => return 0.,
+            GameResult::Continue => {}
         }
 
-        let mut best_move = None;
+        if self.num_simulations == 0 {
+            return 0.;
+        }
 
-        for (current_move, wins, losses, draws) in results {
-            if best_move.is_none() {
-                best_move = Some((current_move, wins, losses, draws));
-            } else {
-                let (_, best_wins, best_losses, _) = best_move.unwrap();
+        let mut rng = match self.seed {
+            Some(seed) => ChaCha20Rng::seed_from_u64(seed ^ board.get_hash()),
+            None => ChaCha20Rng::from_entropy(),
+        };
 
-                if (wins > best_wins) || (wins == best_wins && losses < best_losses) {
-                    best_move = Some((current_move, wins, losses, draws));
-                }
-            }
-        }
+        let mut wins = 0;
+        let mut losses = 0;
 
-        if best_move.is_none() {
-            return 0.;
+        for _ in 0..self.num_simulations {
+            match MonteCarloGameSearchHeuristic::random_game(board, &mut rng) {
+                GameResult::Win(winner) if winner == player => wins += 1,
+                GameResult::Win(_) => losses += 1,
+                _ => {}
+            }
         }
 
-        let (_, wins, losses, _) = best_move.unwrap();
+        let win_probability = (wins as f64 + (self.num_simulations - wins - losses) as f64 / 2.)
+            / self.num_simulations as f64;
 
-        (wins - losses) as f64
+        (2. * win_probability - 1.) * MAX_ESTIMATE
     }
+
     fn get_name(&self) -> String {
         "MH".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monte_carlo_game_search_heuristic() {
+        // X has won the top row of boards except the top right board, which X can win with the next move
+        let squares = format!("XXX......XXX......XX.......{}", ".".repeat(54));
+        let board = UltimateBoard::from_position(&format!("{} 2 X", squares)).unwrap();
+
+        let one = MonteCarloGameSearchHeuristic::new(Player::One, 100).with_seed(0);
+        let two = MonteCarloGameSearchHeuristic::new(Player::Two, 100).with_seed(0);
+
+        // The evaluation is reproducible with a seed and consistent for both players
+        assert_eq!(one.evaluate(board), one.evaluate(board));
+        assert_eq!(one.evaluate(board), -two.evaluate(board));
+        assert!(one.evaluate(board) > 0.5);
+        assert!(one.evaluate(board) < MAX_VALUE);
+
+        let mut won = board;
+        won.make_move(20);
+        assert_eq!(one.evaluate(won), MAX_VALUE);
+        assert_eq!(two.evaluate(won), MIN_VALUE);
+    }
+}
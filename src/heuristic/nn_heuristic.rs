@@ -0,0 +1,87 @@
+//! # Contains the [NnHeuristic] struct
+//! The NnHeuristic struct represents a [Heuristic] that evaluates a board with the value of a neural network.
+//!
+//! The network is loaded from an ONNX file by an [OnnxEvaluator], see the [network format](crate::agent::neural_net_agent)
+//! for the encoding of the board into planes. Only the value output of the network is used.
+//! As a heuristic, the network can be used by every agent using a heuristic, e.g. the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//!
+//! The module is only available with the `onnx` feature.
+
+use crate::agent::neural_net_agent::OnnxEvaluator;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{Heuristic, MAX_ESTIMATE, MAX_VALUE, MIN_VALUE};
+use tract_onnx::prelude::TractResult;
+
+/// # Struct representing a [Heuristic] that evaluates a board with a neural network
+///
+/// The value of the network is from the perspective of the player to move,
+/// it is converted to the perspective of the player and scaled to the range of unproven values.
+/// Won and lost boards are not evaluated by the network.
+///
+/// The heuristic is cheap to clone, the clones share the network.
+#[derive(Clone)]
+pub struct NnHeuristic {
+    player: Player,
+    evaluator: OnnxEvaluator,
+}
+
+impl NnHeuristic {
+    /// Creates a new [NnHeuristic]
+    ///
+    /// # Arguments
+    /// * `player` - The [Player] for which the heuristic should evaluate the board
+    /// * `evaluator` - The network
+    pub fn new(player: Player, evaluator: OnnxEvaluator) -> Self {
+        NnHeuristic { player, evaluator }
+    }
+
+    /// Loads the network from an ONNX file and creates a new [NnHeuristic]
+    ///
+    /// # Arguments
+    /// * `player` - The [Player] for which the heuristic should evaluate the board
+    /// * `path` - The path of the ONNX file
+    /// # Returns
+    /// The heuristic, an error if the network could not be loaded
+    pub fn load(player: Player, path: &str) -> TractResult<Self> {
+        Ok(NnHeuristic::new(player, OnnxEvaluator::load(path)?))
+    }
+}
+
+impl Heuristic for NnHeuristic {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.evaluate_for(board, self.player)
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        match board.get_game_status() {
+            GameResult::Win(winner) if winner == player => return MAX_VALUE,
+            GameResult::Win(_) => return MIN_VALUE,
+            GameResult::Draw => return 0.,
+            GameResult::Continue => {}
+        }
+
+        let value = self.evaluator.evaluate_value(board) as f64 * MAX_ESTIMATE;
+
+        if board.get_current_player() == player {
+            value
+        } else {
+            -value
+        }
+    }
+
+    fn get_name(&self) -> String {
+        "NN".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_network() {
+        assert!(NnHeuristic::load(Player::One, "missing.onnx").is_err());
+    }
+}
@@ -4,20 +4,68 @@
 //! The weights may be optimized using [GeneticAlgorithm](crate::genetic_algorithm::GeneticAlgorithm).
 //!
 //! The features of the heuristic are described in the [ParameterizedHeuristic::values] field.
+//! The weights may depend on the phase of the game, the weights of the opening, the middlegame and the endgame are interpolated by [phase_weights].
 //!
 //! The heuristic uses a [ParameterizedMiniBoardHeuristic] to evaluate small boards.
+//! The evaluations can be shared by multiple heuristics with the same weights using an [EvaluationCache].
+//! The lookup tables of [ParameterizedHeuristic::with_look_up_table] are shared process-wide by all heuristics with the same weights.
+//!
+//! The heuristic supports the [incremental evaluation](Heuristic::initialize_features) of a search, its features are the coefficients of the weights.
+//! The features of a board state can be extracted with [extract_features], e.g. to fit the weights to the results of games.
 //!
 //! The [ParameterizedMiniBoardHeuristic] struct represents a [MiniBoardHeuristic] that uses weights for the features to evaluate small boards.
+//!
+//! The [ParameterizedMetaBoardHeuristic] struct represents a [MetaBoardHeuristic] that uses weights for the features to evaluate the won small boards.
 
+use crate::game::bitboard::BitBoard;
 use crate::game::board::Board;
 use crate::game::game_result::GameResult;
 use crate::game::player::Player;
+use crate::game::ultimate_board::partial_wins_difference;
 use crate::game::ultimate_board::{UltimateBoard, CENTER_INDEX, CORNER_INDICES, EDGE_INDICES};
-use crate::heuristic::{Heuristic, MiniBoardHeuristic, MAX_VALUE, MIN_VALUE};
+use crate::heuristic::evaluation_cache::EvaluationCache;
+use crate::heuristic::{
+    normalize, Heuristic, MetaBoardHeuristic, MiniBoardHeuristic, MAX_VALUE, MIN_VALUE,
+};
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
 
 /// The number of features the heuristic uses
-pub const NUM_FEATURES: usize = 12;
+pub const NUM_FEATURES: usize = 14;
+
+/// The number of phases of the game with their own weights: the opening, the middlegame and the endgame
+pub const NUM_PHASES: usize = 3;
+
+/// The number of weights of a heuristic with separate weights for every phase of the game
+pub const NUM_PHASED_FEATURES: usize = NUM_PHASES * NUM_FEATURES;
+
+/// The weighted sum of the features that is [normalized](normalize) to about 0.75
+pub const SCALE: f64 = 50.;
+
+/// The descriptions of the [features](extract_features), e.g. to explain the difference between two board states
+pub const FEATURE_NAMES: [&str; NUM_FEATURES] = [
+    "the difference in small boards won",
+    "the number of small boards drawn",
+    "an unused feature",
+    "the difference in squares set on the small boards",
+    "the difference in partial wins on the small boards",
+    "the centers of the small boards occupied",
+    "the difference in corners of the small boards occupied",
+    "the difference in edges of the small boards occupied and the won center board",
+    "the difference in corner boards won",
+    "the difference in edge boards won",
+    "the difference in partial wins on the entire board",
+    "whether the player to move can choose a small board freely",
+    "the tempo of the player",
+    "the quality of the small board the player to move is sent to",
+];
+
+/// The evaluation caches of the weights in use, keyed by the bits of the weights
+///
+/// Only weak references are stored, so the lookup tables are freed when the last heuristic using them is dropped.
+static SHARED_EVALUATION_CACHES: Lazy<Mutex<HashMap<Vec<u64>, Weak<EvaluationCache>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 #[allow(rustdoc::private_intra_doc_links)]
 /// # Struct representing a [Heuristic] that uses weights for the features to evaluate the best move
@@ -27,6 +75,7 @@ pub const NUM_FEATURES: usize = 12;
 /// The features of the heuristic are described in the [ParameterizedHeuristic::values] field.
 ///
 /// The heuristic uses a [ParameterizedMiniBoardHeuristic] to evaluate small boards.
+/// The weighted sum of the features is [normalized](normalize).
 #[derive(Clone, Debug)]
 pub struct ParameterizedHeuristic {
     /// The [player](Player) for which the heuristic should evaluate the best move
@@ -46,8 +95,18 @@ pub struct ParameterizedHeuristic {
     /// 10. Difference in number of edges of the entire board won
     /// 11. Number of partial wins difference on the entire board
     /// 12. Whether the current player can freely choose a small board
+    /// 13. Whether it is the turn of the player (tempo)
+    /// 14. Quality of the small board the player to move is sent to (see [sent_board_quality])
+    ///
+    /// Weights for 12 or 13 features are accepted as well, the missing features are not evaluated then.
+    ///
+    /// With [NUM_PHASED_FEATURES] values, the values are the weights of the opening, the middlegame and the endgame
+    /// one after another, which are interpolated by the progress of the game (see [phase_weights]).
     pub values: Vec<f64>,
-    small_board_lookup_table: Option<HashMap<u32, f64>>,
+    /// The evaluations shared with other heuristics, None if every board is evaluated directly
+    evaluation_cache: Option<Arc<EvaluationCache>>,
+    /// The heuristics with the weights of the phases of the game, empty if the weights do not depend on the phase
+    phases: Vec<ParameterizedHeuristic>,
 }
 
 impl ParameterizedHeuristic {
@@ -62,7 +121,10 @@ impl ParameterizedHeuristic {
         ParameterizedHeuristic {
             player,
             values: values.clone(),
-            small_board_lookup_table: None,
+            evaluation_cache: None,
+            phases: phase_heuristics(&values, |values| {
+                ParameterizedHeuristic::new(player, values)
+            }),
         }
     }
 
@@ -71,84 +133,197 @@ impl ParameterizedHeuristic {
     /// The lookup table is used to evaluate small boards.
     /// The values are used as weights for the features.
     ///
+    /// The lookup table is taken from the [shared evaluation cache](ParameterizedHeuristic::shared_evaluation_cache)
+    /// of the values, so it is only built once for heuristics with the same weights.
+    /// With weights for every phase of the game, every phase uses the lookup table of its weights.
     /// # Arguments
     /// * `player` - The [player](Player) for which the heuristic should evaluate the best move
     /// * `values` - The weights for the features
     pub fn with_look_up_table(player: Player, values: Vec<f64>) -> Self {
+        let evaluation_cache = ParameterizedHeuristic::shared_evaluation_cache(&values);
+
+        ParameterizedHeuristic::with_evaluation_cache(player, values, evaluation_cache)
+    }
+
+    /// Returns the process-wide [EvaluationCache] of the given weights
+    ///
+    /// The cache contains the lookup tables of the small boards and the meta-board, but no position cache.
+    /// With weights for every phase of the game, the cache contains the shared caches of the weights of the phases instead.
+    /// It is built when it is requested for the first time and reused as long as it is in use,
+    /// e.g. by a heuristic created with [ParameterizedHeuristic::with_look_up_table].
+    /// Holding the returned cache keeps the lookup tables alive, e.g. for all games of a generation of a genetic algorithm.
+    /// # Arguments
+    /// * `values` - The weights for the features
+    /// # Returns
+    /// The cache shared by all heuristics with the same weights
+    pub fn shared_evaluation_cache(values: &[f64]) -> Arc<EvaluationCache> {
+        // A panicking thread does not leave the map in an inconsistent state
+        let mut caches = SHARED_EVALUATION_CACHES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        shared_evaluation_cache(&mut caches, values)
+    }
+
+    /// Creates a new [ParameterizedHeuristic] using a shared [EvaluationCache]
+    ///
+    /// In contrast to [ParameterizedHeuristic::with_look_up_table], the lookup table is not rebuilt,
+    /// so many heuristics with the same weights can be created cheaply, e.g. for the agents of a tournament.
+    /// If the cache contains a position cache, the evaluations of whole positions are shared as well.
+    ///
+    /// The cache must be created with a [ParameterizedMiniBoardHeuristic] using the same values,
+    /// which are [recorded](EvaluationCache::with_weights) in the cache.
+    /// With weights for every phase of the game, the cache must be [created from the caches of the phases](EvaluationCache::from_phases),
+    /// e.g. the [shared evaluation caches](ParameterizedHeuristic::shared_evaluation_cache) of the weights of the phases.
+    /// # Arguments
+    /// * `player` - The [player](Player) for which the heuristic should evaluate the best move
+    /// * `values` - The weights for the features
+    /// * `evaluation_cache` - The cache shared with other heuristics
+    /// # Panics
+    /// If the cache was built with other weights or has no caches of the phases of phase-dependent weights
+    pub fn with_evaluation_cache(
+        player: Player,
+        values: Vec<f64>,
+        evaluation_cache: Arc<EvaluationCache>,
+    ) -> Self {
+        assert_eq!(
+            evaluation_cache.get_weights(),
+            Some(values.as_slice()),
+            "The evaluation cache was built with other weights"
+        );
+
+        let num_phases = if values.len() == NUM_PHASED_FEATURES {
+            NUM_PHASES
+        } else {
+            0
+        };
+        assert_eq!(
+            evaluation_cache.get_phases().len(),
+            num_phases,
+            "The evaluation cache does not match the phases of the weights"
+        );
+
         ParameterizedHeuristic {
             player,
-            values: values.clone(),
-            small_board_lookup_table: Some(
-                ParameterizedMiniBoardHeuristic::new(values).initialize(),
-            ),
+            phases: evaluation_cache
+                .get_phases()
+                .iter()
+                .zip(values.chunks(NUM_FEATURES))
+                .map(|(phase, weights)| {
+                    ParameterizedHeuristic::with_evaluation_cache(
+                        player,
+                        weights.to_vec(),
+                        phase.clone(),
+                    )
+                })
+                .collect(),
+            values,
+            evaluation_cache: Some(evaluation_cache),
         }
     }
-}
 
-impl Heuristic for ParameterizedHeuristic {
-    fn evaluate(&self, board: UltimateBoard) -> f64 {
-        let mut value = 0.;
+    /// Evaluates a small board from the perspective of a player
+    fn get_small_board_value(&self, small_board: Board, player: Player) -> f64 {
+        let value = match &self.evaluation_cache {
+            Some(evaluation_cache) => evaluation_cache
+                .get_small_board_value(small_board.to_key())
+                .unwrap(),
+            None => evaluate_small_board(&self.values, small_board),
+        };
 
-        if board.get_game_status() == GameResult::Win(self.player) {
-            return MAX_VALUE - 1.;
-        }
+        value * (if player == Player::One { 1 } else { -1 }) as f64
+    }
 
-        if board.get_game_status() == GameResult::Win(self.player.get_opponent()) {
-            return MIN_VALUE + 1.;
+    /// Evaluates the won small boards from the perspective of a player
+    fn get_meta_board_value(&self, board: UltimateBoard, player: Player) -> f64 {
+        match self.evaluation_cache.as_ref().and_then(|evaluation_cache| {
+            evaluation_cache.get_meta_board_value(board.get_meta_board_key(player))
+        }) {
+            Some(value) => value,
+            None => evaluate_meta_board(&self.values, board.get_board_status(), player),
         }
+    }
 
-        let mini_heuristic = ParameterizedMiniBoardHeuristic::new(self.values.clone());
-
-        for small_board in board.get_boards() {
-            match &self.small_board_lookup_table {
-                Some(small_board_lookup_table) => {
-                    value += *small_board_lookup_table.get(&small_board.to_key()).unwrap()
-                        * (if self.player == Player::One { 1 } else { -1 }) as f64;
-                }
-                None => {
-                    value += mini_heuristic.evaluate(small_board)
-                        * (if self.player == Player::One { 1 } else { -1 }) as f64;
-                }
-            }
+    /// Evaluates the board for a player using the position cache, if any
+    ///
+    /// # Arguments
+    /// * `board` - The board state to evaluate
+    /// * `player` - The player to evaluate the board state for
+    /// * `small_boards` - Returns the values of the small boards for the player, only called if the board is not cached
+    fn evaluate_cached(
+        &self,
+        board: UltimateBoard,
+        player: Player,
+        small_boards: impl FnOnce() -> [f64; 9],
+    ) -> f64 {
+        if board.get_game_status() == GameResult::Win(player) {
+            return MAX_VALUE;
         }
 
-        let mut diff_wins: f64 = 0.;
-        for board_status in board.get_board_status() {
-            if let GameResult::Win(winner) = board_status {
-                if winner == self.player {
-                    diff_wins += 1.;
-                } else {
-                    diff_wins -= 1.;
-                }
-            } else {
-                value += self.values[1];
-            }
+        if board.get_game_status() == GameResult::Win(player.get_opponent()) {
+            return MIN_VALUE;
         }
-        value += diff_wins * self.values[0];
 
-        value += if board.get_board_status()[CENTER_INDEX] == GameResult::Win(self.player) {
-            self.values[7]
-        } else {
-            -self.values[7]
+        let Some(evaluation_cache) = self
+            .evaluation_cache
+            .as_ref()
+            .filter(|evaluation_cache| evaluation_cache.has_position_cache())
+        else {
+            return normalize(self.raw_value(board, player, small_boards), SCALE);
         };
 
-        for corner_index in CORNER_INDICES.iter() {
-            value += if board.get_board_status()[*corner_index] == GameResult::Win(self.player) {
-                self.values[8]
-            } else {
-                -self.values[8]
-            };
+        let (hash, fingerprint) = (board.get_hash(), board.get_fingerprint(0));
+
+        if let Some(value) = evaluation_cache.probe_position(hash, fingerprint, player) {
+            return value;
         }
 
-        for edge_index in EDGE_INDICES.iter() {
-            value += if board.get_board_status()[*edge_index] == GameResult::Win(self.player) {
-                self.values[9]
-            } else {
-                -self.values[9]
-            };
+        let value = normalize(self.raw_value(board, player, small_boards), SCALE);
+        evaluation_cache.store_position(hash, fingerprint, player, value);
+
+        value
+    }
+
+    /// Computes the weighted sum of the features of the board for a player, before it is normalized
+    ///
+    /// # Arguments
+    /// * `board` - The board state to evaluate
+    /// * `player` - The player to evaluate the board state for
+    /// * `small_boards` - Returns the values of the small boards for the player, not called if the weights depend on the phase
+    fn raw_value(
+        &self,
+        board: UltimateBoard,
+        player: Player,
+        small_boards: impl FnOnce() -> [f64; 9],
+    ) -> f64 {
+        if !self.phases.is_empty() {
+            // The sum is linear in the weights, so the sums of the phases can be interpolated instead of the weights
+            return phase_weights(board)
+                .iter()
+                .zip(&self.phases)
+                .filter(|(weight, _)| **weight > 0.)
+                .map(|(weight, phase)| {
+                    let small_boards = board
+                        .get_boards()
+                        .map(|small_board| phase.get_small_board_value(small_board, player));
+
+                    weight * phase.weighted_sum(board, player, &small_boards)
+                })
+                .sum();
         }
 
-        value += board.get_partial_wins_difference(self.player) as f64 * self.values[10];
+        self.weighted_sum(board, player, &small_boards())
+    }
+
+    /// Computes the weighted sum of the features of the board for a player with the weights of a single phase
+    fn weighted_sum(&self, board: UltimateBoard, player: Player, small_boards: &[f64; 9]) -> f64 {
+        let mut value = 0.;
+
+        for small_board_value in small_boards {
+            value += small_board_value;
+        }
+
+        value += self.get_meta_board_value(board, player);
 
         value += if board.get_next_board_index().is_none() {
             self.values[11]
@@ -156,13 +331,311 @@ impl Heuristic for ParameterizedHeuristic {
             -self.values[11]
         };
 
+        if let Some(tempo) = self.values.get(12) {
+            value += tempo_feature(board, player) * tempo;
+        }
+
+        if let Some(sent_board) = self.values.get(13) {
+            value += tempo_feature(board, player) * sent_board_quality(board) * sent_board;
+        }
+
         value
     }
+}
+
+impl Heuristic for ParameterizedHeuristic {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.evaluate_for(board, self.player)
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        self.evaluate_cached(board, player, || {
+            board
+                .get_boards()
+                .map(|small_board| self.get_small_board_value(small_board, player))
+        })
+    }
+
+    /// The features are the coefficients of the [weights](ParameterizedHeuristic::values), see [extract_features].
+    /// The coefficients that change with every move are computed when the board state is evaluated.
+    fn initialize_features(&self, board: UltimateBoard, player: Player) -> Option<Vec<f64>> {
+        Some(extract_features(board, player))
+    }
+
+    /// Only the coefficients of the small board the move was made on and, if its status changed, of the status of the small boards are updated
+    fn update_features(
+        &self,
+        features: &mut [f64],
+        previous: UltimateBoard,
+        board: UltimateBoard,
+        index: u8,
+        player: Player,
+    ) {
+        let board_index = (index / 9) as usize;
+
+        add_features(
+            features,
+            &small_board_features(previous.get_boards()[board_index], player),
+            -1.,
+        );
+        add_features(
+            features,
+            &small_board_features(board.get_boards()[board_index], player),
+            1.,
+        );
+
+        // The status features only change if the small board was won or drawn by the move
+        if previous.get_board_status()[board_index] != board.get_board_status()[board_index] {
+            add_features(features, &board_status_features(previous, player), -1.);
+            add_features(features, &board_status_features(board, player), 1.);
+        }
+    }
+
+    fn evaluate_features(&self, board: UltimateBoard, player: Player, features: &[f64]) -> f64 {
+        if board.get_game_status() == GameResult::Win(player) {
+            return MAX_VALUE;
+        }
+
+        if board.get_game_status() == GameResult::Win(player.get_opponent()) {
+            return MIN_VALUE;
+        }
+
+        let mut features: [f64; NUM_FEATURES] = features.try_into().unwrap();
+        set_move_features(&mut features, board, player);
+
+        let weighted_sum = |values: &[f64]| -> f64 {
+            values
+                .iter()
+                .zip(&features)
+                .map(|(value, feature)| value * feature)
+                .sum()
+        };
+
+        let value = if self.phases.is_empty() {
+            weighted_sum(&self.values)
+        } else {
+            phase_weights(board)
+                .iter()
+                .zip(&self.phases)
+                .map(|(weight, phase)| weight * weighted_sum(&phase.values))
+                .sum()
+        };
+
+        normalize(value, SCALE)
+    }
+
     fn get_name(&self) -> String {
         "PH".to_string()
     }
 }
 
+/// Extracts the features of a board state for a player
+///
+/// The features are the coefficients of the [weights](ParameterizedHeuristic::values) before weighting,
+/// the value of a [ParameterizedHeuristic] is the [normalized](normalize) sum of the weights multiplied by the features.
+/// Features whose values are multiplied by the same weight in the evaluation are added up in the coefficient of the weight,
+/// e.g. the edges of the small boards and the won center board.
+/// The features can be used to fit the weights to the results of games, e.g. with a logistic regression.
+/// # Arguments
+/// * `board` - The board state
+/// * `player` - The player to extract the features for
+/// # Returns
+/// The [NUM_FEATURES] features of the board state
+pub fn extract_features(board: UltimateBoard, player: Player) -> Vec<f64> {
+    compute_features(board, player).to_vec()
+}
+
+/// Computes the coefficients of the weights of a board state for a player
+fn compute_features(board: UltimateBoard, player: Player) -> [f64; NUM_FEATURES] {
+    let mut features = board_status_features(board, player);
+
+    for small_board in board.get_boards() {
+        add_features(
+            &mut features,
+            &small_board_features(small_board, player),
+            1.,
+        );
+    }
+
+    set_move_features(&mut features, board, player);
+
+    features
+}
+
+/// Returns the coefficients of the weights contributed by a small board, from the perspective of the player
+fn small_board_features(board: Board, player: Player) -> [f64; NUM_FEATURES] {
+    let board = board.to_canonical();
+    let sign = if player == Player::One { 1. } else { -1. };
+    let mut features = [0.; NUM_FEATURES];
+
+    features[3] = board.get_positions_set_difference(Player::One).max(0) as f64 * sign;
+    features[4] = board.get_partial_wins_difference(Player::One) as f64 * sign;
+    features[5] = board.center_occupied(Player::One) as f64 * sign;
+    features[6] = board.get_corners_difference(Player::One) as f64 * sign;
+    features[7] = board.get_edges_difference(Player::One) as f64 * sign;
+
+    features
+}
+
+/// Returns the coefficients of the weights contributed by the status of the small boards
+fn board_status_features(board: UltimateBoard, player: Player) -> [f64; NUM_FEATURES] {
+    let board_status = board.get_board_status();
+    let won = |index: usize| {
+        if board_status[index] == GameResult::Win(player) {
+            1.
+        } else {
+            -1.
+        }
+    };
+    let mut features = [0.; NUM_FEATURES];
+
+    for status in board_status {
+        match status {
+            GameResult::Win(winner) if winner == player => features[0] += 1.,
+            GameResult::Win(_) => features[0] -= 1.,
+            _ => features[1] += 1.,
+        }
+    }
+
+    features[7] = won(CENTER_INDEX);
+    features[8] = CORNER_INDICES.iter().map(|&index| won(index)).sum();
+    features[9] = EDGE_INDICES.iter().map(|&index| won(index)).sum();
+    features[10] = board.get_partial_wins_difference(player) as f64;
+
+    features
+}
+
+/// Sets the coefficients of the weights that change with every move
+fn set_move_features(features: &mut [f64; NUM_FEATURES], board: UltimateBoard, player: Player) {
+    features[11] = if board.get_next_board_index().is_none() {
+        1.
+    } else {
+        -1.
+    };
+
+    features[12] = tempo_feature(board, player);
+    features[13] = tempo_feature(board, player) * sent_board_quality(board);
+}
+
+/// Returns the [EvaluationCache] of the given weights from the shared caches, building it if it is not in use
+///
+/// The caches of the phases of phase-dependent weights are taken from the shared caches as well.
+/// # Arguments
+/// * `caches` - The locked [shared caches](SHARED_EVALUATION_CACHES)
+/// * `values` - The weights for the features
+/// # Returns
+/// The cache shared by all heuristics with the same weights
+fn shared_evaluation_cache(
+    caches: &mut HashMap<Vec<u64>, Weak<EvaluationCache>>,
+    values: &[f64],
+) -> Arc<EvaluationCache> {
+    let key: Vec<u64> = values.iter().map(|value| value.to_bits()).collect();
+
+    if let Some(evaluation_cache) = caches.get(&key).and_then(Weak::upgrade) {
+        return evaluation_cache;
+    }
+
+    let evaluation_cache = if values.len() == NUM_PHASED_FEATURES {
+        EvaluationCache::from_phases(
+            values
+                .chunks(NUM_FEATURES)
+                .map(|weights| shared_evaluation_cache(caches, weights))
+                .collect(),
+        )
+    } else {
+        EvaluationCache::new(&ParameterizedMiniBoardHeuristic::new(values.to_vec()))
+            .with_meta_board_heuristic(&ParameterizedMetaBoardHeuristic::new(values.to_vec()))
+    };
+    let evaluation_cache = Arc::new(evaluation_cache.with_weights(values.to_vec()));
+
+    caches.retain(|_, evaluation_cache| evaluation_cache.strong_count() > 0);
+    caches.insert(key, Arc::downgrade(&evaluation_cache));
+
+    evaluation_cache
+}
+
+/// Creates the heuristics of the phases of the game from phase-dependent weights
+///
+/// # Arguments
+/// * `values` - The weights, see [ParameterizedHeuristic::values]
+/// * `create` - Creates the heuristic of a phase from its weights
+/// # Returns
+/// The heuristics of the opening, the middlegame and the endgame, empty if the values are not [NUM_PHASED_FEATURES] weights
+fn phase_heuristics(
+    values: &[f64],
+    create: impl Fn(Vec<f64>) -> ParameterizedHeuristic,
+) -> Vec<ParameterizedHeuristic> {
+    if values.len() != NUM_PHASED_FEATURES {
+        return vec![];
+    }
+
+    values
+        .chunks(NUM_FEATURES)
+        .map(|weights| create(weights.to_vec()))
+        .collect()
+}
+
+/// Returns the share of the opening, the middlegame and the endgame in the weights of a board state
+///
+/// The progress of the game is the share of the 81 squares that can not be played anymore, see [UltimateBoard::count_empty_squares].
+/// The weights of the opening are used for the empty board, the weights of the middlegame when half of the squares can not be played anymore
+/// and the weights of the endgame at the end of the game. In between, the weights are interpolated linearly.
+/// # Arguments
+/// * `board` - The board state
+/// # Returns
+/// The shares of the phases, adding up to 1
+pub fn phase_weights(board: UltimateBoard) -> [f64; NUM_PHASES] {
+    let progress = 1. - board.count_empty_squares() as f64 / 81.;
+
+    if progress < 0.5 {
+        [1. - 2. * progress, 2. * progress, 0.]
+    } else {
+        [0., 2. - 2. * progress, 2. * progress - 1.]
+    }
+}
+
+/// Returns 1 if it is the turn of the player and -1 otherwise
+fn tempo_feature(board: UltimateBoard, player: Player) -> f64 {
+    if board.get_current_player() == player {
+        1.
+    } else {
+        -1.
+    }
+}
+
+/// Evaluates the small board the player to move is sent to, from the perspective of the player to move
+///
+/// Being sent to a finished small board allows choosing any small board, which is good for the player to move.
+/// Being sent to a small board the player to move can win immediately is good as well.
+/// Being sent to a small board only the opponent can win immediately forces the player to move to block it.
+/// # Arguments
+/// * `board` - The board state
+/// # Returns
+/// 1 for a free choice or a small board the player to move can win, -1 for a small board only the opponent can win, 0 otherwise
+fn sent_board_quality(board: UltimateBoard) -> f64 {
+    let Some(next_board_index) = board.get_next_board_index() else {
+        return 1.;
+    };
+
+    let player = board.get_current_player();
+    let small_board = board.get_boards()[next_board_index as usize];
+
+    if small_board.get_winning_squares(player) != BitBoard::EMPTY {
+        1.
+    } else if small_board.get_winning_squares(player.get_opponent()) != BitBoard::EMPTY {
+        -1.
+    } else {
+        0.
+    }
+}
+
+/// Adds the features multiplied by the factor
+fn add_features(features: &mut [f64], other: &[f64; NUM_FEATURES], factor: f64) {
+    for (feature, other) in features.iter_mut().zip(other) {
+        *feature += other * factor;
+    }
+}
+
 /// # Struct representing a [MiniBoardHeuristic] that uses weights for the features to evaluate small boards
 pub struct ParameterizedMiniBoardHeuristic {
     /// The weights for the features
@@ -179,21 +652,255 @@ impl ParameterizedMiniBoardHeuristic {
 
 impl MiniBoardHeuristic for ParameterizedMiniBoardHeuristic {
     fn evaluate(&self, board: Board) -> f64 {
-        let mut value = 0.;
+        evaluate_small_board(&self.values, board)
+    }
+}
+
+/// Evaluates a small board from the perspective of [Player::One]
+///
+/// The [canonical](Board::to_canonical) small board is evaluated, like in the lookup table.
+/// # Arguments
+/// * `values` - The weights for the features, see [ParameterizedHeuristic::values]
+/// * `board` - The small board to evaluate
+/// # Returns
+/// The value of the small board
+fn evaluate_small_board(values: &[f64], board: Board) -> f64 {
+    let board = board.to_canonical();
+    let mut value = 0.;
+
+    let positions_set_difference = board.get_positions_set_difference(Player::One) as f64;
+    if positions_set_difference > 0. {
+        value += positions_set_difference * values[3];
+    }
+
+    value += board.get_partial_wins_difference(Player::One) as f64 * values[4];
+
+    value += board.center_occupied(Player::One) as f64 * values[5];
+
+    value += board.get_corners_difference(Player::One) as f64 * values[6];
+
+    value += board.get_edges_difference(Player::One) as f64 * values[7];
+
+    value
+}
+
+/// # Struct representing a [MetaBoardHeuristic] that uses weights for the features to evaluate the won small boards
+pub struct ParameterizedMetaBoardHeuristic {
+    /// The weights for the features
+    ///
+    /// For the features, see [ParameterizedHeuristic::values]
+    values: Vec<f64>,
+}
+
+impl ParameterizedMetaBoardHeuristic {
+    pub fn new(values: Vec<f64>) -> Self {
+        ParameterizedMetaBoardHeuristic { values }
+    }
+}
+
+impl MetaBoardHeuristic for ParameterizedMetaBoardHeuristic {
+    fn evaluate(&self, board_status: [GameResult; 9]) -> f64 {
+        evaluate_meta_board(&self.values, board_status, Player::One)
+    }
+}
 
-        let positions_set_difference = board.get_positions_set_difference(Player::One) as f64;
-        if positions_set_difference > 0. {
-            value += positions_set_difference * self.values[3];
+/// Evaluates the won small boards from the perspective of a player
+///
+/// # Arguments
+/// * `values` - The weights for the features, see [ParameterizedHeuristic::values]
+/// * `board_status` - The status of the small boards
+/// * `player` - The player to evaluate the small boards for
+/// # Returns
+/// The value of the won small boards
+fn evaluate_meta_board(values: &[f64], board_status: [GameResult; 9], player: Player) -> f64 {
+    let mut value = 0.;
+
+    let mut diff_wins: f64 = 0.;
+    for status in board_status {
+        if let GameResult::Win(winner) = status {
+            if winner == player {
+                diff_wins += 1.;
+            } else {
+                diff_wins -= 1.;
+            }
+        } else {
+            value += values[1];
         }
+    }
+    value += diff_wins * values[0];
+
+    value += if board_status[CENTER_INDEX] == GameResult::Win(player) {
+        values[7]
+    } else {
+        -values[7]
+    };
+
+    for corner_index in CORNER_INDICES.iter() {
+        value += if board_status[*corner_index] == GameResult::Win(player) {
+            values[8]
+        } else {
+            -values[8]
+        };
+    }
 
-        value += board.get_partial_wins_difference(Player::One) as f64 * self.values[4];
+    for edge_index in EDGE_INDICES.iter() {
+        value += if board_status[*edge_index] == GameResult::Win(player) {
+            values[9]
+        } else {
+            -values[9]
+        };
+    }
 
-        value += board.center_occupied(Player::One) as f64 * self.values[5];
+    value += partial_wins_difference(&board_status, player) as f64 * values[10];
 
-        value += board.get_corners_difference(Player::One) as f64 * self.values[6];
+    value
+}
 
-        value += board.get_edges_difference(Player::One) as f64 * self.values[7];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        value
+    #[test]
+    fn test_tempo() {
+        let squares = format!("....X....{}", ".".repeat(72));
+        let x_to_move = UltimateBoard::from_position(&format!("{} - X", squares)).unwrap();
+        let o_to_move = UltimateBoard::from_position(&format!("{} - O", squares)).unwrap();
+
+        // Only the tempo is weighted
+        let mut values = vec![0.; NUM_FEATURES];
+        values[12] = 1.;
+        let one = ParameterizedHeuristic::new(Player::One, values.clone());
+        let two = ParameterizedHeuristic::new(Player::Two, values);
+
+        assert!(one.evaluate(x_to_move) > 0.);
+        assert_eq!(one.evaluate(x_to_move), -one.evaluate(o_to_move));
+        assert_eq!(one.evaluate(x_to_move), -two.evaluate(x_to_move));
+        assert_eq!(one.evaluate(o_to_move), two.evaluate(x_to_move));
+
+        // Without a weight for the tempo, the side to move is not evaluated
+        let old = ParameterizedHeuristic::new(Player::One, vec![1.; 12]);
+        assert_eq!(old.evaluate(x_to_move), old.evaluate(o_to_move));
+    }
+
+    #[test]
+    fn test_evaluate_for() {
+        let values: Vec<f64> = (0..NUM_FEATURES).map(|i| i as f64 / 3. - 2.).collect();
+
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 44, 74, 21] {
+            board.make_move(index);
+        }
+
+        // A heuristic evaluates the board for the other player like a heuristic created for the other player
+        for (one, two) in [
+            (
+                ParameterizedHeuristic::new(Player::One, values.clone()),
+                ParameterizedHeuristic::new(Player::Two, values.clone()),
+            ),
+            (
+                ParameterizedHeuristic::with_look_up_table(Player::One, values.clone()),
+                ParameterizedHeuristic::with_look_up_table(Player::Two, values.clone()),
+            ),
+        ] {
+            assert_eq!(one.evaluate_for(board, Player::One), one.evaluate(board));
+            assert_eq!(one.evaluate_for(board, Player::Two), two.evaluate(board));
+            assert_eq!(two.evaluate_for(board, Player::One), one.evaluate(board));
+        }
+    }
+
+    #[test]
+    fn test_extract_features() {
+        let values: Vec<f64> = (0..NUM_FEATURES).map(|i| i as f64 / 3. - 2.).collect();
+
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 44, 74, 21] {
+            board.make_move(index);
+        }
+
+        for player in [Player::One, Player::Two] {
+            let features = extract_features(board, player);
+            assert_eq!(features.len(), NUM_FEATURES);
+
+            // The heuristic is the normalized weighted sum of the features
+            let value = values.iter().zip(&features).map(|(v, f)| v * f).sum();
+            let heuristic = ParameterizedHeuristic::new(player, values.clone());
+            assert!((normalize(value, SCALE) - heuristic.evaluate(board)).abs() < 1e-9);
+
+            // Player One is to move
+            let tempo = if player == Player::One { 1. } else { -1. };
+            assert_eq!(features[12], tempo);
+        }
+    }
+
+    #[test]
+    fn test_phased_values() {
+        let values: Vec<f64> = (0..NUM_FEATURES).map(|i| i as f64 / 3. - 2.).collect();
+
+        let mut board = UltimateBoard::new();
+        for index in [40, 36, 4, 44, 74, 21] {
+            board.make_move(index);
+        }
+
+        let weights = phase_weights(board);
+        assert!((weights.iter().sum::<f64>() - 1.).abs() < 1e-9);
+        assert_eq!(phase_weights(UltimateBoard::new()), [1., 0., 0.]);
+
+        // The same weights in every phase evaluate the board like weights independent of the phase
+        let phased = values.repeat(NUM_PHASES);
+        for player in [Player::One, Player::Two] {
+            let expected = ParameterizedHeuristic::new(player, values.clone()).evaluate(board);
+
+            for heuristic in [
+                ParameterizedHeuristic::new(player, phased.clone()),
+                ParameterizedHeuristic::with_look_up_table(player, phased.clone()),
+            ] {
+                assert!((heuristic.evaluate(board) - expected).abs() < 1e-9);
+            }
+        }
+
+        // Weights only in the endgame do not affect the opening
+        let mut endgame = vec![0.; NUM_PHASED_FEATURES];
+        endgame[2 * NUM_FEATURES..].copy_from_slice(&values);
+        let heuristic = ParameterizedHeuristic::new(Player::One, endgame);
+        assert_eq!(heuristic.evaluate(board), 0.);
+
+        // The accumulated features are weighted with the phases as well
+        let heuristic = ParameterizedHeuristic::new(Player::One, phased);
+        let features = heuristic.initialize_features(board, Player::One).unwrap();
+        assert!(
+            (heuristic.evaluate_features(board, Player::One, &features)
+                - heuristic.evaluate(board))
+            .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_sent_board_quality() {
+        // X threatens to win board 0 in square 2, O threatens to win board 1 in square 2
+        let squares = format!("XX.......OO.......{}", ".".repeat(63));
+        let board = |next: &str, player: &str| {
+            UltimateBoard::from_position(&format!("{} {} {}", squares, next, player)).unwrap()
+        };
+
+        assert_eq!(sent_board_quality(board("-", "X")), 1.);
+        assert_eq!(sent_board_quality(board("0", "X")), 1.);
+        assert_eq!(sent_board_quality(board("1", "X")), -1.);
+        assert_eq!(sent_board_quality(board("2", "X")), 0.);
+        assert_eq!(sent_board_quality(board("0", "O")), -1.);
+
+        // Only the sent-to board is weighted
+        let mut values = vec![0.; NUM_FEATURES];
+        values[13] = 1.;
+        let one = ParameterizedHeuristic::new(Player::One, values.clone());
+        let two = ParameterizedHeuristic::new(Player::Two, values);
+
+        // Sending the opponent to a board they can win is bad
+        assert!(one.evaluate(board("1", "O")) < 0.);
+        assert!(one.evaluate(board("0", "O")) > 0.);
+        assert_eq!(
+            one.evaluate(board("1", "O")),
+            -two.evaluate(board("1", "O"))
+        );
     }
 }
@@ -0,0 +1,137 @@
+//! # Contains the [TablebaseHeuristic] struct
+//! The TablebaseHeuristic struct wraps a [Heuristic] and returns the exact values of the positions stored in a [Tablebase].
+//!
+//! Positions close to the end of the game are evaluated exactly without changing the search,
+//! e.g. the leaves at the end of the quiescence lines of the [MiniMaxAgent](crate::agent::minimax_agent::MiniMaxAgent).
+//! All other positions are evaluated by the wrapped heuristic.
+
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::tablebase::Tablebase;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{Heuristic, MAX_VALUE, MIN_VALUE};
+use std::sync::Arc;
+
+/// # Struct representing a [Heuristic] probing a [Tablebase] before evaluating a position with another heuristic
+///
+/// Won and lost positions of the tablebase are evaluated as [MAX_VALUE] and [MIN_VALUE], drawn positions as 0.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use hausarbeit::game::player::Player;
+/// use hausarbeit::game::tablebase::Tablebase;
+/// use hausarbeit::heuristic::custom_heuristic::CustomHeuristic;
+/// use hausarbeit::heuristic::tablebase_heuristic::TablebaseHeuristic;
+///
+/// let tablebase = Arc::new(Tablebase::generate(8, 10, 0));
+///
+/// let heuristic = TablebaseHeuristic::new(Player::One, tablebase, CustomHeuristic::new(Player::One));
+/// ```
+#[derive(Clone)]
+pub struct TablebaseHeuristic<H: Heuristic> {
+    player: Player,
+    tablebase: Arc<Tablebase>,
+    heuristic: H,
+}
+
+impl<H: Heuristic> TablebaseHeuristic<H> {
+    /// Creates a new [TablebaseHeuristic]
+    ///
+    /// # Arguments
+    /// * `player` - The [Player] for which the heuristic should evaluate the board, should be the player of the wrapped heuristic
+    /// * `tablebase` - The tablebase probed before every evaluation
+    /// * `heuristic` - The heuristic evaluating the positions not stored in the tablebase
+    pub fn new(player: Player, tablebase: Arc<Tablebase>, heuristic: H) -> TablebaseHeuristic<H> {
+        TablebaseHeuristic {
+            player,
+            tablebase,
+            heuristic,
+        }
+    }
+
+    /// Returns the exact value of a position stored in the tablebase for a player
+    fn probe(&self, board: UltimateBoard, player: Player) -> Option<f64> {
+        self.tablebase.probe(board).map(|result| match result {
+            GameResult::Win(winner) if winner == player => MAX_VALUE,
+            GameResult::Win(_) => MIN_VALUE,
+            _ => 0.,
+        })
+    }
+}
+
+impl<H: Heuristic> Heuristic for TablebaseHeuristic<H> {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.probe(board, self.player)
+            .unwrap_or_else(|| self.heuristic.evaluate(board))
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        self.probe(board, player)
+            .unwrap_or_else(|| self.heuristic.evaluate_for(board, player))
+    }
+
+    fn get_name(&self) -> String {
+        format!("TB({})", self.heuristic.get_name())
+    }
+
+    fn initialize_features(&self, board: UltimateBoard, player: Player) -> Option<Vec<f64>> {
+        self.heuristic.initialize_features(board, player)
+    }
+
+    fn update_features(
+        &self,
+        features: &mut [f64],
+        previous: UltimateBoard,
+        board: UltimateBoard,
+        index: u8,
+        player: Player,
+    ) {
+        self.heuristic
+            .update_features(features, previous, board, index, player)
+    }
+
+    fn evaluate_features(&self, board: UltimateBoard, player: Player, features: &[f64]) -> f64 {
+        self.probe(board, player)
+            .unwrap_or_else(|| self.heuristic.evaluate_features(board, player, features))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristic::custom_heuristic::CustomHeuristic;
+    use rand::seq::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_tablebase_heuristic() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut tablebase = Tablebase::new(10);
+
+        let mut board = UltimateBoard::new();
+        while board.get_game_status() == GameResult::Continue && board.count_empty_squares() > 10 {
+            board.make_move(board.get_possible_moves().choose(&mut rng).unwrap());
+        }
+        let result = tablebase.solve(board).unwrap();
+
+        let tablebase = Arc::new(tablebase);
+        let custom = CustomHeuristic::new(Player::One);
+        let heuristic = TablebaseHeuristic::new(Player::One, tablebase, custom.clone());
+
+        // Stored positions are evaluated exactly
+        let expected = match result {
+            GameResult::Win(Player::One) => MAX_VALUE,
+            GameResult::Win(Player::Two) => MIN_VALUE,
+            _ => 0.,
+        };
+        assert_eq!(heuristic.evaluate(board), expected);
+
+        // Other positions are evaluated by the wrapped heuristic
+        let mut board = UltimateBoard::new();
+        board.make_move(40);
+        assert_eq!(heuristic.evaluate(board), custom.evaluate(board));
+        assert_eq!(heuristic.get_name(), "TB(SH)");
+    }
+}
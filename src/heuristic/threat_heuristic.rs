@@ -0,0 +1,154 @@
+//! # Contains the [ThreatHeuristic] struct
+//! The ThreatHeuristic struct represents a heuristic that evaluates the tactical threats of both players.
+//!
+//! A threat is an empty square completing a win position of a small board,
+//! or an unfinished small board completing a win position of the meta-board.
+//! Two threats at once (a fork) can only be blocked once, so forks are rewarded more than single threats.
+//! Threats on small boards that complete a win position of the meta-board are decisive and rewarded the most.
+
+use crate::game::board::Board;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::heuristic::{normalize, Heuristic, MAX_VALUE, MIN_VALUE};
+
+/// The raw evaluation that is [normalized](normalize) to about 0.75
+const SCALE: f64 = 50.;
+
+/// The raw evaluation of a won small board
+const WON_BOARD: f64 = 10.;
+/// The raw evaluation of an empty square winning a small board
+const SMALL_BOARD_THREAT: f64 = 1.;
+/// The raw evaluation of a small board with at least two winning squares
+const SMALL_BOARD_FORK: f64 = 3.;
+/// The raw evaluation of an unfinished small board winning the game
+const META_BOARD_THREAT: f64 = 6.;
+/// The raw evaluation of at least two unfinished small boards winning the game
+const META_BOARD_FORK: f64 = 12.;
+/// The raw evaluation of an empty square winning a small board that wins the game
+const DECISIVE_THREAT: f64 = 8.;
+/// The raw evaluation of the player to move being able to win the game with the next move
+const IMMEDIATE_WIN: f64 = 30.;
+
+/// A [Heuristic] that evaluates the won small boards and the threats of both players.
+///
+/// The value is the weighted difference of the threats of the player and the threats of the opponent.
+/// # Fields
+/// * `player` - The [Player] for which the heuristic should evaluate the board state.
+#[derive(Clone)]
+pub struct ThreatHeuristic {
+    player: Player,
+}
+
+impl ThreatHeuristic {
+    pub fn new(player: Player) -> Self {
+        ThreatHeuristic { player }
+    }
+
+    /// Computes the raw evaluation of the threats of a player
+    ///
+    /// # Arguments
+    /// * `board` - The board state to evaluate
+    /// * `player` - The player whose threats are evaluated
+    /// # Returns
+    /// The raw evaluation, not normalized
+    fn evaluate_threats(board: &UltimateBoard, player: Player) -> f64 {
+        let mut value = 0.;
+
+        value += board.won_boards_mask(player).into_iter().count() as f64 * WON_BOARD;
+
+        let winning_boards = board.get_meta_winning_boards(player);
+        let meta_board_threats = winning_boards.into_iter().count();
+
+        value += meta_board_threats as f64 * META_BOARD_THREAT;
+        if meta_board_threats >= 2 {
+            value += META_BOARD_FORK;
+        }
+
+        let board_status = board.get_board_status();
+        for (index, small_board) in board.get_boards().iter().enumerate() {
+            if board_status[index] != GameResult::Continue {
+                continue;
+            }
+
+            let threats = small_board.get_winning_squares(player).into_iter().count();
+            if threats == 0 {
+                continue;
+            }
+
+            value += threats as f64 * SMALL_BOARD_THREAT;
+            if threats >= 2 {
+                value += SMALL_BOARD_FORK;
+            }
+
+            let bit = 1 << Board::from_human_to_bit(index as u8);
+            if u16::from(winning_boards) & bit != 0 {
+                value += DECISIVE_THREAT;
+            }
+        }
+
+        value
+    }
+}
+
+impl Heuristic for ThreatHeuristic {
+    fn evaluate(&self, board: UltimateBoard) -> f64 {
+        self.evaluate_for(board, self.player)
+    }
+
+    fn evaluate_for(&self, board: UltimateBoard, player: Player) -> f64 {
+        if board.get_game_status() == GameResult::Win(player) {
+            return MAX_VALUE;
+        }
+
+        if board.get_game_status() == GameResult::Win(player.get_opponent()) {
+            return MIN_VALUE;
+        }
+
+        let mut value = ThreatHeuristic::evaluate_threats(&board, player)
+            - ThreatHeuristic::evaluate_threats(&board, player.get_opponent());
+
+        // Only the player to move can use a decisive threat on the boards they may play on
+        let current_player = board.get_current_player();
+        if board
+            .get_game_winning_moves(current_player)
+            .next()
+            .is_some()
+        {
+            value += if current_player == player {
+                IMMEDIATE_WIN
+            } else {
+                -IMMEDIATE_WIN
+            };
+        }
+
+        normalize(value, SCALE)
+    }
+
+    fn get_name(&self) -> String {
+        "TH".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threat_heuristic() {
+        // X has won the boards 0 and 1 and threatens to win board 2 in its square 2
+        let position = format!("XXX......XXX......XX{}", ".".repeat(61));
+        let board = UltimateBoard::from_position(&format!("{} 2 X", position)).unwrap();
+
+        let one = ThreatHeuristic::new(Player::One);
+        let two = ThreatHeuristic::new(Player::Two);
+        assert!(one.evaluate(board) > 0.);
+        assert_eq!(one.evaluate(board), -two.evaluate(board));
+
+        // The same threat is worth more for the player to move
+        let o_to_move = UltimateBoard::from_position(&format!("{} 2 O", position)).unwrap();
+        assert!(one.evaluate(board) > one.evaluate(o_to_move));
+
+        assert_eq!(one.evaluate(UltimateBoard::new()), 0.);
+    }
+}
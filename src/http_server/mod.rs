@@ -0,0 +1,490 @@
+//! # Contains the [HttpServer] struct
+//!
+//! The HttpServer lets anyone with a browser play against the agents of the [AgentRegistry].
+//! It serves a static web board and a small JSON API, the games are played by [Game] on background threads.
+//!
+//! This module requires the `http` feature, the server only uses the standard library.
+//!
+//! # Endpoints
+//! * `GET /` - The web board
+//! * `GET /agents` - The agent kinds clients can play against, e.g. `["greedy", "mcts", ..]`
+//! * `POST /games` with `{"agent": "mcts:i=1000", "player": "One"}` - Starts a game against the agent, the client plays as the given player
+//! * `GET /games/<id>` - The [GameState] of the game
+//! * `POST /games/<id>/moves` with `{"index": 40}` - Plays the move for the client, only accepted if it is the turn of the client
+//!
+//! The responses to `POST /games` and the moves are the [GameState] after the request.
+//! Invalid requests are answered with a status code of 400 or 404 and `{"error": ".."}`.
+//!
+//! The agents are created for the opponent of the client with [AgentRegistry::create].
+//! The kinds in [EXCLUDED_KINDS] can not be started by clients, as they would read the console of the server or connect to other hosts.
+use crate::agent::agent_error::AgentError;
+use crate::agent::registry::{AgentRegistry, AgentSpec};
+use crate::agent::{Agent, AgentInfo};
+use crate::game::board_export::BoardExport;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::game::ultimate_board::UltimateBoard;
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{BufRead, BufReader, Error, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// The agent kinds clients can not play against
+pub const EXCLUDED_KINDS: [&str; 2] = ["human", "remote"];
+
+/// The web board served at `/`
+const BOARD_PAGE: &str = include_str!("board.html");
+
+/// # Struct representing the state of a game sent to the client
+///
+/// # Fields
+/// * `id` - The id of the game used in the paths of the endpoints
+/// * `opponent` - The specification of the agent the client plays against
+/// * `client_player` - The player of the client
+/// * `board` - The current board
+/// * `your_turn` - Whether the server waits for a move of the client
+/// * `result` - The result of the game, [GameResult::Continue] while the game is played
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    pub id: usize,
+    pub opponent: String,
+    pub client_player: Player,
+    pub board: BoardExport,
+    pub your_turn: bool,
+    pub result: GameResult,
+}
+
+/// # Struct representing the request to start a game
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewGameRequest {
+    /// The specification of the opponent, e.g. `mcts:i=1000`
+    pub agent: String,
+    /// The player of the client, [Player::One] if missing
+    #[serde(default)]
+    pub player: Player,
+}
+
+/// # Struct representing the request to play a move
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MoveRequest {
+    /// The human index of the square (0-80)
+    pub index: u8,
+}
+
+/// # Struct representing the state of a game shared by the server and the agent of the client
+struct SessionState {
+    board: UltimateBoard,
+    your_turn: bool,
+    result: GameResult,
+}
+
+/// # Struct representing a game played on the server
+struct Session {
+    opponent: String,
+    client_player: Player,
+    state: Arc<Mutex<SessionState>>,
+    /// The moves of the client, received by the [ClientAgent]
+    moves: Sender<u8>,
+}
+
+/// An agent playing the moves the client submits over HTTP
+struct ClientAgent {
+    state: Arc<Mutex<SessionState>>,
+    moves: Mutex<Receiver<u8>>,
+    player: Player,
+    turn: u32,
+}
+
+impl Agent for ClientAgent {
+    fn act(&mut self, board: &UltimateBoard, player: Player, turn: u32) -> Result<u8, AgentError> {
+        self.player = player;
+        self.turn = turn;
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.board = *board;
+            state.your_turn = true;
+        }
+
+        // The server only submits legal moves
+        self.moves
+            .lock()
+            .unwrap()
+            .recv()
+            .map_err(|_| AgentError::Io("The server has stopped".to_string()))
+    }
+
+    fn get_info(&self) -> AgentInfo {
+        AgentInfo::new("HTTP".to_string(), self.player, self.turn, "".to_string())
+    }
+
+    fn opponent_moved(&mut self, board: UltimateBoard, _index: u8) {
+        self.state.lock().unwrap().board = board;
+    }
+
+    fn game_over(&mut self, board: UltimateBoard, _result: GameResult) {
+        self.state.lock().unwrap().board = board;
+    }
+}
+
+/// Server letting browser clients play against the agents of an [AgentRegistry] over HTTP
+///
+/// The requests are handled one after another, every game is played on its own thread.
+///
+/// # Example
+/// ```no_run
+/// use hausarbeit::agent::registry::AgentRegistry;
+/// use hausarbeit::http_server::HttpServer;
+///
+/// let server = HttpServer::bind("127.0.0.1:8080", AgentRegistry::new()).unwrap();
+///
+/// // Open http://127.0.0.1:8080 in a browser
+/// server.run();
+/// ```
+pub struct HttpServer {
+    listener: TcpListener,
+    registry: AgentRegistry,
+    sessions: Mutex<Vec<Session>>,
+}
+
+impl HttpServer {
+    /// Creates a new [HttpServer] accepting clients on the given address
+    ///
+    /// # Arguments
+    /// * `address` - The address to listen on
+    /// * `registry` - The registry creating the agents the clients play against
+    /// # Returns
+    /// The server, an error if the address could not be bound
+    pub fn bind(address: impl ToSocketAddrs, registry: AgentRegistry) -> Result<HttpServer, Error> {
+        Ok(HttpServer {
+            listener: TcpListener::bind(address)?,
+            registry,
+            sessions: Mutex::new(vec![]),
+        })
+    }
+
+    /// Returns the address the server accepts clients on
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.listener.local_addr()
+    }
+
+    /// Handles requests until the listener fails
+    pub fn run(&self) {
+        for stream in self.listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+
+            // A client closing the connection early does not stop the server
+            let _ = self.handle(stream);
+        }
+    }
+
+    /// Reads a request from the stream and writes the response
+    fn handle(&self, mut stream: TcpStream) -> Result<(), Error> {
+        let (method, path, body) = read_request(&mut BufReader::new(&stream))?;
+
+        let (status, content_type, body) = match self.route(&method, &path, &body) {
+            Ok(Response::Page(page)) => ("200 OK", "text/html; charset=utf-8", page.to_string()),
+            Ok(Response::Json(json)) => ("200 OK", "application/json", json),
+            Err((status, message)) => (
+                status,
+                "application/json",
+                json!({ "error": message }).to_string(),
+            ),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// Answers a request
+    ///
+    /// # Returns
+    /// The response, or the status and the description of the error
+    fn route(
+        &self,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> Result<Response, (&'static str, String)> {
+        let segments: Vec<&str> = path
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match (method, segments.as_slice()) {
+            ("GET", []) => Ok(Response::Page(BOARD_PAGE)),
+            ("GET", ["agents"]) => Ok(Response::Json(json!(self.get_kinds()).to_string())),
+            ("POST", ["games"]) => {
+                let request: NewGameRequest = parse_body(body)?;
+                self.start_game(request).map(to_json)
+            }
+            ("GET", ["games", id]) => self.get_state(parse_id(id)?).map(to_json),
+            ("POST", ["games", id, "moves"]) => {
+                let request: MoveRequest = parse_body(body)?;
+                self.play_move(parse_id(id)?, request.index).map(to_json)
+            }
+            _ => Err((
+                "404 Not Found",
+                format!("Unknown endpoint {} {}", method, path),
+            )),
+        }
+    }
+
+    /// Returns the agent kinds clients can play against
+    fn get_kinds(&self) -> Vec<&str> {
+        self.registry
+            .get_kinds()
+            .into_iter()
+            .filter(|kind| !EXCLUDED_KINDS.contains(kind))
+            .collect()
+    }
+
+    /// Creates the opponent and starts the game on a new thread
+    fn start_game(&self, request: NewGameRequest) -> Result<GameState, (&'static str, String)> {
+        let spec: AgentSpec = request.agent.parse().map_err(bad_request)?;
+        if !self.get_kinds().contains(&spec.get_kind()) {
+            return Err(bad_request(format!(
+                "Agent '{}' can not be played against",
+                spec.get_kind()
+            )));
+        }
+        let opponent = self
+            .registry
+            .create_from_spec(&spec, request.player.get_opponent())
+            .map_err(bad_request)?;
+
+        let state = Arc::new(Mutex::new(SessionState {
+            board: UltimateBoard::new(),
+            your_turn: false,
+            result: GameResult::Continue,
+        }));
+        let (sender, receiver) = channel();
+        let client = Box::new(ClientAgent {
+            state: state.clone(),
+            moves: Mutex::new(receiver),
+            player: request.player,
+            turn: 0,
+        });
+
+        let mut game = match request.player {
+            Player::One => Game::new(client, opponent),
+            Player::Two => Game::new(opponent, client),
+        };
+        let game_state = state.clone();
+        std::thread::spawn(move || {
+            let result = game.play();
+            game_state.lock().unwrap().result = result;
+        });
+
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.push(Session {
+            opponent: spec.to_string(),
+            client_player: request.player,
+            state,
+            moves: sender,
+        });
+        let id = sessions.len() - 1;
+        drop(sessions);
+
+        self.get_state(id)
+    }
+
+    /// Returns the state of the game with the given id
+    fn get_state(&self, id: usize) -> Result<GameState, (&'static str, String)> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(id)
+            .ok_or(("404 Not Found", format!("Unknown game {}", id)))?;
+        let state = session.state.lock().unwrap();
+
+        Ok(GameState {
+            id,
+            opponent: session.opponent.clone(),
+            client_player: session.client_player,
+            board: state.board.into(),
+            your_turn: state.your_turn,
+            result: state.result,
+        })
+    }
+
+    /// Submits a move of the client to the game with the given id
+    fn play_move(&self, id: usize, index: u8) -> Result<GameState, (&'static str, String)> {
+        {
+            let sessions = self.sessions.lock().unwrap();
+            let session = sessions
+                .get(id)
+                .ok_or(("404 Not Found", format!("Unknown game {}", id)))?;
+            let mut state = session.state.lock().unwrap();
+
+            if !state.your_turn {
+                return Err(bad_request("It is not your turn".to_string()));
+            }
+            if !state.board.is_legal_move(index) {
+                return Err(bad_request(format!("Illegal move {}", index)));
+            }
+
+            // The move is shown until the opponent has replied
+            state.board.make_move(index);
+            state.your_turn = false;
+            session
+                .moves
+                .send(index)
+                .map_err(|_| bad_request("The game has ended".to_string()))?;
+        }
+
+        self.get_state(id)
+    }
+}
+
+/// # Enum representing a successful response
+enum Response {
+    Page(&'static str),
+    Json(String),
+}
+
+fn to_json(state: GameState) -> Response {
+    Response::Json(serde_json::to_string(&state).unwrap())
+}
+
+fn bad_request(error: impl ToString) -> (&'static str, String) {
+    ("400 Bad Request", error.to_string())
+}
+
+fn parse_body<T: for<'a> Deserialize<'a>>(body: &str) -> Result<T, (&'static str, String)> {
+    serde_json::from_str(body).map_err(bad_request)
+}
+
+fn parse_id(id: &str) -> Result<usize, (&'static str, String)> {
+    id.parse()
+        .map_err(|_| ("404 Not Found", format!("Unknown game {}", id)))
+}
+
+/// Reads the method, the path and the body of a request
+///
+/// # Arguments
+/// * `input` - The input of the connection
+fn read_request(input: &mut impl BufRead) -> Result<(String, String, String), Error> {
+    let mut request_line = String::new();
+    input.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    input.read_exact(&mut body)?;
+
+    Ok((method, path, String::from_utf8_lossy(&body).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// Sends a request to the server
+    ///
+    /// # Returns
+    /// The status line and the body of the response
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+        (head.lines().next().unwrap().to_string(), body.to_string())
+    }
+
+    #[test]
+    fn test_http_server() {
+        let server = HttpServer::bind("127.0.0.1:0", AgentRegistry::new()).unwrap();
+        let address = server.local_addr().unwrap();
+        std::thread::spawn(move || server.run());
+
+        assert!(request(address, "GET", "/", "").1.contains("<html"));
+        let (_, kinds) = request(address, "GET", "/agents", "");
+        assert!(kinds.contains("\"random\"") && !kinds.contains("\"human\""));
+        let (status, _) = request(address, "POST", "/games", r#"{"agent": "human"}"#);
+        assert!(status.contains("400"));
+
+        let (status, body) = request(
+            address,
+            "POST",
+            "/games",
+            r#"{"agent": "random", "player": "Two"}"#,
+        );
+        assert!(status.contains("200"), "{}", body);
+        let mut state: GameState = serde_json::from_str(&body).unwrap();
+        assert_eq!(state.client_player, Player::Two);
+
+        let path = format!("/games/{}", state.id);
+        let moves_path = format!("{}/moves", path);
+        let mut illegal_checked = false;
+
+        while state.result == GameResult::Continue {
+            if !state.your_turn {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                state = serde_json::from_str(&request(address, "GET", &path, "").1).unwrap();
+                continue;
+            }
+
+            if !illegal_checked {
+                let (status, _) = request(address, "POST", &moves_path, r#"{"index": 81}"#);
+                assert!(status.contains("400"));
+                illegal_checked = true;
+            }
+
+            let index = state.board.possible_moves[0];
+            let (status, body) = request(
+                address,
+                "POST",
+                &moves_path,
+                &format!(r#"{{"index": {}}}"#, index),
+            );
+            assert!(status.contains("200"), "{}", body);
+            state = serde_json::from_str(&body).unwrap();
+            assert_eq!(state.board.squares[index as usize], Some(Player::Two));
+        }
+
+        assert!(illegal_checked);
+        assert_eq!(state.result, state.board.game_status);
+    }
+}
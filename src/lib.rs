@@ -7,23 +7,48 @@
 //! * [HumanAgent](agent::human_agent::HumanAgent): An agent that requires user input to play.
 //! * [MiniMaxAgent](agent::minimax_agent::MiniMaxAgent): An agent that uses the minimax algorithm to determine the best move.
 //! * [MonteCarloTreeAgent](agent::monte_carlo_tree_agent::MonteCarloTreeAgent): An agent that uses the Monte Carlo Tree Search algorithm to determine the best move.
+//! * [GreedyHeuristicAgent](agent::greedy_agent::GreedyHeuristicAgent): An agent that plays the move with the best heuristic evaluation after one ply.
 //! * [RandomAgent](agent::random_agent::RandomAgent): An agent that plays random moves.
+//! * [SoftmaxAgent](agent::softmax_agent::SoftmaxAgent): An agent that samples moves with a probability given by the softmax of their heuristic evaluations.
 //!
 //! ## Utility agents:
 //! * [BenchedAgent](agent::benched::BenchedAgent): An agent that logs the time it takes to make a move.
-//! * [RandomStartAgent](agent::random_start::RandomStartAgent): An agent that uses a random agent for the first `depth` turns, then switches to another agent.
+//! * [CoachAgent](agent::coach_agent::CoachAgent): An agent that reviews the moves of another agent and explains blunders.
+//! * [EnsembleAgent](agent::ensemble_agent::EnsembleAgent): An agent that plays the move with the most (weighted) votes of several agents.
+//! * [EpsilonGreedyAgent](agent::epsilon_greedy_agent::EpsilonGreedyAgent): An agent that plays a random move with the probability epsilon, and the move of another agent otherwise.
+//! * [LoggingAgent](agent::logging_agent::LoggingAgent): An agent that records the position, the move and the evaluation of another agent for every move.
+//! * [HybridAgent](agent::hybrid_agent::HybridAgent): An agent that delegates to different agents depending on the turn or the number of empty squares.
+//! * [RandomStartAgent](agent::random_start::RandomStartAgent): An agent that plays random moves, a fixed sequence of moves or a book opening for the first turns, then switches to another agent.
+//! * [RemoteAgent](agent::remote_agent::RemoteAgent): An agent that plays the moves of an engine connected over TCP, e.g. a local agent exposed by an [AgentServer](agent::remote_agent::AgentServer). A [Relay](agent::remote_agent::Relay) plays games between two connected clients, e.g. two human players.
+//! * `WebSocketAgent`: An agent that plays the moves of a browser client connected over a WebSocket, requires the `net` feature.
+//! * [ScriptedAgent](agent::scripted_agent::ScriptedAgent): An agent that replays a fixed list of moves, e.g. of a recorded game.
+//! * [SymmetryAgent](agent::symmetry_agent::SymmetryAgent): An agent that lets another agent play on a rotated or reflected board.
+//! * [TablebaseAgent](agent::tablebase_agent::TablebaseAgent): An agent that plays perfectly in the positions solved by a [Tablebase](game::tablebase::Tablebase), and uses another agent otherwise.
+//! * [TimeLimitedAgent](agent::time_limited_agent::TimeLimitedAgent): An agent that enforces a hard deadline for every move of another agent and falls back to a fast agent.
 //!
 //! A custom agent can be implemented by implementing the [Agent](agent::Agent) trait.
+//! Agents can be created by name, e.g. `mcts:i=10000`, with the [AgentRegistry](agent::registry::AgentRegistry).
 //!
 //! ## Provided heuristics:
 //! * [CustomHeuristic](heuristic::custom_heuristic::CustomHeuristic): A heuristic that uses a custom evaluation function.
-//! * [MonteCarloGameSearchHeuristic](heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic): A heuristic that uses Monte Carlo Tree Search to evaluate the best move.
+//! * [MonteCarloGameSearchHeuristic](heuristic::monte_carlo_game_search_heuristic::MonteCarloGameSearchHeuristic): A heuristic that evaluates the board with the results of random games.
 //! * [ParameterizedHeuristic](heuristic::parameterized_heuristic::ParameterizedHeuristic): A heuristic that uses a parameterized evaluation function.
+//! * [CombinedHeuristic](heuristic::combined_heuristic::CombinedHeuristic): A heuristic that sums the weighted, normalized evaluations of other heuristics.
+//! * [ThreatHeuristic](heuristic::threat_heuristic::ThreatHeuristic): A heuristic that counts the winning threats and forks of both players on the small boards and the meta-board.
+//! * `NnHeuristic`: A heuristic that evaluates the board with the value of a neural network, requires the `onnx` feature.
+//! * [TablebaseHeuristic](heuristic::tablebase_heuristic::TablebaseHeuristic): A heuristic that returns the exact values of the positions stored in a [Tablebase](game::tablebase::Tablebase) and evaluates the other positions with another heuristic.
 //!
-//! A custom heuristic can be implemented by implementing the [Heuristic](heuristic::Heuristic) and [MiniBoardHeuristic](heuristic::MiniBoardHeuristic) trait.
+//! A custom heuristic can be implemented by implementing the [Heuristic](heuristic::Heuristic) and [MiniBoardHeuristic](heuristic::MiniBoardHeuristic) trait,
+//! the won small boards can be evaluated with a lookup table of a [MetaBoardHeuristic](heuristic::MetaBoardHeuristic).
+//! Heuristics implementing [Heuristic::initialize_features](heuristic::Heuristic::initialize_features) are updated move by move in the search of the [MiniMaxAgent](agent::minimax_agent::MiniMaxAgent).
+//!
+//! ## HTTP server
+//! The `HttpServer` serves a web board and a JSON API to play against the agents of the [AgentRegistry](agent::registry::AgentRegistry) in a browser,
+//! it requires the `http` feature. Start it with `cargo run --example http_server --features http`.
 //!
 //! ## Genetic algorithm
 //! The library also contains a [GeneticAlgorithm](genetic_algorithm::GeneticAlgorithm) as well as various [Selection](genetic_algorithm::selection), [Mutation](genetic_algorithm::mutation), [Recombination](genetic_algorithm::recombination) and [Fitness](genetic_algorithm::fitness) operators to optimize the weights of the [ParameterizedHeuristic](heuristic::parameterized_heuristic::ParameterizedHeuristic).
+//! The weights can also be fitted to the results of recorded games with the [TexelTuner](tuner::TexelTuner).
 //!
 //! # Usage
 //! Initialize a game with two agents and play it:
@@ -46,5 +71,8 @@ pub mod agent;
 pub mod game;
 pub mod genetic_algorithm;
 pub mod heuristic;
+#[cfg(feature = "http")]
+pub mod http_server;
 pub mod quality_test;
 pub mod runtime_test;
+pub mod tuner;
@@ -7,6 +7,7 @@ use hausarbeit::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
 use hausarbeit::agent::random_agent::RandomAgent;
 use hausarbeit::agent::random_start::RandomStartAgent;
 use hausarbeit::agent::Agent;
+use hausarbeit::game::clock::TimeControl;
 use hausarbeit::game::game_result::GameResult;
 use hausarbeit::game::player::Player::{One, Two};
 use hausarbeit::game::Game;
@@ -28,17 +29,32 @@ use std::fs::{File, OpenOptions};
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+// The experiments are run by uncommenting their call in main
+#[allow(dead_code)]
 const NUM_GAMES: u32 = 100;
+#[allow(dead_code)]
 const DEPTH: u32 = 8;
+#[allow(dead_code)]
 const QUIESCENCE_SEARCH_DEPTH: u32 = 1;
 
 fn main() {
     //agent::human_agent::start_game_with_human();
 
+    //agent::human_agent::resume_game_with_human("game.json");
+
+    //agent::human_agent::start_tui_game_with_human();
+
+    //agent::human_agent::start_timed_game_with_human(TimeControl::new(Duration::from_secs(300), Duration::from_secs(5)));
+
     //agent::human_agent::human_against_human();
 
+    //agent::human_agent::host_network_games("0.0.0.0:7878");
+    //agent::human_agent::join_network_game_with_human("127.0.0.1:7878");
+
+    //agent::human_agent::replay::replay_game("game.json", Some("annotations.jsonl"));
+
     rayon::ThreadPoolBuilder::new()
         .num_threads(7)
         .build_global()
@@ -46,8 +62,12 @@ fn main() {
 
     //runtime_test::run();
 
+    //runtime_test::run_symmetric_transpositions();
+
     //quality_test::run();
 
+    //quality_test::run_forced_move_extensions();
+
     //agent::monte_carlo_tree_agent::run();
 
     //run();
@@ -55,6 +75,7 @@ fn main() {
     //genetic_algorithm::run();
 }
 
+#[allow(dead_code)]
 fn run() {
     let mut wins = [0, 0, 0];
     let mut games = vec![];
@@ -69,15 +90,17 @@ fn run() {
     // MonteCarloTreeAgent::new(10000)
     // RandomAgent::new()
 
+    let agent1 = RandomStartAgent::new(2, BenchedAgent::new(writer.clone(), RandomAgent::new()));
+    let agent2 = RandomStartAgent::new(
+        2,
+        BenchedAgent::new(writer.clone(), MonteCarloTreeAgent::new(10000)),
+    );
+
     for _ in 0..NUM_GAMES {
-        let agent1 =
-            RandomStartAgent::new(2, BenchedAgent::new(writer.clone(), RandomAgent::new()));
-        let agent2 = RandomStartAgent::new(
-            2,
-            BenchedAgent::new(writer.clone(), MonteCarloTreeAgent::new(10000)),
-        );
-
-        games.push(Game::new(Box::new(agent1), Box::new(agent2)));
+        games.push(Game::new(
+            Box::new(agent1.clone()),
+            Box::new(agent2.clone()),
+        ));
     }
 
     let counter = AtomicUsize::new(0);
@@ -1,6 +1,8 @@
+#[allow(unused_imports)]
 use crate::agent::benched::BenchedAgent;
 use crate::agent::minimax_agent::MiniMaxAgent;
 use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
+#[allow(unused_imports)]
 use crate::agent::random_agent::RandomAgent;
 use crate::agent::random_start::RandomStartAgent;
 use crate::game::game_result::GameResult;
@@ -29,7 +31,7 @@ pub fn run() {
     writer
         .lock()
         .unwrap()
-        .write_record(&[
+        .write_record([
             "Configuration",
             "Player One Wins",
             "Player Two Wins",
@@ -51,20 +53,9 @@ pub fn run() {
             ));
         }
 
-        let mut stats = vec![0; NUM_GAMES as usize];
-
         let pre_run = Instant::now();
 
-        let results: Vec<GameResult> = games.par_iter_mut().map(|game| game.play()).collect();
-
-        for result in results {
-            match result {
-                Win(One) => stats[0] += 1,
-                Win(Two) => stats[1] += 1,
-                Draw => stats[2] += 1,
-                _ => (),
-            }
-        }
+        let stats = play_games(&mut games);
 
         let duration = pre_run.elapsed();
 
@@ -85,3 +76,84 @@ pub fn run() {
         );
     }
 }
+
+/// Measures the effect of [forced move extensions](MiniMaxAgent::with_forced_move_extensions)
+///
+/// The agent plays against the [MonteCarloTreeAgent] with and without the extensions.
+pub fn run_forced_move_extensions() {
+    let writer = Arc::new(Mutex::new(
+        Writer::from_path("forced move extensions win stats.csv")
+            .expect("Could not create CSV writer"),
+    ));
+
+    writer
+        .lock()
+        .unwrap()
+        .write_record([
+            "Configuration",
+            "Player One Wins",
+            "Player Two Wins",
+            "Draws",
+            "Duration",
+        ])
+        .expect("Could not write record");
+
+    for forced_move_extensions in [false, true] {
+        let mut games = vec![];
+
+        for _ in 0..NUM_GAMES {
+            let agent1 = MiniMaxAgent::new(DEPTH, 1, CustomHeuristic::new(One))
+                .with_forced_move_extensions(forced_move_extensions);
+            let agent2 = MonteCarloTreeAgent::new(10000);
+
+            games.push(Game::new(
+                Box::new(RandomStartAgent::new(2, agent1)),
+                Box::new(agent2),
+            ));
+        }
+
+        let pre_run = Instant::now();
+
+        let stats = play_games(&mut games);
+
+        let duration = pre_run.elapsed();
+
+        writer
+            .lock()
+            .unwrap()
+            .write_record(&[
+                format!("{}+1 (ext: {})", DEPTH, forced_move_extensions),
+                stats[0].to_string(),
+                stats[1].to_string(),
+                stats[2].to_string(),
+                duration.as_millis().to_string(),
+            ])
+            .expect("Could not write record");
+
+        println!(
+            "Forced Move Extensions: {}, Duration: {:?}",
+            forced_move_extensions, duration
+        );
+    }
+}
+
+/// Plays the given games in parallel
+///
+/// # Returns
+/// The number of wins of player one, wins of player two and draws
+fn play_games(games: &mut [Game]) -> [u32; 3] {
+    let mut stats = [0; 3];
+
+    let results: Vec<GameResult> = games.par_iter_mut().map(|game| game.play()).collect();
+
+    for result in results {
+        match result {
+            Win(One) => stats[0] += 1,
+            Win(Two) => stats[1] += 1,
+            Draw => stats[2] += 1,
+            _ => (),
+        }
+    }
+
+    stats
+}
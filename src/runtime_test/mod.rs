@@ -2,14 +2,17 @@
 
 use crate::agent::benched::BenchedAgent;
 use crate::agent::minimax_agent::MiniMaxAgent;
+#[allow(unused_imports)]
 use crate::agent::monte_carlo_tree_agent::MonteCarloTreeAgent;
 use crate::agent::random_agent::RandomAgent;
+#[allow(unused_imports)]
 use crate::game::player::Player::{One, Two};
 use crate::game::Game;
 use crate::heuristic::custom_heuristic::CustomHeuristic;
 use csv::Writer;
 use rayon::iter::IntoParallelRefMutIterator;
 use rayon::iter::ParallelIterator;
+#[allow(unused_imports)]
 use std::ops::{Range, RangeInclusive};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
@@ -22,7 +25,10 @@ const MAX_DEPTH: u32 = 7;
 const MIN_QUIESCENCE_SEARCH_DEPTH: u32 = 0;
 const MAX_QUIESCENCE_SEARCH_DEPTH: u32 = 4;
 
+// Used by the commented-out iteration benchmark of the MonteCarloTreeAgent in run
+#[allow(dead_code)]
 const RANGE: RangeInclusive<u32> = 1000..=10000;
+#[allow(dead_code)]
 const RANGE_STEP: usize = 1000;
 
 pub fn run() {
@@ -84,3 +90,40 @@ pub fn run() {
     //     println!("Iterations: {}, Duration: {:?}", i, duration);
     // }
 }
+
+/// Measures the effect of [symmetric transpositions](MiniMaxAgent::with_symmetric_transpositions)
+///
+/// The [BenchedAgent] records the number of searched nodes per move, so the node reduction can be compared.
+pub fn run_symmetric_transpositions() {
+    let writer = Arc::new(Mutex::new(
+        Writer::from_path("symmetric transpositions.csv").expect("Could not create CSV writer"),
+    ));
+
+    for symmetric_transpositions in [false, true] {
+        let mut games = vec![];
+
+        for _ in 0..NUM_GAMES {
+            let agent1 = MiniMaxAgent::new(MAX_DEPTH, 1, CustomHeuristic::new(One))
+                .with_symmetric_transpositions(symmetric_transpositions);
+            let agent2 = RandomAgent::new();
+
+            games.push(Game::new(
+                Box::new(BenchedAgent::new(writer.clone(), agent1)),
+                Box::new(agent2),
+            ));
+        }
+
+        let pre_run = Instant::now();
+
+        games.par_iter_mut().for_each(|game| {
+            game.play();
+        });
+
+        let duration = pre_run.elapsed();
+
+        println!(
+            "Symmetric Transpositions: {}, Duration: {:?}",
+            symmetric_transpositions, duration
+        );
+    }
+}
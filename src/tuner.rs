@@ -0,0 +1,337 @@
+//! # Contains the [TexelTuner] struct
+//! The TexelTuner fits the weights of the [ParameterizedHeuristic](crate::heuristic::parameterized_heuristic::ParameterizedHeuristic) to the results of recorded games.
+//!
+//! Every position of a [GameRecord] is labeled with the result of its game. The tuner minimizes the mean squared error
+//! between the results and the expected scores predicted by the heuristic, i.e. it fits a logistic regression
+//! of the results on the [features](extract_features) of the positions.
+//! Compared to the [GeneticAlgorithm](crate::genetic_algorithm::GeneticAlgorithm), no games are played while tuning,
+//! so the weights are fitted much faster and without the noise of single game results.
+//!
+//! The records can be produced by any [Game](crate::game::Game), e.g. self-play games of agents using the heuristic.
+
+use crate::agent::agent_error::AgentError;
+use crate::game::game_record::GameRecord;
+use crate::game::game_result::GameResult;
+use crate::game::player::Player;
+use crate::heuristic::parameterized_heuristic::{
+    extract_features, phase_weights, NUM_FEATURES, NUM_PHASED_FEATURES, NUM_PHASES, SCALE,
+};
+use crate::heuristic::{normalize, MAX_ESTIMATE};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// The numbers of weights the [ParameterizedHeuristic](crate::heuristic::parameterized_heuristic::ParameterizedHeuristic) accepts
+const VALID_NUM_VALUES: [usize; 4] = [12, 13, NUM_FEATURES, NUM_PHASED_FEATURES];
+
+/// Panics if the heuristic does not accept the number of weights
+fn assert_num_values(values: &[f64]) {
+    assert!(
+        VALID_NUM_VALUES.contains(&values.len()),
+        "{} weights can not be tuned, the heuristic accepts {:?} weights",
+        values.len(),
+        VALID_NUM_VALUES
+    );
+}
+
+/// # Struct representing a position of a recorded game
+///
+/// # Fields
+/// * `features` - The features of the position for [Player::One]
+/// * `phases` - The share of the phases of the game in the weights, see [phase_weights]
+/// * `result` - The result of the game for [Player::One], 1 for a win, 0.5 for a draw and 0 for a loss
+struct TrainingPosition {
+    features: Vec<f64>,
+    phases: [f64; NUM_PHASES],
+    result: f64,
+}
+
+impl TrainingPosition {
+    /// Returns the coefficient of a weight of the heuristic in the position
+    ///
+    /// With weights for every phase of the game, the features are split up by the [phase_weights].
+    fn coefficient(&self, index: usize, num_values: usize) -> f64 {
+        if num_values == NUM_PHASED_FEATURES {
+            self.phases[index / NUM_FEATURES] * self.features[index % NUM_FEATURES]
+        } else {
+            self.features[index]
+        }
+    }
+
+    /// Returns the weighted sum of the features, the raw evaluation of the heuristic with the given weights
+    fn weighted_sum(&self, values: &[f64]) -> f64 {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| value * self.coefficient(index, values.len()))
+            .sum()
+    }
+}
+
+/// # Struct representing a tuner fitting the weights of a [ParameterizedHeuristic](crate::heuristic::parameterized_heuristic::ParameterizedHeuristic) to recorded games
+///
+/// The expected score of [Player::One] in a position is `(1 + value) / 2`, where `value` is the evaluation of the
+/// heuristic for [Player::One]. The weights are fitted by gradient descent on the mean squared error of the expected scores.
+/// Weight vectors of any length accepted by the heuristic can be tuned, including weights for every phase of the game.
+///
+/// # Example
+/// ```
+/// use hausarbeit::agent::random_agent::RandomAgent;
+/// use hausarbeit::game::Game;
+/// use hausarbeit::heuristic::parameterized_heuristic::NUM_FEATURES;
+/// use hausarbeit::tuner::TexelTuner;
+///
+/// let mut tuner = TexelTuner::new().with_iterations(10);
+///
+/// for _ in 0..10 {
+///     let mut game = Game::new(Box::new(RandomAgent::new()), Box::new(RandomAgent::new()));
+///     game.play();
+///     tuner.add_record(game.get_record()).unwrap();
+/// }
+///
+/// let values = tuner.tune(vec![0.; NUM_FEATURES]);
+/// ```
+pub struct TexelTuner {
+    positions: Vec<TrainingPosition>,
+    iterations: usize,
+    learning_rate: f64,
+}
+
+impl Default for TexelTuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TexelTuner {
+    /// Creates a new [TexelTuner] without positions
+    ///
+    /// The weights are tuned with 1000 iterations and a learning rate of 100 by default.
+    pub fn new() -> Self {
+        TexelTuner {
+            positions: vec![],
+            iterations: 1000,
+            learning_rate: 100.,
+        }
+    }
+
+    /// Sets the number of gradient descent steps
+    ///
+    /// # Arguments
+    /// * `iterations` - The number of steps
+    /// # Returns
+    /// The tuner with the number of steps set
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the learning rate of the gradient descent
+    ///
+    /// # Arguments
+    /// * `learning_rate` - The factor of the gradient subtracted from the weights in every step
+    /// # Returns
+    /// The tuner with the learning rate set
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Adds the positions of a finished game
+    ///
+    /// Unfinished and forfeited games are skipped, as their results do not follow from the positions.
+    /// The final position is not added, the heuristic does not use its weights for finished games.
+    /// # Arguments
+    /// * `record` - The record of the game
+    /// # Returns
+    /// The number of positions added, or [AgentError::IllegalMove] if the record contains an illegal move
+    pub fn add_record(&mut self, record: &GameRecord) -> Result<usize, AgentError> {
+        let result = match record.get_result() {
+            GameResult::Win(Player::One) => 1.,
+            GameResult::Win(Player::Two) => 0.,
+            GameResult::Draw => 0.5,
+            GameResult::Continue => return Ok(0),
+        };
+
+        if record.get_forfeit().is_some() {
+            return Ok(0);
+        }
+
+        let num_positions = self.positions.len();
+
+        for board in record.replay()? {
+            if board.get_game_status() != GameResult::Continue {
+                continue;
+            }
+
+            self.positions.push(TrainingPosition {
+                features: extract_features(board, Player::One),
+                phases: phase_weights(board),
+                result,
+            });
+        }
+
+        Ok(self.positions.len() - num_positions)
+    }
+
+    /// Returns the number of positions the weights are fitted to
+    pub fn num_positions(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the mean squared error of the expected scores of the positions
+    ///
+    /// # Arguments
+    /// * `values` - The weights of the heuristic, see [ParameterizedHeuristic::values](crate::heuristic::parameterized_heuristic::ParameterizedHeuristic::values)
+    /// # Returns
+    /// The error, 0 if there are no positions
+    /// # Panics
+    /// If the heuristic does not accept the number of weights, i.e. 12, 13, [NUM_FEATURES] or [NUM_PHASED_FEATURES]
+    pub fn error(&self, values: &[f64]) -> f64 {
+        assert_num_values(values);
+
+        if self.positions.is_empty() {
+            return 0.;
+        }
+
+        let error: f64 = self
+            .positions
+            .par_iter()
+            .map(|position| {
+                let score = (1. + normalize(position.weighted_sum(values), SCALE)) / 2.;
+                (score - position.result).powi(2)
+            })
+            .sum();
+
+        error / self.positions.len() as f64
+    }
+
+    /// Returns the gradient of the [error](TexelTuner::error) with respect to the weights
+    fn gradient(&self, values: &[f64]) -> Vec<f64> {
+        let num_values = values.len();
+
+        let gradient = self
+            .positions
+            .par_iter()
+            .map(|position| {
+                let tanh = (position.weighted_sum(values) / SCALE).tanh();
+                let score = (1. + tanh * MAX_ESTIMATE) / 2.;
+                let factor = (score - position.result) * MAX_ESTIMATE * (1. - tanh * tanh) / SCALE;
+
+                (0..num_values)
+                    .map(|index| factor * position.coefficient(index, num_values))
+                    .collect::<Vec<_>>()
+            })
+            .reduce(
+                || vec![0.; num_values],
+                |mut lhs, rhs| {
+                    lhs.iter_mut().zip(rhs).for_each(|(lhs, rhs)| *lhs += rhs);
+                    lhs
+                },
+            );
+
+        gradient
+            .into_iter()
+            .map(|value| value / self.positions.len() as f64)
+            .collect()
+    }
+
+    /// Fits the weights to the positions
+    ///
+    /// # Arguments
+    /// * `values` - The initial weights, e.g. the weights found by the genetic algorithm
+    /// # Returns
+    /// The fitted weights, the initial weights if there are no positions
+    /// # Panics
+    /// If the heuristic does not accept the number of weights, i.e. 12, 13, [NUM_FEATURES] or [NUM_PHASED_FEATURES]
+    pub fn tune(&self, values: Vec<f64>) -> Vec<f64> {
+        assert_num_values(&values);
+        let mut values = values;
+
+        if self.positions.is_empty() {
+            return values;
+        }
+
+        for _ in 0..self.iterations {
+            let gradient = self.gradient(&values);
+
+            values
+                .iter_mut()
+                .zip(gradient)
+                .for_each(|(value, gradient)| *value -= self.learning_rate * gradient);
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::game_metadata::GameMetadata;
+    use crate::game::ultimate_board::UltimateBoard;
+    use crate::heuristic::parameterized_heuristic::ParameterizedHeuristic;
+    use crate::heuristic::Heuristic;
+    use rand::seq::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    /// Records a game of random moves
+    fn random_record(rng: &mut ChaCha20Rng) -> GameRecord {
+        let mut record = GameRecord::new(GameMetadata::new());
+        let mut board = UltimateBoard::new();
+
+        while board.get_game_status() == GameResult::Continue {
+            let index = board.get_possible_moves().choose(rng).unwrap();
+            board.make_move(index);
+            record.push_move(index);
+        }
+        record.set_result(board.get_game_status());
+
+        record
+    }
+
+    #[test]
+    fn test_texel_tuner() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut tuner = TexelTuner::new().with_iterations(50);
+
+        for _ in 0..20 {
+            tuner.add_record(&random_record(&mut rng)).unwrap();
+        }
+        assert!(tuner.num_positions() > 20 * 17);
+
+        // Unfinished games are skipped
+        let mut unfinished = GameRecord::new(GameMetadata::new());
+        unfinished.push_move(40);
+        assert_eq!(tuner.add_record(&unfinished), Ok(0));
+
+        for initial in [vec![0.; NUM_FEATURES], vec![0.; NUM_PHASED_FEATURES]] {
+            // Weights of zero predict a draw in every position
+            assert!(tuner.error(&initial) <= 0.25);
+
+            let values = tuner.tune(initial.clone());
+            assert!(tuner.error(&values) < tuner.error(&initial));
+
+            // The error is the error of the evaluations of the heuristic
+            let board = random_record(&mut rng).replay().unwrap()[10];
+            let heuristic = ParameterizedHeuristic::new(Player::One, values.clone());
+            let position = TrainingPosition {
+                features: extract_features(board, Player::One),
+                phases: phase_weights(board),
+                result: 1.,
+            };
+            let value = normalize(position.weighted_sum(&values), SCALE);
+            assert!((heuristic.evaluate(board) - value).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "15 weights can not be tuned")]
+    fn test_invalid_num_values() {
+        let mut tuner = TexelTuner::new().with_iterations(1);
+        tuner
+            .add_record(&random_record(&mut ChaCha20Rng::seed_from_u64(0)))
+            .unwrap();
+
+        tuner.tune(vec![0.; 15]);
+    }
+}